@@ -0,0 +1,97 @@
+"""
+Benchmarks for the estimation hot path.
+
+Measures compute_tiers on an eth_feeHistory result, GasTracker.current()
+against a canned JSON-RPC endpoint (no network), and serializing a quote
+with wire.to_json()/to_cbor(). Run from the repository root:
+
+    python benches/bench_estimation.py [--blocks 20] [--repeat 5]
+
+Each line reports the best of `repeat` timed runs, so numbers from one
+machine can be compared before and after a change.
+"""
+
+import argparse
+import asyncio
+import sys
+import timeit
+from pathlib import Path
+
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent))
+
+from ethgas.core import RecencyWeight  # noqa: E402
+from ethgas.providers import JsonRpcProvider  # noqa: E402
+from ethgas.testing import MockRpcSession  # noqa: E402
+from ethgas.tracker import FeeHistoryConfig, GasTracker, compute_tiers  # noqa: E402
+from ethgas.wire import to_cbor, to_json  # noqa: E402
+
+GWEI = 10 ** 9
+
+
+def fee_history(blocks: int, percentiles: int = 3) -> dict:
+    """eth_feeHistory result for `blocks` blocks with one reward per percentile."""
+    return {
+        "oldestBlock": hex(19_000_000),
+        "baseFeePerGas": [hex((20 + i % 7) * GWEI) for i in range(blocks + 1)],
+        "gasUsedRatio": [0.3 + (i % 5) / 10 for i in range(blocks)],
+        "reward": [[hex((1 + p) * GWEI + i * 10 ** 6) for p in range(percentiles)] for i in range(blocks)],
+    }
+
+
+def canned_session(blocks: int) -> MockRpcSession:
+    """Session answering JSON-RPC requests from fixed results, with no I/O."""
+    return MockRpcSession({
+        "eth_gasPrice": hex(25 * GWEI),
+        "eth_blockNumber": hex(19_000_000 + blocks),
+        "eth_feeHistory": fee_history(blocks, 1),
+        "eth_getBlockByNumber": {"number": hex(19_000_000 + blocks), "baseFeePerGas": hex(20 * GWEI)},
+    })
+
+
+def best(stmt, number: int, repeat: int) -> float:
+    """Best time per call in microseconds."""
+    return min(timeit.repeat(stmt, number=number, repeat=repeat)) / number * 1e6
+
+
+def report(name: str, micros: float) -> None:
+    print(f"{name:<42} {micros:>10.2f} us/op {1e6 / micros:>12,.0f} ops/s")
+
+
+def main(argv=None) -> int:
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[1])
+    parser.add_argument("--blocks", type=int, default=20, help="blocks in the fee history (default: 20)")
+    parser.add_argument("--repeat", type=int, default=5, help="timed runs per benchmark (default: 5)")
+    parser.add_argument("--number", type=int, default=2000, help="calls per timed run (default: 2000)")
+    args = parser.parse_args(argv)
+
+    print(f"Python {sys.version.split()[0]}, {args.blocks} blocks, best of {args.repeat} x {args.number}")
+
+    history = fee_history(args.blocks)
+    for weighting in (RecencyWeight.NONE, RecencyWeight.EXPONENTIAL):
+        config = FeeHistoryConfig(args.blocks, recency_weight=weighting)
+        report(f"compute_tiers ({weighting.value})", best(lambda: compute_tiers(history, config), args.number, args.repeat))
+
+    session = canned_session(args.blocks)
+    tracker = GasTracker("http://bench.invalid", "ethereum", provider=JsonRpcProvider("http://bench.invalid"))
+    loop = asyncio.new_event_loop()
+    try:
+        loop.run_until_complete(tracker.current(session))  # pays the one-off EIP-1559 probe
+        report("GasTracker.current() (mock endpoint)", best(
+            lambda: loop.run_until_complete(tracker.current(session)), args.number, args.repeat
+        ))
+        quote = loop.run_until_complete(tracker.current(session))
+    finally:
+        loop.close()
+
+    report("wire.to_json(quote)", best(lambda: to_json(quote), args.number, args.repeat))
+    try:
+        to_cbor(quote)
+    except ImportError:
+        print(f"{'wire.to_cbor(quote)':<42} skipped (pip install cbor2)")
+    else:
+        report("wire.to_cbor(quote)", best(lambda: to_cbor(quote), args.number, args.repeat))
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -1,41 +1,207 @@
-"""ETH Gas Tracker - Multi-network gas price monitoring."""
+"""
+ETH Gas Tracker - Multi-network gas price monitoring.
+
+The top level exports only the names most code needs: GasTracker,
+GasProvider, GasQuote, Eip1559Estimate, Tier, Chain and GasError, which
+`from ethgas.prelude import *` brings in as well. Everything else is
+imported from its module (ethgas.providers, ethgas.errors, ethgas.units,
+...); the names this package used to export still work here but raise a
+DeprecationWarning.
+
+These names and the public names of the modules they point to are the
+supported API and only change in a major release. Quotes and estimates
+are dicts described by the TypedDicts in ethgas.models; they may gain
+keys in minor releases, so ignore keys you don't use. ethgas.core, the
+estimation math without any networking, needs only the standard library;
+the names here are imported on first use, so it loads without the
+"http" extra (aiohttp) installed. ethgas.testing holds the test doubles.
+Modules not named here (l2, mempool, tracing) and underscore helpers are
+implementation details.
+"""
+
+import importlib
+import logging
+import warnings
 
 __version__ = "2.0.0"
 __author__ = "pavlenkotm"
 
-from .tracker import GasTracker
-from .networks import NETWORKS, TX_TYPES
-from .history import GasHistory
-from .stats import GasStats
-from .graphs import ASCIIGraph
-from .alerts import GasAlerts
-from .api import GasAPI
-from .compare import NetworkComparator, compare_networks
-from .export import DataExporter, export_history
-from .prediction import GasPredictor, predict_gas_price
-from .notifications import DesktopNotifier, notify_gas_price
-from .webhooks import WebhookManager, send_gas_alert_webhook
-from .web_ui import WebUI, run_web_ui
-
-__all__ = [
-    "GasTracker",
-    "NETWORKS",
-    "TX_TYPES",
-    "GasHistory",
-    "GasStats",
-    "ASCIIGraph",
-    "GasAlerts",
-    "GasAPI",
-    "NetworkComparator",
-    "compare_networks",
-    "DataExporter",
-    "export_history",
-    "GasPredictor",
-    "predict_gas_price",
-    "DesktopNotifier",
-    "notify_gas_price",
-    "WebhookManager",
-    "send_gas_alert_webhook",
-    "WebUI",
-    "run_web_ui",
-]
+# Library logging stays silent unless the application configures it (see ethgas.tracing)
+logging.getLogger(__name__).addHandler(logging.NullHandler())
+
+# The curated public API, and the module each name is defined in. Names are
+# imported on first use, so the networking modules (and aiohttp) only load
+# when needed and `import ethgas.core` works without them.
+_EXPORTS = {
+    "GasTracker": "tracker",
+    "GasProvider": "providers",
+    "GasQuote": "models",
+    "Eip1559Estimate": "models",
+    "Tier": "models",
+    "Chain": "models",
+    "GasError": "errors",
+}
+
+# Names this package used to export, still importable here with a
+# DeprecationWarning; import them from their module instead
+_DEPRECATED = {
+    # Models and estimation helpers
+    "FeeHistory": "models",
+    "FeeRecommendation": "models",
+    "FeeChange": "models",
+    "SnapshotDiff": "models",
+    "TrackerStatus": "models",
+    "HourStat": "models",
+    "ProviderCounters": "models",
+    "Histogram": "models",
+    "ObservedResponse": "models",
+    "BlobFee": "models",
+    "TxFeeFields": "models",
+    "BatchCost": "models",
+    "DeploymentCost": "models",
+    "BudgetDecision": "models",
+    "Overpayment": "models",
+    "overpayment": "receipts",
+    "diff_snapshots": "diff",
+    "bump_fee": "core",
+    "tx_fee_fields": "core",
+    "percentile": "core",
+    "PercentileMethod": "core",
+    "FeeRounding": "core",
+    "Congestion": "core",
+    "TierFees": "models",
+    "TierEstimate": "models",
+    "TierEstimates": "models",
+    "SolanaChain": "models",
+    "SolanaFee": "models",
+    "TransactionRequest": "models",
+    # Errors
+    "HttpError": "errors",
+    "RateLimitedError": "errors",
+    "RpcError": "errors",
+    "DeserializeError": "errors",
+    "GasTimeoutError": "errors",
+    "AllProvidersFailedError": "errors",
+    "EmptyFeeHistoryError": "errors",
+    "ConfigError": "errors",
+    "CircuitOpenError": "errors",
+    "EstimationRevertedError": "errors",
+    "RpcIdMismatchError": "errors",
+    "FeeCeilingExceededError": "errors",
+    "StalePriceError": "errors",
+    "NoPriceConsensusError": "errors",
+    "UnknownProviderError": "errors",
+    "InsufficientHistoryError": "errors",
+    "SchemaMismatchError": "errors",
+    "HistoricalDataUnavailableError": "errors",
+    "SuspectQuoteError": "errors",
+    # Tracker configuration and wrappers
+    "FeeHistoryConfig": "tracker",
+    "compute_tiers": "tracker",
+    "RecencyWeight": "core",
+    "BlockingGasTracker": "blocking",
+    # Units
+    "Wei": "units",
+    "Gwei": "units",
+    "parse_fee": "units",
+    "parse_hex_quantity": "units",
+    "format_eth": "units",
+    # Providers
+    "JsonRpcProvider": "providers",
+    "InfuraProvider": "providers",
+    "AlchemyProvider": "providers",
+    "EtherscanProvider": "providers",
+    "BlocknativeProvider": "providers",
+    "RaceProvider": "providers",
+    "AggregateProvider": "providers",
+    "Aggregation": "providers",
+    "PricingModel": "providers",
+    "FeeEncoding": "providers",
+    "ProviderStatus": "providers",
+    "ProviderHealth": "models",
+    "FreezeDetector": "providers",
+    "check_health": "providers",
+    "WsProvider": "ws",
+    "IpcProvider": "ipc",
+    "WatchHandle": "watch",
+    "RecordingProvider": "replay",
+    "RecordingSession": "replay",
+    "ReplayProvider": "replay",
+    "ReplaySession": "replay",
+    "SolanaProvider": "solana",
+    "RetryPolicy": "retry",
+    "RetryingProvider": "retry",
+    "RateLimiter": "ratelimit",
+    "RateLimitedProvider": "ratelimit",
+    "CircuitBreakerProvider": "circuit",
+    "CircuitState": "circuit",
+    "BaseFeeCheckProvider": "sanity",
+    "Strategy": "strategy",
+    "StrategyProfile": "strategy",
+    "STRATEGY_PROFILES": "strategy",
+    "QuoteCache": "cache",
+    "PersistentQuoteCache": "cache",
+    "ResponseObserver": "audit",
+    "ObservedProvider": "audit",
+    "Clock": "clock",
+    "SystemClock": "clock",
+    # Prices, networks and tools built on the tracker
+    "PriceFeed": "prices",
+    "CoinGeckoPriceFeed": "prices",
+    "ChainlinkPriceFeed": "prices",
+    "MedianPriceFeed": "prices",
+    "PriceQuote": "models",
+    "NETWORKS": "networks",
+    "SOLANA_NETWORKS": "networks",
+    "TX_TYPES": "networks",
+    "register_network": "networks",
+    "GasHistory": "history",
+    "HistoryStore": "history",
+    "InMemoryHistory": "history",
+    "GasStats": "stats",
+    "GasRegime": "stats",
+    "ASCIIGraph": "graphs",
+    "GasAlerts": "alerts",
+    "filter_below": "alerts",
+    "on_threshold": "alerts",
+    "significant_changes": "alerts",
+    "GasAPI": "api",
+    "NetworkComparator": "compare",
+    "compare_chains": "compare",
+    "CompareOptions": "compare",
+    "CompareSortKey": "compare",
+    "compare_networks": "compare",
+    "DataExporter": "export",
+    "export_history": "export",
+    "GasPredictor": "prediction",
+    "predict_gas_price": "prediction",
+    "DesktopNotifier": "notifications",
+    "notify_gas_price": "notifications",
+    "WebhookManager": "webhooks",
+    "send_gas_alert_webhook": "webhooks",
+    "WebUI": "web_ui",
+    "run_web_ui": "web_ui",
+    "GasMetrics": "metrics",
+    "StatsdSink": "metrics",
+}
+
+__all__ = list(_EXPORTS)
+
+
+def __getattr__(name):
+    if name in _EXPORTS:
+        value = getattr(importlib.import_module(f".{_EXPORTS[name]}", __name__), name)
+        globals()[name] = value
+        return value
+    if name in _DEPRECATED:
+        module = _DEPRECATED[name]
+        # Not cached, so every use outside the submodule is reported
+        warnings.warn(
+            f"ethgas.{name} is deprecated; import it from ethgas.{module}", DeprecationWarning, stacklevel=2
+        )
+        return getattr(importlib.import_module(f".{module}", __name__), name)
+    raise AttributeError(f"module {__name__!r} has no attribute {name!r}")
+
+
+def __dir__():
+    return sorted(set(globals()) | set(__all__))
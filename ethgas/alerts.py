@@ -1,14 +1,25 @@
 """Gas price alert system."""
 
+import math
 import sys
-from typing import Optional
+from typing import AsyncIterator, Callable, Dict, Optional
 
 
 class GasAlerts:
     """Manages gas price alerts and notifications."""
 
-    def __init__(self, threshold: Optional[float] = None):
+    def __init__(self, threshold: Optional[float] = None, rearm_above: Optional[float] = None):
+        """
+        Initialize alerts.
+
+        Args:
+            threshold: Alert when the fee drops to or below this level (gwei)
+            rearm_above: Fee the price must climb back above before the alert can
+                fire again (default: threshold). A level above the threshold adds
+                hysteresis so the alert doesn't flap around the boundary.
+        """
         self.threshold = threshold
+        self.rearm_above = rearm_above
         self.alert_triggered = False
 
     def check_alert(self, current_fee: float) -> Optional[str]:
@@ -19,7 +30,7 @@ class GasAlerts:
         if current_fee <= self.threshold and not self.alert_triggered:
             self.alert_triggered = True
             return f"🔔 ALERT: Gas fee {current_fee:.2f} gwei is below threshold {self.threshold:.2f} gwei!"
-        elif current_fee > self.threshold:
+        elif current_fee > max(self.threshold, self.rearm_above or self.threshold):
             self.alert_triggered = False
 
         return None
@@ -37,3 +48,89 @@ class GasAlerts:
         print("=" * 60 + "\n")
         if beep:
             self.beep()
+
+
+async def filter_below(
+    quotes: AsyncIterator[Dict],
+    tier: str,
+    below_gwei: float,
+    rearm_gwei: Optional[float] = None,
+) -> AsyncIterator[Dict]:
+    """
+    Yield only the quotes where a tier's price crosses below a threshold.
+
+    A quote is yielded on the transition below the threshold, not on every
+    sample while the price stays low. Error and warning items are skipped.
+
+    Args:
+        quotes: Quote stream, e.g. GasTracker.watch()
+        tier: Tier to compare ('slow', 'standard' or 'fast')
+        below_gwei: Threshold in gwei
+        rearm_gwei: Level the price must exceed before the next crossing counts
+
+    Yields:
+        Quotes that crossed below the threshold
+    """
+    alerts = GasAlerts(threshold=below_gwei, rearm_above=rearm_gwei)
+    async for quote in quotes:
+        if "error" in quote or "warning" in quote:
+            continue
+        if alerts.check_alert(quote["tiers"][tier]):
+            yield quote
+
+
+def quote_fee(quote: Dict) -> float:
+    """Base fee of a quote, or its gas price on chains without one (gwei)."""
+    return quote["base_fee"] if quote.get("base_fee") is not None else quote["gas_price"]
+
+
+async def significant_changes(
+    quotes: AsyncIterator[Dict],
+    pct: float,
+    step_gwei: Optional[float] = None,
+) -> AsyncIterator[Dict]:
+    """
+    Yield only the quotes whose base fee moved significantly since the last one yielded.
+
+    The first quote is always yielded. After that a quote is yielded when
+    its base fee (gas price on legacy chains) differs from the last yielded
+    one by more than `pct` percent, or, with `step_gwei`, lies in another
+    multiple of `step_gwei` gwei (e.g. 10 for each 10 gwei boundary).
+    Updates within the band are dropped, as are error and warning items.
+
+    Args:
+        quotes: Quote stream, e.g. GasTracker.watch()
+        pct: Percentage change that counts as significant
+        step_gwei: Optional width of the gwei bands whose boundaries count too
+
+    Yields:
+        Quotes that changed significantly
+    """
+    if pct < 0:
+        raise ValueError("pct must not be negative")
+    if step_gwei is not None and step_gwei <= 0:
+        raise ValueError("step_gwei must be positive")
+    last: Optional[float] = None
+    async for quote in quotes:
+        if "error" in quote or "warning" in quote:
+            continue
+        fee = quote_fee(quote)
+        if last is not None:
+            moved = abs(fee - last) * 100 > pct * last if last else fee != last
+            crossed = step_gwei is not None and math.floor(fee / step_gwei) != math.floor(last / step_gwei)
+            if not moved and not crossed:
+                continue
+        last = fee
+        yield quote
+
+
+async def on_threshold(
+    quotes: AsyncIterator[Dict],
+    tier: str,
+    below_gwei: float,
+    callback: Callable[[Dict], None],
+    rearm_gwei: Optional[float] = None,
+) -> None:
+    """Call `callback` with each quote that crosses below the threshold."""
+    async for quote in filter_below(quotes, tier, below_gwei, rearm_gwei):
+        callback(quote)
@@ -6,11 +6,15 @@ import aiohttp
 from aiohttp import web
 from typing import Dict
 
+from .providers import AppSession
 from .tracker import GasTracker
 from .networks import NETWORKS, TX_TYPES
 from .history import GasHistory
 from .stats import GasStats
 
+# Longest lookback /cheapest-hours accepts, in days
+MAX_CHEAPEST_HOURS_DAYS = 365
+
 
 class GasAPI:
     """REST API server for gas tracking."""
@@ -20,8 +24,14 @@ class GasAPI:
         self.port = port
         self.app = web.Application()
         self.history = GasHistory()
+        # One pooled session for every request's RPC calls, closed with the app
+        self.session = AppSession(self.app)
         self.setup_routes()
 
+    def http_session(self) -> aiohttp.ClientSession:
+        """Get the server's shared HTTP session, created on first use."""
+        return self.session.get()
+
     def setup_routes(self):
         """Setup API routes."""
         self.app.router.add_get("/", self.index)
@@ -29,6 +39,7 @@ class GasAPI:
         self.app.router.add_get("/networks", self.get_networks)
         self.app.router.add_get("/history/{network}", self.get_history)
         self.app.router.add_get("/stats/{network}", self.get_stats)
+        self.app.router.add_get("/cheapest-hours/{network}", self.get_cheapest_hours)
         self.app.router.add_get("/health", self.health_check)
 
     async def index(self, request: web.Request) -> web.Response:
@@ -42,6 +53,7 @@ class GasAPI:
                 "/networks": "List available networks",
                 "/history/{network}": "Get historical data (params: limit=N)",
                 "/stats/{network}": "Get statistics (params: hours=N)",
+                "/cheapest-hours/{network}": "UTC hours of day by median base fee, cheapest first (params: days=N, up to 365)",
                 "/health": "Health check",
             },
             "available_networks": list(NETWORKS.keys()),
@@ -63,29 +75,29 @@ class GasAPI:
         )
 
         try:
-            async with aiohttp.ClientSession() as session:
-                gas_data = await tracker.get_gas_data(session)
-
-                # Calculate costs for different tx types
-                tx_costs = {}
-                for tx_type, tx_info in TX_TYPES.items():
-                    cost = tracker.calculate_tx_cost(
-                        gas_data["max_fee"],
-                        tx_info["gas"],
-                        gas_data["token_price_usd"],
-                    )
-                    tx_costs[tx_type] = {
-                        "name": tx_info["name"],
-                        "gas_units": tx_info["gas"],
-                        "cost_usd": cost["cost_usd"],
-                    }
-
-                response = {
-                    **gas_data,
-                    "tx_costs": tx_costs,
+            session = self.http_session()
+            gas_data = await tracker.get_gas_data(session)
+
+            # Calculate costs for different tx types
+            tx_costs = {}
+            for tx_type, tx_info in TX_TYPES.items():
+                cost = tracker.calculate_tx_cost(
+                    gas_data["max_fee"],
+                    tx_info["gas"],
+                    gas_data["token_price_usd"],
+                )
+                tx_costs[tx_type] = {
+                    "name": tx_info["name"],
+                    "gas_units": tx_info["gas"],
+                    "cost_usd": cost["cost_usd"],
                 }
 
-                return web.json_response(response)
+            response = {
+                **gas_data,
+                "tx_costs": tx_costs,
+            }
+
+            return web.json_response(response)
 
         except Exception as e:
             return web.json_response({"error": str(e)}, status=500)
@@ -143,6 +155,34 @@ class GasAPI:
             {"network": network["name"], "timeframe_hours": hours, "stats": stats}
         )
 
+    async def get_cheapest_hours(self, request: web.Request) -> web.Response:
+        """Rank the UTC hours of day by median base fee over recent history."""
+        network_name = request.match_info["network"]
+
+        if network_name not in NETWORKS:
+            return web.json_response(
+                {"error": f"Unknown network: {network_name}"}, status=404
+            )
+
+        try:
+            days = int(request.query.get("days", 7))
+        except ValueError:
+            return web.json_response(
+                {"error": f"days must be a whole number, got {request.query['days']!r}"}, status=400
+            )
+        if not 1 <= days <= MAX_CHEAPEST_HOURS_DAYS:
+            return web.json_response(
+                {"error": f"days must be between 1 and {MAX_CHEAPEST_HOURS_DAYS}, got {days}"}, status=400
+            )
+
+        network = NETWORKS[network_name]
+        records = self.history.get_records(network=network["name"])
+        records = GasStats.filter_by_timeframe(records, days * 24)
+
+        return web.json_response(
+            {"network": network["name"], "days": days, "hours": GasStats.cheapest_hours(records)}
+        )
+
     async def health_check(self, request: web.Request) -> web.Response:
         """Health check endpoint."""
         return web.json_response({"status": "healthy"})
@@ -0,0 +1,111 @@
+"""
+Raw provider responses alongside the quotes built from them, for auditing.
+
+    observer = ResponseObserver()
+    tracker.with_response_observer(observer)
+    ...
+    async for record in observer:
+        archive(record["provider"], record["bodies"], record["quote"])
+
+Responses are only kept when an observer is attached; without one, providers
+decode them exactly as before. The observer never slows a fetch down: records
+go into a bounded queue and are dropped, and counted, once it is full.
+"""
+
+import asyncio
+import copy
+from datetime import datetime, timezone
+from typing import AsyncIterator, Dict, List, Optional
+
+import aiohttp
+
+from .models import ObservedResponse
+from .providers import GasProvider
+from .tracing import capture_bodies
+
+# Records an observer holds before it starts dropping new ones
+DEFAULT_OBSERVER_CAPACITY = 1000
+
+
+class ResponseObserver:
+    """
+    Bounded queue of ObservedResponse records, filled by ObservedProvider.
+
+    Consume it with `async for`, get() or drain(). When `capacity` records
+    are waiting, new ones are dropped and counted in `dropped`.
+    """
+
+    def __init__(self, capacity: int = DEFAULT_OBSERVER_CAPACITY):
+        if capacity < 1:
+            raise ValueError("capacity must be at least 1")
+        self.capacity = capacity
+        self.dropped = 0
+        # Created on first use, inside the event loop that uses it
+        self._queue: Optional[asyncio.Queue] = None
+
+    @property
+    def queue(self) -> asyncio.Queue:
+        if self._queue is None:
+            self._queue = asyncio.Queue(self.capacity)
+        return self._queue
+
+    def observe(self, record: ObservedResponse) -> bool:
+        """Queue a record without waiting; returns False if it was dropped."""
+        try:
+            self.queue.put_nowait(record)
+        except asyncio.QueueFull:
+            self.dropped += 1
+            return False
+        return True
+
+    async def get(self) -> ObservedResponse:
+        """Wait for the next record."""
+        return await self.queue.get()
+
+    def drain(self) -> List[ObservedResponse]:
+        """Take every record waiting now."""
+        records = []
+        while not self.queue.empty():
+            records.append(self.queue.get_nowait())
+        return records
+
+    def __aiter__(self) -> AsyncIterator[ObservedResponse]:
+        return self
+
+    async def __anext__(self) -> ObservedResponse:
+        return await self.get()
+
+
+class ObservedProvider(GasProvider):
+    """
+    Wrap a provider so every fetch reports its raw responses to an observer.
+
+    Failed fetches are reported too, with the bodies read before the
+    failure and the error. The record's quote is a copy, so later changes
+    to the returned quote don't alter the archive.
+    """
+
+    def __init__(self, provider: GasProvider, observer: ResponseObserver):
+        self.provider = provider
+        self.observer = observer
+        self.name = provider.name
+
+    async def fetch(self, session: aiohttp.ClientSession) -> Dict:
+        """Fetch from the wrapped provider and report what it read."""
+        with capture_bodies() as bodies:
+            try:
+                quote = await self.provider.fetch(session)
+            except Exception as e:
+                self._report(list(bodies), None, str(e) or type(e).__name__)
+                raise
+        self._report(list(bodies), copy.deepcopy(quote), None)
+        return quote
+
+    def _report(self, bodies: List[bytes], quote: Optional[Dict], error: Optional[str]) -> None:
+        self.observer.observe({
+            "provider": self.name,
+            "observed_at": datetime.now(timezone.utc),
+            "bodies": bodies,
+            "quote": quote,
+            "error": error,
+        })
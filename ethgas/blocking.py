@@ -0,0 +1,186 @@
+"""Synchronous wrapper around GasTracker for scripts without an event loop."""
+
+import asyncio
+from datetime import timedelta
+from typing import Dict, List, Optional
+
+from .models import BudgetDecision
+from .stats import GasRegime
+from .tracker import GasTracker
+from .units import Gwei
+
+
+class BlockingGasTracker:
+    """
+    Blocking version of GasTracker.
+
+    Every call runs the matching GasTracker coroutine to completion on a
+    private event loop, so both versions share the same estimation code. The
+    loop and the tracker's http_session() are kept between calls (caches,
+    rate limiters and connection reuse keep working); call close() or use it
+    as a context manager when done. Do not use it from inside a running
+    event loop.
+    """
+
+    def __init__(self, *args, tracker: Optional[GasTracker] = None, **kwargs):
+        """
+        Initialize blocking tracker.
+
+        Args:
+            *args, **kwargs: GasTracker constructor arguments
+            tracker: Already configured GasTracker to wrap instead
+        """
+        self.tracker = tracker or GasTracker(*args, **kwargs)
+        self._loop: Optional[asyncio.AbstractEventLoop] = None
+
+    @classmethod
+    def for_network(cls, network_id: str, **kwargs) -> "BlockingGasTracker":
+        """Create a blocking tracker from a NETWORKS entry."""
+        return cls(tracker=GasTracker.for_network(network_id, **kwargs))
+
+    def _run(self, method, *args, **kwargs):
+        """Run a GasTracker coroutine method with the shared session."""
+        try:
+            asyncio.get_running_loop()
+        except RuntimeError:
+            pass
+        else:
+            raise RuntimeError("BlockingGasTracker cannot be used inside a running event loop; use GasTracker")
+
+        if self._loop is None:
+            self._loop = asyncio.new_event_loop()
+
+        async def call():
+            return await method(self.tracker.http_session(), *args, **kwargs)
+
+        return self._loop.run_until_complete(call())
+
+    def current(self) -> Dict:
+        """Get the latest gas quote (see GasTracker.current)."""
+        return self._run(self.tracker.current)
+
+    def current_from(self, provider_name: str) -> Dict:
+        """Get a fresh quote from one named provider (see GasTracker.current_from)."""
+        return self._run(self.tracker.current_from, provider_name)
+
+    def force_refresh(self) -> Dict:
+        """Get a fresh gas quote, bypassing the cache."""
+        return self._run(self.tracker.force_refresh)
+
+    def status(self) -> Dict:
+        """Summarize the tracker's health without network calls (see GasTracker.status)."""
+        return self.tracker.status()
+
+    def get_base_fee_gwei(self) -> Gwei:
+        """Get the current base fee in gwei."""
+        return self._run(self.tracker.get_base_fee_gwei)
+
+    def estimate_eip1559(self, block_count: Optional[int] = None) -> Dict:
+        """Estimate EIP-1559 fee tiers (see GasTracker.estimate_eip1559)."""
+        return self._run(self.tracker.estimate_eip1559, block_count)
+
+    def recommend(self) -> Dict:
+        """Get standard-tier fees for signing a transaction (see GasTracker.recommend)."""
+        return self._run(self.tracker.recommend)
+
+    def adaptive_max_fee(self, tier: str = "standard", confidence: float = 0.9) -> int:
+        """Get a tier's max fee with volatility-sized headroom (see GasTracker.adaptive_max_fee)."""
+        return self._run(self.tracker.adaptive_max_fee, tier, confidence)
+
+    def estimate_at_base_fee(self, base_fee_gwei: float, tier: str = "standard", estimate: Optional[Dict] = None) -> Dict:
+        """Get a tier's fees at a hypothetical base fee (see GasTracker.estimate_at_base_fee)."""
+        return self._run(self.tracker.estimate_at_base_fee, base_fee_gwei, tier, estimate)
+
+    def fee_for_target_blocks(self, blocks: int) -> Dict:
+        """Get fees for inclusion within `blocks` blocks (see GasTracker.fee_for_target_blocks)."""
+        return self._run(self.tracker.fee_for_target_blocks, blocks)
+
+    def priority_fee_histogram(self, buckets: int = 10, block_count: Optional[int] = None) -> Dict:
+        """Bin recent priority fees into buckets (see GasTracker.priority_fee_histogram)."""
+        return self._run(self.tracker.priority_fee_histogram, buckets, block_count)
+
+    def replacement_fee(self, original: Dict, min_bump_pct: float = 12.5, max_fee_ceiling: Optional[int] = None) -> Dict:
+        """Get bumped fees for replacing a stuck transaction (see GasTracker.replacement_fee)."""
+        return self._run(self.tracker.replacement_fee, original, min_bump_pct, max_fee_ceiling)
+
+    def check_budget(self, tier: str, max_gwei: float) -> BudgetDecision:
+        """Check a tier against a gas price ceiling (see GasTracker.check_budget)."""
+        return self._run(self.tracker.check_budget, tier, max_gwei)
+
+    def wait_until_below(self, tier: str, max_gwei: float, poll: float = 12.0, timeout: float = 600.0) -> Dict:
+        """Block until a tier is at or below a ceiling (see GasTracker.wait_until_below)."""
+        return self._run(self.tracker.wait_until_below, tier, max_gwei, poll, timeout)
+
+    def estimate_inclusion(self, target: float = 0.9, block_count: int = 20) -> Dict:
+        """Estimate the priority fee for quick inclusion (see GasTracker.estimate_inclusion)."""
+        return self._run(self.tracker.estimate_inclusion, target, block_count)
+
+    def hybrid_estimate(self, mempool_weight: Optional[float] = None, target: float = 0.9) -> Dict:
+        """Get standard-tier fees blending fee history and the mempool (see GasTracker.hybrid_estimate)."""
+        return self._run(self.tracker.hybrid_estimate, mempool_weight, target)
+
+    def estimate_l2_cost(self, calldata: bytes, **kwargs) -> Dict:
+        """Estimate an L2 transaction's total cost (see GasTracker.estimate_l2_cost)."""
+        return self._run(self.tracker.estimate_l2_cost, calldata, **kwargs)
+
+    def snapshot(self) -> Dict:
+        """Get the EIP-1559 estimate, token price and tx costs together (see GasTracker.snapshot)."""
+        return self._run(self.tracker.snapshot)
+
+    def backfill(self, store, from_block: int, to_block: int, batch_size: int = 100) -> List[Dict]:
+        """Load a block range's base fees into a HistoryStore (see GasTracker.backfill)."""
+        return self._run(self.tracker.backfill, store, from_block, to_block, batch_size)
+
+    def current_regime(self, store, window: timedelta = timedelta(hours=24)) -> GasRegime:
+        """Classify the current base fee against stored history (see GasTracker.current_regime)."""
+        return self._run(self.tracker.current_regime, store, window)
+
+    def blob_fee_estimate(self, blobs: int = 1, block_count: Optional[int] = None) -> Optional[Dict]:
+        """Recommend maxFeePerBlobGas for a blob transaction (see GasTracker.blob_fee_estimate)."""
+        return self._run(self.tracker.blob_fee_estimate, blobs, block_count)
+
+    def flush(self) -> None:
+        """Commit buffered history samples (see GasTracker.flush)."""
+        self._run(self.tracker.flush)
+
+    def smoothed_current(self, history, half_life: timedelta = timedelta(minutes=1)) -> Dict:
+        """Get the current quote blended with recent history (see GasTracker.smoothed_current)."""
+        return self._run(self.tracker.smoothed_current, history, half_life)
+
+    def estimate_batch(self, tx_types: List[str], tier: str = "standard") -> Dict:
+        """Price a batch of transactions with one quote (see GasTracker.estimate_batch)."""
+        return self._run(self.tracker.estimate_batch, tx_types, tier)
+
+    def estimate_for_tx(self, tx, eth_price_usd: Optional[float] = None) -> Dict:
+        """Price an arbitrary transaction at every tier (see GasTracker.estimate_for_tx)."""
+        return self._run(self.tracker.estimate_for_tx, tx, eth_price_usd)
+
+    def overpayment_for_tx(self, tx_hash: str) -> Dict:
+        """Compare a mined transaction's maximum fee with what it paid (see GasTracker.overpayment_for_tx)."""
+        return self._run(self.tracker.overpayment_for_tx, tx_hash)
+
+    def check_provider_health(self, max_lag_blocks: int = 3) -> List[Dict]:
+        """Compare the block height of every provider (see GasTracker.check_provider_health)."""
+        return self._run(self.tracker.check_provider_health, max_lag_blocks)
+
+    def get_gas_data(self, priority_tip: float = 1.5) -> Dict:
+        """Get base fee, priority tip, max fee and token price."""
+        return self._run(self.tracker.get_gas_data, priority_tip)
+
+    def get_fiat_costs(self, gas_limit: int) -> Dict:
+        """Price a gas limit at every tier in USD."""
+        return self._run(self.tracker.get_fiat_costs, gas_limit)
+
+    def close(self) -> None:
+        """Close the session the tracker created and the private event loop."""
+        if self._loop is None:
+            return
+        self._loop.run_until_complete(self.tracker.close_session())
+        self._loop.close()
+        self._loop = None
+
+    def __enter__(self) -> "BlockingGasTracker":
+        return self
+
+    def __exit__(self, *exc) -> None:
+        self.close()
@@ -0,0 +1,269 @@
+"""Short-lived quote cache with single-flight fetching, optionally kept on disk."""
+
+import asyncio
+import copy
+import json
+import logging
+import os
+from datetime import datetime, timedelta
+from pathlib import Path
+from typing import Any, Awaitable, Callable, Dict, Hashable, Optional, Tuple, Union
+
+from .clock import SYSTEM_CLOCK, Clock
+from .errors import SchemaMismatchError
+from .providers import PricingModel
+from .tracing import spawn
+from .units import Gwei
+from .wire import tag_values, untag_values
+
+logger = logging.getLogger(__name__)
+
+# Schema version of PersistentQuoteCache's file layout. Version 1 files are
+# migrated on load (see migrate_cache_v1); other versions are rejected
+CACHE_FILE_VERSION = 2
+
+
+class QuoteCache:
+    """
+    Keep the last quote per key for `ttl` seconds.
+
+    Concurrent callers that miss the cache share one in-flight fetch, so a
+    burst of requests costs a single provider call. Failed fetches are not
+    cached. One instance can be shared between trackers since entries are
+    keyed by (network, provider).
+    """
+
+    def __init__(self, ttl: float = 12.0, clock: Optional[Clock] = None):
+        """
+        Initialize cache.
+
+        Args:
+            ttl: Seconds a quote stays fresh (one Ethereum block by default)
+            clock: Time source for expiry (default: the system clock)
+        """
+        self.ttl = ttl
+        self.clock = clock or SYSTEM_CLOCK
+        self._entries: Dict[Hashable, Tuple[float, Dict]] = {}
+        self._inflight: Dict[Hashable, asyncio.Future] = {}
+        # Calls served without starting a fetch, and calls that started one
+        self.hits = 0
+        self.misses = 0
+
+    @property
+    def hit_rate(self) -> Optional[float]:
+        """Share of get_or_fetch calls served without starting a fetch (None before any call)."""
+        total = self.hits + self.misses
+        return self.hits / total if total else None
+
+    def get(self, key: Hashable):
+        """Get an unexpired quote for `key`, or None."""
+        entry = self._entries.get(key)
+        if entry is None or entry[0] <= self.clock.monotonic():
+            return None
+        return copy.deepcopy(entry[1])
+
+    def invalidate(self, key: Hashable) -> None:
+        """Drop the cached quote for `key`."""
+        self._entries.pop(key, None)
+
+    async def get_or_fetch(
+        self, key: Hashable, fetch: Callable[[], Awaitable[Dict]], force: bool = False
+    ) -> Dict:
+        """
+        Return the cached quote for `key`, fetching it if missing or expired.
+
+        Args:
+            key: Cache key, e.g. (network, provider name)
+            fetch: Coroutine factory producing a fresh quote
+            force: Skip the cached value (an already running fetch is still joined)
+
+        Returns:
+            Copy of the quote, safe for the caller to modify
+        """
+        if not force:
+            cached = self.get(key)
+            if cached is not None:
+                self.hits += 1
+                return cached
+
+        task = self._inflight.get(key)
+        if task is None:
+            self.misses += 1
+            task = spawn(fetch(), f"fetch {key!r}")
+            self._inflight[key] = task
+            task.add_done_callback(lambda t: self._store(key, t))
+        else:
+            self.hits += 1
+
+        # Shielded so one cancelled caller doesn't abort the fetch for the others
+        quote = await asyncio.shield(task)
+        return copy.deepcopy(quote)
+
+    async def cancel_fetches(self) -> None:
+        """Cancel the fetches in flight, e.g. on shutdown, and wait for them to finish."""
+        tasks = list(self._inflight.values())
+        for task in tasks:
+            task.cancel()
+        if tasks:
+            await asyncio.wait(tasks)
+
+    def _store(self, key: Hashable, task: asyncio.Future) -> None:
+        """Record a finished fetch (this also retrieves its exception)."""
+        self._inflight.pop(key, None)
+        if not task.cancelled() and task.exception() is None:
+            self._entries[key] = (self.clock.monotonic() + self.ttl, task.result())
+
+
+class PersistentQuoteCache(QuoteCache):
+    """
+    QuoteCache that also keeps its quotes in a JSON file, so they survive restarts.
+
+    The file is rewritten after every fetch and read back on construction.
+    Loaded quotes stay fresh until the expiry they were stored with, so a
+    service restarted within the TTL answers from the file instead of
+    calling providers; quotes that expired in the meantime are dropped.
+    A missing file starts empty, and an unreadable or corrupt one is
+    logged and discarded.
+
+    The file records its schema_version. Files from older releases are
+    migrated when read; one written by a newer release raises
+    SchemaMismatchError rather than being misread or overwritten.
+    """
+
+    def __init__(self, path: Union[str, Path], ttl: float = 12.0, clock: Optional[Clock] = None):
+        """
+        Initialize cache.
+
+        Args:
+            path: JSON file to keep the quotes in (created on the first fetch)
+            ttl: Seconds a quote stays fresh (one Ethereum block by default)
+            clock: Time source for expiry (default: the system clock)
+
+        Raises:
+            SchemaMismatchError: If the file has a schema version this release can't read
+        """
+        super().__init__(ttl, clock)
+        self.path = Path(path).expanduser()
+        # Wall-clock expiry of each entry, which is what the file stores
+        self._expires_at: Dict[Hashable, datetime] = {}
+        self._load()
+
+    def invalidate(self, key: Hashable) -> None:
+        """Drop the cached quote for `key`, from the file as well."""
+        super().invalidate(key)
+        if self._expires_at.pop(key, None) is not None:
+            self._save()
+
+    def _store(self, key: Hashable, task: asyncio.Future) -> None:
+        super()._store(key, task)
+        if key in self._entries:
+            self._expires_at[key] = self.clock.now() + timedelta(seconds=self.ttl)
+            self._save()
+
+    def _load(self) -> None:
+        """Read unexpired entries from the file."""
+        try:
+            data = json.loads(self.path.read_text())
+        except FileNotFoundError:
+            return
+        except (OSError, ValueError) as e:
+            logger.warning("Discarding unreadable quote cache %s: %s", self.path, e)
+            return
+        try:
+            found = data.get("schema_version", data.get("version"))
+            if found is None:
+                raise ValueError("no schema version")
+            if found == 1:
+                data = migrate_cache_v1(data)
+            elif found != CACHE_FILE_VERSION:
+                raise SchemaMismatchError(found, CACHE_FILE_VERSION, "quote cache")
+            entries = [
+                (_decode_key(entry["key"]), datetime.fromisoformat(entry["expires_at"]), _restore_quote(entry["quote"]))
+                for entry in data["entries"]
+            ]
+        except (AttributeError, KeyError, TypeError, ValueError) as e:
+            logger.warning("Discarding corrupt quote cache %s: %s", self.path, e)
+            return
+
+        now, monotonic = self.clock.now(), self.clock.monotonic()
+        for key, expires_at, quote in entries:
+            remaining = (expires_at - now).total_seconds()
+            if remaining > 0:
+                self._entries[key] = (monotonic + remaining, quote)
+                self._expires_at[key] = expires_at
+
+    def _save(self) -> None:
+        """Write the cached entries to the file, replacing it atomically."""
+        now = self.clock.now()
+        entries = [
+            {"key": _encode_key(key), "expires_at": expires_at.isoformat(), "quote": tag_values(self._entries[key][1])}
+            for key, expires_at in self._expires_at.items()
+            if expires_at > now and key in self._entries
+        ]
+        tmp = self.path.with_name(self.path.name + ".tmp")
+        try:
+            # TypeError and ValueError: a key or quote value JSON can't hold
+            payload = json.dumps({"schema_version": CACHE_FILE_VERSION, "entries": entries})
+            self.path.parent.mkdir(parents=True, exist_ok=True)
+            tmp.write_text(payload)
+            os.replace(tmp, self.path)
+        except (OSError, TypeError, ValueError) as e:
+            # The in-memory cache still works; only the restart benefit is lost
+            logger.warning("Could not write quote cache %s: %s", self.path, e)
+
+
+def _encode_key(key: Hashable) -> Any:
+    """Get the JSON form of a cache key, tagging tuples so they read back as tuples."""
+    if isinstance(key, tuple):
+        return {"tuple": [_encode_key(part) for part in key]}
+    return key
+
+
+def _decode_key(data: Any) -> Hashable:
+    """Restore a key written by _encode_key; a plain list is a tuple key from an earlier release."""
+    if isinstance(data, dict):
+        return tuple(_decode_key(part) for part in data["tuple"])
+    if isinstance(data, list):
+        return tuple(data)
+    return data
+
+
+def migrate_cache_v1(data: Dict) -> Dict:
+    """
+    Convert a version 1 quote cache file to the current layout.
+
+    Version 1 wrote prices as plain numbers and fetched_at as an ISO string,
+    typed again by field name when read, so fields it didn't know about came
+    back untyped. Version 2 tags every Wei, Gwei and datetime value (see
+    wire.tag_values) and names its version "schema_version".
+    """
+    return {
+        "schema_version": CACHE_FILE_VERSION,
+        "entries": [dict(entry, quote=tag_values(_decode_v1_quote(entry["quote"]))) for entry in data["entries"]],
+    }
+
+
+def _decode_v1_quote(data: Dict) -> Dict:
+    """Restore the types of a quote from a version 1 file."""
+    if not isinstance(data, dict):
+        raise ValueError("quote is not an object")
+    quote = dict(data)
+    quote["fetched_at"] = datetime.fromisoformat(data["fetched_at"])
+    quote["gas_price"] = Gwei(data["gas_price"])
+    if data.get("base_fee") is not None:
+        quote["base_fee"] = Gwei(data["base_fee"])
+    for field in ("tiers", "priority_fees"):
+        if isinstance(data.get(field), dict):
+            quote[field] = {tier: Gwei(price) for tier, price in data[field].items()}
+    return quote
+
+
+def _restore_quote(data: Dict) -> Dict:
+    """Restore a quote read back from a current-version file."""
+    if not isinstance(data, dict):
+        raise ValueError("quote is not an object")
+    quote = untag_values(data)
+    if not isinstance(quote.get("fetched_at"), datetime):
+        raise ValueError("quote has no fetched_at time")
+    quote["pricing_model"] = PricingModel(quote["pricing_model"])
+    return quote
@@ -0,0 +1,92 @@
+"""Circuit breaker that takes a failing provider out of rotation for a while."""
+
+import enum
+from typing import Dict, Optional
+
+import aiohttp
+
+from .clock import SYSTEM_CLOCK, Clock
+from .errors import CircuitOpenError
+from .providers import GasProvider
+
+
+class CircuitState(enum.Enum):
+    CLOSED = "closed"
+    OPEN = "open"
+    HALF_OPEN = "half_open"
+
+
+class CircuitBreakerProvider(GasProvider):
+    """
+    Wrap a provider so repeated failures stop further requests for a cooldown.
+
+    After `failure_threshold` consecutive failures the circuit opens and
+    fetch raises CircuitOpenError without touching the provider. Once
+    `cooldown` seconds pass the circuit is half-open: the next fetch is sent
+    as a single probe (concurrent fetches still fail fast). A successful probe
+    closes the circuit, a failed one opens it for another cooldown.
+
+    Inside a RaceProvider or AggregateProvider the immediate error simply
+    counts as that source failing, so dead endpoints stop slowing them down.
+    """
+
+    def __init__(
+        self, provider: GasProvider, failure_threshold: int = 5, cooldown: float = 30.0, clock: Optional[Clock] = None
+    ):
+        """
+        Initialize circuit breaker.
+
+        Args:
+            provider: Provider to protect
+            failure_threshold: Consecutive failures that open the circuit
+            cooldown: Seconds the circuit stays open before a probe is allowed
+            clock: Time source for the cooldown (default: the system clock)
+        """
+        if failure_threshold < 1:
+            raise ValueError("failure_threshold must be at least 1")
+        if cooldown < 0:
+            raise ValueError("cooldown must not be negative")
+
+        self.provider = provider
+        self.failure_threshold = failure_threshold
+        self.cooldown = cooldown
+        self.clock = clock or SYSTEM_CLOCK
+        self.name = provider.name
+        self.failures = 0
+        self.opened_at: Optional[float] = None
+        self._probing = False
+
+    @property
+    def state(self) -> CircuitState:
+        """Current state, moving from open to half-open once the cooldown has passed."""
+        if self.opened_at is None:
+            return CircuitState.CLOSED
+        if self.clock.monotonic() - self.opened_at >= self.cooldown:
+            return CircuitState.HALF_OPEN
+        return CircuitState.OPEN
+
+    def reset(self) -> None:
+        """Close the circuit and forget past failures."""
+        self.failures = 0
+        self.opened_at = None
+
+    async def fetch(self, session: aiohttp.ClientSession) -> Dict:
+        """Fetch from the wrapped provider unless the circuit is open."""
+        state = self.state
+        if state is CircuitState.OPEN or (state is CircuitState.HALF_OPEN and self._probing):
+            retry_in = max(0.0, self.opened_at + self.cooldown - self.clock.monotonic())
+            raise CircuitOpenError(self.name, retry_in)
+
+        self._probing = state is CircuitState.HALF_OPEN
+        try:
+            quote = await self.provider.fetch(session)
+        except Exception:
+            self.failures += 1
+            if self._probing or self.failures >= self.failure_threshold:
+                self.opened_at = self.clock.monotonic()
+            raise
+        finally:
+            self._probing = False
+
+        self.reset()
+        return quote
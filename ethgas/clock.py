@@ -0,0 +1,42 @@
+"""
+Time source for cache expiry, rate limiting, circuit breakers, retries, quote timestamps and staleness checks.
+
+Components take an optional `clock` and use the real one by default. Tests
+pass testing.MockClock instead and move time forward by hand, so expiry can
+be checked at the exact boundary without sleeping.
+"""
+
+import asyncio
+import time
+from abc import ABC, abstractmethod
+from datetime import datetime, timezone
+
+
+class Clock(ABC):
+    """Source of the current time."""
+
+    @abstractmethod
+    def monotonic(self) -> float:
+        """Get seconds from an arbitrary starting point for measuring intervals."""
+
+    @abstractmethod
+    def now(self) -> datetime:
+        """Get the current time as a timezone-aware UTC datetime."""
+
+    async def sleep(self, seconds: float) -> None:
+        """Wait until `seconds` have passed on this clock."""
+        await asyncio.sleep(seconds)
+
+
+class SystemClock(Clock):
+    """The real time from the time and datetime modules."""
+
+    def monotonic(self) -> float:
+        return time.monotonic()
+
+    def now(self) -> datetime:
+        return datetime.now(timezone.utc)
+
+
+# Shared default for every component not given a clock
+SYSTEM_CLOCK = SystemClock()
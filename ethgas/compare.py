@@ -1,12 +1,67 @@
 """Network comparison functionality."""
 import asyncio
+import enum
 import json
 import aiohttp
+from dataclasses import dataclass
 from typing import Dict, List, Optional
+from .errors import ConfigError
+from .providers import make_session
 from .tracker import GasTracker
 from .networks import NETWORKS, TX_TYPES
 
 
+class CompareSortKey(str, enum.Enum):
+    """What compare_chains orders chains by."""
+
+    # Transaction cost in USD, or ETH for chains without a token price
+    COST = "cost"
+    # Display name of the chain
+    NAME = "name"
+    # Current base fee; legacy chains without one come last
+    BASE_FEE = "base_fee"
+
+
+@dataclass(frozen=True)
+class CompareOptions:
+    """
+    How compare_chains prices, filters and orders chains.
+
+    `only_chains_under_usd` drops chains whose cost is above that many USD,
+    and chains without a USD price, since they can't be shown to fit.
+    """
+
+    sort_by: CompareSortKey = CompareSortKey.COST
+    ascending: bool = True
+    tier: str = "standard"
+    only_chains_under_usd: Optional[float] = None
+
+    def __post_init__(self):
+        if not isinstance(self.sort_by, CompareSortKey):
+            try:
+                object.__setattr__(self, "sort_by", CompareSortKey(self.sort_by))
+            except ValueError:
+                keys = ", ".join(key.value for key in CompareSortKey)
+                raise ConfigError(f"sort_by must be one of {keys}, got {self.sort_by!r}") from None
+        if self.only_chains_under_usd is not None and not self.only_chains_under_usd >= 0:
+            raise ConfigError("only_chains_under_usd must not be negative")
+
+    def order(self, entries: List[Dict]) -> List[Dict]:
+        """Sort priced chains; those missing the sort field come last in either direction."""
+        if self.sort_by is CompareSortKey.NAME:
+            return sorted(entries, key=lambda e: e["network"].lower(), reverse=not self.ascending)
+        if self.sort_by is CompareSortKey.BASE_FEE:
+            field, fallback = "base_fee_gwei", None
+        else:
+            field, fallback = "cost_usd", "cost_eth"
+        known = [e for e in entries if e[field] is not None]
+        missing = [e for e in entries if e[field] is None]
+        known.sort(key=lambda e: e[field], reverse=not self.ascending)
+        if fallback:
+            missing.sort(key=lambda e: e[fallback], reverse=not self.ascending)
+        return known + missing
+
+
 class NetworkComparator:
     """Compare gas prices across multiple networks."""
 
@@ -16,7 +71,7 @@ class NetworkComparator:
 
     async def get_all_gas_data(self) -> Dict[str, dict]:
         """Fetch gas data from all networks in parallel."""
-        async with aiohttp.ClientSession() as session:
+        async with make_session() as session:
             tasks = []
             network_names = []
 
@@ -158,6 +213,81 @@ class NetworkComparator:
         return cheapest
 
 
+async def compare_chains(
+    networks: List[str],
+    tx_type: str = "simple",
+    tier: Optional[str] = None,
+    session: Optional[aiohttp.ClientSession] = None,
+    options: Optional[CompareOptions] = None,
+) -> List[Dict]:
+    """
+    Price one transaction type on several chains concurrently.
+
+    Chains that fail are kept in the result with an "error" note instead of
+    failing the whole comparison.
+
+    Args:
+        networks: Network IDs from NETWORKS (e.g. ['ethereum', 'arbitrum', 'base'])
+        tx_type: Key in TX_TYPES
+        tier: Tier to price at ('slow', 'standard' or 'fast'; default: standard)
+        session: HTTP session to reuse (a new one is opened if omitted)
+        options: Sort order, tier and budget filter (default: cheapest first),
+            instead of `tier`
+
+    Returns:
+        Per-chain costs in the requested order, each with its 1-based
+        "rank" and "base_fee_gwei", followed by the chains that failed
+        (rank None)
+
+    Raises:
+        ConfigError: If both `tier` and `options` are given
+    """
+    if tx_type not in TX_TYPES:
+        raise ValueError(f"Unknown transaction type: {tx_type}")
+    if options is None:
+        options = CompareOptions(tier=tier or "standard")
+    elif tier is not None:
+        raise ConfigError("compare_chains takes the tier from options; pass one or the other")
+    tier = options.tier
+
+    async def price_chain(network_id: str, session: aiohttp.ClientSession) -> Dict:
+        entry = {"network_id": network_id, "network": NETWORKS.get(network_id, {}).get("name", network_id)}
+        if network_id not in NETWORKS:
+            entry["error"] = "Unknown network"
+            return entry
+        try:
+            tracker = GasTracker.for_network(network_id)
+            quote, token_price = await asyncio.gather(
+                tracker.current(session), tracker.get_token_price_usd(session)
+            )
+            entry.update(tracker.estimate_tx_cost(quote, tx_type, tier, eth_price_usd=token_price))
+            entry["base_fee_gwei"] = quote.get("base_fee")
+        except Exception as e:
+            entry["error"] = str(e)
+        return entry
+
+    async def run(session: aiohttp.ClientSession) -> List[Dict]:
+        return await asyncio.gather(*(price_chain(n, session) for n in networks))
+
+    if session is None:
+        async with make_session() as own_session:
+            results = await run(own_session)
+    else:
+        results = await run(session)
+
+    priced = [r for r in results if "error" not in r]
+    failed = [r for r in results if "error" in r]
+    budget = options.only_chains_under_usd
+    if budget is not None:
+        priced = [r for r in priced if r["cost_usd"] is not None and r["cost_usd"] <= budget]
+    priced = options.order(priced)
+    for rank, entry in enumerate(priced, 1):
+        entry["rank"] = rank
+    for entry in failed:
+        entry["rank"] = None
+    return priced + failed
+
+
 async def compare_networks(networks: Optional[List[str]] = None,
                           tx_type: str = "simple",
                           output_format: str = "table") -> str:
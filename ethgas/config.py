@@ -0,0 +1,210 @@
+"""Tracker configuration loaded from a TOML file."""
+
+import os
+from dataclasses import dataclass, field, fields
+from pathlib import Path
+from typing import Dict, List, Mapping, Optional
+
+try:
+    import tomllib
+except ImportError:  # Python < 3.11
+    import tomli as tomllib
+
+from .errors import ConfigError
+from .networks import NETWORKS
+from .providers import (
+    DEFAULT_REQUEST_TIMEOUT,
+    AggregateProvider,
+    AlchemyProvider,
+    BlocknativeProvider,
+    EtherscanProvider,
+    GasProvider,
+    InfuraProvider,
+    JsonRpcProvider,
+    RaceProvider,
+)
+from .retry import RetryPolicy
+
+DEFAULT_CONFIG_PATH = Path.home() / ".config" / "eth-gas-tracker" / "config.toml"
+
+# Environment variables that override secrets from the file
+ENV_OVERRIDES = {
+    "ETH_GAS_INFURA_KEY": ("infura", "project_id"),
+    "ETH_GAS_ALCHEMY_KEY": ("alchemy", "api_key"),
+    "ETH_GAS_ETHERSCAN_KEY": ("etherscan", "api_key"),
+    "ETH_GAS_BLOCKNATIVE_KEY": ("blocknative", "api_key"),
+}
+
+PROVIDER_STRATEGIES = ("single", "race", "aggregate")
+
+
+@dataclass
+class ChainConfig:
+    network: str = "ethereum"
+    rpc_url: Optional[str] = None
+    rpc_headers: Dict[str, str] = field(default_factory=dict)
+
+
+@dataclass
+class ProvidersConfig:
+    strategy: str = "single"
+    order: List[str] = field(default_factory=list)
+    infura: Dict[str, str] = field(default_factory=dict)
+    alchemy: Dict[str, str] = field(default_factory=dict)
+    etherscan: Dict[str, str] = field(default_factory=dict)
+    blocknative: Dict[str, object] = field(default_factory=dict)
+    rpc: Dict[str, object] = field(default_factory=dict)
+
+
+@dataclass
+class RetryConfig:
+    max_retries: int = 3
+    base_delay: float = 0.5
+    max_delay: float = 10.0
+    jitter: float = 0.1
+
+
+@dataclass
+class CacheConfig:
+    ttl: float = 12.0
+    # File the quotes are kept in across restarts (see cache.PersistentQuoteCache)
+    path: Optional[str] = None
+
+
+@dataclass
+class Config:
+    """
+    Parsed configuration file.
+
+    Sections that are missing from the file are None (retry, cache) or use
+    their defaults (chain, providers).
+    """
+
+    chain: ChainConfig = field(default_factory=ChainConfig)
+    providers: ProvidersConfig = field(default_factory=ProvidersConfig)
+    retry: Optional[RetryConfig] = None
+    cache: Optional[CacheConfig] = None
+
+    @classmethod
+    def load(cls, path, env: Optional[Mapping[str, str]] = None) -> "Config":
+        """
+        Read and validate a TOML config file.
+
+        Raises:
+            ConfigError: If the file is unreadable, not valid TOML or has bad fields
+        """
+        try:
+            with open(path, "rb") as f:
+                data = tomllib.load(f)
+        except OSError as e:
+            raise ConfigError(f"Cannot read config file {path}: {e.strerror}") from e
+        except tomllib.TOMLDecodeError as e:
+            raise ConfigError(f"Invalid TOML in {path}: {e}") from e
+        return cls.from_dict(data, env)
+
+    @classmethod
+    def from_dict(cls, data: Dict, env: Optional[Mapping[str, str]] = None) -> "Config":
+        """Build a config from parsed TOML, applying environment overrides for secrets."""
+        env = os.environ if env is None else env
+        _reject_unknown(data, {"chain", "providers", "retry", "cache"}, "")
+
+        config = cls(
+            chain=_section(ChainConfig, data.get("chain", {}), "chain"),
+            providers=_section(ProvidersConfig, data.get("providers", {}), "providers"),
+            retry=_section(RetryConfig, data["retry"], "retry") if "retry" in data else None,
+            cache=_section(CacheConfig, data["cache"], "cache") if "cache" in data else None,
+        )
+
+        for var, (provider, key) in ENV_OVERRIDES.items():
+            if env.get(var):
+                getattr(config.providers, provider)[key] = env[var]
+
+        config.validate()
+        return config
+
+    def validate(self) -> None:
+        """Check values that TOML types alone can't express."""
+        if self.chain.network not in NETWORKS:
+            raise ConfigError(f"chain.network: unknown network '{self.chain.network}'")
+        if self.providers.strategy not in PROVIDER_STRATEGIES:
+            raise ConfigError(
+                f"providers.strategy: must be one of {', '.join(PROVIDER_STRATEGIES)}"
+            )
+        for name in self.providers.order:
+            if name not in ("infura", "alchemy", "etherscan", "blocknative", "rpc"):
+                raise ConfigError(f"providers.order: unknown provider '{name}'")
+        if self.providers.strategy == "single" and len(self.providers.order) > 1:
+            raise ConfigError("providers.order: 'single' strategy takes one provider")
+        if self.retry and self.retry.max_retries < 0:
+            raise ConfigError("retry.max_retries: must not be negative")
+        if self.cache and self.cache.ttl <= 0:
+            raise ConfigError("cache.ttl: must be positive")
+
+    def build_provider(self) -> Optional[GasProvider]:
+        """Create the configured provider, or None to use the chain's RPC endpoint."""
+        built = [self._build_one(name) for name in self.providers.order]
+        if not built:
+            return None
+        if self.providers.strategy == "race":
+            return RaceProvider(built)
+        if self.providers.strategy == "aggregate":
+            return AggregateProvider(built)
+        return built[0]
+
+    def _build_one(self, name: str) -> GasProvider:
+        settings = getattr(self.providers, name)
+        timeout = settings.get("timeout", DEFAULT_REQUEST_TIMEOUT)
+        if isinstance(timeout, bool) or not isinstance(timeout, (int, float)) or timeout <= 0:
+            raise ConfigError(f"providers.{name}.timeout: expected a positive number, got {timeout!r}")
+        try:
+            if name == "infura":
+                return InfuraProvider(settings["project_id"], settings.get("network", "mainnet"), timeout)
+            if name == "alchemy":
+                return AlchemyProvider(settings["api_key"], settings.get("network", "eth-mainnet"), timeout)
+            if name == "etherscan":
+                return EtherscanProvider(settings.get("api_key"), timeout=timeout)
+            if name == "blocknative":
+                return BlocknativeProvider(settings.get("api_key"), settings.get("chain_id", 1), timeout=timeout)
+            return JsonRpcProvider(
+                settings["url"],
+                headers=settings.get("headers"),
+                api_key=settings.get("api_key"),
+                timeout=timeout,
+                legacy_multipliers=settings.get("legacy_multipliers"),
+            )
+        except KeyError as e:
+            raise ConfigError(f"providers.{name}.{e.args[0]}: required field is missing") from e
+
+    def retry_policy(self) -> Optional[RetryPolicy]:
+        """Create the configured retry policy, if any."""
+        if self.retry is None:
+            return None
+        return RetryPolicy(
+            self.retry.max_retries, self.retry.base_delay, self.retry.max_delay, self.retry.jitter
+        )
+
+
+def _reject_unknown(data: Dict, allowed, prefix: str) -> None:
+    """Raise ConfigError naming the first unexpected key."""
+    for key in data:
+        if key not in allowed:
+            raise ConfigError(f"{prefix}{key}: unknown field")
+
+
+def _section(cls, data, name: str):
+    """Build one dataclass section, type-checking every field against its default."""
+    if not isinstance(data, dict):
+        raise ConfigError(f"{name}: expected a table")
+    defaults = cls()
+    _reject_unknown(data, {f.name for f in fields(cls)}, f"{name}.")
+
+    values = {}
+    for key, value in data.items():
+        default = getattr(defaults, key)
+        expected = str if default is None else type(default)
+        if expected is float and isinstance(value, int) and not isinstance(value, bool):
+            value = float(value)
+        if isinstance(value, bool) or not isinstance(value, expected):
+            raise ConfigError(f"{name}.{key}: expected {expected.__name__}, got {value!r}")
+        values[key] = value
+    return cls(**values)
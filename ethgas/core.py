@@ -0,0 +1,875 @@
+"""
+Pure fee estimation math, without networking.
+
+Everything here works on plain eth_feeHistory results and numbers, and this
+module (like units, l2 and errors) imports nothing outside the standard
+library, so the same code can run where aiohttp is unavailable, e.g. an
+off-chain worker that receives fee history from elsewhere. GasTracker and
+the providers are built on these functions.
+
+Re-exported here for convenience: Wei, Gwei, parse_hex_quantity, calldata_gas
+and op_l1_fee.
+"""
+
+import bisect
+import enum
+import math
+import statistics
+from dataclasses import dataclass
+from fractions import Fraction
+from typing import Dict, List, Optional, Sequence, Tuple, Union
+
+from .errors import ConfigError, DeserializeError, EmptyFeeHistoryError, FeeCeilingExceededError
+from .l2 import calldata_gas, op_l1_fee
+from .models import BlobFee, TxFeeFields
+from .units import Gwei, Wei, parse_hex_quantity
+
+__all__ = [
+    "FEE_TIERS",
+    "TARGET_BLOCK_PERCENTILES",
+    "MAX_TARGET_BLOCKS",
+    "percentile_for_target_blocks",
+    "check_fee_history",
+    "RecencyWeight",
+    "RECENCY_HALF_LIFE_BLOCKS",
+    "recency_weights",
+    "PercentileMethod",
+    "percentile",
+    "STATIC_PRIORITY_FEE",
+    "fee_history_from_block",
+    "fee_history_tiers",
+    "fee_history_recommendation",
+    "fees_at_base_fee",
+    "RISING_BASE_FEE_PCT",
+    "base_fee_rising",
+    "hybrid_mempool_weight",
+    "blend_priority_fees",
+    "MIN_REPLACEMENT_BUMP_PCT",
+    "DEFAULT_REPLACEMENT_BUMP_PCT",
+    "bump_fee",
+    "tx_fee_fields",
+    "FeeRounding",
+    "apply_priority_floor",
+    "cap_priority_fee",
+    "BASE_FEE_MULTIPLIER",
+    "apply_base_fee_multiplier",
+    "Congestion",
+    "congestion_from_history",
+    "HISTOGRAM_PERCENTILES",
+    "priority_fee_histogram",
+    "decayed_mean",
+    "HEADROOM_BLOCKS",
+    "adaptive_headroom",
+    "GAS_PER_BLOB",
+    "MAX_BLOBS_PER_TX",
+    "blob_fee_from_history",
+    "calldata_cost",
+    "MAX_INITCODE_SIZE",
+    "MAX_CODE_SIZE",
+    "deployment_gas",
+    "Wei",
+    "Gwei",
+    "parse_hex_quantity",
+    "calldata_gas",
+    "op_l1_fee",
+]
+
+# Intrinsic gas of every transaction, and the extra a contract creation pays
+TX_BASE_GAS = 21000
+CREATE_GAS = 32000
+# EIP-3860: gas per 32-byte word of init code, and the largest init code allowed
+INITCODE_WORD_GAS = 2
+MAX_INITCODE_SIZE = 49152
+# Gas per byte of deployed runtime code, and its EIP-170 size limit
+CODE_DEPOSIT_GAS_PER_BYTE = 200
+MAX_CODE_SIZE = 24576
+
+# Blob gas of one EIP-4844 blob, and the most blobs one transaction may carry
+GAS_PER_BLOB = 2 ** 17
+MAX_BLOBS_PER_TX = 6
+
+# Reward percentiles requested from eth_feeHistory for each priority tier
+FEE_TIERS = {"slow": 10, "standard": 50, "fast": 90}
+
+# Reward percentile needed to be included within N blocks; values between
+# these anchors are interpolated linearly
+TARGET_BLOCK_PERCENTILES = {1: 90, 2: 75, 3: 60, 5: 40, 10: 25, 20: 10}
+MAX_TARGET_BLOCKS = 20
+
+# Mean gas used ratio at or above which blocks count as congested, and
+# below which they count as underutilized (the base fee targets 0.5)
+CONGESTED_RATIO = 0.9
+UNDERUTILIZED_RATIO = 0.4
+
+# Blocks back at which a block's rewards count half under RecencyWeight.EXPONENTIAL
+RECENCY_HALF_LIFE_BLOCKS = 5
+
+# Reward percentiles sampled from each block for priority_fee_histogram
+HISTOGRAM_PERCENTILES = list(range(0, 101, 5))
+
+# Nodes reject a replacement transaction unless both its max fee and its
+# priority fee are at least this much (percent) above the original's
+MIN_REPLACEMENT_BUMP_PCT = 10.0
+# Bump used by default, with headroom over the minimum for rounding
+DEFAULT_REPLACEMENT_BUMP_PCT = 12.5
+
+# Blocks of base fee movement adaptive_headroom prepares for, and the most
+# the base fee can rise in one block (EIP-1559: 1/8 per full block)
+HEADROOM_BLOCKS = 6
+MAX_BASE_FEE_STEP = 1.125
+
+# Multiple of the base fee in recommended max fees: doubling survives
+# several consecutive full blocks
+BASE_FEE_MULTIPLIER = 2
+
+# Multiples of a suggested priority fee standing in for reward percentiles
+# when a node has no eth_feeHistory, interpolated between these points
+DEGRADED_PRIORITY_SCALE = {10: 0.8, 50: 1.0, 90: 1.5}
+# Priority fee assumed when the node can't suggest one either (1.5 gwei)
+STATIC_PRIORITY_FEE = Wei(1_500_000_000)
+
+# Base fee growth (percent) across an eth_feeHistory window that counts as rising
+RISING_BASE_FEE_PCT = 5.0
+# Share of the mempool estimate in a hybrid estimate while fees are rising,
+# when the mempool sees the pressure history doesn't yet, and otherwise
+RISING_MEMPOOL_WEIGHT = 0.7
+STABLE_MEMPOOL_WEIGHT = 0.3
+
+
+def percentile_for_target_blocks(blocks: int) -> float:
+    """
+    Map an inclusion window to the eth_feeHistory reward percentile to pay.
+
+    N=1 pays p90, N=2 p75, N=3 p60, N=5 p40, N=10 p25 and N=20 p10.
+    """
+    if not 1 <= blocks <= MAX_TARGET_BLOCKS:
+        raise ConfigError(f"Target blocks must be between 1 and {MAX_TARGET_BLOCKS}")
+    anchors = sorted(TARGET_BLOCK_PERCENTILES.items())
+    for (low, low_pct), (high, high_pct) in zip(anchors, anchors[1:]):
+        if low <= blocks <= high:
+            return low_pct + (high_pct - low_pct) * (blocks - low) / (high - low)
+    return float(TARGET_BLOCK_PERCENTILES[blocks])
+
+
+@dataclass(frozen=True)
+class FeeRounding:
+    """
+    Snap recommended fees to a multiple of `to_nearest_gwei` gwei (e.g. 0.1 or 1).
+
+    Fees are rounded up by default, so a rounded fee is never below the
+    computed one; round_up=False rounds to the nearest multiple instead.
+    Without `to_nearest_gwei` fees are left as they are.
+    """
+
+    to_nearest_gwei: Optional[float] = None
+    round_up: bool = True
+
+    def __post_init__(self):
+        if self.to_nearest_gwei is not None and not self.to_nearest_gwei > 0:
+            raise ConfigError("to_nearest_gwei must be positive")
+
+    def apply(self, wei: int) -> Wei:
+        """Round one fee in wei."""
+        if self.to_nearest_gwei is None:
+            return Wei(wei)
+        step = Gwei(self.to_nearest_gwei).to_wei()
+        if self.round_up:
+            return Wei(-(-int(wei) // step) * step)
+        return Wei((int(wei) + step // 2) // step * step)
+
+    def apply_fees(self, fees: Dict) -> Dict:
+        """Round the max_priority_fee_per_gas and max_fee_per_gas of a recommendation or tier."""
+        priority = self.apply(fees["max_priority_fee_per_gas"])
+        # Rounding to nearest can't be allowed to push the tip above the max fee
+        max_fee = max(self.apply(fees["max_fee_per_gas"]), priority)
+        return dict(fees, max_priority_fee_per_gas=priority, max_fee_per_gas=max_fee)
+
+
+def apply_priority_floor(fees: Dict, floor: Optional[int]) -> Dict:
+    """
+    Raise a recommendation's or tier's priority fee to at least `floor` wei.
+
+    max_fee_per_gas rises by the same amount, keeping its base fee headroom.
+    Raised fees are marked with floor_applied=True; fees already at or
+    above the floor, or any fees when `floor` is None, are returned as they are.
+    """
+    priority = fees["max_priority_fee_per_gas"]
+    if floor is None or priority >= floor:
+        return fees
+    raise_by = floor - priority
+    return dict(
+        fees,
+        max_priority_fee_per_gas=Wei(floor),
+        max_fee_per_gas=Wei(fees["max_fee_per_gas"] + raise_by),
+        floor_applied=True,
+    )
+
+
+def apply_base_fee_multiplier(fees: Dict, base_fee: int, multiplier: float) -> Dict:
+    """
+    Re-price a recommendation's or tier's max_fee_per_gas as `multiplier` times `base_fee` plus its priority fee.
+
+    The result is rounded up to a whole wei. A multiplier of 1 leaves no
+    room for the base fee to rise; each 1.125 survives another full block.
+
+    Raises:
+        ConfigError: If the multiplier is below 1, since the max fee must cover the base fee
+    """
+    if isinstance(multiplier, bool) or not isinstance(multiplier, (int, float)) or not 1 <= multiplier < math.inf:
+        raise ConfigError(f"Base fee multiplier must be a number of at least 1, got {multiplier!r}")
+    headroom = math.ceil(int(base_fee) * Fraction(multiplier))
+    return dict(fees, max_fee_per_gas=Wei(headroom + fees["max_priority_fee_per_gas"]))
+
+
+def cap_priority_fee(fees: Dict, base_fee: int, max_ratio: Optional[float]) -> Dict:
+    """
+    Lower a recommendation's or tier's priority fee to at most `max_ratio` times `base_fee`.
+
+    max_fee_per_gas falls by the same amount. Capped fees are marked with
+    priority_capped=True; fees within the ratio, or any fees when
+    `max_ratio` is None, are returned as they are.
+    """
+    if max_ratio is None:
+        return fees
+    cap = Wei(math.floor(int(base_fee) * Fraction(max_ratio)))
+    priority = fees["max_priority_fee_per_gas"]
+    if priority <= cap:
+        return fees
+    return dict(
+        fees,
+        max_priority_fee_per_gas=cap,
+        max_fee_per_gas=Wei(fees["max_fee_per_gas"] - (priority - cap)),
+        priority_capped=True,
+    )
+
+
+class Congestion(str, enum.Enum):
+    """How full recent blocks were, which drives where the base fee goes next."""
+
+    # Blocks nearly full: the base fee climbs up to 12.5% per block
+    CONGESTED = "congested"
+    # Around the 50% target: the base fee holds roughly steady
+    NORMAL = "normal"
+    # Well below target: the base fee falls
+    UNDERUTILIZED = "underutilized"
+
+
+class RecencyWeight(str, enum.Enum):
+    """How much more the newest blocks count when averaging eth_feeHistory rewards."""
+
+    # Every block of the window counts the same
+    NONE = "none"
+    # The newest of n blocks counts n times as much as the oldest
+    LINEAR = "linear"
+    # A block's weight halves every RECENCY_HALF_LIFE_BLOCKS blocks back
+    EXPONENTIAL = "exponential"
+
+
+class PercentileMethod(str, enum.Enum):
+    """How percentile() picks a value between the ranks of a sorted sample."""
+
+    # Linear interpolation between the two nearest ranks, the smoothest on small samples
+    LINEAR = "linear"
+    # The smallest value with at least that share of the sample at or below it
+    NEAREST_RANK = "nearest-rank"
+    # The lower and the higher of the two nearest ranks
+    LOWER = "lower"
+    HIGHER = "higher"
+
+
+def percentile_method_param(method: Union[PercentileMethod, str]) -> PercentileMethod:
+    """Validate a PercentileMethod or its value, raising ConfigError listing the choices."""
+    try:
+        return PercentileMethod(method)
+    except ValueError:
+        choices = ", ".join(m.value for m in PercentileMethod)
+        raise ConfigError(f"percentile method must be one of {choices}, got {method!r}") from None
+
+
+def percentile(values: Sequence, pct: float, method: Union[PercentileMethod, str] = PercentileMethod.LINEAR):
+    """
+    Get the `pct` percentile (0-100) of `values` by `method`.
+
+    LINEAR and NEAREST_RANK agree at the extremes but differ in between,
+    most on small samples: for [1, 2, 3, 10], p50 is 2.5 linear, 2 by
+    nearest rank (and LOWER) and 3 by HIGHER; p90 is 7.9 linear and 10 by
+    nearest rank. LINEAR may return a float between two ints.
+
+    Raises:
+        ConfigError: If `values` is empty, pct is outside 0-100 or the method is unknown
+    """
+    method = percentile_method_param(method)
+    if not values:
+        raise ConfigError("percentile of an empty sample")
+    if not 0 <= pct <= 100:
+        raise ConfigError(f"percentile must be within 0-100, got {pct!r}")
+    ordered = sorted(values)
+    if method is PercentileMethod.NEAREST_RANK:
+        rank = math.ceil(pct / 100 * len(ordered))
+        return ordered[min(max(rank, 1), len(ordered)) - 1]
+    position = (len(ordered) - 1) * pct / 100
+    low, high = math.floor(position), math.ceil(position)
+    if method is PercentileMethod.LOWER or low == high:
+        return ordered[low]
+    if method is PercentileMethod.HIGHER:
+        return ordered[high]
+    return ordered[low] + (ordered[high] - ordered[low]) * (position - low)
+
+
+def recency_weights(count: int, weighting: RecencyWeight = RecencyWeight.NONE) -> List[float]:
+    """Weights of `count` blocks ordered oldest first, the newest weighing 1."""
+    try:
+        weighting = RecencyWeight(weighting)
+    except ValueError:
+        choices = ", ".join(w.value for w in RecencyWeight)
+        raise ConfigError(f"recency_weight must be one of {choices}, got {weighting!r}") from None
+    if weighting is RecencyWeight.LINEAR:
+        return [(i + 1) / count for i in range(count)]
+    if weighting is RecencyWeight.EXPONENTIAL:
+        return [0.5 ** ((count - 1 - i) / RECENCY_HALF_LIFE_BLOCKS) for i in range(count)]
+    return [1.0] * count
+
+
+def _reward_weights(count: int, weighting: RecencyWeight) -> Optional[List[Fraction]]:
+    """Exact weights for _mean_reward over `count` blocks, or None when they are all equal."""
+    weights = recency_weights(count, weighting)
+    if all(w == 1 for w in weights):
+        return None
+    return [Fraction(w) for w in weights]
+
+
+def _mean_reward(samples: List[Wei], weights: Optional[List[Fraction]]) -> Wei:
+    """Average of per-block rewards ordered oldest first, weighted by _reward_weights."""
+    if not samples:
+        return Wei(0)
+    # Summed as plain ints: adding Wei checks and wraps every partial sum
+    if weights is None:
+        return Wei(sum(map(int, samples)) // len(samples))
+    return Wei(math.floor(sum(w * int(s) for w, s in zip(weights, samples)) / sum(weights)))
+
+
+def congestion_from_history(fee_history: Dict) -> Optional[Dict]:
+    """
+    Get the mean gasUsedRatio of an eth_feeHistory result and its Congestion level.
+
+    Returns:
+        Dictionary with gas_used_ratio and congestion, or None if the
+        history has no gasUsedRatio entries
+    """
+    ratios = check_fee_history(fee_history, require_base_fees=False).get("gasUsedRatio") or []
+    if not ratios:
+        return None
+    ratio = sum(ratios) / len(ratios)
+    if ratio >= CONGESTED_RATIO:
+        congestion = Congestion.CONGESTED
+    elif ratio < UNDERUTILIZED_RATIO:
+        congestion = Congestion.UNDERUTILIZED
+    else:
+        congestion = Congestion.NORMAL
+    return {"gas_used_ratio": ratio, "congestion": congestion}
+
+
+def decayed_mean(samples: List[Tuple[float, float]], half_life: float) -> float:
+    """
+    Mean of (age in seconds, value) samples weighted by 0.5 ** (age / half_life).
+
+    A sample `half_life` seconds old counts half as much as one taken now,
+    so a shorter half-life follows changes faster and a longer one smooths
+    more. Negative ages (clock skew) count as zero.
+    """
+    if half_life <= 0:
+        raise ConfigError("half_life must be positive")
+    if not samples:
+        raise ValueError("decayed_mean needs at least one sample")
+    weights = [0.5 ** (max(age, 0.0) / half_life) for age, _ in samples]
+    return sum(w * value for w, (_, value) in zip(weights, samples)) / sum(weights)
+
+
+def adaptive_headroom(base_fees: List[int], confidence: float, blocks: int = HEADROOM_BLOCKS) -> float:
+    """
+    Get the factor to multiply the base fee by for max_fee_per_gas, from its recent volatility.
+
+    The per-block relative changes of `base_fees` give a standard deviation
+    that grows with the square root of `blocks`; the factor covers that
+    many deviations at the normal quantile of `confidence`. It is never
+    below one full block's rise, so the next block can't price the
+    transaction out, nor above `blocks` full blocks in a row, the most
+    the base fee can move. With the defaults that ceiling is about the
+    usual 2x, which calm markets therefore undercut.
+
+    Raises:
+        ConfigError: If confidence isn't within 0.5-0.99 or blocks is below 1
+    """
+    if not 0.5 <= confidence <= 0.99:
+        raise ConfigError("confidence must be within 0.5-0.99")
+    if blocks < 1:
+        raise ConfigError("blocks must be at least 1")
+    changes = [new / old - 1 for old, new in zip(base_fees, base_fees[1:]) if old]
+    sigma = statistics.pstdev(changes) if len(changes) > 1 else 0.0
+    factor = 1 + statistics.NormalDist().inv_cdf(confidence) * sigma * math.sqrt(blocks)
+    return min(max(factor, MAX_BASE_FEE_STEP), MAX_BASE_FEE_STEP ** blocks)
+
+
+def check_fee_history(fee_history, reward_count: Optional[int] = None, require_base_fees: bool = True) -> Dict:
+    """
+    Check that an eth_feeHistory result has the shape the functions here read.
+
+    baseFeePerGas must be a non-empty list of hex quantities (or may be
+    missing, without `require_base_fees`); reward (a list of
+    per-block lists of hex quantities, each with at least `reward_count`
+    entries unless empty), gasUsedRatio and blobGasUsedRatio (lists of
+    numbers), baseFeePerBlobGas and oldestBlock may be missing or null.
+    Every function here checks its input this way, so a malformed
+    response raises DeserializeError naming the bad field instead of an
+    arbitrary exception.
+
+    Returns:
+        The result, unchanged
+
+    Raises:
+        DeserializeError: If a field is missing or has the wrong type
+        EmptyFeeHistoryError: If base fees are required and baseFeePerGas is empty
+    """
+    if not isinstance(fee_history, dict):
+        raise DeserializeError(f"eth_feeHistory result must be an object, got {type(fee_history).__name__}")
+    _check_quantities(fee_history, "baseFeePerGas", required=require_base_fees)
+    if require_base_fees and not fee_history["baseFeePerGas"]:
+        raise EmptyFeeHistoryError("eth_feeHistory returned no base fees")
+    _check_quantities(fee_history, "baseFeePerBlobGas")
+    for field in ("gasUsedRatio", "blobGasUsedRatio"):
+        ratios = _check_list(fee_history, field)
+        for idx, ratio in enumerate(ratios):
+            if isinstance(ratio, bool) or not isinstance(ratio, (int, float)) or not math.isfinite(ratio):
+                raise DeserializeError(f"eth_feeHistory {field}[{idx}] must be a number, got {ratio!r}")
+    for block, rewards in enumerate(_check_list(fee_history, "reward")):
+        if not isinstance(rewards, list):
+            raise DeserializeError(f"eth_feeHistory reward[{block}] must be a list, got {rewards!r}")
+        if rewards and reward_count is not None and len(rewards) < reward_count:
+            raise DeserializeError(
+                f"eth_feeHistory reward[{block}] has {len(rewards)} percentiles, expected {reward_count}"
+            )
+        for idx, reward in enumerate(rewards):
+            _check_quantity(reward, f"reward[{block}][{idx}]")
+    if fee_history.get("oldestBlock") is not None:
+        _check_quantity(fee_history["oldestBlock"], "oldestBlock")
+    return fee_history
+
+
+def _check_list(fee_history: Dict, field: str, required: bool = False) -> list:
+    if field not in fee_history and required:
+        raise DeserializeError(f"eth_feeHistory result has no {field}")
+    value = fee_history.get(field)
+    if value is None and not required:
+        return []
+    if not isinstance(value, list):
+        raise DeserializeError(f"eth_feeHistory {field} must be a list, got {value!r}")
+    return value
+
+
+def _check_quantities(fee_history: Dict, field: str, required: bool = False) -> None:
+    for idx, value in enumerate(_check_list(fee_history, field, required)):
+        _check_quantity(value, f"{field}[{idx}]")
+
+
+def _check_quantity(value, where: str) -> None:
+    try:
+        parse_hex_quantity(value)
+    except DeserializeError as e:
+        raise DeserializeError(f"eth_feeHistory {where}: {e}") from None
+
+
+def _base_fees(fee_history: Dict) -> List[Wei]:
+    base_fees = [Wei.from_hex(fee) for fee in fee_history["baseFeePerGas"]]
+    if not base_fees:
+        raise EmptyFeeHistoryError("eth_feeHistory returned no base fees")
+    return base_fees
+
+
+def _block_rewards(fee_history: Dict) -> List[List[Wei]]:
+    """Rewards per block; empty blocks report empty arrays and reuse the previous block's."""
+    rewards: List[List[Wei]] = []
+    previous: Optional[List[Wei]] = None
+    for block_rewards in fee_history.get("reward") or []:
+        if block_rewards:
+            previous = [Wei.from_hex(r) for r in block_rewards]
+        if previous is not None:
+            rewards.append(previous)
+    return rewards
+
+
+def _latest_block(fee_history: Dict, base_fees: List[Wei]) -> Optional[int]:
+    if fee_history.get("oldestBlock") is None:
+        return None
+    # baseFeePerGas has one more entry than blocks, for the upcoming block
+    return parse_hex_quantity(fee_history["oldestBlock"]) + len(base_fees) - 2
+
+
+def _degraded_scale(pct: float, method: PercentileMethod = PercentileMethod.LINEAR) -> float:
+    points = sorted(DEGRADED_PRIORITY_SCALE.items())
+    if pct <= points[0][0]:
+        return points[0][1]
+    for (low, low_scale), (high, high_scale) in zip(points, points[1:]):
+        if pct <= high:
+            if method is PercentileMethod.LINEAR:
+                return low_scale + (high_scale - low_scale) * (pct - low) / (high - low)
+            if method is PercentileMethod.LOWER or (method is PercentileMethod.NEAREST_RANK and pct - low < high - pct):
+                return low_scale if pct < high else high_scale
+            return high_scale
+    return points[-1][1]
+
+
+def fee_history_from_block(
+    block: Dict,
+    priority_fee: int,
+    percentiles: List[float],
+    method: Union[PercentileMethod, str] = PercentileMethod.LINEAR,
+) -> Dict:
+    """
+    Approximate an eth_feeHistory result from one block, for nodes without the method.
+
+    The block's own baseFeePerGas stands in for the upcoming block's, and
+    each reward percentile is `priority_fee` (usually the node's
+    eth_maxPriorityFeePerGas) scaled by DEGRADED_PRIORITY_SCALE, so p50 is
+    the suggestion itself. The result has "degraded": True and works with
+    every function here, but reflects a single block and the node's own
+    guess rather than what recent transactions paid.
+
+    Args:
+        block: eth_getBlockByNumber result (without transactions)
+        priority_fee: Priority fee the percentiles are scaled from, in wei
+        percentiles: Reward percentiles to fill in, as for eth_feeHistory
+        method: How percentiles between DEGRADED_PRIORITY_SCALE's anchors are
+            scaled: interpolated (linear) or snapped to an anchor
+
+    Raises:
+        DeserializeError: If the block is missing or has no baseFeePerGas
+    """
+    method = percentile_method_param(method)
+    if not isinstance(block, dict) or block.get("baseFeePerGas") is None:
+        raise DeserializeError("Latest block has no baseFeePerGas")
+    base_fee = hex(parse_hex_quantity(block["baseFeePerGas"]))
+    gas_limit = parse_hex_quantity(block.get("gasLimit") or "0x0")
+    gas_used = parse_hex_quantity(block.get("gasUsed") or "0x0")
+    number = block.get("number")
+    return {
+        "oldestBlock": hex(parse_hex_quantity(number)) if number is not None else None,
+        "baseFeePerGas": [base_fee, base_fee],
+        "gasUsedRatio": [gas_used / gas_limit if gas_limit else 0.0],
+        "reward": [[hex(int(priority_fee * _degraded_scale(p, method))) for p in percentiles]],
+        "degraded": True,
+    }
+
+
+def fee_history_tiers(
+    fee_history: Dict, fee_tiers: Optional[Dict] = None, recency_weight: RecencyWeight = RecencyWeight.NONE
+) -> Dict:
+    """
+    Build fee tiers from an eth_feeHistory result.
+
+    Args:
+        fee_history: eth_feeHistory result requested with one reward
+            percentile per tier, in tier order
+        fee_tiers: Tier names mapped to their percentile (default: FEE_TIERS)
+        recency_weight: How the blocks' rewards are weighted in each
+            tier's average (default: equally)
+
+    Returns:
+        Dictionary with the next block's base fee, the base fee history and
+        per-tier priority and max fees, all in wei, and the latest block the
+        history covers (None if it has no oldestBlock)
+    """
+    fee_tiers = fee_tiers or FEE_TIERS
+    base_fees = _base_fees(check_fee_history(fee_history, len(fee_tiers)))
+    # The last entry is the base fee of the upcoming block
+    base_fee = base_fees[-1]
+    rewards = _block_rewards(fee_history)
+    # The same for every tier, so computed once
+    weights = _reward_weights(len(rewards), recency_weight)
+
+    tiers = {}
+    for idx, tier in enumerate(fee_tiers):
+        priority = _mean_reward([block[idx] for block in rewards], weights)
+        tiers[tier] = {
+            "max_priority_fee_per_gas": priority,
+            # Doubling the base fee survives several consecutive full blocks
+            "max_fee_per_gas": base_fee * 2 + priority,
+        }
+
+    return {
+        "base_fee_per_gas": base_fee,
+        "base_fee_history": base_fees,
+        "tiers": tiers,
+        "block_number": _latest_block(fee_history, base_fees),
+    }
+
+
+def blob_fee_from_history(fee_history: Dict, blobs: int = 1) -> Optional[BlobFee]:
+    """
+    Recommend maxFeePerBlobGas from an eth_feeHistory result.
+
+    The blob base fee moves by at most 12.5% per block, like the base fee,
+    so doubling the upcoming block's one survives several full blocks.
+
+    Returns:
+        Dictionary with the upcoming blob base fee, the recommended max fee,
+        the mean blobGasUsedRatio and the most `blobs` blobs can cost, all
+        in wei, or None if the node reports no blob fields (pre-Cancun
+        chains and nodes)
+    """
+    if not 1 <= blobs <= MAX_BLOBS_PER_TX:
+        raise ConfigError(f"blobs must be between 1 and {MAX_BLOBS_PER_TX}")
+    blob_base_fees = check_fee_history(fee_history, require_base_fees=False).get("baseFeePerBlobGas")
+    if not blob_base_fees:
+        return None
+    blob_base_fees = [Wei.from_hex(fee) for fee in blob_base_fees]
+    # The last entry is the blob base fee of the upcoming block
+    base_fee = blob_base_fees[-1]
+    max_fee = base_fee * 2
+    ratios = fee_history.get("blobGasUsedRatio") or []
+    blob_gas = blobs * GAS_PER_BLOB
+    return {
+        "base_fee_per_blob_gas": base_fee,
+        "max_fee_per_blob_gas": max_fee,
+        "blob_gas_used_ratio": sum(ratios) / len(ratios) if ratios else 0.0,
+        "blobs": blobs,
+        "blob_gas": blob_gas,
+        "max_cost_wei": max_fee * blob_gas,
+        "block_number": _latest_block(fee_history, blob_base_fees),
+    }
+
+
+def calldata_cost(calldata: bytes, fee_per_gas: int) -> Wei:
+    """Cost of a transaction's calldata on L1 at `fee_per_gas` wei (4 gas per zero byte, 16 per other)."""
+    return Wei(calldata_gas(calldata) * fee_per_gas)
+
+
+def deployment_gas(initcode: bytes, runtime_size: Optional[int] = None) -> Dict[str, int]:
+    """
+    Gas of a contract creation transaction, apart from running its constructor.
+
+    The intrinsic gas is the 21000 base, 32000 for CREATE, the init code's
+    calldata gas (16 per non-zero byte, 4 per zero byte) and 2 per 32-byte
+    word of it (EIP-3860). Storing the deployed code costs 200 gas per byte;
+    without `runtime_size` the whole init code (up to MAX_CODE_SIZE) is
+    assumed to be deployed, which overestimates by the constructor's size.
+
+    Returns:
+        Dictionary with intrinsic_gas, code_deposit_gas and their total, gas
+
+    Raises:
+        ConfigError: If the init code is empty or over MAX_INITCODE_SIZE bytes,
+            or runtime_size is negative or over MAX_CODE_SIZE
+    """
+    if not initcode:
+        raise ConfigError("initcode must not be empty")
+    if len(initcode) > MAX_INITCODE_SIZE:
+        raise ConfigError(f"initcode is {len(initcode):,} bytes, over the EIP-3860 limit of {MAX_INITCODE_SIZE:,}")
+    if runtime_size is None:
+        runtime_size = min(len(initcode), MAX_CODE_SIZE)
+    elif not 0 <= runtime_size <= MAX_CODE_SIZE:
+        raise ConfigError(f"runtime_size must be between 0 and the EIP-170 limit of {MAX_CODE_SIZE:,} bytes")
+
+    words = -(-len(initcode) // 32)
+    intrinsic = TX_BASE_GAS + CREATE_GAS + calldata_gas(initcode) + INITCODE_WORD_GAS * words
+    deposit = CODE_DEPOSIT_GAS_PER_BYTE * runtime_size
+    return {"intrinsic_gas": intrinsic, "code_deposit_gas": deposit, "gas": intrinsic + deposit}
+
+
+def fee_history_recommendation(fee_history: Dict, recency_weight: RecencyWeight = RecencyWeight.NONE) -> Dict:
+    """
+    Get max fees at the single reward percentile an eth_feeHistory result was requested with.
+
+    The blocks' rewards are averaged with `recency_weight` (default: equally).
+
+    Returns:
+        Dictionary with max_priority_fee_per_gas and max_fee_per_gas (wei)
+        and the latest block the history covers
+    """
+    base_fees = _base_fees(check_fee_history(fee_history, 1))
+    rewards = _block_rewards(fee_history)
+    priority = _mean_reward([block[0] for block in rewards], _reward_weights(len(rewards), recency_weight))
+    return {
+        "max_priority_fee_per_gas": priority,
+        "max_fee_per_gas": base_fees[-1] * 2 + priority,
+        "block_number": _latest_block(fee_history, base_fees),
+    }
+
+
+def fees_at_base_fee(estimate: Dict, base_fee: int, tier: str = "standard") -> Dict:
+    """
+    Re-price one tier of a fee_history_tiers (or estimate_eip1559) result at another base fee.
+
+    The tier keeps its priority fee, and max_fee_per_gas is `base_fee`
+    doubled plus that priority fee, as for the estimate's own base fee.
+    Nothing is fetched, so one estimate can price any number of
+    "what if the base fee were X" scenarios.
+
+    Args:
+        estimate: Result holding the tier's priority fee
+        base_fee: Hypothetical base fee in wei
+        tier: Tier whose priority fee is used
+
+    Returns:
+        Dictionary with max_priority_fee_per_gas and max_fee_per_gas (wei)
+        and the estimate's block_number
+
+    Raises:
+        ConfigError: If the tier is unknown or the base fee is negative
+    """
+    if base_fee < 0:
+        raise ConfigError("base_fee must not be negative")
+    if tier not in estimate["tiers"]:
+        raise ConfigError(f"Unknown tier '{tier}' (available: {', '.join(estimate['tiers'])})")
+    priority = Wei(estimate["tiers"][tier]["max_priority_fee_per_gas"])
+    return {
+        "max_priority_fee_per_gas": priority,
+        "max_fee_per_gas": Wei(base_fee) * 2 + priority,
+        "block_number": estimate.get("block_number"),
+    }
+
+
+def base_fee_rising(fee_history: Dict, threshold_pct: float = RISING_BASE_FEE_PCT) -> bool:
+    """Whether the next block's base fee is at least `threshold_pct` above the window's first."""
+    base_fees = _base_fees(check_fee_history(fee_history))
+    return base_fees[-1] > base_fees[0] and base_fees[-1] * 100 >= base_fees[0] * (100 + threshold_pct)
+
+
+def hybrid_mempool_weight(fee_history: Dict) -> float:
+    """Mempool weight of a hybrid estimate when none is given: mempool-leaning while fees rise."""
+    return RISING_MEMPOOL_WEIGHT if base_fee_rising(fee_history) else STABLE_MEMPOOL_WEIGHT
+
+
+def blend_priority_fees(history_fee: int, mempool_fee: int, mempool_weight: float) -> Wei:
+    """
+    Weighted mean of a fee history and a mempool priority fee, rounded to a whole wei.
+
+    Raises:
+        ConfigError: If mempool_weight is outside 0-1
+    """
+    if not 0 <= mempool_weight <= 1:
+        raise ConfigError("mempool_weight must be between 0 and 1")
+    weight = Fraction(mempool_weight)
+    return Wei(round(history_fee * (1 - weight) + mempool_fee * weight))
+
+
+def tx_fee_fields(fees: Dict, gas_limit: Optional[int] = None) -> TxFeeFields:
+    """
+    Get fees as transaction fields, ready to merge into an eth_sendTransaction object.
+
+    `fees` is an estimate_eip1559 tier, a recommend() result or any dict
+    with max_fee_per_gas and max_priority_fee_per_gas, or for a legacy
+    chain one with gas_price, all in wei. Values become minimal hex
+    quantities ("0x0" for zero), as JSON-RPC requires.
+
+        {**tx, **tx_fee_fields(estimate["tiers"]["fast"], gas_limit=21000)}
+
+    Raises:
+        ConfigError: If `fees` has neither shape, a fee is negative, the max
+            fee is below the priority fee or the gas limit is below TX_BASE_GAS
+        TypeError: If a fee is Gwei or a float rather than wei
+    """
+    def quantity(value) -> str:
+        wei = Wei(value)
+        if wei < 0:
+            raise ConfigError(f"Fees must not be negative, got {int(wei)} wei")
+        return hex(wei)
+
+    if fees.get("max_fee_per_gas") is not None and fees.get("max_priority_fee_per_gas") is not None:
+        if Wei(fees["max_fee_per_gas"]) < Wei(fees["max_priority_fee_per_gas"]):
+            raise ConfigError("max_fee_per_gas must not be below max_priority_fee_per_gas")
+        fields: TxFeeFields = {
+            "maxFeePerGas": quantity(fees["max_fee_per_gas"]),
+            "maxPriorityFeePerGas": quantity(fees["max_priority_fee_per_gas"]),
+        }
+    elif fees.get("gas_price") is not None:
+        fields = {"gasPrice": quantity(fees["gas_price"])}
+    else:
+        raise ConfigError("fees need max_fee_per_gas and max_priority_fee_per_gas, or gas_price")
+    if gas_limit is not None:
+        if isinstance(gas_limit, bool) or not isinstance(gas_limit, int) or gas_limit < TX_BASE_GAS:
+            raise ConfigError(f"gas_limit must be an integer of at least {TX_BASE_GAS}, got {gas_limit!r}")
+        fields["gas"] = hex(gas_limit)
+    return fields
+
+
+def bump_fee(
+    original: Dict,
+    min_bump_pct: float = DEFAULT_REPLACEMENT_BUMP_PCT,
+    current: Optional[Dict] = None,
+    max_fee_ceiling: Optional[int] = None,
+) -> Dict:
+    """
+    Get fees for a replacement of a transaction that is stuck with `original` fees.
+
+    Both max_fee_per_gas and max_priority_fee_per_gas are raised by at least
+    `min_bump_pct` percent (rounded up to the next wei). If `current` network
+    fees (e.g. from GasTracker.recommend) are higher still, those are used
+    instead, as a replacement that only clears the bump would be stuck too.
+
+    Args:
+        original: Fees the pending transaction was signed with
+        min_bump_pct: Percentage to raise both fees by, at least 10
+        current: Current recommended fees to use as a floor
+        max_fee_ceiling: Highest max_fee_per_gas (wei) the caller will sign
+
+    Returns:
+        Dictionary with max_priority_fee_per_gas, max_fee_per_gas (wei) and
+        block_number (from `current` if given)
+
+    Raises:
+        FeeCeilingExceededError: If the bumped max fee is above the ceiling
+    """
+    if not min_bump_pct >= MIN_REPLACEMENT_BUMP_PCT:
+        raise ConfigError(f"Replacement fees must be bumped by at least {MIN_REPLACEMENT_BUMP_PCT}%")
+    factor = 1 + Fraction(min_bump_pct) / 100
+
+    def bumped(key: str) -> Wei:
+        fee = Wei(math.ceil(int(original[key]) * factor))
+        if current is not None:
+            fee = max(fee, Wei(current[key]))
+        return fee
+
+    priority = bumped("max_priority_fee_per_gas")
+    # The max fee has to cover the priority fee for the bump to count
+    max_fee = max(bumped("max_fee_per_gas"), priority)
+    if max_fee_ceiling is not None and max_fee > max_fee_ceiling:
+        raise FeeCeilingExceededError(max_fee, max_fee_ceiling)
+    source = current if current is not None else original
+    return {
+        "max_priority_fee_per_gas": priority,
+        "max_fee_per_gas": max_fee,
+        "block_number": source.get("block_number"),
+    }
+
+
+def priority_fee_histogram(fee_history: Dict, buckets: int) -> Dict:
+    """
+    Bin every reward in an eth_feeHistory result into `buckets` equal-width buckets.
+
+    Request the history with many percentiles (e.g. HISTOGRAM_PERCENTILES)
+    to see the spread of the fee market, not just a few points of it.
+    Blocks without transactions report no rewards and are skipped.
+
+    Returns:
+        Dictionary with the bucket edges (wei), the count per bucket, the
+        number of samples and the number of blocks they came from
+
+    Raises:
+        EmptyFeeHistoryError: If no block in the history had transactions
+    """
+    if buckets < 1:
+        raise ConfigError("A histogram needs at least one bucket")
+    rewards = check_fee_history(fee_history, require_base_fees=False).get("reward") or []
+    blocks = [[Wei.from_hex(r) for r in block] for block in rewards if block]
+    samples = [fee for block in blocks for fee in block]
+    if not samples:
+        raise EmptyFeeHistoryError("eth_feeHistory has no rewards to histogram")
+
+    low, high = min(samples), max(samples)
+    # A market where every fee is equal still gets buckets of non-zero width
+    span = max(high - low, buckets)
+    edges = [low + span * i // buckets for i in range(buckets + 1)]
+    counts = [0] * buckets
+    for fee in samples:
+        counts[min(bisect.bisect_right(edges, fee) - 1, buckets - 1)] += 1
+    return {"edges": edges, "counts": counts, "samples": len(samples), "blocks": len(blocks)}
@@ -0,0 +1,71 @@
+"""
+Change detection between two snapshots (GasTracker.snapshot or estimate_eip1559).
+
+    previous = await tracker.snapshot(session)
+    ...
+    change = diff_snapshots(previous, await tracker.snapshot(session), threshold_gwei=20)
+    if change["crossed_down"]:
+        notify(...)
+"""
+
+from typing import Dict, Optional
+
+from .models import FeeChange, SnapshotDiff
+from .units import Gwei, Wei
+
+
+def fee_change(old: int, new: int) -> FeeChange:
+    """
+    Get the absolute and percentage change from `old` to `new` wei.
+
+    The percentage is 0.0 when both are zero and None when only the old
+    value is, rather than dividing by zero.
+    """
+    old, new = Wei(old), Wei(new)
+    if old:
+        change_pct: Optional[float] = round((new - old) * 100 / old, 2)
+    else:
+        change_pct = 0.0 if not new else None
+    return {"old": old, "new": new, "change": new - old, "change_pct": change_pct}
+
+
+def diff_snapshots(old: Dict, new: Dict, threshold_gwei: Optional[float] = None) -> SnapshotDiff:
+    """
+    Report what changed between two snapshots.
+
+    Tier prices are the base fee plus the tier's priority fee, the price a
+    transaction actually pays, for every tier present in both snapshots.
+
+    Args:
+        old: Earlier snapshot or estimate_eip1559 result
+        new: Later snapshot or estimate_eip1559 result
+        threshold_gwei: Base fee level for the crossed_up/crossed_down flags
+
+    Returns:
+        Dictionary with the block delta, base fee and per-tier changes (wei)
+        and whether the base fee crossed the threshold
+    """
+    old_base, new_base = old["base_fee_per_gas"], new["base_fee_per_gas"]
+    tiers = {}
+    for tier, fees in new["tiers"].items():
+        if tier in old["tiers"]:
+            tiers[tier] = fee_change(
+                old_base + old["tiers"][tier]["max_priority_fee_per_gas"],
+                new_base + fees["max_priority_fee_per_gas"],
+            )
+
+    crossed_up = crossed_down = False
+    if threshold_gwei is not None:
+        threshold = Gwei(threshold_gwei).to_wei()
+        crossed_up = old_base < threshold <= new_base
+        crossed_down = new_base < threshold <= old_base
+
+    old_block, new_block = old.get("block_number"), new.get("block_number")
+    return {
+        "network": new.get("network", old.get("network")),
+        "block_delta": new_block - old_block if old_block is not None and new_block is not None else None,
+        "base_fee": fee_change(old_base, new_base),
+        "tiers": tiers,
+        "crossed_up": crossed_up,
+        "crossed_down": crossed_down,
+    }
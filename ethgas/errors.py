@@ -0,0 +1,273 @@
+"""Typed errors raised by gas providers and the tracker."""
+
+import asyncio
+import json
+import sys
+from contextlib import contextmanager
+from typing import Dict, List, Optional
+
+
+class GasError(Exception):
+    """Base class for all gas tracking errors."""
+
+
+class HttpError(GasError):
+    """HTTP request failed (connection error or non-2xx status)."""
+
+    def __init__(self, message: str, status: Optional[int] = None, headers=None):
+        super().__init__(message)
+        self.status = status
+        self.headers = headers
+
+
+class RateLimitedError(HttpError):
+    """Provider responded with HTTP 429 Too Many Requests."""
+
+    def __init__(self, message: str = "Rate limited", headers=None):
+        super().__init__(message, status=429, headers=headers)
+
+
+class RpcError(GasError):
+    """JSON-RPC endpoint returned an error object."""
+
+    def __init__(self, code: int, message: str, data=None):
+        super().__init__(f"RPC error {code}: {message}")
+        self.code = code
+        self.message = message
+        self.data = data
+
+
+class RpcIdMismatchError(GasError):
+    """JSON-RPC response id doesn't match the id of the request it answers."""
+
+    def __init__(self, expected, actual):
+        super().__init__(f"RPC response id {actual!r} does not match request id {expected!r}")
+        self.expected = expected
+        self.actual = actual
+
+
+class EstimationRevertedError(RpcError):
+    """eth_estimateGas failed because the transaction would revert."""
+
+    def __init__(self, code: int, message: str, data=None, reason: Optional[str] = None):
+        super().__init__(code, message, data)
+        self.reason = reason
+
+
+class DeserializeError(GasError):
+    """Response was missing fields or had unexpected types."""
+
+
+class SchemaMismatchError(DeserializeError):
+    """Persisted data was written in a schema version this release can't read or migrate."""
+
+    def __init__(self, found, expected: int, what: str = "data"):
+        super().__init__(f"Unsupported {what} schema version {found!r} (expected {expected})")
+        self.found = found
+        self.expected = expected
+
+
+class GasTimeoutError(GasError):
+    """Request did not complete in time."""
+
+
+class AllProvidersFailedError(GasError):
+    """Every provider in a composite provider failed."""
+
+    def __init__(self, errors: List[BaseException]):
+        super().__init__(f"All providers failed: {[str(e) for e in errors]}")
+        self.errors = errors
+
+
+class EmptyFeeHistoryError(GasError):
+    """eth_feeHistory returned no base fees."""
+
+
+class CircuitOpenError(GasError):
+    """Provider was skipped because its circuit breaker is open."""
+
+    def __init__(self, provider: str, retry_in: float):
+        super().__init__(f"Circuit open for {provider}; next probe in {retry_in:.1f}s")
+        self.provider = provider
+        self.retry_in = retry_in
+
+
+class FeeCeilingExceededError(GasError):
+    """A fee needed for a transaction is above the caller's ceiling."""
+
+    def __init__(self, required: int, ceiling: int):
+        super().__init__(f"Required max fee of {required} wei exceeds the ceiling of {ceiling} wei")
+        self.required = required
+        self.ceiling = ceiling
+
+
+class StalePriceError(GasError):
+    """An on-chain price feed's latest round is older than allowed."""
+
+    def __init__(self, feed: str, age: float, max_age: float):
+        super().__init__(f"{feed} price is {age:.0f}s old, older than the {max_age:.0f}s limit")
+        self.feed = feed
+        self.age = age
+        self.max_age = max_age
+
+
+class NoPriceConsensusError(GasError):
+    """No price feed gave a usable price: each failed, or they disagreed too much."""
+
+    def __init__(self, rejected: Dict[str, str]):
+        reasons = "; ".join(f"{feed}: {reason}" for feed, reason in rejected.items())
+        super().__init__(f"No price consensus from {len(rejected)} feeds ({reasons})")
+        self.rejected = rejected
+
+
+class UnknownProviderError(GasError, KeyError):
+    """A provider was requested by a name the tracker doesn't have."""
+
+    def __init__(self, name: str, available: List[str]):
+        super().__init__(f"Unknown provider '{name}'; configured: {', '.join(available) or 'none'}")
+        self.name = name
+        self.available = available
+
+    def __str__(self) -> str:
+        return self.args[0]
+
+
+class InsufficientHistoryError(GasError):
+    """Too few history samples for a statistic or prediction to mean anything."""
+
+    def __init__(self, have: int, need: int):
+        super().__init__(f"Need at least {need} history samples, have {have}")
+        self.have = have
+        self.need = need
+
+
+class SuspectQuoteError(GasError):
+    """A quote's base fee moved further than EIP-1559 allows since the last one."""
+
+    def __init__(self, provider: str, reason: str):
+        super().__init__(f"Suspect quote from {provider}: {reason}")
+        self.provider = provider
+        self.reason = reason
+
+
+class HistoricalDataUnavailableError(GasError):
+    """The node no longer serves a past block's data, as non-archive nodes prune it."""
+
+    def __init__(self, block_number: int, reason: str):
+        super().__init__(f"Block {block_number} is unavailable on this node ({reason}); an archive node is needed")
+        self.block_number = block_number
+        self.reason = reason
+
+
+class ConfigError(GasError, ValueError):
+    """Invalid tracker or provider configuration."""
+
+
+def from_exception(error: BaseException) -> GasError:
+    """Convert a low-level exception into the matching GasError."""
+    if isinstance(error, GasError):
+        return error
+    # Looked up rather than imported so this module (and ethgas.core) works
+    # without aiohttp; if it was never imported, none of its errors can occur
+    aiohttp = sys.modules.get("aiohttp")
+    if aiohttp is not None and isinstance(error, aiohttp.ClientResponseError):
+        headers = getattr(error, "headers", None)
+        if error.status == 429:
+            return RateLimitedError(str(error), headers=headers)
+        return HttpError(f"HTTP {error.status}: {error.message}", status=error.status, headers=headers)
+    if isinstance(error, asyncio.TimeoutError):
+        return GasTimeoutError("Request timed out")
+    if aiohttp is not None and isinstance(error, aiohttp.ClientError):
+        return HttpError(str(error))
+    if isinstance(error, (KeyError, IndexError, TypeError, ValueError, json.JSONDecodeError)):
+        return DeserializeError(f"Malformed response: {error!r}")
+    return GasError(str(error))
+
+
+# JSON-RPC error code of a method the endpoint doesn't implement
+METHOD_NOT_FOUND = -32601
+
+# JSON-RPC error code of a call with parameters the endpoint doesn't accept
+INVALID_PARAMS = -32602
+
+
+def check_rpc_response(data) -> None:
+    """Raise RpcError if a JSON-RPC response carries an error object."""
+    if isinstance(data, dict) and data.get("error"):
+        error = data["error"]
+        if isinstance(error, dict):
+            raise RpcError(error.get("code", 0), error.get("message", ""), error.get("data"))
+        raise RpcError(0, str(error))
+
+
+# Error code Geth returns for blocks whose history it pruned (EIP-4444)
+PRUNED_HISTORY = 4444
+
+# Fragments of the messages nodes answer with for blocks or state they have pruned
+HISTORY_UNAVAILABLE_MESSAGES = (
+    "missing trie node",
+    "header not found",
+    "pruned",
+    "historical state",
+    "state not available",
+    "unknown block",
+)
+
+
+def history_unavailable(error: RpcError) -> bool:
+    """
+    Check whether an RPC error means the node has pruned the requested block.
+
+    A missing method (-32601, "... does not exist/is not available" on
+    Geth) never counts, whatever its message says.
+    """
+    if error.code == METHOD_NOT_FOUND:
+        return False
+    message = error.message.lower()
+    return error.code == PRUNED_HISTORY or any(fragment in message for fragment in HISTORY_UNAVAILABLE_MESSAGES)
+
+
+# Fragments of the messages nodes answer with for a block tag they don't serve
+BLOCK_TAG_REJECTED_MESSAGES = (
+    "pending block",
+    "block tag",
+    "pending not supported",
+)
+
+
+def rejects_block_tag(error: RpcError) -> bool:
+    """Check whether an RPC error means the node doesn't accept the requested block tag."""
+    message = error.message.lower()
+    return error.code == INVALID_PARAMS or any(fragment in message for fragment in BLOCK_TAG_REJECTED_MESSAGES)
+
+
+# Selector of Solidity's Error(string), the payload of require/revert messages
+ERROR_STRING_SELECTOR = "08c379a0"
+
+
+def revert_reason(error: RpcError) -> Optional[str]:
+    """Get the revert message from a reverted call's error data or message, if there is one."""
+    data = error.data
+    if isinstance(data, dict):  # some nodes nest it as {"data": "0x..."}
+        data = data.get("data")
+    if isinstance(data, str) and data[2:10] == ERROR_STRING_SELECTOR:
+        try:
+            payload = bytes.fromhex(data[10:])
+            length = int.from_bytes(payload[32:64], "big")
+            return payload[64:64 + length].decode("utf-8", errors="replace")
+        except ValueError:
+            pass
+
+    _, sep, reason = error.message.partition("execution reverted:")
+    return (reason.strip() or None) if sep else None
+
+
+@contextmanager
+def translate_errors():
+    """Re-raise any exception from the block as a GasError, keeping the cause."""
+    try:
+        yield
+    except GasError:
+        raise
+    except Exception as e:
+        raise from_exception(e) from e
@@ -1,9 +1,25 @@
-"""Data export functionality for CSV and Excel formats."""
+"""Data export functionality for CSV, Excel and Parquet formats."""
 import csv
 import json
 from pathlib import Path
-from typing import List, Dict, Optional
-from datetime import datetime
+from typing import List, Dict, Optional, Union
+from datetime import datetime, timezone
+
+from .units import Gwei
+
+# Fee columns of a Parquet export, as (column, sample tier or field)
+PARQUET_FEE_COLUMNS = [
+    ("base_fee_wei", "base_fee"),
+    ("gas_price_wei", "gas_price"),
+    ("slow_wei", "slow"),
+    ("standard_wei", "standard"),
+    ("fast_wei", "fast"),
+]
+
+# Columns of a CSV export of history samples, fees in gwei
+SAMPLE_CSV_COLUMNS = [
+    "timestamp", "network", "provider", "block_number", "base_fee", "gas_price", "slow", "standard", "fast",
+]
 
 
 class DataExporter:
@@ -40,6 +56,29 @@ class DataExporter:
                 row = {field: record.get(field, "") for field in fieldnames}
                 writer.writerow(row)
 
+    @staticmethod
+    def export_samples_to_csv(samples: List[Dict], output_path: Union[str, Path]) -> None:
+        """
+        Export history samples (HistoryStore.query or InMemoryHistory) to CSV.
+
+        Fees are in gwei as stored, one column per tier; missing values are
+        empty cells.
+
+        Args:
+            samples: History samples, oldest first
+            output_path: Path to save CSV file
+        """
+        if not samples:
+            raise ValueError("No samples to export")
+
+        with open(output_path, 'w', newline='', encoding='utf-8') as csvfile:
+            writer = csv.DictWriter(csvfile, fieldnames=SAMPLE_CSV_COLUMNS)
+            writer.writeheader()
+            for sample in samples:
+                tiers = sample.get("tiers") or {}
+                row = {column: sample.get(column, tiers.get(column)) for column in SAMPLE_CSV_COLUMNS}
+                writer.writerow({column: "" if value is None else value for column, value in row.items()})
+
     @staticmethod
     def export_to_excel(records: List[Dict], output_path: str) -> None:
         """
@@ -110,6 +149,61 @@ class DataExporter:
         # Save workbook
         wb.save(output_path)
 
+    @staticmethod
+    def export_to_parquet(samples: List[Dict], output_path: Union[str, Path]) -> None:
+        """
+        Export history samples to Parquet using pyarrow.
+
+        Takes samples as returned by HistoryStore.query or InMemoryHistory.
+        Timestamps are written as timestamp[ns, UTC] (naive sample times are
+        taken as local time), block numbers as uint64 and every fee in wei as
+        uint64, so pandas and polars load them typed without parsing. Missing
+        values are nulls.
+
+        Args:
+            samples: History samples, oldest first
+            output_path: Path to save Parquet file
+        """
+        try:
+            import pyarrow as pa
+            import pyarrow.parquet as pq
+        except ImportError:
+            raise ImportError(
+                "pyarrow is required for Parquet export. "
+                "Install with: pip install eth-gas-tracker[parquet]"
+            )
+
+        if not samples:
+            raise ValueError("No samples to export")
+
+        def utc(value) -> datetime:
+            if isinstance(value, str):
+                value = datetime.fromisoformat(value)
+            return value.astimezone(timezone.utc)
+
+        def wei(value) -> Optional[int]:
+            return None if value is None else int(Gwei(value).to_wei())
+
+        columns = {
+            "timestamp": [utc(s["timestamp"]) for s in samples],
+            "network": [s.get("network") for s in samples],
+            "provider": [s.get("provider") for s in samples],
+            "block_number": [s.get("block_number") for s in samples],
+        }
+        for column, key in PARQUET_FEE_COLUMNS:
+            columns[column] = [wei(s.get(key, s.get("tiers", {}).get(key))) for s in samples]
+
+        schema = pa.schema(
+            [
+                ("timestamp", pa.timestamp("ns", tz="UTC")),
+                ("network", pa.string()),
+                ("provider", pa.string()),
+                ("block_number", pa.uint64()),
+            ]
+            + [(column, pa.uint64()) for column, _ in PARQUET_FEE_COLUMNS]
+        )
+        pq.write_table(pa.Table.from_pydict(columns, schema=schema), str(output_path))
+
     @staticmethod
     def export_to_json(records: List[Dict], output_path: str) -> None:
         """
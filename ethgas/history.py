@@ -2,9 +2,15 @@
 
 import json
 import os
+import sqlite3
+import threading
+from collections import deque
 from datetime import datetime
 from pathlib import Path
-from typing import List, Dict, Optional
+from typing import List, Dict, Optional, Tuple
+
+from .errors import SchemaMismatchError
+from .units import Wei, parse_hex_quantity
 
 
 class GasHistory:
@@ -51,3 +57,376 @@ class GasHistory:
         """Clear all historical data."""
         if self.history_file.exists():
             self.history_file.unlink()
+
+
+def _head_fields(header: Dict) -> Tuple[int, str, Optional[float]]:
+    """Block number, hash and base fee (gwei, if any) of a JSON-RPC block header."""
+    base_fee = header.get("baseFeePerGas")
+    return (
+        parse_hex_quantity(header["number"]),
+        header["hash"],
+        Wei.from_hex(base_fee).to_gwei() if base_fee else None,
+    )
+
+
+class HistoryStore:
+    """SQLite-backed store of gas quotes for offline trend analysis."""
+
+    SCHEMA = """
+        CREATE TABLE IF NOT EXISTS samples (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            timestamp REAL NOT NULL,
+            network TEXT,
+            provider TEXT,
+            base_fee REAL,
+            gas_price REAL,
+            slow REAL,
+            standard REAL,
+            fast REAL,
+            block_number INTEGER,
+            block_hash TEXT,
+            orphaned INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX IF NOT EXISTS idx_samples_timestamp ON samples (timestamp);
+        CREATE TABLE IF NOT EXISTS checkpoints (
+            name TEXT PRIMARY KEY,
+            block INTEGER NOT NULL
+        );
+    """
+
+    # Columns version 1 databases are missing; migrating adds them
+    ADDED_COLUMNS = (
+        ("block_number", "INTEGER"),
+        ("block_hash", "TEXT"),
+        ("orphaned", "INTEGER NOT NULL DEFAULT 0"),
+    )
+
+    # Schema version kept in the database's user_version. 0 is a database
+    # from before versioning, which is migrated like version 1
+    SCHEMA_VERSION = 2
+
+    def __init__(self, db_path: Optional[str] = None, data_dir: str = ".ethgas", buffer_size: int = 1):
+        """
+        Open (or create) the history database.
+
+        Args:
+            db_path: SQLite file path (default: ~/<data_dir>/history.db)
+            data_dir: Directory under the home directory for the default path
+            buffer_size: Samples record() buffers before committing them in
+                one transaction; 1 commits every sample. Buffered samples are
+                lost on a crash unless flush() or close() runs first.
+
+        Raises:
+            SchemaMismatchError: If the database was written by a newer release
+        """
+        if buffer_size < 1:
+            raise ValueError("buffer_size must be at least 1")
+        self.buffer_size = buffer_size
+        self._buffer: List[Tuple[Dict, datetime]] = []
+        self._lock = threading.Lock()
+        if db_path is None:
+            directory = Path.home() / data_dir
+            directory.mkdir(exist_ok=True)
+            db_path = str(directory / "history.db")
+
+        self.db_path = db_path
+        self.conn = sqlite3.connect(db_path)
+        found = self.conn.execute("PRAGMA user_version").fetchone()[0]
+        if found > self.SCHEMA_VERSION:
+            self.conn.close()
+            raise SchemaMismatchError(found, self.SCHEMA_VERSION, "history database")
+        self.conn.executescript(self.SCHEMA)
+        if found < self.SCHEMA_VERSION:
+            self._migrate_v1()
+
+    def _migrate_v1(self) -> None:
+        """Add the block tracking columns version 1 databases lack and mark the schema current."""
+        columns = [row[1] for row in self.conn.execute("PRAGMA table_info(samples)")]
+        with self.conn:
+            for column, definition in self.ADDED_COLUMNS:
+                if column not in columns:
+                    self.conn.execute(f"ALTER TABLE samples ADD COLUMN {column} {definition}")
+            self.conn.execute("CREATE INDEX IF NOT EXISTS idx_samples_block ON samples (network, block_number)")
+            self.conn.execute(f"PRAGMA user_version = {self.SCHEMA_VERSION}")
+
+    def record(self, quote: Dict, timestamp: Optional[datetime] = None) -> bool:
+        """
+        Store a gas quote.
+
+        Error and warning items from GasTracker.watch() are skipped, so a watch stream can
+        be fed straight into this method.
+
+        Args:
+            quote: Gas quote dictionary
+            timestamp: Sample time (default: now)
+
+        Returns:
+            True if the quote was stored
+        """
+        if "error" in quote or "warning" in quote:
+            return False
+
+        with self._lock:
+            self._buffer.append((quote, timestamp or datetime.now()))
+            if len(self._buffer) >= self.buffer_size:
+                self._commit_buffer()
+        return True
+
+    def flush(self) -> None:
+        """Commit samples buffered by record(); does nothing if none are pending."""
+        with self._lock:
+            self._commit_buffer()
+
+    def _commit_buffer(self) -> None:
+        if not self._buffer:
+            return
+        with self.conn:
+            for quote, timestamp in self._buffer:
+                self._insert(quote, timestamp)
+        self._buffer.clear()
+
+    def record_many(self, quotes: List[Dict], checkpoint: Optional[Tuple[str, int]] = None) -> None:
+        """
+        Store quotes timestamped by their fetched_at, in a single transaction.
+
+        Args:
+            quotes: Gas quote dictionaries
+            checkpoint: Optional (name, block) progress marker saved in the same
+                transaction, so the samples and the marker are never out of step
+        """
+        with self._lock, self.conn:
+            self._commit_buffer()
+            for quote in quotes:
+                self._insert(quote, quote["fetched_at"])
+            if checkpoint is not None:
+                self.conn.execute(
+                    "INSERT OR REPLACE INTO checkpoints (name, block) VALUES (?, ?)", checkpoint
+                )
+
+    def checkpoint(self, name: str) -> Optional[int]:
+        """Get the block saved under a checkpoint name, if any."""
+        row = self.conn.execute("SELECT block FROM checkpoints WHERE name = ?", (name,)).fetchone()
+        return row[0] if row else None
+
+    def handle_reorg(self, new_head: Dict, network: Optional[str] = None) -> int:
+        """
+        Reconcile stored samples with a new canonical block header.
+
+        Samples for the header's block number recorded under another hash
+        came from a block that was reorged out. If the header has a
+        baseFeePerGas they are updated to it and its hash; otherwise they
+        are flagged as orphaned. Samples of later blocks on the old branch
+        are flagged as orphaned either way, until samples for the new
+        branch replace them. Orphaned samples are left out of query() unless
+        asked for, so stats only see the canonical chain.
+
+        Args:
+            new_head: Block header with hex "number" and "hash", e.g. from
+                newHeads or eth_getBlockByNumber
+            network: Only reconcile samples of this network
+
+        Returns:
+            Number of samples updated or flagged
+        """
+        number, block_hash, base_fee = _head_fields(new_head)
+        with self._lock, self.conn:
+            self._commit_buffer()
+            return self._reorg(network, number, block_hash, base_fee)
+
+    def _reorg(self, network: Optional[str], number: int, block_hash: str, base_fee: Optional[float]) -> int:
+        where = "block_hash IS NOT NULL AND orphaned = 0 AND (? IS NULL OR network = ?)"
+        scope = (network, network)
+        conflicting = self.conn.execute(
+            f"SELECT COUNT(*) FROM samples WHERE {where} AND block_number = ? AND block_hash != ?",
+            scope + (number, block_hash),
+        ).fetchone()[0]
+        if not conflicting:
+            return 0
+        if base_fee is not None:
+            self.conn.execute(
+                f"UPDATE samples SET base_fee = ?, block_hash = ?, orphaned = 0 "
+                f"WHERE {where} AND block_number = ? AND block_hash != ?",
+                (base_fee,) + (block_hash,) + scope + (number, block_hash),
+            )
+        else:
+            self.conn.execute(
+                f"UPDATE samples SET orphaned = 1 WHERE {where} AND block_number = ? AND block_hash != ?",
+                scope + (number, block_hash),
+            )
+        descendants = self.conn.execute(
+            f"UPDATE samples SET orphaned = 1 WHERE {where} AND block_number > ?",
+            scope + (number,),
+        ).rowcount
+        return conflicting + descendants
+
+    def _insert(self, quote: Dict, timestamp: datetime) -> None:
+        tiers = quote.get("tiers", {})
+        network, number, block_hash = quote.get("network"), quote.get("block_number"), quote.get("block_hash")
+        if number is not None and block_hash:
+            # A sample for a block number seen under another hash means a reorg
+            self._reorg(network, number, block_hash, None)
+        self.conn.execute(
+            "INSERT INTO samples "
+            "(timestamp, network, provider, base_fee, gas_price, slow, standard, fast, block_number, block_hash) "
+            "VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            (
+                timestamp.timestamp(),
+                network,
+                quote.get("provider"),
+                quote.get("base_fee"),
+                quote.get("gas_price"),
+                tiers.get("slow"),
+                tiers.get("standard"),
+                tiers.get("fast"),
+                number,
+                block_hash,
+            ),
+        )
+
+    def query(
+        self, start: datetime, end: datetime, network: Optional[str] = None, include_orphaned: bool = False
+    ) -> List[Dict]:
+        """
+        Get samples recorded between two times, oldest first.
+
+        Args:
+            start: Start of the range (inclusive)
+            end: End of the range (inclusive)
+            network: Optional network name filter
+            include_orphaned: Also return samples of blocks that were reorged
+                out (see handle_reorg), with "orphaned" set
+
+        Returns:
+            List of gas sample dictionaries, buffered ones included
+        """
+        self.flush()
+        sql = (
+            "SELECT timestamp, network, provider, base_fee, gas_price, slow, standard, fast, block_number, "
+            "block_hash, orphaned FROM samples WHERE timestamp BETWEEN ? AND ?"
+        )
+        params: list = [start.timestamp(), end.timestamp()]
+        if network is not None:
+            sql += " AND network = ?"
+            params.append(network)
+        if not include_orphaned:
+            sql += " AND orphaned = 0"
+        sql += " ORDER BY timestamp"
+
+        return [
+            {
+                "timestamp": datetime.fromtimestamp(row[0]).isoformat(),
+                "network": row[1],
+                "provider": row[2],
+                "base_fee": row[3],
+                "gas_price": row[4],
+                "tiers": {"slow": row[5], "standard": row[6], "fast": row[7]},
+                "block_number": row[8],
+                "block_hash": row[9],
+                "orphaned": bool(row[10]),
+            }
+            for row in self.conn.execute(sql, params)
+        ]
+
+    def close(self) -> None:
+        """Commit buffered samples and close the database connection."""
+        self.flush()
+        self.conn.close()
+
+
+class InMemoryHistory:
+    """
+    Fixed-capacity in-memory history; the oldest samples are evicted once full.
+
+    Has the same record/record_many/query/checkpoint methods as HistoryStore,
+    so it can back watch() collection, backfill() and current_regime() without
+    a database. samples() returns copies, so a collector task and readers can
+    share one instance.
+    """
+
+    def __init__(self, capacity: int = 1000):
+        if capacity < 1:
+            raise ValueError("capacity must be at least 1")
+        self.capacity = capacity
+        self._samples: deque = deque(maxlen=capacity)
+        self._checkpoints: Dict[str, int] = {}
+
+    def __len__(self) -> int:
+        return len(self._samples)
+
+    def record(self, quote: Dict, timestamp: Optional[datetime] = None) -> bool:
+        """Store a gas quote, skipping error and warning items (see HistoryStore.record)."""
+        if "error" in quote or "warning" in quote:
+            return False
+        self._append(quote, timestamp or datetime.now())
+        return True
+
+    def record_many(self, quotes: List[Dict], checkpoint: Optional[Tuple[str, int]] = None) -> None:
+        """Store quotes timestamped by their fetched_at (see HistoryStore.record_many)."""
+        for quote in quotes:
+            self._append(quote, quote["fetched_at"])
+        if checkpoint is not None:
+            name, block = checkpoint
+            self._checkpoints[name] = block
+
+    def checkpoint(self, name: str) -> Optional[int]:
+        """Get the block saved under a checkpoint name, if any."""
+        return self._checkpoints.get(name)
+
+    def flush(self) -> None:
+        """Nothing to commit; present so either store can back GasTracker.with_history."""
+
+    def samples(self) -> List[Dict]:
+        """Get copies of all buffered samples, oldest first."""
+        return [dict(sample, tiers=dict(sample["tiers"])) for _, sample in self._samples]
+
+    def query(
+        self, start: datetime, end: datetime, network: Optional[str] = None, include_orphaned: bool = False
+    ) -> List[Dict]:
+        """Get buffered samples between two times, oldest first (see HistoryStore.query)."""
+        low, high = start.timestamp(), end.timestamp()
+        return [
+            dict(sample, tiers=dict(sample["tiers"]))
+            for ts, sample in sorted(self._samples, key=lambda entry: entry[0])
+            if low <= ts <= high and (network is None or sample["network"] == network)
+            and (include_orphaned or not sample["orphaned"])
+        ]
+
+    def handle_reorg(self, new_head: Dict, network: Optional[str] = None) -> int:
+        """Reconcile buffered samples with a new canonical block header (see HistoryStore.handle_reorg)."""
+        number, block_hash, base_fee = _head_fields(new_head)
+        return self._reorg(network, number, block_hash, base_fee)
+
+    def _reorg(self, network: Optional[str], number: int, block_hash: str, base_fee: Optional[float]) -> int:
+        scoped = [
+            sample for _, sample in self._samples
+            if sample["block_hash"] and not sample["orphaned"] and (network is None or sample["network"] == network)
+        ]
+        conflicting = [s for s in scoped if s["block_number"] == number and s["block_hash"] != block_hash]
+        if not conflicting:
+            return 0
+        for sample in conflicting:
+            if base_fee is not None:
+                sample.update(base_fee=base_fee, block_hash=block_hash, orphaned=False)
+            else:
+                sample["orphaned"] = True
+        descendants = [s for s in scoped if s["block_number"] > number]
+        for sample in descendants:
+            sample["orphaned"] = True
+        return len(conflicting) + len(descendants)
+
+    def _append(self, quote: Dict, timestamp: datetime) -> None:
+        tiers = quote.get("tiers", {})
+        ts = timestamp.timestamp()
+        if quote.get("block_number") is not None and quote.get("block_hash"):
+            self._reorg(quote.get("network"), quote["block_number"], quote["block_hash"], None)
+        self._samples.append((ts, {
+            "timestamp": datetime.fromtimestamp(ts).isoformat(),
+            "network": quote.get("network"),
+            "provider": quote.get("provider"),
+            "base_fee": quote.get("base_fee"),
+            "gas_price": quote.get("gas_price"),
+            "tiers": {"slow": tiers.get("slow"), "standard": tiers.get("standard"), "fast": tiers.get("fast")},
+            "block_number": quote.get("block_number"),
+            "block_hash": quote.get("block_hash"),
+            "orphaned": False,
+        }))
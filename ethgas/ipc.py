@@ -0,0 +1,157 @@
+"""
+JSON-RPC over a local node's IPC endpoint (a Unix domain socket).
+
+Geth and Reth expose the same API on a socket file as over HTTP, without
+the HTTP overhead or any rate limit, which makes it the fastest way for a
+node operator to price gas from their own node:
+
+    tracker = GasTracker(rpc_url, provider=IpcProvider("~/.ethereum/geth.ipc"))
+
+IPC needs a Unix platform; on others IpcProvider raises ConfigError, and
+JsonRpcProvider over HTTP is the alternative.
+"""
+
+import asyncio
+import json
+import logging
+import os
+import socket
+from typing import Dict, Optional
+
+import aiohttp
+
+from .errors import ConfigError, HttpError, translate_errors
+from .providers import DEFAULT_BLOCK_TAG, DEFAULT_REQUEST_TIMEOUT, BlockTag, JsonRpcProvider
+
+logger = logging.getLogger(__name__)
+
+# Whether this platform has Unix domain sockets
+IPC_SUPPORTED = hasattr(socket, "AF_UNIX")
+
+# Bytes read from the socket at a time
+READ_CHUNK = 64 * 1024
+
+
+class IpcProvider(JsonRpcProvider):
+    """
+    JsonRpcProvider speaking to a node over its IPC socket instead of HTTP.
+
+    Quotes, batching, EIP-1559 detection and the fallbacks work exactly as
+    for JsonRpcProvider; only the transport differs. One connection is kept
+    open and requests take turns on it. Responses are decoded as a stream
+    of JSON values, so both newline-delimited framing (Geth) and values
+    written back to back (Reth) are read correctly.
+
+    When the node closes the connection (EOF, e.g. after a restart) the
+    request is retried once on a fresh connection; if that fails too, or
+    the socket can't be opened, HttpError is raised. A request that times
+    out drops the connection, so a late response can't be mistaken for
+    the next request's.
+    """
+
+    name = "ipc"
+
+    def __init__(
+        self,
+        ipc_path: str,
+        name: Optional[str] = None,
+        timeout: float = DEFAULT_REQUEST_TIMEOUT,
+        legacy_multipliers: Optional[Dict[str, float]] = None,
+        block_tag: BlockTag = DEFAULT_BLOCK_TAG,
+    ):
+        """
+        Initialize IPC provider.
+
+        Args:
+            ipc_path: Path of the node's socket file, e.g. "~/.ethereum/geth.ipc"
+            name: Provider name used in quotes
+            timeout: Seconds allowed for connecting and reading each response
+            legacy_multipliers: Slow/standard/fast multiples of the gas price
+                used on legacy chains (default: LEGACY_TIER_MULTIPLIERS)
+            block_tag: Block the fee history ends at: "latest", "pending" or
+                a block number
+
+        Raises:
+            ConfigError: If the platform has no Unix domain sockets
+        """
+        if not IPC_SUPPORTED:
+            raise ConfigError("IPC sockets need a Unix platform; use JsonRpcProvider over HTTP instead")
+        self.ipc_path = os.path.expanduser(ipc_path)
+        super().__init__(
+            self.ipc_path, name=name, timeout=timeout, legacy_multipliers=legacy_multipliers, block_tag=block_tag
+        )
+        self._reader: Optional[asyncio.StreamReader] = None
+        self._writer: Optional[asyncio.StreamWriter] = None
+        self._buffer = b""
+        # Created on first use, inside the running event loop
+        self._lock: Optional[asyncio.Lock] = None
+
+    def __repr__(self) -> str:
+        return f"{type(self).__name__}(name={self.name!r}, path={self.ipc_path!r})"
+
+    async def _post(self, session: aiohttp.ClientSession, payload):
+        """Send a JSON-RPC request (single or batch) over the socket; the session is unused."""
+        if self._lock is None:
+            self._lock = asyncio.Lock()
+
+        async with self._lock:
+            try:
+                with translate_errors():
+                    return await asyncio.wait_for(self._exchange(payload), self.timeout)
+            except BaseException:
+                # The response may still arrive; a fresh connection keeps it from answering the next request
+                await self.close()
+                raise
+
+    async def _exchange(self, payload):
+        """Write one request and read its response, reconnecting once if the node hung up."""
+        message = json.dumps(payload).encode() + b"\n"
+        for attempt in range(2):
+            if self._writer is None:
+                await self._connect()
+            try:
+                self._writer.write(message)
+                await self._writer.drain()
+                return await self._read_value()
+            except (ConnectionError, EOFError) as e:
+                await self.close()
+                if attempt:
+                    raise HttpError(f"IPC connection to {self.ipc_path} closed: {e!r}")
+                logger.info("%s: IPC connection closed, reconnecting", self.name)
+
+    async def _connect(self) -> None:
+        try:
+            self._reader, self._writer = await asyncio.open_unix_connection(self.ipc_path)
+        except OSError as e:
+            raise HttpError(f"Cannot connect to IPC socket {self.ipc_path}: {e}")
+        self._buffer = b""
+
+    async def _read_value(self):
+        """Read the next complete JSON value from the socket."""
+        decoder = json.JSONDecoder()
+        while True:
+            self._buffer = self._buffer.lstrip()
+            if self._buffer:
+                try:
+                    text = self._buffer.decode()
+                    value, end = decoder.raw_decode(text)
+                except ValueError:  # UnicodeDecodeError included
+                    pass  # Incomplete so far; a malformed value runs into the timeout
+                else:
+                    self._buffer = text[end:].encode()
+                    return value
+            chunk = await self._reader.read(READ_CHUNK)
+            if not chunk:
+                raise EOFError("end of stream")
+            self._buffer += chunk
+
+    async def close(self) -> None:
+        """Close the socket connection; the next request opens a new one."""
+        writer, self._reader, self._writer = self._writer, None, None
+        self._buffer = b""
+        if writer is not None:
+            writer.close()
+            try:
+                await writer.wait_closed()
+            except (ConnectionError, OSError):
+                pass
@@ -0,0 +1,89 @@
+"""Layer 2 fee models (L1 data fee on top of L2 execution gas)."""
+
+from .units import MAX_UINT256, Wei
+
+# OP-stack GasPriceOracle predeploy and its l1BaseFee(), getL1Fee(bytes) and
+# Bedrock-era overhead(), scalar() and decimals() selectors (the first two
+# revert since Ecotone)
+OP_GAS_PRICE_ORACLE = "0x420000000000000000000000000000000000000F"
+OP_L1_BASE_FEE_SELECTOR = "0x519b4bd3"
+OP_GET_L1_FEE_SELECTOR = "0x49948e0e"
+OP_OVERHEAD_SELECTOR = "0x0c18c162"
+OP_SCALAR_SELECTOR = "0xf45e65d8"
+OP_DECIMALS_SELECTOR = "0x313ce567"
+
+# Arbitrum NodeInterface and gasEstimateL1Component(address,bool,bytes) selector
+ARB_NODE_INTERFACE = "0x00000000000000000000000000000000000000C8"
+ARB_L1_COMPONENT_SELECTOR = "0x77d488a2"
+
+ZERO_ADDRESS = "0x" + "00" * 20
+
+# Most decimals() the oracle's scalar may have; the real one has 6, and a larger
+# value is garbage that would make 10 ** decimals needlessly huge
+MAX_OP_DECIMALS = 36
+
+
+def calldata_gas(calldata: bytes) -> int:
+    """Gas charged for calldata: 4 per zero byte, 16 per non-zero byte."""
+    zeros = calldata.count(0)
+    return zeros * 4 + (len(calldata) - zeros) * 16
+
+
+def op_l1_fee(calldata: bytes, l1_base_fee: int, overhead: int, scalar: int, decimals: int = 6) -> Wei:
+    """
+    L1 data fee of a Bedrock OP-stack transaction, as the GasPriceOracle computed it.
+
+    Chains upgraded to Ecotone or later price blob data too and no longer
+    have overhead and scalar; ask the oracle's getL1Fee() for those (see
+    encode_op_l1_fee_call).
+
+    fee = (calldata_gas + overhead) * l1_base_fee * scalar / 10**decimals,
+    rounded down. The oracle does this in uint256, so arguments outside that
+    range (including negative ones) are rejected rather than wrapped, as are
+    more than MAX_OP_DECIMALS decimals.
+
+    Args:
+        calldata: The serialized (signed) transaction
+        l1_base_fee: l1BaseFee() of the oracle, in wei
+        overhead: overhead() of the oracle, in gas
+        scalar: scalar() of the oracle, fixed point with `decimals` decimals
+        decimals: decimals() of the oracle
+    """
+    for name, value in (("l1_base_fee", l1_base_fee), ("overhead", overhead),
+                        ("scalar", scalar), ("decimals", decimals)):
+        if not 0 <= value <= MAX_UINT256:
+            raise ValueError(f"{name} must be a uint256, got {value}")
+    if decimals > MAX_OP_DECIMALS:
+        raise ValueError(f"decimals must be at most {MAX_OP_DECIMALS}, got {decimals}")
+    fee = (calldata_gas(calldata) + overhead) * l1_base_fee * scalar // 10 ** decimals
+    if fee > MAX_UINT256:
+        raise ValueError("L1 fee overflows uint256")
+    return Wei(fee)
+
+
+def encode_op_l1_fee_call(tx: bytes) -> str:
+    """
+    ABI-encode a GasPriceOracle.getL1Fee(tx) call.
+
+    The oracle applies the formula of the chain's current upgrade (Bedrock,
+    Ecotone or Fjord) to the RLP-encoded transaction, so its answer matches
+    the l1Fee of the receipt.
+    """
+    offset = (32).to_bytes(32, "big")  # Offset of the dynamic bytes argument
+    return OP_GET_L1_FEE_SELECTOR + offset.hex() + _abi_bytes(tx)
+
+
+def encode_l1_component_call(to: str, calldata: bytes, contract_creation: bool = False) -> str:
+    """ABI-encode a NodeInterface.gasEstimateL1Component(to, contractCreation, data) call."""
+    words = [
+        int(to, 16).to_bytes(32, "big"),
+        int(contract_creation).to_bytes(32, "big"),
+        (3 * 32).to_bytes(32, "big"),  # Offset of the dynamic bytes argument
+    ]
+    return ARB_L1_COMPONENT_SELECTOR + b"".join(words).hex() + _abi_bytes(calldata)
+
+
+def _abi_bytes(data: bytes) -> str:
+    """Length word and zero-padded contents of an ABI `bytes` argument, in hex."""
+    padded = data + b"\x00" * (-len(data) % 32)
+    return len(data).to_bytes(32, "big").hex() + padded.hex()
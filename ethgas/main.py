@@ -3,25 +3,46 @@
 import argparse
 import asyncio
 import aiohttp
+import csv
 import json
+import re
+import signal
 import sys
-from datetime import datetime
+from datetime import datetime, timedelta
+from pathlib import Path
 
 from .networks import NETWORKS, TX_TYPES
+from .config import DEFAULT_CONFIG_PATH, Config
+from .errors import ConfigError, InsufficientHistoryError
+from .providers import make_session
 from .tracker import GasTracker
-from .history import GasHistory
-from .stats import GasStats
+from .units import parse_fee, to_jsonable
+from .history import GasHistory, HistoryStore
+from .stats import MIN_STATS_SAMPLES, GasStats
 from .graphs import ASCIIGraph
 from .alerts import GasAlerts
 from .api import GasAPI
 from .compare import compare_networks
-from .export import export_history
+from .export import DataExporter, export_history
 from .prediction import predict_gas_price, GasPredictor
 from .notifications import get_notifier
 from .webhooks import create_webhook_manager
 from .web_ui import run_web_ui
 
 
+def record_sample(tracker: GasTracker, gas_data: dict, tiers: dict = None) -> None:
+    """Record a checked price into the tracker's --history-db store, if there is one."""
+    if tracker.history is None:
+        return
+    price = gas_data["base_fee"] + gas_data["priority_tip"]
+    tracker.history.record({
+        "network": gas_data["network"],
+        "base_fee": gas_data["base_fee"],
+        "gas_price": price,
+        "tiers": tiers or {"standard": price},
+    })
+
+
 async def track_once(
     tracker: GasTracker,
     session: aiohttp.ClientSession,
@@ -34,6 +55,7 @@ async def track_once(
     # Save to history if enabled
     if args.history and history:
         history.add_record(gas_data)
+    record_sample(tracker, gas_data)
 
     # JSON output mode
     if args.json:
@@ -113,6 +135,129 @@ async def track_once(
     return gas_data
 
 
+TIER_COLORS = {"slow": "\033[32m", "standard": "\033[33m", "fast": "\033[31m"}
+RESET = "\033[0m"
+
+# Samples --history-db buffers before each commit
+HISTORY_DB_BATCH = 20
+
+
+def render_tiers(estimate: dict, color: bool = True) -> list:
+    """Render slow/standard/fast fee tiers as display lines."""
+    base_fee = estimate["base_fee_per_gas"].to_gwei()
+    lines = [
+        f"[{estimate['network']}] Base fee: {base_fee:.2f} gwei "
+        f"(updated {datetime.now().strftime('%H:%M:%S')})"
+    ]
+    for tier, fees in estimate["tiers"].items():
+        label = f"{fees.get('label', tier.title()):<9}"
+        if color:
+            label = f"{TIER_COLORS.get(tier, '')}{label}{RESET}"
+        lines.append(
+            f"  {label} Max: {fees['max_fee_per_gas'].to_gwei():>8.2f} gwei | "
+            f"Tip: {fees['max_priority_fee_per_gas'].to_gwei():>6.2f} gwei"
+        )
+    return lines
+
+
+async def track_tiers(
+    tracker: GasTracker,
+    session: aiohttp.ClientSession,
+    args,
+    history: GasHistory = None,
+    previous_lines: int = 0,
+) -> tuple:
+    """Fetch fee tiers and redraw them over the previous render."""
+    estimate = await tracker.estimate_eip1559(session)
+    standard = estimate["tiers"]["standard"]
+    gas_data = {
+        "network": estimate["network"],
+        "base_fee": estimate["base_fee_per_gas"].to_gwei(),
+        "priority_tip": standard["max_priority_fee_per_gas"].to_gwei(),
+        "max_fee": standard["max_fee_per_gas"].to_gwei(),
+        "token_price_usd": None,
+    }
+
+    if args.history and history:
+        history.add_record(gas_data)
+    base_fee = estimate["base_fee_per_gas"]
+    record_sample(tracker, gas_data, {
+        tier: (base_fee + fees["max_priority_fee_per_gas"]).to_gwei() for tier, fees in estimate["tiers"].items()
+    })
+
+    color = not args.no_color
+    lines = render_tiers(estimate, color=color)
+    if color and previous_lines:
+        # Move back up over the last render and clear it
+        print(f"\033[{previous_lines}F\033[J", end="")
+    print("\n".join(lines))
+
+    return gas_data, len(lines)
+
+
+def json_default(value):
+    """Serialize quote fields json doesn't handle natively (fetched_at)."""
+    if isinstance(value, datetime):
+        return value.isoformat()
+    raise TypeError(f"Object of type {type(value).__name__} is not JSON serializable")
+
+
+def flatten_quote(quote: dict, prefix: str = "") -> dict:
+    """Flatten nested quote fields (e.g. tiers.fast -> tiers_fast) for CSV rows."""
+    flat = {}
+    for key, value in quote.items():
+        name = f"{prefix}{key}"
+        if isinstance(value, dict):
+            flat.update(flatten_quote(value, prefix=f"{name}_"))
+        elif isinstance(value, datetime):
+            flat[name] = value.isoformat()
+        elif isinstance(value, list):
+            flat[name] = ";".join(str(to_jsonable(v)) for v in value)
+        else:
+            flat[name] = to_jsonable(value)
+    return flat
+
+
+async def output_quotes(tracker: GasTracker, args) -> None:
+    """Print gas quotes as JSON or CSV, once or continuously with --watch."""
+    writer = None
+
+    def emit(quote: dict) -> None:
+        nonlocal writer
+        record = {"timestamp": datetime.now().isoformat(), **quote}
+
+        if args.format == "json":
+            if args.watch:
+                print(json.dumps(record, default=json_default), flush=True)  # Newline-delimited JSON
+            else:
+                print(json.dumps(record, indent=2, default=json_default))
+            return
+
+        row = flatten_quote(record)
+        if writer is None:
+            writer = csv.DictWriter(sys.stdout, fieldnames=list(row), extrasaction="ignore")
+            writer.writeheader()
+        writer.writerow(row)
+        sys.stdout.flush()
+
+    async with make_session() as session:
+        if not args.watch:
+            emit(await tracker.current(session))
+            return
+
+        try:
+            async for quote in tracker.watch(session, args.watch):
+                if "error" in quote:
+                    print(f"❌ Error: {quote['error']}", file=sys.stderr)
+                    continue
+                if "warning" in quote:
+                    print(f"⚠️  Warning: {quote['warning']}", file=sys.stderr)
+                    continue
+                emit(quote)
+        except (KeyboardInterrupt, asyncio.CancelledError):
+            pass
+
+
 async def watch_mode(
     tracker: GasTracker, args, history: GasHistory = None, alerts: GasAlerts = None,
     notifier=None, webhook_manager=None
@@ -131,22 +276,35 @@ async def watch_mode(
         print(f"🔗 Webhooks configured: {len(webhook_manager.webhook_urls)}\n")
 
     iteration = 0
+    # Plain human output redraws the fee tiers in place
+    live = not args.json and not args.detailed
+    rendered_lines = 0
+
+    if live and not args.no_color:
+        print("\033[?25l", end="")  # Hide cursor while redrawing
 
     try:
-        async with aiohttp.ClientSession() as session:
+        async with make_session() as session:
             while True:
                 try:
                     # Clear screen for detailed mode
                     if args.detailed and iteration > 0:
                         print("\033[2J\033[H", end="")  # Clear screen and move cursor
 
-                    gas_data = await track_once(tracker, session, args, history)
+                    if live:
+                        gas_data, rendered_lines = await track_tiers(
+                            tracker, session, args, history, rendered_lines
+                        )
+                    else:
+                        gas_data = await track_once(tracker, session, args, history)
 
                     # Check alerts
                     if alerts:
                         alert_msg = alerts.check_alert(gas_data["base_fee"])
                         if alert_msg:
                             alerts.notify(alert_msg, beep=args.beep)
+                            # Start the next render below the alert
+                            rendered_lines = 0
 
                             # Send desktop notification
                             if notifier:
@@ -172,14 +330,149 @@ async def watch_mode(
                     raise
                 except Exception as e:
                     print(f"❌ Error: {e}", file=sys.stderr)
+                    rendered_lines = 0
                     await asyncio.sleep(args.watch)
 
-    except KeyboardInterrupt:
+    except (KeyboardInterrupt, asyncio.CancelledError):
         print("\n\n👋 Stopped watching")
+    finally:
+        if live and not args.no_color:
+            print("\033[?25h", end="", flush=True)  # Restore cursor
+
+
+# Units of relative --from/--to specs such as "24h" or "7d"
+TIME_SPEC_UNITS = {"s": "seconds", "m": "minutes", "h": "hours", "d": "days", "w": "weeks"}
+
+# Store `ethgas history` reads when --db isn't given, HistoryStore's default
+DEFAULT_HISTORY_DB = Path.home() / ".ethgas" / "history.db"
+
+
+def parse_time_spec(value: str, now: datetime = None) -> datetime:
+    """
+    Parse a --from/--to time: "now", a span before now ("90m", "24h", "7d")
+    or an ISO 8601 timestamp.
+    """
+    now = now or datetime.now()
+    value = value.strip()
+    if value == "now":
+        return now
+    match = re.fullmatch(r"(\d+)([smhdw])", value)
+    if match:
+        return now - timedelta(**{TIME_SPEC_UNITS[match.group(2)]: int(match.group(1))})
+    try:
+        return datetime.fromisoformat(value)
+    except ValueError:
+        raise argparse.ArgumentTypeError(
+            f"invalid time '{value}': use 'now', a span like 24h or 7d, or an ISO 8601 timestamp"
+        )
+
+
+def parse_fee_gwei(value: str) -> float:
+    """Parse a gwei option that also takes units ("2gwei", "2000000000wei"; see units.parse_fee)."""
+    try:
+        return float(parse_fee(value).to_gwei())
+    except ConfigError as e:
+        raise argparse.ArgumentTypeError(str(e))
+
+
+def history_command(argv: list) -> int:
+    """
+    Run `ethgas history`: list, summarize or export samples from a --history-db store.
+
+    Returns:
+        Process exit status
+    """
+    parser = argparse.ArgumentParser(
+        prog="ethgas history",
+        description="Read gas samples recorded with --history-db",
+        formatter_class=argparse.RawDescriptionHelpFormatter,
+        epilog="""
+Examples:
+  # Base fee statistics for the last 24 hours
+  ethgas history --stats
+
+  # Samples of one week on Polygon
+  ethgas history --from 7d --network polygon
+
+  # Export a day's samples
+  ethgas history export --from 2024-05-01 --to 2024-05-02 --format parquet --out may1.parquet
+        """,
+    )
+    parser.add_argument("action", nargs="?", choices=["show", "export"], default="show",
+                        help="List samples (default) or export them to a file")
+    parser.add_argument("--db", default=str(DEFAULT_HISTORY_DB), help=f"History database (default: {DEFAULT_HISTORY_DB})")
+    parser.add_argument("--network", choices=list(NETWORKS.keys()), help="Only samples of this network")
+    parser.add_argument("--from", dest="start", type=parse_time_spec, default="24h",
+                        help="Start of the range: ISO time, 'now' or a span before now like 24h (default: 24h)")
+    parser.add_argument("--to", dest="end", type=parse_time_spec, default="now",
+                        help="End of the range, same forms as --from (default: now)")
+    parser.add_argument("--stats", action="store_true", help="Print min/max/mean and percentiles of the base fee")
+    parser.add_argument("--min-samples", type=int, default=MIN_STATS_SAMPLES,
+                        help=f"Fewest samples --stats summarizes (default: {MIN_STATS_SAMPLES})")
+    parser.add_argument("--format", choices=["csv", "parquet"], default="csv", help="Export format (default: csv)")
+    parser.add_argument("--out", help="Export file (required for export)")
+    args = parser.parse_args(argv)
+
+    if args.action == "export" and not args.out:
+        parser.error("export requires --out")
+    if args.start > args.end:
+        parser.error("--from must not be later than --to")
+    if not Path(args.db).exists():
+        print(f"❌ No history database at {args.db} (record one with --history-db)", file=sys.stderr)
+        return 1
+
+    store = HistoryStore(args.db)
+    try:
+        network = NETWORKS[args.network]["name"] if args.network else None
+        samples = store.query(args.start, args.end, network=network)
+    finally:
+        store.close()
+
+    span = f"between {args.start.isoformat(timespec='seconds')} and {args.end.isoformat(timespec='seconds')}"
+    if not samples:
+        where = f" for {network}" if network else ""
+        print(f"❌ No history samples{where} {span}", file=sys.stderr)
+        return 1
+
+    if args.action == "export":
+        try:
+            if args.format == "parquet":
+                DataExporter.export_to_parquet(samples, args.out)
+            else:
+                DataExporter.export_samples_to_csv(samples, args.out)
+        except (ImportError, OSError, ValueError) as e:
+            print(f"❌ Export failed: {e}", file=sys.stderr)
+            return 1
+        print(f"✅ Exported {len(samples)} samples to {args.out}")
+        return 0
+
+    if args.stats:
+        try:
+            stats = GasStats.window_stats(samples, min_samples=args.min_samples)
+        except InsufficientHistoryError as e:
+            print(f"❌ Not enough data for statistics: {e}", file=sys.stderr)
+            return 1
+        print(f"Base fee (gwei) over {stats['count']} samples {span}:")
+        for key in ("min", "max", "mean", "median", "p25", "p75", "p95"):
+            print(f"  {key:<8}{stats[key]:>10.2f}")
+        return 0
+
+    print(f"{'Time':<20} {'Network':<12} {'Block':>10} {'Base fee':>10} {'Slow':>8} {'Standard':>9} {'Fast':>8}")
+    for sample in samples:
+        tiers = sample["tiers"]
+        cells = [sample["base_fee"], tiers["slow"], tiers["standard"], tiers["fast"]]
+        base, slow, standard, fast = ("-" if value is None else f"{value:.2f}" for value in cells)
+        block = "-" if sample["block_number"] is None else sample["block_number"]
+        print(f"{sample['timestamp'][:19]:<20} {sample['network']:<12} {block:>10} "
+              f"{base:>10} {slow:>8} {standard:>9} {fast:>8}")
+    return 0
 
 
 async def main():
     """Main entry point."""
+    if sys.argv[1:2] == ["history"]:
+        sys.exit(history_command(sys.argv[2:]))
+
     parser = argparse.ArgumentParser(
         description="ETH Gas Tracker - Multi-network gas price monitoring",
         formatter_class=argparse.RawDescriptionHelpFormatter,
@@ -191,6 +484,9 @@ Examples:
   # Watch Ethereum gas prices with alerts
   python -m ethgas.main --watch 10 --alert 30
 
+  # Live fee tier dashboard, redrawn in place every 5 seconds
+  python -m ethgas.main --watch --interval 5
+
   # Detailed view with graph and costs
   python -m ethgas.main --detailed --graph --show-costs
 
@@ -200,17 +496,27 @@ Examples:
   # JSON output for scripting
   python -m ethgas.main --json --show-costs
 
+  # Newline-delimited JSON / CSV quote stream
+  python -m ethgas.main --format json --watch 10
+  python -m ethgas.main --format csv --watch 10 > gas.csv
+
   # Start API server
   python -m ethgas.main --api --port 8080
+
+  # Statistics from a --history-db store (see: ethgas history --help)
+  python -m ethgas.main history --from 24h --stats
         """,
     )
 
     # Network options
+    parser.add_argument(
+        "--config",
+        help=f"TOML config file (default: {DEFAULT_CONFIG_PATH} if it exists)",
+    )
     parser.add_argument(
         "--network",
-        default="ethereum",
         choices=list(NETWORKS.keys()),
-        help="Network to monitor (default: ethereum)",
+        help="Network to monitor (default: from config, else ethereum)",
     )
     parser.add_argument(
         "--rpc", help="Custom RPC URL (overrides network default)"
@@ -219,9 +525,9 @@ Examples:
     # Gas parameters
     parser.add_argument(
         "--priority",
-        type=float,
+        type=parse_fee_gwei,
         default=1.5,
-        help="Priority tip in gwei (default: 1.5)",
+        help="Priority tip, in gwei unless a unit is given (e.g. 2gwei, 2000000000wei; default: 1.5)",
     )
 
     # Display options
@@ -250,21 +556,47 @@ Examples:
         action="store_true",
         help="Output in JSON format",
     )
+    parser.add_argument(
+        "--format",
+        default="human",
+        choices=["human", "json", "csv"],
+        help="Gas quote output format; json/csv emit one record per refresh (default: human)",
+    )
 
     # Watch mode
     parser.add_argument(
         "--watch",
         type=int,
+        nargs="?",
+        const=0,
         metavar="SECONDS",
-        help="Watch mode: update every N seconds",
+        help="Watch mode: update every N seconds (default: --interval)",
+    )
+    parser.add_argument(
+        "--interval",
+        type=int,
+        default=12,
+        metavar="SECONDS",
+        help="Refresh interval for --watch (default: 12)",
+    )
+    parser.add_argument(
+        "--no-color",
+        action="store_true",
+        help="Disable colors and in-place redraw (for piping to files)",
     )
 
     # Alerts
     parser.add_argument(
         "--alert",
-        type=float,
-        metavar="GWEI",
-        help="Alert when base fee drops below threshold (gwei)",
+        type=parse_fee_gwei,
+        metavar="FEE",
+        help="Alert when base fee drops below threshold (gwei unless a unit is given, e.g. 20gwei)",
+    )
+    parser.add_argument(
+        "--alert-rearm",
+        type=parse_fee_gwei,
+        metavar="FEE",
+        help="Base fee must rise above this before the alert can fire again (default: --alert)",
     )
     parser.add_argument(
         "--beep",
@@ -278,6 +610,11 @@ Examples:
         action="store_true",
         help="Save gas prices to history",
     )
+    parser.add_argument(
+        "--history-db",
+        metavar="PATH",
+        help="Record every fetched quote into a SQLite history database (committed in batches)",
+    )
     parser.add_argument(
         "--stats-hours",
         type=int,
@@ -392,6 +729,21 @@ Examples:
 
     args = parser.parse_args()
 
+    config = None
+    config_path = args.config or (DEFAULT_CONFIG_PATH if DEFAULT_CONFIG_PATH.exists() else None)
+    if config_path:
+        try:
+            config = Config.load(config_path)
+        except ConfigError as e:
+            print(f"❌ Config error: {e}", file=sys.stderr)
+            sys.exit(1)
+    args.network = args.network or (config.chain.network if config else "ethereum")
+
+    if args.watch is not None:
+        args.watch = args.watch or args.interval
+    if args.format == "json":
+        args.json = True
+
     # Web UI mode
     if args.web_ui:
         run_web_ui(host=args.host, port=args.port)
@@ -434,11 +786,14 @@ Examples:
     if args.predict:
         history = GasHistory()
         network_name = NETWORKS[args.network]["name"]
-        prediction = predict_gas_price(
-            history,
-            network=network_name,
-            method=args.predict_method
-        )
+        try:
+            prediction = predict_gas_price(
+                history,
+                network=network_name,
+                method=args.predict_method
+            )
+        except InsufficientHistoryError as e:
+            prediction = {"error": str(e), "have": e.have, "need": e.need}
 
         if args.json:
             print(json.dumps(prediction, indent=2))
@@ -465,11 +820,15 @@ Examples:
             (r.get("token_price_usd") for r in records if r.get("token_price_usd")),
             None,
         )
-        recommendations = predictor.suggest_fee_bands(
-            gas_units=gas_units,
-            token_price_usd=latest_token_price,
-            base_prediction_method=args.predict_method,
-        )
+        try:
+            recommendations = predictor.suggest_fee_bands(
+                gas_units=gas_units,
+                token_price_usd=latest_token_price,
+                base_prediction_method=args.predict_method,
+            )
+        except InsufficientHistoryError as e:
+            print(f"❌ Not enough history for smart fee bands: {e}")
+            return
 
         if args.json:
             print(json.dumps(recommendations, indent=2))
@@ -477,14 +836,37 @@ Examples:
             print(predictor.format_fee_bands(recommendations))
         return
 
-    # Get network config
-    network = NETWORKS[args.network]
-    rpc_url = args.rpc or network["rpc"]
-
     # Initialize components
-    tracker = GasTracker(rpc_url, network["coingecko_id"], network["name"])
+    if config:
+        # Command-line network and RPC take precedence over the file
+        config.chain.network = args.network
+        config.chain.rpc_url = args.rpc or config.chain.rpc_url
+        tracker = GasTracker.from_config(config)
+    else:
+        tracker = GasTracker.for_network(args.network, rpc_url=args.rpc)
+    if args.history_db:
+        tracker.with_history(HistoryStore(args.history_db, buffer_size=HISTORY_DB_BATCH))
+    stop_on_sigterm()
+    try:
+        await run_tracker(tracker, args)
+    finally:
+        # Runs on SIGTERM too, so the last batch of samples isn't lost
+        await tracker.flush()
+
+
+def stop_on_sigterm() -> None:
+    """Cancel the running main task on SIGTERM, so its cleanup runs before exit."""
+    task = asyncio.current_task()
+    try:
+        asyncio.get_running_loop().add_signal_handler(signal.SIGTERM, task.cancel)
+    except (NotImplementedError, AttributeError):
+        pass  # Windows event loops have no signal handlers; SIGTERM ends the process there
+
+
+async def run_tracker(tracker: GasTracker, args) -> None:
+    """Run the statistics, output or watch mode the arguments select."""
     history = GasHistory() if (args.history or args.detailed or args.advanced_stats) else None
-    alerts = GasAlerts(threshold=args.alert) if args.alert else None
+    alerts = GasAlerts(threshold=args.alert, rearm_above=args.alert_rearm) if args.alert else None
 
     # Initialize notifier
     notifier = None
@@ -506,29 +888,41 @@ Examples:
         if records:
             filtered = GasStats.filter_by_timeframe(records, args.stats_hours)
             if filtered:
-                advanced_stats = GasStats.calculate_advanced_stats(filtered)
-                if advanced_stats:
-                    print(GasStats.format_advanced_stats(advanced_stats))
-                else:
-                    print("❌ Not enough data for advanced statistics")
+                try:
+                    print(GasStats.format_advanced_stats(GasStats.calculate_advanced_stats(filtered)))
+                except InsufficientHistoryError as e:
+                    print(f"❌ Not enough data for advanced statistics: {e}")
             else:
                 print(f"❌ No data found for the last {args.stats_hours} hours")
         else:
             print("❌ No historical data available")
         return
 
+    # Machine-readable quote output
+    if args.format != "human":
+        await output_quotes(tracker, args)
+        return
+
     # Watch mode
     if args.watch:
         await watch_mode(tracker, args, history, alerts, notifier, webhook_manager)
     else:
         # Single check
-        async with aiohttp.ClientSession() as session:
+        async with make_session() as session:
             await track_once(tracker, session, args, history)
 
 
-if __name__ == "__main__":
+def run():
+    """Entry point of the `ethgas` console script: run main() in a new event loop."""
     try:
         asyncio.run(main())
     except KeyboardInterrupt:
         print("\n👋 Goodbye!")
         sys.exit(0)
+    except asyncio.CancelledError:
+        # Stopped by SIGTERM after flushing history
+        sys.exit(128 + signal.SIGTERM)
+
+
+if __name__ == "__main__":
+    run()
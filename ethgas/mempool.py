@@ -0,0 +1,117 @@
+"""Forward-looking priority fee estimates from the pending mempool."""
+
+import asyncio
+from typing import Dict, List, Optional, Tuple
+
+import aiohttp
+
+from .errors import DeserializeError, translate_errors
+from .tracing import read_json
+from .units import Gwei, Wei, parse_hex_quantity
+
+BLOCKNATIVE_API = "https://api.blocknative.com/gasprices/blockprices"
+
+# Blocknative publishes estimates at these confidence levels (percent)
+BLOCKNATIVE_CONFIDENCES = (99, 95, 90, 80, 70)
+
+
+def pending_priority_fees(txpool: Dict, base_fee: Wei) -> List[Tuple[Wei, int]]:
+    """
+    Get (effective priority fee, gas) pairs for pending transactions.
+
+    Transactions whose fee cap is below the next base fee cannot be included
+    in the next block and are left out.
+    """
+    fees = []
+    for txs_by_nonce in txpool.get("pending", {}).values():
+        for tx in txs_by_nonce.values():
+            gas = parse_hex_quantity(tx["gas"])
+            if tx.get("maxFeePerGas"):
+                max_fee = Wei.from_hex(tx["maxFeePerGas"])
+                tip = Wei.from_hex(tx["maxPriorityFeePerGas"])
+            else:
+                max_fee = tip = Wei.from_hex(tx["gasPrice"])
+            if max_fee < base_fee:
+                continue
+            fees.append((min(tip, max_fee - base_fee), gas))
+    return fees
+
+
+def inclusion_priority_fee(
+    fees: List[Tuple[Wei, int]],
+    gas_limit: int,
+    fill_ratio: float,
+    blocks: int,
+    target: float = 0.9,
+) -> Wei:
+    """
+    Priority fee needed to land within `blocks` blocks with roughly `target` probability.
+
+    Heuristic: the next block has room for a full gas limit of pending
+    transactions, and each later block only frees the space recent blocks left
+    unused (new arrivals are assumed to fill the rest, as they did recently).
+    Only `target` of that room is counted as ours, leaving a margin for
+    higher-paying transactions that arrive meanwhile.
+    """
+    if not fees:
+        return Wei(0)
+
+    room = gas_limit * (1 + (blocks - 1) * max(0.0, 1 - fill_ratio)) * target
+    ranked = sorted(fees, key=lambda fee: fee[0], reverse=True)
+
+    used = 0
+    for tip, gas in ranked:
+        used += gas
+        if used >= room:
+            # Outbid the marginal transaction by one wei
+            return tip + 1
+    # The whole eligible mempool fits, so the lowest pending tip is enough
+    return ranked[-1][0]
+
+
+def mean_fill_ratio(gas_used_ratios: List[float]) -> float:
+    """Average gas used / gas limit over recent blocks."""
+    return sum(gas_used_ratios) / len(gas_used_ratios) if gas_used_ratios else 0.0
+
+
+def confidence_for_blocks(target: float, blocks: int) -> float:
+    """Per-block inclusion probability that gives `target` over `blocks` independent tries."""
+    return 1 - (1 - target) ** (1 / blocks)
+
+
+def blocknative_bucket(estimated_prices: List[Dict], confidence: float) -> Optional[Dict]:
+    """Get the lowest published estimate with a confidence (percent) at or above `confidence`."""
+    eligible = [p for p in estimated_prices if p["confidence"] >= confidence]
+    return min(eligible, key=lambda p: p["confidence"]) if eligible else None
+
+
+def pick_blocknative_fee(estimated_prices: List[Dict], confidence: float) -> Optional[Wei]:
+    """Get the priority fee of the lowest published confidence at or above `confidence`."""
+    best = blocknative_bucket(estimated_prices, confidence * 100)
+    if best is None:
+        return None
+    return Gwei(best["maxPriorityFeePerGas"]).to_wei()
+
+
+async def fetch_blocknative(
+    session: aiohttp.ClientSession,
+    api_key: str,
+    chain_id: int = 1,
+    api_url: str = BLOCKNATIVE_API,
+    timeout: float = 15,
+) -> Dict:
+    """Fetch Blocknative's next-block price estimates."""
+    headers = {"Authorization": api_key}
+
+    async def get():
+        async with session.get(api_url, params={"chainid": chain_id}, headers=headers, timeout=timeout) as r:
+            r.raise_for_status()
+            return await read_json(r)
+
+    with translate_errors():
+        data = await asyncio.wait_for(get(), timeout)
+
+    with translate_errors():
+        if not data["blockPrices"]:
+            raise DeserializeError("Blocknative returned no block prices")
+        return data["blockPrices"][0]
@@ -0,0 +1,221 @@
+"""
+Gas metrics for monitoring: Prometheus text from GasMetrics, or StatsD
+gauges pushed over UDP by StatsdSink for StatsD and Datadog agents.
+"""
+
+import logging
+import socket
+import time
+from typing import AsyncIterator, Dict, List, Optional, Tuple
+
+import aiohttp
+
+from .units import Gwei
+from .watch import close_stream
+
+logger = logging.getLogger(__name__)
+
+# Default StatsD agent address and metric name prefix
+STATSD_HOST = "127.0.0.1"
+STATSD_PORT = 8125
+STATSD_PREFIX = "eth.gas"
+
+# Largest UDP payload StatsdSink sends, which stays within a 1500 byte
+# Ethernet MTU after IP and UDP headers
+STATSD_MAX_PACKET = 1432
+
+
+class GasMetrics:
+    """Keeps the latest gas quote per network and renders Prometheus text."""
+
+    def __init__(self):
+        self.quotes: Dict[str, Dict] = {}
+        self.updated_at: Dict[str, float] = {}
+        self.fetch_errors: Dict[str, int] = {}
+
+    def record_quote(self, quote: Dict) -> None:
+        """Store a freshly fetched quote as the current value for its network."""
+        network = quote.get("network", "unknown")
+        self.quotes[network] = quote
+        self.updated_at[network] = time.time()
+        self.fetch_errors.setdefault(network, 0)
+
+    def record_error(self, network: str) -> None:
+        """Count a failed fetch for a network."""
+        self.fetch_errors[network] = self.fetch_errors.get(network, 0) + 1
+
+    async def scrape(self, tracker, session: aiohttp.ClientSession) -> Dict:
+        """Fetch a quote through the tracker, recording either the quote or the failure."""
+        try:
+            quote = await tracker.current(session)
+        except Exception:
+            self.record_error(tracker.network_name)
+            raise
+
+        self.record_quote(quote)
+        return quote
+
+    def render_prometheus(self) -> str:
+        """Serialize all metrics in the Prometheus text exposition format."""
+        lines: List[str] = []
+
+        def gauge(name: str, help_text: str, samples: List[tuple], kind: str = "gauge"):
+            lines.append(f"# HELP {name} {help_text}")
+            lines.append(f"# TYPE {name} {kind}")
+            for labels, value in samples:
+                label_str = ",".join(f'{k}="{v}"' for k, v in labels.items())
+                lines.append(f"{name}{{{label_str}}} {value}")
+
+        base_fees = []
+        gas_prices = []
+        priority_fees = []
+        for network, quote in self.quotes.items():
+            base_fee = quote.get("base_fee")
+            if base_fee is not None:
+                base_fees.append(({"network": network}, float(base_fee)))
+            for tier, price in quote.get("tiers", {}).items():
+                gas_prices.append(({"network": network, "tier": tier}, float(price)))
+                if base_fee is not None:
+                    priority = max(0.0, float(price - base_fee))
+                    priority_fees.append(({"network": network, "tier": tier}, priority))
+
+        gauge("eth_gas_base_fee_gwei", "Current base fee in gwei.", base_fees)
+        gauge("eth_gas_price_gwei", "Recommended gas price per tier in gwei.", gas_prices)
+        gauge(
+            "eth_gas_priority_fee_gwei",
+            "Recommended priority fee per tier in gwei.",
+            priority_fees,
+        )
+        gauge(
+            "eth_gas_last_update_timestamp_seconds",
+            "Unix time of the last successful fetch.",
+            [({"network": n}, ts) for n, ts in self.updated_at.items()],
+        )
+        gauge(
+            "eth_gas_fetch_errors_total",
+            "Number of failed gas price fetches.",
+            [({"network": n}, count) for n, count in self.fetch_errors.items()],
+            kind="counter",
+        )
+
+        return "\n".join(lines) + "\n"
+
+
+class StatsdSink:
+    """
+    Push the gauges of each quote to a StatsD agent over UDP.
+
+        sink = StatsdSink(prefix="eth.gas.mainnet")
+        await sink.forward(tracker.watch(session))
+
+    Every quote becomes gauge lines like "eth.gas.base_fee:30|g" (see
+    gauges), batched into as few packets as STATSD_MAX_PACKET allows.
+    StatsD has no labels, so use one prefix per network. Sending is fire and
+    forget: a datagram that can't be sent is logged and dropped.
+    """
+
+    def __init__(
+        self,
+        host: str = STATSD_HOST,
+        port: int = STATSD_PORT,
+        prefix: str = STATSD_PREFIX,
+        max_packet_size: int = STATSD_MAX_PACKET,
+    ):
+        """
+        Initialize StatsD sink.
+
+        Args:
+            host: StatsD agent host
+            port: StatsD agent UDP port
+            prefix: Dotted prefix of every metric name; "" for none
+            max_packet_size: Largest datagram payload in bytes
+        """
+        if max_packet_size < 1:
+            raise ValueError("max_packet_size must be positive")
+        self.host = host
+        self.port = port
+        self.prefix = prefix.rstrip(".")
+        self.max_packet_size = max_packet_size
+        self._socket: Optional[socket.socket] = None
+        self._address: Optional[Tuple] = None
+
+    def __repr__(self) -> str:
+        return f"{type(self).__name__}(address={self.host}:{self.port}, prefix={self.prefix!r})"
+
+    def gauges(self, quote: Dict) -> List[str]:
+        """
+        Get a quote's StatsD gauge lines, in gwei.
+
+        base_fee (when known), gas_price, tier.<tier> for each tier price
+        and priority_fee.<tier> (the tier price above the base fee).
+        """
+        lines = []
+
+        def gauge(name: str, value) -> None:
+            name = f"{self.prefix}.{name}" if self.prefix else name
+            lines.append(f"{name}:{Gwei(float(value)).to_json()}|g")
+
+        base_fee = quote.get("base_fee")
+        if base_fee is not None:
+            gauge("base_fee", base_fee)
+        gauge("gas_price", quote["gas_price"])
+        for tier, price in quote.get("tiers", {}).items():
+            gauge(f"tier.{tier}", price)
+            if base_fee is not None:
+                gauge(f"priority_fee.{tier}", max(0.0, float(price) - float(base_fee)))
+        return lines
+
+    def packets(self, lines: List[str]) -> List[bytes]:
+        """Join lines into newline-separated payloads of at most max_packet_size bytes (a longer line goes alone)."""
+        packets: List[bytes] = []
+        current = b""
+        for line in lines:
+            data = line.encode()
+            if current and len(current) + 1 + len(data) > self.max_packet_size:
+                packets.append(current)
+                current = b""
+            current = current + b"\n" + data if current else data
+        if current:
+            packets.append(current)
+        return packets
+
+    def send(self, quote: Dict) -> int:
+        """
+        Send a quote's gauges.
+
+        Returns:
+            Number of datagrams sent
+        """
+        sent = 0
+        for packet in self.packets(self.gauges(quote)):
+            try:
+                if self._socket is None:
+                    self._open()
+                self._socket.sendto(packet, self._address)
+            except OSError as e:
+                logger.warning("StatsD send to %s:%s failed: %s", self.host, self.port, e)
+                continue
+            sent += 1
+        return sent
+
+    async def forward(self, stream: AsyncIterator[Dict]) -> None:
+        """Send every quote of a quote stream (e.g. GasTracker.watch), closing it when done or cancelled."""
+        try:
+            async for quote in stream:
+                if "error" in quote or "warning" in quote:
+                    continue
+                self.send(quote)
+        finally:
+            await close_stream(stream)
+
+    def _open(self) -> None:
+        family, kind, proto, _, address = socket.getaddrinfo(self.host, self.port, type=socket.SOCK_DGRAM)[0]
+        self._socket = socket.socket(family, kind, proto)
+        self._socket.setblocking(False)
+        self._address = address
+
+    def close(self) -> None:
+        """Close the UDP socket; the next send opens a new one."""
+        if self._socket is not None:
+            self._socket.close()
+            self._socket = None
@@ -0,0 +1,445 @@
+"""
+Typed shapes of the dictionaries the public API takes and returns.
+
+These are TypedDicts for type checkers and documentation only; the values
+are still plain dicts (TierEstimates is a dict with one helper method).
+They are open for extension: new keys may be added in minor releases (as
+"pricing_model" and "priority_fees" were), so read the keys you need and
+ignore the rest rather than matching on the full set.
+"""
+
+from dataclasses import dataclass
+from datetime import datetime
+from typing import Any, Dict, List, Optional, TypedDict, Union
+
+from .errors import ConfigError
+from .units import Gwei, Wei, parse_hex_quantity
+
+# Tier names: "slow", "standard" and "fast" everywhere, plus "urgent" from
+# BlocknativeProvider and "pNN" names for custom FeeHistoryConfig percentiles
+Tier = str
+
+# Display names of the standard tiers; other tiers are labelled with their name
+TIER_LABELS = {"slow": "Slow", "standard": "Standard", "fast": "Fast", "urgent": "Urgent"}
+
+
+class _GasQuoteRequired(TypedDict):
+    provider: str
+    block_number: Optional[int]
+    fetched_at: datetime
+    base_fee: Optional[Gwei]
+    gas_price: Gwei
+    pricing_model: str
+    tiers: Dict[Tier, Gwei]
+
+
+class GasQuote(_GasQuoteRequired, total=False):
+    """Quote from a GasProvider (see providers.make_quote); current() adds "network"."""
+
+    network: str
+    sources: List[str]
+    priority_fees: Dict[Tier, Gwei]
+    estimated_transaction_count: Optional[int]
+    # Mean gas used / gas limit over recent blocks, and core.Congestion from it
+    gas_used_ratio: float
+    congestion: str
+    # Unit of the tier prices when they aren't gwei, e.g. "micro-lamports/CU"
+    unit: str
+    # History samples blended into a GasTracker.smoothed_current quote
+    smoothed_samples: int
+    # Hash of block_number, from providers that see block headers (WsProvider)
+    block_hash: str
+    # True when the node has no eth_feeHistory and the base fee is the
+    # latest block's rather than the upcoming one's
+    degraded: bool
+    # Set by sanity.BaseFeeCheckProvider when the base fee jumped further than EIP-1559 allows
+    suspect: bool
+    suspect_reason: str
+    # Names of AggregateProvider members repeating one quote while the others move on
+    frozen: List[str]
+    # True when GasTracker.current() raised tiers to its min_priority_fee
+    floor_applied: bool
+
+
+class _TierFeesRequired(TypedDict):
+    max_priority_fee_per_gas: Wei
+    max_fee_per_gas: Wei
+
+
+class TierFees(_TierFeesRequired, total=False):
+    # True when the priority fee was raised to the tracker's min_priority_fee
+    floor_applied: bool
+    # True when the priority fee was lowered to the tracker's max_priority_ratio
+    priority_capped: bool
+
+
+class TierEstimate(TierFees):
+    """One tier of GasTracker.estimate_eip1559, with the reward percentile it was priced at."""
+
+    percentile: float
+    label: str
+
+
+class TierEstimates(Dict[Tier, TierEstimate]):
+    """
+    Tiers of an Eip1559Estimate by name, in configured order (slowest first by default).
+
+        for name, tier in estimate["tiers"].items():
+            print(f"{tier['label']}: {tier['max_fee_per_gas'].to_gwei():.2f} gwei (p{tier['percentile']:g})")
+        fast = estimate["tiers"].tier("fast")
+    """
+
+    def tier(self, name: Tier) -> TierEstimate:
+        """Get a tier by name, raising ConfigError listing the available ones if it's missing."""
+        if name not in self:
+            raise ConfigError(f"Unknown tier '{name}' (available: {', '.join(self)})")
+        return self[name]
+
+
+class _FeeHistoryRequired(TypedDict):
+    baseFeePerGas: List[str]
+
+
+class FeeHistory(_FeeHistoryRequired, total=False):
+    """
+    eth_feeHistory result as the node returns it, with hex quantity strings.
+
+    For N blocks starting at oldestBlock, baseFeePerGas has N + 1 entries
+    (the last is the upcoming block's base fee), gasUsedRatio has N, and
+    reward has N rows with one entry per requested reward percentile, in
+    ascending percentile order. A block without transactions may have an
+    empty reward row; it reuses the previous block's rewards.
+    """
+
+    oldestBlock: str
+    gasUsedRatio: List[float]
+    reward: List[List[str]]
+    # Cancun nodes only, shaped like baseFeePerGas and gasUsedRatio
+    baseFeePerBlobGas: List[str]
+    blobGasUsedRatio: List[float]
+    # Set on a history approximated from the latest block (see core.fee_history_from_block)
+    degraded: bool
+
+
+class _Eip1559EstimateRequired(TypedDict):
+    network: str
+    base_fee_per_gas: Wei
+    base_fee_history: List[Wei]
+    tiers: TierEstimates
+    block_number: Optional[int]
+
+
+class Eip1559Estimate(_Eip1559EstimateRequired, total=False):
+    """Result of GasTracker.estimate_eip1559."""
+
+    # True when the node has no eth_feeHistory and fees were approximated
+    # from the latest block (see core.fee_history_from_block)
+    degraded: bool
+
+
+class FeeChange(TypedDict):
+    """Change of one fee between two snapshots, in wei."""
+
+    old: Wei
+    new: Wei
+    change: Wei
+    # None when the old value was zero and the new one isn't
+    change_pct: Optional[float]
+
+
+class SnapshotDiff(TypedDict):
+    """Result of diff.diff_snapshots."""
+
+    network: str
+    block_delta: Optional[int]
+    base_fee: FeeChange
+    # Base fee plus the tier's priority fee, for tiers in both snapshots
+    tiers: Dict[Tier, FeeChange]
+    # Whether the base fee moved to or above / below threshold_gwei
+    crossed_up: bool
+    crossed_down: bool
+
+
+class _FeeRecommendationRequired(_TierFeesRequired):
+    block_number: int
+
+
+class FeeRecommendation(_FeeRecommendationRequired, total=False):
+    """Result of GasTracker.recommend."""
+
+    # True when the priority fee was raised to the tracker's min_priority_fee
+    floor_applied: bool
+    # True when the priority fee was lowered to the tracker's max_priority_ratio
+    priority_capped: bool
+    # True when the node has no eth_feeHistory and fees were approximated
+    # from the latest block
+    degraded: bool
+    # hybrid_estimate only: "hybrid", or "fee_history" without a mempool
+    # source, and the share of the mempool estimate in the priority fee
+    source: str
+    mempool_weight: float
+
+
+@dataclass
+class BudgetDecision:
+    """
+    Result of GasTracker.check_budget.
+
+    Truthy when the tier is within the ceiling, in which case
+    `recommendation` holds fees for that tier with max_fee_per_gas capped
+    at the ceiling.
+    """
+
+    allowed: bool
+    tier: Tier
+    current: Gwei
+    ceiling: float
+    quote: GasQuote
+    recommendation: Optional[FeeRecommendation] = None
+
+    def __bool__(self) -> bool:
+        return self.allowed
+
+
+class TxFeeFields(TypedDict, total=False):
+    """
+    Fee fields of an eth_sendTransaction object, as hex quantities in wei (see core.tx_fee_fields).
+
+    EIP-1559 chains get maxFeePerGas and maxPriorityFeePerGas, legacy
+    chains gasPrice; "gas", the gas limit, only when one is given.
+    """
+
+    maxFeePerGas: str
+    maxPriorityFeePerGas: str
+    gasPrice: str
+    gas: str
+
+
+class BlobFee(TypedDict):
+    """Result of core.blob_fee_from_history and GasTracker.blob_fee_estimate; fees in wei."""
+
+    base_fee_per_blob_gas: Wei
+    max_fee_per_blob_gas: Wei
+    blob_gas_used_ratio: float
+    blobs: int
+    blob_gas: int
+    # Blob gas cost at max_fee_per_blob_gas, the most the blobs can cost
+    max_cost_wei: Wei
+    block_number: Optional[int]
+
+
+class DeploymentCost(TypedDict):
+    """Result of GasTracker.estimate_deployment; gas per core.deployment_gas."""
+
+    tier: str
+    initcode_size: int
+    intrinsic_gas: int
+    code_deposit_gas: int
+    # Intrinsic plus code deposit gas; constructor execution comes on top
+    gas_limit: int
+    gas_price_gwei: Gwei
+    cost_gwei: Gwei
+    cost_wei: Wei
+    cost_eth: float
+    cost_usd: Optional[float]
+
+
+class BatchCost(TypedDict):
+    """Result of GasTracker.estimate_batch; totals over every transaction in the batch."""
+
+    network: str
+    tier: str
+    # estimate_tx_cost result of each transaction, in batch order
+    transactions: List[Dict]
+    total_gas: int
+    total_cost_gwei: Gwei
+    total_cost_wei: Wei
+    total_cost_eth: float
+    total_cost_usd: Optional[float]
+    token_price_usd: Optional[float]
+    # Block of the quote every transaction was priced with (None for an empty batch)
+    block_number: Optional[int]
+
+
+class ObservedResponse(TypedDict):
+    """One fetch seen by an audit.ObservedProvider."""
+
+    provider: str
+    observed_at: datetime
+    # Raw response bodies in the order they were read
+    bodies: List[bytes]
+    # The quote built from them, or None and the error if the fetch failed
+    quote: Optional[GasQuote]
+    error: Optional[str]
+
+
+class Histogram(TypedDict):
+    """Result of core.priority_fee_histogram and GasTracker.priority_fee_histogram."""
+
+    # len(counts) + 1 ascending edges (wei); bucket i covers edges[i] up to
+    # edges[i + 1], the last one including its upper edge
+    edges: List[Wei]
+    counts: List[int]
+    samples: int
+    blocks: int
+
+
+class Overpayment(TypedDict):
+    """Result of receipts.overpayment and GasTracker.overpayment_for_tx."""
+
+    gas_used: int
+    max_fee_per_gas: Wei
+    effective_gas_price: Wei
+    authorized_wei: Wei
+    paid_wei: Wei
+    overpaid_wei: Wei
+    overpaid_eth: float
+    overpaid_pct: float
+
+
+class PriceQuote(TypedDict):
+    """Result of GasTracker.eth_price and prices.MedianPriceFeed.quote."""
+
+    price_usd: float
+    # Feeds the price was taken from, and why the others were left out
+    sources: List[str]
+    rejected: Dict[str, str]
+
+
+class ProviderHealth(TypedDict):
+    """Entry of providers.check_health and GasTracker.check_provider_health."""
+
+    provider: str
+    status: str  # providers.ProviderStatus
+    block_number: Optional[int]
+    blocks_behind: Optional[int]
+    latency_ms: float
+    error: Optional[str]
+
+
+class ProviderCounters(TypedDict):
+    """Entry of TrackerStatus["providers"]."""
+
+    provider: str
+    consecutive_failures: int
+    circuit_state: Optional[str]  # circuit.CircuitState value, None without a breaker
+
+
+class TrackerStatus(TypedDict):
+    """Result of GasTracker.status."""
+
+    network: str
+    last_success_at: Optional[datetime]
+    block_number: Optional[int]
+    cache_hit_rate: Optional[float]
+    providers: List[ProviderCounters]
+
+
+class HourStat(TypedDict):
+    """One UTC hour of day in GasTracker.cheapest_hours / GasStats.cheapest_hours."""
+
+    hour: int
+    samples: int
+    # Base fee statistics in gwei; None when the hour has too few samples
+    mean_base_fee: Optional[float]
+    median_base_fee: Optional[float]
+    sufficient: bool
+
+
+class _ChainRequired(TypedDict):
+    name: str
+    rpc: str
+    chain_id: int
+    coingecko_id: str
+    explorer: str
+
+
+class Chain(_ChainRequired, total=False):
+    """Entry of networks.NETWORKS (built in or added with register_network)."""
+
+    l1_fee_model: str
+    # Missing means EIP-1559 support is detected from the latest block
+    eip1559: bool
+    # Lowest priority fee recommended on the chain, in gwei (see GasTracker.with_min_priority_fee)
+    min_priority_fee_gwei: float
+    # Highest priority fee recommended, as a multiple of the base fee (see GasTracker.with_max_priority_ratio)
+    max_priority_ratio: float
+
+
+class SolanaChain(TypedDict):
+    """Entry of networks.SOLANA_NETWORKS."""
+
+    name: str
+    rpc: str
+    coingecko_id: str
+    explorer: str
+
+
+class SolanaFee(TypedDict):
+    """Result of SolanaProvider.estimate_solana_tx_fee; fees in lamports."""
+
+    compute_units: int
+    target_slots: int
+    micro_lamports_per_cu: int
+    base_fee: int
+    priority_fee: int
+    total: int
+
+
+# Standard transaction fields (JSON-RPC, wallets, ethers) that from_dict()
+# skips: the gas limit being estimated, fee and signing fields, and where a
+# mined transaction was included
+IGNORED_TX_FIELDS = frozenset({
+    "gas", "gasLimit", "nonce", "chainId", "type", "gasPrice", "maxFeePerGas", "maxPriorityFeePerGas",
+    "maxFeePerBlobGas", "accessList", "blobVersionedHashes", "authorizationList", "hash", "blockHash",
+    "blockNumber", "transactionIndex", "v", "r", "s", "yParity",
+})
+
+
+@dataclass
+class TransactionRequest:
+    """
+    Transaction to estimate gas for with eth_estimateGas.
+
+    from_dict() accepts the usual JSON-RPC field names ("from", "to",
+    "data" or "input", "value" as an int or hex string), so a full
+    transaction object from a wallet or eth_getTransactionByHash can be
+    passed as it is: its other standard fields (IGNORED_TX_FIELDS) are
+    skipped.
+    """
+
+    to: Optional[str] = None
+    from_address: Optional[str] = None
+    data: Optional[str] = None
+    value: int = 0
+
+    @classmethod
+    def from_dict(cls, tx: Dict[str, Any]) -> "TransactionRequest":
+        """
+        Build a request from a JSON-style transaction object.
+
+        Raises:
+            ValueError: If the object has fields that aren't transaction fields
+        """
+        unknown = set(tx) - {"from", "to", "data", "input", "value"} - IGNORED_TX_FIELDS
+        if unknown:
+            raise ValueError(f"Unknown transaction fields: {', '.join(sorted(unknown))}")
+        value: Union[int, str] = tx.get("value", 0)
+        if isinstance(value, str):
+            value = parse_hex_quantity(value)
+        return cls(
+            to=tx.get("to"),
+            from_address=tx.get("from"),
+            data=tx.get("data", tx.get("input")),
+            value=value,
+        )
+
+    def to_rpc(self) -> Dict[str, str]:
+        """Get the eth_estimateGas call object (fields that are unset are left out)."""
+        if self.value < 0:
+            raise ValueError("value must not be negative")
+        call = {"from": self.from_address, "to": self.to, "data": self.data}
+        call = {key: value for key, value in call.items() if value is not None}
+        if self.value:
+            call["value"] = hex(self.value)
+        return call
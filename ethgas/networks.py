@@ -1,5 +1,12 @@
 """Network configurations for different blockchains."""
 
+from typing import Optional
+
+from .errors import ConfigError
+
+# Values of a network's "l1_fee_model" (see GasTracker.estimate_l2_cost)
+L1_FEE_MODELS = ("op-stack", "arbitrum")
+
 NETWORKS = {
     "ethereum": {
         "name": "Ethereum",
@@ -7,6 +14,7 @@ NETWORKS = {
         "chain_id": 1,
         "coingecko_id": "ethereum",
         "explorer": "https://etherscan.io",
+        "min_priority_fee_gwei": 1.0,
     },
     "polygon": {
         "name": "Polygon",
@@ -14,6 +22,8 @@ NETWORKS = {
         "chain_id": 137,
         "coingecko_id": "matic-network",
         "explorer": "https://polygonscan.com",
+        # Bor nodes drop transactions tipping less than this
+        "min_priority_fee_gwei": 25.0,
     },
     "arbitrum": {
         "name": "Arbitrum One",
@@ -21,6 +31,7 @@ NETWORKS = {
         "chain_id": 42161,
         "coingecko_id": "ethereum",  # ARB использует ETH для газа
         "explorer": "https://arbiscan.io",
+        "l1_fee_model": "arbitrum",
     },
     "optimism": {
         "name": "Optimism",
@@ -28,6 +39,7 @@ NETWORKS = {
         "chain_id": 10,
         "coingecko_id": "ethereum",  # OP использует ETH для газа
         "explorer": "https://optimistic.etherscan.io",
+        "l1_fee_model": "op-stack",
     },
     "bsc": {
         "name": "BNB Smart Chain",
@@ -42,6 +54,7 @@ NETWORKS = {
         "chain_id": 8453,
         "coingecko_id": "ethereum",  # Base использует ETH для газа
         "explorer": "https://basescan.org",
+        "l1_fee_model": "op-stack",
     },
     "zksync": {
         "name": "zkSync Era",
@@ -59,10 +72,98 @@ NETWORKS = {
     },
 }
 
+# Solana clusters, kept apart from NETWORKS: they have no chain ID or gas
+# price and are quoted by solana.SolanaProvider in micro-lamports per compute unit
+SOLANA_NETWORKS = {
+    "solana": {
+        "name": "Solana",
+        "rpc": "https://api.mainnet-beta.solana.com",
+        "coingecko_id": "solana",
+        "explorer": "https://explorer.solana.com",
+    },
+    "solana-devnet": {
+        "name": "Solana Devnet",
+        "rpc": "https://api.devnet.solana.com",
+        "coingecko_id": "solana",
+        "explorer": "https://explorer.solana.com/?cluster=devnet",
+    },
+}
+
+
+def register_network(
+    network_id: str,
+    name: str,
+    rpc: str,
+    chain_id: int,
+    coingecko_id: str = "ethereum",
+    explorer: str = "",
+    l1_fee_model: Optional[str] = None,
+    eip1559: bool = True,
+    min_priority_fee_gwei: Optional[float] = None,
+    max_priority_ratio: Optional[float] = None,
+    replace: bool = False,
+) -> dict:
+    """
+    Add a network to NETWORKS at runtime, e.g. an L2 released after this version.
+
+    Registered networks work everywhere a built-in network ID does
+    (GasTracker.for_network, compare_chains, config files).
+
+    Args:
+        network_id: Key to register the network under
+        name: Display name
+        rpc: Default JSON-RPC endpoint
+        chain_id: Expected eth_chainId of the endpoint
+        coingecko_id: CoinGecko ID of the gas token
+        explorer: Block explorer URL
+        l1_fee_model: "op-stack", "arbitrum" or None for no L1 data fee
+        eip1559: False for legacy chains, which skips base fee detection
+        min_priority_fee_gwei: Lowest priority fee trackers for the chain recommend
+        max_priority_ratio: Highest priority fee they recommend, as a multiple of the base fee
+        replace: Allow overwriting an existing entry
+
+    Returns:
+        The new NETWORKS entry
+
+    Raises:
+        ConfigError: If the ID is taken or a field is invalid
+    """
+    if network_id in NETWORKS and not replace:
+        raise ConfigError(f"Network '{network_id}' is already registered")
+    if not isinstance(chain_id, int) or isinstance(chain_id, bool) or chain_id <= 0:
+        raise ConfigError("chain_id must be a positive integer")
+    if l1_fee_model is not None and l1_fee_model not in L1_FEE_MODELS:
+        raise ConfigError(f"l1_fee_model must be one of {', '.join(L1_FEE_MODELS)} or None")
+    if not rpc:
+        raise ConfigError("rpc must not be empty")
+    if min_priority_fee_gwei is not None and not min_priority_fee_gwei >= 0:
+        raise ConfigError("min_priority_fee_gwei must not be negative")
+    if max_priority_ratio is not None and not max_priority_ratio > 0:
+        raise ConfigError("max_priority_ratio must be positive")
+
+    network = {
+        "name": name,
+        "rpc": rpc,
+        "chain_id": chain_id,
+        "coingecko_id": coingecko_id,
+        "explorer": explorer,
+        "eip1559": eip1559,
+    }
+    if l1_fee_model:
+        network["l1_fee_model"] = l1_fee_model
+    if min_priority_fee_gwei is not None:
+        network["min_priority_fee_gwei"] = float(min_priority_fee_gwei)
+    if max_priority_ratio is not None:
+        network["max_priority_ratio"] = float(max_priority_ratio)
+    NETWORKS[network_id] = network
+    return network
+
+
 # Gas costs for different transaction types (in gas units)
 TX_TYPES = {
     "simple": {"gas": 21000, "name": "Simple Transfer"},
     "erc20": {"gas": 65000, "name": "ERC-20 Transfer"},
+    "erc20_approve": {"gas": 46000, "name": "ERC-20 Approve"},
     "swap": {"gas": 150000, "name": "DEX Swap"},
     "nft_mint": {"gas": 100000, "name": "NFT Mint"},
     "nft_transfer": {"gas": 85000, "name": "NFT Transfer"},
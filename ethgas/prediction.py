@@ -1,16 +1,36 @@
-"""Gas price prediction based on historical data."""
+"""
+Gas price prediction based on historical data.
+
+Extrapolating from a few points gives confident-looking nonsense, so
+predict_next_hour, predict and suggest_fee_bands raise
+InsufficientHistoryError (with the `have` and `need` counts) when the
+records they would use number fewer than the predictor's `min_samples`,
+MIN_PREDICTION_SAMPLES (30) by default.
+"""
 import statistics
 from datetime import datetime, timedelta
 from typing import List, Dict, Optional, Tuple
 from dateutil import parser as date_parser
 
+from .stats import require_samples
+
+# Fewest records the predictions are made from
+MIN_PREDICTION_SAMPLES = 30
+
 
 class GasPredictor:
     """Predict future gas prices using historical data."""
 
-    def __init__(self, historical_records: List[Dict]):
-        """Initialize predictor with historical data."""
+    def __init__(self, historical_records: List[Dict], min_samples: int = MIN_PREDICTION_SAMPLES):
+        """
+        Initialize predictor with historical data.
+
+        Args:
+            historical_records: Gas price records, in any order
+            min_samples: Fewest records a prediction is made from (at least 3)
+        """
         self.records = historical_records
+        self.min_samples = max(min_samples, 3)
         self._prepare_data()
 
     def _prepare_data(self):
@@ -76,12 +96,11 @@ class GasPredictor:
 
         Returns:
             Dictionary with predicted values
+
+        Raises:
+            InsufficientHistoryError: If there are fewer than `min_samples` records
         """
-        if len(self.records) < 3:
-            return {
-                "error": "Insufficient data for prediction (need at least 3 records)",
-                "confidence": 0
-            }
+        require_samples(len(self.records), self.min_samples)
 
         if method == "moving_average":
             return self._predict_moving_average()
@@ -92,6 +111,88 @@ class GasPredictor:
         else:
             return {"error": f"Unknown prediction method: {method}"}
 
+    def predict(
+        self,
+        horizon: timedelta = timedelta(minutes=30),
+        window: timedelta = timedelta(hours=2),
+        stable_pct: float = 5.0,
+    ) -> Dict:
+        """
+        Extrapolate the recent base fee trend `horizon` into the future.
+
+        This is a heuristic: it fits a straight line over the base fees of the
+        last `window` against sample time and assumes the trend simply carries
+        on. Gas prices react to demand spikes nobody can see coming, so treat
+        the result as a rough "likely to drop or rise" signal only.
+
+        Args:
+            horizon: How far ahead to predict
+            window: How much recent history the line is fitted to
+            stable_pct: Predicted change (percent) below which the direction is 'stable'
+
+        Returns:
+            Dictionary with the predicted base fee, a ~95% interval around it and
+            a direction of 'rising', 'falling' or 'stable'
+
+        Raises:
+            InsufficientHistoryError: If fewer than `min_samples` records with
+                a base fee fall within the window
+        """
+        samples = [
+            (r["timestamp_parsed"], r["base_fee"])
+            for r in self.records
+            if r.get("timestamp_parsed") and r.get("base_fee") is not None
+        ]
+        if samples:
+            cutoff = samples[-1][0] - window
+            samples = [s for s in samples if s[0] >= cutoff]
+        require_samples(len(samples), self.min_samples)
+
+        # Hours since the first sample keeps the numbers well conditioned
+        start = samples[0][0]
+        x = [(t - start).total_seconds() / 3600 for t, _ in samples]
+        y = [float(fee) for _, fee in samples]
+        n = len(samples)
+
+        x_mean = statistics.mean(x)
+        y_mean = statistics.mean(y)
+        sxx = sum((xi - x_mean) ** 2 for xi in x)
+        if sxx == 0:
+            return {"error": "Samples share a single timestamp"}
+
+        slope = sum((x[i] - x_mean) * (y[i] - y_mean) for i in range(n)) / sxx
+        intercept = y_mean - slope * x_mean
+
+        x_now = x[-1]
+        x_future = x_now + horizon.total_seconds() / 3600
+        current = slope * x_now + intercept
+        predicted = max(0.0, slope * x_future + intercept)
+
+        # Prediction interval from the residual spread (normal approximation)
+        residuals = [y[i] - (slope * x[i] + intercept) for i in range(n)]
+        stderr = (sum(r ** 2 for r in residuals) / (n - 2)) ** 0.5 if n > 2 else 0.0
+        margin = 1.96 * stderr * (1 + 1 / n + (x_future - x_mean) ** 2 / sxx) ** 0.5
+
+        change_pct = (predicted - current) / current * 100 if current > 0 else 0.0
+        if change_pct > stable_pct:
+            direction = "rising"
+        elif change_pct < -stable_pct:
+            direction = "falling"
+        else:
+            direction = "stable"
+
+        return {
+            "method": "linear_trend",
+            "horizon_minutes": round(horizon.total_seconds() / 60, 1),
+            "predicted_base_fee": round(predicted, 2),
+            "lower_bound": round(max(0.0, predicted - margin), 2),
+            "upper_bound": round(predicted + margin, 2),
+            "direction": direction,
+            "change_pct": round(change_pct, 1),
+            "slope_per_hour": round(slope, 4),
+            "sample_size": n,
+        }
+
     def suggest_fee_bands(
         self,
         gas_units: int = 21_000,
@@ -108,16 +209,16 @@ class GasPredictor:
 
         Returns:
             Dictionary containing fee bands with confidence scores
+
+        Raises:
+            InsufficientHistoryError: If there are fewer than `min_samples` records
         """
-        if len(self.records) < 5:
-            return {"error": "Need at least 5 records to recommend fees"}
+        require_samples(len(self.records), self.min_samples)
 
         prediction = self.predict_next_hour(method=base_prediction_method)
         if "error" in prediction:
-            # Fall back to moving average if preferred method fails
+            # Fall back to moving average (which always predicts) if preferred method fails
             prediction = self.predict_next_hour(method="moving_average")
-            if "error" in prediction:
-                return prediction
 
         base_fees = [r.get("base_fee", 0) for r in self.records[-50:]]
         priority_tips = [r.get("priority_tip", 0) for r in self.records[-50:]]
@@ -405,7 +506,8 @@ class GasPredictor:
 
 def predict_gas_price(history_manager,
                      network: Optional[str] = None,
-                     method: str = "moving_average") -> Dict:
+                     method: str = "moving_average",
+                     min_samples: int = MIN_PREDICTION_SAMPLES) -> Dict:
     """
     Convenience function for gas price prediction.
 
@@ -413,14 +515,14 @@ def predict_gas_price(history_manager,
         history_manager: GasHistory instance
         network: Filter by network (optional)
         method: Prediction method
+        min_samples: Fewest records to predict from
 
     Returns:
         Prediction dictionary
+
+    Raises:
+        InsufficientHistoryError: If the history has fewer than `min_samples` records
     """
     records = history_manager.get_records(network=network, limit=100)
-
-    if not records:
-        return {"error": "No historical data available"}
-
-    predictor = GasPredictor(records)
+    predictor = GasPredictor(records, min_samples=min_samples)
     return predictor.predict_next_hour(method=method)
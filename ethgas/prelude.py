@@ -0,0 +1,28 @@
+"""
+The names most scripts need, for a single star import:
+
+    from ethgas.prelude import *
+
+    tracker = GasTracker.for_network("ethereum")
+    quote: GasQuote = await tracker.current(tracker.http_session())
+    await tracker.close_session()
+
+These are exactly the names `ethgas` exports: the tracker, the provider
+interface, the common quote and estimate shapes and the base error.
+Everything else is imported from its module.
+"""
+
+from .errors import GasError
+from .models import Chain, Eip1559Estimate, GasQuote, Tier
+from .providers import GasProvider
+from .tracker import GasTracker
+
+__all__ = [
+    "GasTracker",
+    "GasProvider",
+    "GasQuote",
+    "Eip1559Estimate",
+    "Tier",
+    "Chain",
+    "GasError",
+]
@@ -0,0 +1,229 @@
+"""Fiat price feeds for converting gas costs to USD."""
+
+import asyncio
+from abc import ABC, abstractmethod
+from typing import Dict, List, Optional
+
+import aiohttp
+
+from .clock import SYSTEM_CLOCK, Clock
+from .errors import ConfigError, DeserializeError, GasError, NoPriceConsensusError, StalePriceError, translate_errors
+from .models import PriceQuote
+from .providers import DEFAULT_REQUEST_TIMEOUT, JsonRpcProvider, response_result, weighted_median
+
+COINGECKO_API = "https://api.coingecko.com/api/v3/simple/price"
+
+# Chainlink ETH/USD aggregator proxy on Ethereum mainnet
+CHAINLINK_ETH_USD = "0x5f4eC3Df9cbd43714FE2740f5E3616155c5b8419"
+
+# latestRoundData() and decimals() selectors of Chainlink's AggregatorV3Interface
+LATEST_ROUND_DATA_SELECTOR = "0xfeaf968c"
+DECIMALS_SELECTOR = "0x313ce567"
+
+# Most decimals() a feed answer may be scaled by; Chainlink feeds use 8 or 18, and a
+# larger value is garbage that would overflow the float division
+MAX_FEED_DECIMALS = 36
+
+# ETH/USD updates at least hourly (its heartbeat); older rounds mean the feed is stuck
+DEFAULT_MAX_STALENESS = 3600.0
+
+# Percent a feed may differ from the median of all feeds before it's left out
+DEFAULT_MAX_PRICE_DEVIATION_PCT = 2.0
+
+
+class PriceFeed(ABC):
+    """Source of a token's USD price."""
+
+    name = "price-feed"
+
+    @abstractmethod
+    async def get_price_usd(self, session: aiohttp.ClientSession) -> float:
+        """Fetch the current USD price of the token."""
+
+
+class CoinGeckoPriceFeed(PriceFeed):
+    """USD price from the CoinGecko simple price API."""
+
+    name = "coingecko"
+
+    def __init__(self, coin_id: str = "ethereum", api_url: str = COINGECKO_API):
+        self.coin_id = coin_id
+        self.api_url = api_url
+
+    async def get_price_usd(self, session: aiohttp.ClientSession) -> float:
+        """Fetch the USD price for the configured coin."""
+        url = f"{self.api_url}?ids={self.coin_id}&vs_currencies=usd"
+        async with session.get(url, timeout=10) as r:
+            r.raise_for_status()
+            data = await r.json()
+        return float(data[self.coin_id]["usd"])
+
+
+def _words(data: str, count: int) -> list:
+    """Split ABI-encoded return data into `count` 32-byte words as unsigned ints."""
+    raw = bytes.fromhex(data[2:] if data.startswith("0x") else data)
+    if len(raw) < 32 * count:
+        raise DeserializeError(f"Expected {count} ABI words, got {len(raw)} bytes")
+    return [int.from_bytes(raw[i * 32:(i + 1) * 32], "big") for i in range(count)]
+
+
+class ChainlinkPriceFeed(PriceFeed):
+    """
+    USD price read from a Chainlink aggregator with eth_call, needing only an RPC endpoint.
+
+    latestRoundData() and decimals() are requested in one batch. A round
+    whose updatedAt is more than `max_staleness` seconds old raises
+    StalePriceError rather than pricing costs with an outdated answer.
+    """
+
+    name = "chainlink"
+
+    def __init__(
+        self,
+        rpc_url: str,
+        aggregator: str = CHAINLINK_ETH_USD,
+        max_staleness: float = DEFAULT_MAX_STALENESS,
+        headers: Optional[Dict[str, str]] = None,
+        timeout: float = DEFAULT_REQUEST_TIMEOUT,
+        clock: Optional[Clock] = None,
+    ):
+        """
+        Initialize Chainlink price feed.
+
+        Args:
+            rpc_url: JSON-RPC endpoint of the chain the aggregator is on
+            aggregator: Aggregator (proxy) address (default: ETH/USD on mainnet)
+            max_staleness: Oldest round accepted, in seconds
+            headers: Extra headers sent with the RPC calls
+            timeout: Seconds allowed for each RPC request
+            clock: Time source for the staleness check (default: system clock)
+        """
+        if max_staleness <= 0:
+            raise ValueError("max_staleness must be positive")
+        self.rpc = JsonRpcProvider(rpc_url, name=self.name, headers=headers, timeout=timeout)
+        self.aggregator = aggregator
+        self.max_staleness = max_staleness
+        self.clock = clock or SYSTEM_CLOCK
+
+    def _call(self, selector: str) -> tuple:
+        return ("eth_call", [{"to": self.aggregator, "data": selector}, "latest"])
+
+    async def get_price_usd(self, session: aiohttp.ClientSession) -> float:
+        """Fetch the latest round's answer, scaled by the aggregator's decimals."""
+        round_data, decimals = await self.rpc.batch(
+            session, [self._call(LATEST_ROUND_DATA_SELECTOR), self._call(DECIMALS_SELECTOR)]
+        )
+        with translate_errors():
+            # (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound)
+            _, answer, _, updated_at, _ = _words(response_result(round_data), 5)
+            (scale,) = _words(response_result(decimals), 1)
+        if scale > MAX_FEED_DECIMALS:
+            raise DeserializeError(f"Chainlink aggregator {self.aggregator} reports {scale} decimals")
+        if answer >= 2 ** 255:
+            answer -= 2 ** 256
+        if answer <= 0:
+            raise GasError(f"Chainlink aggregator {self.aggregator} returned a non-positive answer ({answer})")
+
+        age = self.clock.now().timestamp() - updated_at
+        if age > self.max_staleness:
+            raise StalePriceError(self.name, age, self.max_staleness)
+        return answer / 10 ** scale
+
+
+class MedianPriceFeed(PriceFeed):
+    """
+    USD price agreed on by several feeds, so one failing or wrong feed can't skew costs.
+
+    Every feed is asked concurrently. Feeds that raise are left out, and so
+    are those more than `max_deviation_pct` percent from the weighted median
+    of all the answers; the price is then the weighted median of the feeds
+    that remain. Each feed has a weight (1.0 unless given in
+    `weights` or added with with_feed), so, say, Chainlink can outvote two
+    exchange APIs. quote() also reports which feeds were used and why the
+    others weren't.
+    """
+
+    name = "median"
+
+    def __init__(
+        self,
+        feeds: List[PriceFeed],
+        weights: Optional[List[float]] = None,
+        max_deviation_pct: float = DEFAULT_MAX_PRICE_DEVIATION_PCT,
+    ):
+        """
+        Initialize median price feed.
+
+        Args:
+            feeds: Feeds to combine, with distinct names
+            weights: Weight of each feed (default: 1.0 each)
+            max_deviation_pct: Most a feed may differ from the median, in percent
+
+        Raises:
+            ConfigError: If there are no feeds, a name repeats, or a weight
+                or max_deviation_pct isn't positive
+        """
+        if not feeds:
+            raise ConfigError("MedianPriceFeed needs at least one feed")
+        if max_deviation_pct <= 0:
+            raise ConfigError("max_deviation_pct must be positive")
+        if weights is None:
+            weights = [1.0] * len(feeds)
+        if len(weights) != len(feeds):
+            raise ConfigError("MedianPriceFeed needs one weight per feed")
+        self.feeds: List[PriceFeed] = []
+        self.weights: List[float] = []
+        self.max_deviation_pct = max_deviation_pct
+        for feed, weight in zip(feeds, weights):
+            self.with_feed(feed, weight)
+
+    def with_feed(self, feed: PriceFeed, weight: float = 1.0) -> "MedianPriceFeed":
+        """Add a feed whose answer counts `weight` times as much as a weight of 1.0."""
+        if weight <= 0:
+            raise ConfigError(f"Feed weight must be positive, got {weight!r}")
+        if any(f.name == feed.name for f in self.feeds):
+            raise ConfigError(f"Price feed {feed.name!r} is already registered")
+        self.feeds.append(feed)
+        self.weights.append(float(weight))
+        return self
+
+    async def quote(self, session: aiohttp.ClientSession) -> PriceQuote:
+        """
+        Fetch every feed and get the consensus price with the feeds behind it.
+
+        Raises:
+            NoPriceConsensusError: If every feed failed or deviated
+        """
+        results = await asyncio.gather(*(f.get_price_usd(session) for f in self.feeds), return_exceptions=True)
+        rejected: Dict[str, str] = {}
+        answered = []
+        for feed, weight, result in zip(self.feeds, self.weights, results):
+            if isinstance(result, BaseException):
+                rejected[feed.name] = str(result) or repr(result)
+            elif not result > 0:
+                rejected[feed.name] = f"non-positive price {result!r}"
+            else:
+                answered.append((feed.name, float(result), weight))
+
+        if answered:
+            median = weighted_median([p for _, p, _ in answered], [w for _, _, w in answered])
+            kept = []
+            for name, price, weight in answered:
+                deviation = abs(price - median) / median * 100
+                if deviation > self.max_deviation_pct:
+                    rejected[name] = f"{price:g} is {deviation:.1f}% from the median {median:g}"
+                else:
+                    kept.append((name, price, weight))
+            answered = kept
+        if not answered:
+            raise NoPriceConsensusError(rejected)
+
+        return {
+            "price_usd": weighted_median([p for _, p, _ in answered], [w for _, _, w in answered]),
+            "sources": [name for name, _, _ in answered],
+            "rejected": rejected,
+        }
+
+    async def get_price_usd(self, session: aiohttp.ClientSession) -> float:
+        """Fetch the consensus price (see quote())."""
+        return (await self.quote(session))["price_usd"]
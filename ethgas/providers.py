@@ -0,0 +1,1252 @@
+"""Gas price providers behind a common interface."""
+
+import asyncio
+import copy
+import enum
+import itertools
+import logging
+import math
+import time
+from abc import ABC, abstractmethod
+from contextlib import contextmanager
+from contextvars import ContextVar
+from datetime import timedelta
+from decimal import Decimal, InvalidOperation
+from typing import Callable, Collection, Dict, Hashable, List, Optional, Set, Tuple, Union
+
+import aiohttp
+
+from .clock import SYSTEM_CLOCK, Clock
+from .core import check_fee_history, congestion_from_history, fee_history_from_block, fee_history_tiers
+from .errors import (
+    METHOD_NOT_FOUND,
+    AllProvidersFailedError,
+    ConfigError,
+    DeserializeError,
+    GasError,
+    HttpError,
+    RpcError,
+    RpcIdMismatchError,
+    check_rpc_response,
+    rejects_block_tag,
+    translate_errors,
+)
+from .mempool import BLOCKNATIVE_API, blocknative_bucket, fetch_blocknative
+from .models import GasQuote, ProviderHealth
+from .tracing import read_json, spawn, traced_fetch
+from .units import WEI_PER_GWEI, Gwei, Wei, parse_hex_quantity
+
+logger = logging.getLogger(__name__)
+
+ETHERSCAN_API = "https://api.etherscan.io/api"
+INFURA_URL = "https://{network}.infura.io/v3/{api_key}"
+ALCHEMY_URL = "https://{network}.g.alchemy.com/v2/{api_key}"
+
+API_KEY_PLACEHOLDER = "{api_key}"
+
+# Blocknative confidence (percent) behind each quote tier
+BLOCKNATIVE_TIERS = {"slow": 70, "standard": 90, "fast": 95, "urgent": 99}
+
+# Blocknative statuses for a rejected key or an exhausted quota
+BLOCKNATIVE_UNAVAILABLE = (401, 403, 429)
+
+
+class Secret:
+    """Credential wrapper whose repr and str never show the value."""
+
+    __slots__ = ("_value",)
+
+    def __init__(self, value: str):
+        self._value = value
+
+    def reveal(self) -> str:
+        """Get the actual secret (only when building a request)."""
+        return self._value
+
+    def __repr__(self) -> str:
+        return "Secret('***')"
+
+    __str__ = __repr__
+
+
+# Tier prices as multiples of eth_gasPrice on chains without EIP-1559
+LEGACY_TIER_MULTIPLIERS = {"slow": 0.9, "standard": 1.0, "fast": 1.25}
+
+# Tier priority fees as multiples of Alchemy's eth_maxPriorityFeePerGas suggestion
+ALCHEMY_TIER_MULTIPLIERS = {"slow": 0.8, "standard": 1.0, "fast": 1.5}
+
+# eth_feeHistory window and reward percentiles used when that suggestion is unavailable
+ALCHEMY_FALLBACK_BLOCKS = 20
+ALCHEMY_FALLBACK_PERCENTILES = {"slow": 10, "standard": 50, "fast": 90}
+
+
+class PricingModel(str, enum.Enum):
+    """How a chain prices gas: a single gas price, or base fee plus priority fee."""
+
+    LEGACY = "legacy"
+    EIP1559 = "eip1559"
+    # Priority fee per compute unit (solana.SolanaProvider)
+    SOLANA = "solana"
+
+
+class FeeEncoding(str, enum.Enum):
+    """
+    How an endpoint encodes fee values (gas price, base fees, rewards).
+
+    HEX_WEI is the JSON-RPC standard and accepts nothing else. Some L2
+    endpoints answer with decimal strings instead, in wei or in gwei; with
+    DECIMAL_WEI or DECIMAL_GWEI values are read as hex when "0x"-prefixed
+    and as decimal otherwise, in the declared unit, which can't be told
+    from the value itself.
+    """
+
+    HEX_WEI = "hex-wei"
+    DECIMAL_WEI = "decimal-wei"
+    DECIMAL_GWEI = "decimal-gwei"
+
+
+class ProviderStatus(str, enum.Enum):
+    """Health of a provider relative to the others (see check_health)."""
+
+    HEALTHY = "healthy"
+    LAGGING = "lagging"
+    # Answering, but with the same quote block after block (see FreezeDetector)
+    FROZEN = "frozen"
+    FAILED = "failed"
+
+
+class Aggregation(str, enum.Enum):
+    """How AggregateProvider combines its members' quotes, weighted by provider."""
+
+    MEDIAN = "median"
+    MEAN = "mean"
+
+
+# Blocks a provider may trail the highest one before it counts as lagging
+DEFAULT_MAX_LAG_BLOCKS = 3
+
+# Identical quotes in a row, while other providers move on, before one counts as frozen
+DEFAULT_FREEZE_AFTER = 5
+
+# Blocks of eth_feeHistory JsonRpcProvider averages gasUsedRatio over
+CONGESTION_WINDOW_BLOCKS = 10
+
+# Per-request timeout in seconds unless a provider is given another
+DEFAULT_REQUEST_TIMEOUT = 10.0
+
+# Connection pool of sessions from make_session: open connections in total
+# and per host, and seconds an idle connection is kept alive for reuse
+POOL_SIZE = 100
+POOL_SIZE_PER_HOST = 20
+KEEPALIVE_TIMEOUT = 30.0
+
+
+def make_session(
+    pool_size: int = POOL_SIZE,
+    pool_size_per_host: int = POOL_SIZE_PER_HOST,
+    keepalive_timeout: float = KEEPALIVE_TIMEOUT,
+    **kwargs,
+) -> aiohttp.ClientSession:
+    """
+    Create an HTTP session pooled for polling and racing providers.
+
+    Every provider and tracker call takes the session to send requests on.
+    Sharing one session across all of them is recommended: connections and
+    TLS sessions are then reused between polls instead of set up for each
+    request. Call it inside the running event loop and close the session
+    when done; extra keyword arguments go to aiohttp.ClientSession.
+    """
+    connector = aiohttp.TCPConnector(
+        limit=pool_size, limit_per_host=pool_size_per_host, keepalive_timeout=keepalive_timeout
+    )
+    return aiohttp.ClientSession(connector=connector, **kwargs)
+
+
+class AppSession:
+    """
+    One make_session() session for every request an aiohttp web app handles.
+
+    The session is created on first use, inside the app's event loop, and
+    closed when the app cleans up.
+    """
+
+    def __init__(self, app):
+        self.session: Optional[aiohttp.ClientSession] = None
+        app.on_cleanup.append(self.close)
+
+    def get(self) -> aiohttp.ClientSession:
+        """Get the shared session, creating it on first use."""
+        if self.session is None:
+            self.session = make_session()
+        return self.session
+
+    async def close(self, app=None) -> None:
+        """Close the session; the next get() opens a new one."""
+        if self.session is not None:
+            session, self.session = self.session, None
+            await session.close()
+
+
+# Named blocks fees can be read at; a block number may be given instead
+BLOCK_TAGS = ("latest", "pending")
+DEFAULT_BLOCK_TAG = "latest"
+BlockTag = Union[str, int]
+
+
+def block_tag_param(tag: BlockTag) -> str:
+    """Get the JSON-RPC block parameter for "latest", "pending" or a block number."""
+    if isinstance(tag, int) and not isinstance(tag, bool) and tag >= 0:
+        return hex(tag)
+    if tag in BLOCK_TAGS:
+        return tag
+    raise ConfigError(f"Block tag must be one of {', '.join(BLOCK_TAGS)} or a block number, got {tag!r}")
+
+# Time a composite provider keeps back before the deadline to combine partial results
+DEADLINE_SLACK = 0.05
+
+# Event loop time by which the running tracker operation must finish
+_deadline: ContextVar[Optional[float]] = ContextVar("ethgas_deadline", default=None)
+
+
+@contextmanager
+def operation_deadline(seconds: float):
+    """Let providers called inside the block know they must finish within `seconds`."""
+    token = _deadline.set(asyncio.get_running_loop().time() + seconds)
+    try:
+        yield
+    finally:
+        _deadline.reset(token)
+
+
+def time_left() -> Optional[float]:
+    """Seconds until the current operation's deadline, or None if there is none."""
+    deadline = _deadline.get()
+    if deadline is None:
+        return None
+    return max(0.0, deadline - asyncio.get_running_loop().time())
+
+
+# A JSON-RPC call as (method, params)
+RpcCall = Tuple[str, list]
+
+
+# Process-wide JSON-RPC request ids; next() on a count is atomic, so ids stay
+# unique across concurrent tasks and threads
+_request_ids = itertools.count(1)
+
+
+def rpc_request(method: str, params: Optional[list] = None) -> Dict:
+    """Build a JSON-RPC request with a fresh, unique id."""
+    return {"jsonrpc": "2.0", "id": next(_request_ids), "method": method, "params": params or []}
+
+
+def batch_payload(calls: List[RpcCall]) -> List[Dict]:
+    """Build a JSON-RPC batch request, each call with its own unique id."""
+    return [rpc_request(method, params) for method, params in calls]
+
+
+def check_response_id(response, request: Dict) -> None:
+    """
+    Raise RpcIdMismatchError unless `response` answers `request`.
+
+    An error response with a null id is accepted: servers send one when they
+    couldn't read the request id at all, and its error is more useful.
+    """
+    actual = response.get("id") if isinstance(response, dict) else None
+    if actual == request["id"]:
+        return
+    if actual is None and isinstance(response, dict) and response.get("error"):
+        return
+    raise RpcIdMismatchError(request["id"], actual)
+
+
+def match_batch_responses(data: List, payload: List[Dict]) -> List[Dict]:
+    """Order batch responses to match the request by id (servers may answer in any order)."""
+    by_id = {item.get("id"): item for item in data if isinstance(item, dict)}
+    missing = [request["id"] for request in payload if request["id"] not in by_id]
+    if missing:
+        raise DeserializeError(f"Batch response is missing ids {missing}")
+    return [by_id[request["id"]] for request in payload]
+
+
+def response_result(response: Dict):
+    """Get the result of one JSON-RPC response, raising RpcError for error responses."""
+    check_rpc_response(response)
+    with translate_errors():
+        return response["result"]
+
+
+# Fee fields of a transaction object, in blocks, the txpool and eth_getTransactionByHash
+TX_FEE_FIELDS = ("gasPrice", "maxFeePerGas", "maxPriorityFeePerGas", "maxFeePerBlobGas")
+
+# Fee fields of the results normalize_fees rewrites; None is the result itself
+FEE_RESULT_FIELDS = {
+    "eth_gasPrice": None,
+    "eth_maxPriorityFeePerGas": None,
+    "eth_blobBaseFee": None,
+    "eth_feeHistory": ("baseFeePerGas", "baseFeePerBlobGas", "reward"),
+    "eth_getBlockByNumber": ("baseFeePerGas",),
+    "eth_getBlockByHash": ("baseFeePerGas",),
+    "eth_getTransactionByHash": TX_FEE_FIELDS,
+    "eth_getTransactionReceipt": ("effectiveGasPrice", "blobGasPrice"),
+    # Transactions by sender and nonce, under "pending" and "queued"
+    "txpool_content": (),
+}
+
+
+def fee_to_hex_wei(value, encoding: FeeEncoding) -> str:
+    """
+    Convert one fee value in `encoding` to a standard hex quantity in wei.
+
+    Raises:
+        DeserializeError: If the value isn't a non-negative fee in that encoding
+    """
+    if encoding is FeeEncoding.HEX_WEI or (isinstance(value, str) and value[:2] in ("0x", "0X")):
+        number = Decimal(parse_hex_quantity(value))
+    else:
+        try:
+            if isinstance(value, bool) or not isinstance(value, (str, int, float)):
+                raise InvalidOperation
+            number = Decimal(value.strip() if isinstance(value, str) else repr(value))
+        except InvalidOperation:
+            raise DeserializeError(f"Expected a decimal fee, got {value!r}") from None
+        if not number.is_finite() or number < 0:
+            raise DeserializeError(f"Expected a non-negative fee, got {value!r}")
+    if encoding is FeeEncoding.DECIMAL_GWEI:
+        # Gwei fractions finer than a wei are rounded away
+        number = (number * WEI_PER_GWEI).to_integral_value()
+    elif number != number.to_integral_value():
+        raise DeserializeError(f"Fee in wei must be a whole number, got {value!r}")
+    return hex(int(number))
+
+
+def fee_encoding_param(encoding: Union[FeeEncoding, str]) -> FeeEncoding:
+    """Validate a FeeEncoding or its value, raising ConfigError listing the choices."""
+    try:
+        return FeeEncoding(encoding)
+    except ValueError:
+        choices = ", ".join(e.value for e in FeeEncoding)
+        raise ConfigError(f"fee_encoding must be one of {choices}, got {encoding!r}") from None
+
+
+def normalize_fees(method: str, result, encoding: FeeEncoding):
+    """
+    Rewrite the fee fields of a `method` result from `encoding` to hex wei.
+
+    Results of other methods, nulls and fields that are missing pass
+    through unchanged, as does everything under the standard HEX_WEI.
+    """
+    if encoding is FeeEncoding.HEX_WEI or method not in FEE_RESULT_FIELDS or result is None:
+        return result
+    fields = FEE_RESULT_FIELDS[method]
+    with translate_errors():
+        if fields is None:
+            return fee_to_hex_wei(result, encoding)
+        if not isinstance(result, dict):
+            return result
+        result = _fields_to_hex_wei(result, fields, encoding)
+        if isinstance(result.get("transactions"), list):
+            # Full transaction objects of eth_getBlockBy*(..., true); hashes pass through
+            result["transactions"] = [
+                _fields_to_hex_wei(tx, TX_FEE_FIELDS, encoding) if isinstance(tx, dict) else tx
+                for tx in result["transactions"]
+            ]
+        if method == "txpool_content":
+            for status, by_sender in result.items():
+                if isinstance(by_sender, dict):
+                    result[status] = {
+                        sender: {nonce: _fields_to_hex_wei(tx, TX_FEE_FIELDS, encoding) for nonce, tx in by_nonce.items()}
+                        for sender, by_nonce in by_sender.items()
+                    }
+        return result
+
+
+def _fields_to_hex_wei(obj: Dict, fields: Tuple[str, ...], encoding: FeeEncoding) -> Dict:
+    """Copy `obj` with its `fields` (fees or lists of them, as in "reward") converted to hex wei."""
+    obj = dict(obj)
+    for field in fields:
+        value = obj.get(field)
+        if isinstance(value, list):
+            obj[field] = [
+                [fee_to_hex_wei(v, encoding) for v in item] if isinstance(item, list) else fee_to_hex_wei(item, encoding)
+                for item in value
+            ]
+        elif value is not None:
+            obj[field] = fee_to_hex_wei(value, encoding)
+    return obj
+
+
+def normalize_response(response, request: Dict, encoding: FeeEncoding):
+    """normalize_fees for the result of one JSON-RPC response to `request`; error responses are kept."""
+    if encoding is FeeEncoding.HEX_WEI or not isinstance(response, dict) or "result" not in response:
+        return response
+    return dict(response, result=normalize_fees(request["method"], response["result"], encoding))
+
+
+def make_quote(
+    provider: str,
+    gas_price: float,
+    base_fee: Optional[float] = None,
+    slow: Optional[float] = None,
+    fast: Optional[float] = None,
+    block_number: Optional[int] = None,
+    pricing_model: Optional[PricingModel] = None,
+    clock: Optional[Clock] = None,
+) -> GasQuote:
+    """
+    Build a gas quote dictionary in the shape shared by all providers.
+
+    All prices are stored as Gwei so they cannot be mistaken for wei.
+
+    Args:
+        provider: Name of the provider that produced the numbers
+        gas_price: Recommended (standard) gas price in gwei
+        base_fee: Current base fee in gwei, if the source reports one
+        slow: Gas price for slow inclusion in gwei (defaults to gas_price)
+        fast: Gas price for fast inclusion in gwei (defaults to gas_price)
+        block_number: Latest block the numbers are based on, if known
+        pricing_model: Chain's pricing model (default: EIP-1559 when a base
+            fee is given, legacy otherwise)
+        clock: Time source for fetched_at (default: the system clock)
+
+    Returns:
+        Gas quote dictionary (fetched_at is set to the current UTC time)
+    """
+    gas_price = Gwei(gas_price)
+    return {
+        "provider": provider,
+        "block_number": block_number,
+        "fetched_at": (clock or SYSTEM_CLOCK).now(),
+        "base_fee": Gwei(base_fee) if base_fee is not None else None,
+        "gas_price": gas_price,
+        "pricing_model": pricing_model or (
+            PricingModel.EIP1559 if base_fee is not None else PricingModel.LEGACY
+        ),
+        "tiers": {
+            "slow": Gwei(slow) if slow is not None else gas_price,
+            "standard": gas_price,
+            "fast": Gwei(fast) if fast is not None else gas_price,
+        },
+    }
+
+
+def _check_weight(weight: float) -> None:
+    if isinstance(weight, bool) or not isinstance(weight, (int, float)) or not weight > 0 or math.isinf(weight):
+        raise ConfigError(f"Provider weight must be a positive number, got {weight!r}")
+
+
+def weighted_median(values: List[float], weights: List[float]) -> float:
+    """
+    Get the value at which half of the total weight lies on either side.
+
+    When that point falls exactly between two values they are averaged, so
+    with equal weights this is the ordinary median.
+    """
+    pairs = sorted(zip(values, weights))
+    half = sum(weights) / 2
+    cumulative = 0.0
+    for idx, (value, weight) in enumerate(pairs):
+        cumulative += weight
+        if math.isclose(cumulative, half) and idx + 1 < len(pairs):
+            return (value + pairs[idx + 1][0]) / 2
+        if cumulative > half:
+            return value
+    return pairs[-1][0]
+
+
+def weighted_mean(values: List[float], weights: List[float]) -> float:
+    """Get the mean of `values` with each counted `weights` times."""
+    return sum(v * w for v, w in zip(values, weights)) / sum(weights)
+
+
+def add_congestion(quote: Dict, history_response: Dict) -> None:
+    """Add gas_used_ratio and congestion from an eth_feeHistory response, if it has ratios."""
+    try:
+        congestion = congestion_from_history(response_result(history_response))
+    except GasError:
+        return
+    if congestion:
+        quote.update(congestion)
+
+
+def is_stale(quote: Dict, max_age: timedelta, clock: Optional[Clock] = None) -> bool:
+    """Check whether a quote was fetched more than `max_age` ago (or has no fetch time)."""
+    fetched_at = quote.get("fetched_at")
+    if fetched_at is None:
+        return True
+    return (clock or SYSTEM_CLOCK).now() - fetched_at > max_age
+
+
+async def check_health(
+    providers: List["GasProvider"],
+    session: aiohttp.ClientSession,
+    max_lag_blocks: int = DEFAULT_MAX_LAG_BLOCKS,
+    frozen: Collection["GasProvider"] = (),
+) -> List[ProviderHealth]:
+    """
+    Ask every provider for its block height concurrently and flag the ones behind.
+
+    A provider more than `max_lag_blocks` below the highest reported block is
+    LAGGING; one that errors or times out is FAILED. Providers that report no
+    block number count as HEALTHY with blocks_behind None. Providers in
+    `frozen` (e.g. AggregateProvider.frozen_members()) that still answer
+    are FROZEN: their height can look fine while their quotes don't move.
+
+    Returns:
+        One health entry per provider, in the given order
+    """
+    async def probe(provider: "GasProvider") -> Dict:
+        start = time.monotonic()
+        health = {"provider": provider.name, "block_number": None, "blocks_behind": None, "error": None}
+        try:
+            health["block_number"] = await asyncio.wait_for(provider.block_height(session), provider.timeout)
+            health["status"] = ProviderStatus.HEALTHY
+        except Exception as e:
+            health["status"] = ProviderStatus.FAILED
+            health["error"] = str(e) or repr(e)
+        health["latency_ms"] = round((time.monotonic() - start) * 1000, 1)
+        return health
+
+    results = await asyncio.gather(*(probe(p) for p in providers))
+    heights = [r["block_number"] for r in results if r["block_number"] is not None]
+    for result in results:
+        if heights and result["block_number"] is not None:
+            result["blocks_behind"] = max(heights) - result["block_number"]
+            if result["blocks_behind"] > max_lag_blocks:
+                result["status"] = ProviderStatus.LAGGING
+    for provider, result in zip(providers, results):
+        if result["status"] is not ProviderStatus.FAILED and any(provider is f for f in frozen):
+            result["status"] = ProviderStatus.FROZEN
+    return results
+
+
+def quote_values(quote: Dict) -> Tuple:
+    """The fees of a quote, for telling whether two quotes are identical."""
+    return (
+        quote.get("gas_price"),
+        quote.get("base_fee"),
+        tuple(sorted((quote.get("tiers") or {}).items())),
+        tuple(sorted((quote.get("priority_fees") or {}).items())),
+    )
+
+
+class FreezeDetector:
+    """
+    Spot sources that keep returning the exact same quote while the chain moves on.
+
+    A failing provider sometimes serves one cached response: it answers in
+    time at a plausible height, which the lag check can't tell apart from a
+    healthy one, but its fees never change. observe() takes one round of
+    quotes keyed by source. A source's count of identical quotes grows in
+    rounds where its fees equal its last ones while the newest block among
+    the other sources has advanced; at `after` it is frozen. Any change to
+    its fees resets the count and clears the flag. Rounds in which no other
+    source moved on leave the count as is, so a lone source never freezes.
+
+    On chains whose fees can sit unchanged for many blocks (a base fee at
+    its floor, for instance) every source may look frozen; raise `after`.
+    """
+
+    def __init__(self, after: int = DEFAULT_FREEZE_AFTER):
+        """
+        Initialize freeze detector.
+
+        Args:
+            after: Identical quotes in a row before a source counts as frozen
+
+        Raises:
+            ConfigError: If after is below 2
+        """
+        if after < 2:
+            raise ConfigError(f"after must be at least 2, got {after!r}")
+        self.after = after
+        # Source -> (its last fees, identical quotes in a row, newest other block seen meanwhile)
+        self._streaks: Dict[Hashable, Tuple[Tuple, int, Optional[int]]] = {}
+        self.frozen: Set[Hashable] = set()
+
+    def observe(self, quotes: Dict[Hashable, Dict]) -> Set[Hashable]:
+        """Record one round of quotes and get the sources among them that are frozen."""
+        blocks = {key: quote.get("block_number") for key, quote in quotes.items()}
+        for key, quote in quotes.items():
+            newest = max((b for k, b in blocks.items() if k != key and b is not None), default=None)
+            values = quote_values(quote)
+            last = self._streaks.get(key)
+            if last is None or last[0] != values:
+                self._streaks[key] = (values, 1, newest)
+                self.frozen.discard(key)
+                continue
+            _, count, seen = last
+            if newest is not None and (seen is None or newest > seen):
+                count, seen = count + 1, newest
+            self._streaks[key] = (values, count, seen)
+            if count >= self.after:
+                self.frozen.add(key)
+        return self.frozen & quotes.keys()
+
+
+class GasProvider(ABC):
+    """
+    Source of gas price quotes.
+
+    Providers keep no per-call state, so a single instance can be shared
+    between concurrently running tasks. Providers that make HTTP requests
+    give each one at most `timeout` seconds.
+    """
+
+    name = "provider"
+    timeout = DEFAULT_REQUEST_TIMEOUT
+
+    @abstractmethod
+    async def fetch(self, session: aiohttp.ClientSession) -> GasQuote:
+        """Fetch a fresh gas quote (see make_quote for the shape)."""
+
+    async def block_height(self, session: aiohttp.ClientSession) -> Optional[int]:
+        """Get the latest block the provider knows of (from a quote unless overridden)."""
+        return (await self.fetch(session)).get("block_number")
+
+
+class JsonRpcProvider(GasProvider):
+    """
+    Generic provider that calls eth_gasPrice on any JSON-RPC endpoint.
+
+    The first fetch checks whether the latest block has a baseFeePerGas and
+    remembers the answer. EIP-1559 chains get the next block's base fee from
+    eth_feeHistory; legacy chains (BSC, old testnets) get tiers priced at
+    `legacy_multipliers` times the node's gas price.
+
+    With `block_tag="pending"` the fee history ends at the pending block, so
+    the base fee is the one after it: a block fresher than "latest", but
+    not every node supports it. If the node rejects "pending", the provider
+    falls back to "latest" from then on. A block number reads fees as of
+    that block. Nodes that don't implement eth_feeHistory at all get the
+    latest block's own base fee instead, in quotes marked "degraded".
+
+    The URL and header values may contain an {api_key} placeholder, which is
+    filled in only when a request is sent, so reprs and logs show the
+    template instead of the key. Header values are redacted in the repr too.
+    """
+
+    name = "json-rpc"
+
+    def __init__(
+        self,
+        rpc_url: str,
+        name: Optional[str] = None,
+        headers: Optional[Dict[str, str]] = None,
+        api_key: Optional[str] = None,
+        timeout: float = DEFAULT_REQUEST_TIMEOUT,
+        legacy_multipliers: Optional[Dict[str, float]] = None,
+        block_tag: BlockTag = DEFAULT_BLOCK_TAG,
+        fee_encoding: Union[FeeEncoding, str] = FeeEncoding.HEX_WEI,
+    ):
+        """
+        Initialize JSON-RPC provider.
+
+        Args:
+            rpc_url: Endpoint URL or template, e.g. "https://rpc.example/{api_key}"
+            name: Provider name used in quotes
+            headers: Static headers sent with every request, e.g.
+                {"Authorization": "Bearer {api_key}"}
+            api_key: Value substituted for {api_key} in the URL and headers
+            timeout: Seconds allowed for connecting and reading each response
+            legacy_multipliers: Slow/standard/fast multiples of the gas price
+                used on legacy chains (default: LEGACY_TIER_MULTIPLIERS)
+            block_tag: Block the fee history ends at: "latest", "pending" or
+                a block number
+            fee_encoding: How the endpoint encodes fees (see FeeEncoding);
+                results are converted to hex wei as they arrive
+
+        Raises:
+            ConfigError: If the block tag or fee encoding is invalid
+        """
+        block_tag_param(block_tag)
+        self.block_tag = block_tag
+        self.fee_encoding = fee_encoding_param(fee_encoding)
+        # Cleared once the node rejects the "pending" tag
+        self.supports_pending = True
+        # Cleared once the node turns out not to implement eth_feeHistory
+        self.supports_fee_history = True
+        self.url_template = rpc_url
+        self.timeout = timeout
+        self.legacy_multipliers = dict(LEGACY_TIER_MULTIPLIERS, **(legacy_multipliers or {}))
+        # Detected on the first fetch
+        self.pricing_model: Optional[PricingModel] = None
+        self.headers = dict(headers or {})
+        self.api_key = Secret(api_key) if api_key is not None else None
+        # Cleared once the server shows it can't handle batch requests
+        self.supports_batch = True
+        if name:
+            self.name = name
+
+    def _fill(self, template: str) -> str:
+        """Substitute the API key into a URL or header template."""
+        if self.api_key is None:
+            return template
+        return template.replace(API_KEY_PLACEHOLDER, self.api_key.reveal())
+
+    @property
+    def rpc_url(self) -> str:
+        """Endpoint URL with the API key filled in."""
+        return self._fill(self.url_template)
+
+    def request_headers(self) -> Dict[str, str]:
+        """Headers to send, with the API key filled in."""
+        return {key: self._fill(value) for key, value in self.headers.items()}
+
+    def __repr__(self) -> str:
+        headers = {key: "***" for key in self.headers}
+        return f"{type(self).__name__}(name={self.name!r}, url={self.url_template!r}, headers={headers})"
+
+    async def _post(self, session: aiohttp.ClientSession, payload):
+        """Send a JSON-RPC request (single or batch) and return the decoded body."""
+        async def post():
+            async with session.post(
+                self.rpc_url, json=payload, headers=self.request_headers(), timeout=self.timeout
+            ) as r:
+                r.raise_for_status()
+                return await read_json(r)
+
+        with translate_errors():
+            return await asyncio.wait_for(post(), self.timeout)
+
+    async def rpc_call(
+        self, session: aiohttp.ClientSession, method: str, params: Optional[list] = None
+    ):
+        """Make a JSON-RPC call and return its result."""
+        return response_result(await self._request(session, rpc_request(method, params)))
+
+    async def _request(self, session: aiohttp.ClientSession, payload: Dict) -> Dict:
+        """Send a single JSON-RPC request and check the response answers it."""
+        response = await self._post(session, payload)
+        check_response_id(response, payload)
+        return normalize_response(response, payload, self.fee_encoding)
+
+    async def batch(self, session: aiohttp.ClientSession, calls: List[RpcCall]) -> List[Dict]:
+        """
+        Send several JSON-RPC calls as one batch request.
+
+        Returns the raw response objects in call order (each holds either a
+        "result" or an "error"; see response_result). If the server answers the
+        batch with a single object it doesn't support batching, so the calls
+        are sent one by one, now and on every later batch.
+        """
+        if self.supports_batch:
+            payload = batch_payload(calls)
+            data = await self._post(session, payload)
+            if isinstance(data, list):
+                responses = match_batch_responses(data, payload)
+                return [normalize_response(r, request, self.fee_encoding) for r, request in zip(responses, payload)]
+            self.supports_batch = False
+
+        responses = []
+        for request in batch_payload(calls):
+            responses.append(await self._request(session, request))
+        return responses
+
+    async def detect_pricing_model(self, session: aiohttp.ClientSession) -> PricingModel:
+        """
+        Check (once) whether the chain uses EIP-1559, from the latest block's baseFeePerGas.
+
+        Only a block settles it: when the lookup fails the error is raised
+        and the next call checks again, so a node that's briefly down doesn't
+        turn an EIP-1559 chain legacy for good.
+        """
+        if self.pricing_model is None:
+            block = await self.rpc_call(session, "eth_getBlockByNumber", ["latest", False])
+            if not isinstance(block, dict):
+                raise DeserializeError(f"Expected the latest block, got {block!r}")
+            has_base_fee = block.get("baseFeePerGas") is not None
+            self.pricing_model = PricingModel.EIP1559 if has_base_fee else PricingModel.LEGACY
+        return self.pricing_model
+
+    async def fetch(self, session: aiohttp.ClientSession) -> Dict:
+        """Fetch the node's suggested gas price and the block it applies to in one round trip."""
+        if await self.detect_pricing_model(session) is PricingModel.LEGACY:
+            return await self._fetch_legacy(session)
+        if not self.supports_fee_history:
+            return await self._fetch_degraded(session)
+
+        tag = self.history_tag()
+        window = CONGESTION_WINDOW_BLOCKS
+        price, history = await self.batch(session, [("eth_gasPrice", []), ("eth_feeHistory", [window, tag, []])])
+        error = history.get("error")
+        if isinstance(error, dict) and error.get("code") == METHOD_NOT_FOUND:
+            self.supports_fee_history = False
+            logger.warning("%s does not implement eth_feeHistory, using the latest block's base fee: %s", self.name, error)
+            return await self._fetch_degraded(session)
+        # Only a rejection of the tag itself; a transient failure doesn't stop later calls from trying "pending"
+        rejected = isinstance(error, dict) and rejects_block_tag(RpcError(error.get("code", 0), error.get("message", "")))
+        if tag == "pending" and rejected:
+            self.supports_pending = False
+            logger.warning("%s rejected the pending block tag, using latest: %s", self.name, history["error"])
+            history = await self._request(session, rpc_request("eth_feeHistory", [window, "latest", []]))
+        with translate_errors():
+            gas_price = Wei.from_hex(response_result(price))
+        base_fee = None
+        try:
+            # The last entry is the base fee of the upcoming block
+            base_fee = Wei.from_hex(response_result(history)["baseFeePerGas"][-1]).to_gwei()
+        except (GasError, KeyError, IndexError, TypeError):
+            pass
+        quote = make_quote(
+            self.name,
+            gas_price.to_gwei(),
+            base_fee=base_fee,
+            block_number=self._block_from_history(history),
+            pricing_model=PricingModel.EIP1559,
+        )
+        add_congestion(quote, history)
+        return quote
+
+    def history_tag(self) -> str:
+        """Block parameter for eth_feeHistory: the configured tag, or "latest" if "pending" was rejected."""
+        if self.block_tag == "pending" and not self.supports_pending:
+            return "latest"
+        return block_tag_param(self.block_tag)
+
+    async def _fetch_degraded(self, session: aiohttp.ClientSession) -> Dict:
+        """Quote from eth_gasPrice and the latest block, for nodes without eth_feeHistory."""
+        price, block = await self.batch(session, [("eth_gasPrice", []), ("eth_getBlockByNumber", ["latest", False])])
+        with translate_errors():
+            gas_price = Wei.from_hex(response_result(price)).to_gwei()
+            fee_history = fee_history_from_block(response_result(block), 0, [])
+            base_fee = Wei.from_hex(fee_history["baseFeePerGas"][-1]).to_gwei()
+            block_number = parse_hex_quantity(fee_history["oldestBlock"]) if fee_history["oldestBlock"] else None
+        quote = make_quote(
+            self.name,
+            gas_price,
+            base_fee=base_fee,
+            block_number=block_number,
+            pricing_model=PricingModel.EIP1559,
+        )
+        quote["degraded"] = True
+        quote.update(congestion_from_history(fee_history) or {})
+        return quote
+
+    async def _fetch_legacy(self, session: aiohttp.ClientSession) -> Dict:
+        price, number = await self.batch(session, [("eth_gasPrice", []), ("eth_blockNumber", [])])
+        with translate_errors():
+            gas_price = Wei.from_hex(response_result(price)).to_gwei()
+        try:
+            block_number = parse_hex_quantity(response_result(number))
+        except GasError:
+            block_number = None
+
+        multipliers = self.legacy_multipliers
+        return make_quote(
+            self.name,
+            gas_price * multipliers["standard"],
+            slow=gas_price * multipliers["slow"],
+            fast=gas_price * multipliers["fast"],
+            block_number=block_number,
+            pricing_model=PricingModel.LEGACY,
+        )
+
+    async def block_height(self, session: aiohttp.ClientSession) -> Optional[int]:
+        """Get the node's latest block number from eth_blockNumber."""
+        with translate_errors():
+            return parse_hex_quantity(await self.rpc_call(session, "eth_blockNumber"))
+
+    async def latest_block(self, session: aiohttp.ClientSession) -> Optional[int]:
+        """Get the latest block number from eth_feeHistory (None on pre-London chains)."""
+        return self._block_from_history(await self._request(session, rpc_request("eth_feeHistory", [1, "latest", []])))
+
+    @staticmethod
+    def _block_from_history(response: Dict) -> Optional[int]:
+        try:
+            history = response_result(response)
+        except RpcError:
+            return None
+        with translate_errors():
+            return parse_hex_quantity(history["oldestBlock"]) + len(history["gasUsedRatio"]) - 1
+
+
+class InfuraProvider(JsonRpcProvider):
+    """Infura JSON-RPC endpoint for a given project."""
+
+    name = "infura"
+
+    def __init__(self, project_id: str, network: str = "mainnet", timeout: float = DEFAULT_REQUEST_TIMEOUT):
+        super().__init__(
+            INFURA_URL.format(network=network, api_key=API_KEY_PLACEHOLDER), api_key=project_id, timeout=timeout
+        )
+        self.network = network
+
+
+class AlchemyProvider(JsonRpcProvider):
+    """
+    Alchemy JSON-RPC endpoint (network like "eth-mainnet" or "arb-mainnet").
+
+    Tiers are the next block's base fee plus Alchemy's
+    eth_maxPriorityFeePerGas suggestion scaled by `tier_multipliers`; the
+    standard tier uses the suggestion as-is. If the endpoint rejects that
+    method, priority fees come from eth_feeHistory reward percentiles
+    (10/50/90 over 20 blocks) instead. Both are requested in one batch.
+    Quotes include the per-tier "priority_fees".
+    """
+
+    name = "alchemy"
+
+    def __init__(
+        self,
+        api_key: str,
+        network: str = "eth-mainnet",
+        timeout: float = DEFAULT_REQUEST_TIMEOUT,
+        tier_multipliers: Optional[Dict[str, float]] = None,
+    ):
+        super().__init__(
+            ALCHEMY_URL.format(network=network, api_key=API_KEY_PLACEHOLDER), api_key=api_key, timeout=timeout
+        )
+        self.network = network
+        self.tier_multipliers = dict(ALCHEMY_TIER_MULTIPLIERS, **(tier_multipliers or {}))
+
+    async def fetch(self, session: aiohttp.ClientSession) -> Dict:
+        """Fetch the base fee and Alchemy's priority fee suggestion in one round trip."""
+        percentiles = list(ALCHEMY_FALLBACK_PERCENTILES.values())
+        suggestion, history = await self.batch(session, [
+            ("eth_maxPriorityFeePerGas", []),
+            ("eth_feeHistory", [hex(ALCHEMY_FALLBACK_BLOCKS), "latest", percentiles]),
+        ])
+        fee_history = check_fee_history(response_result(history), len(percentiles))
+        with translate_errors():
+            # The last entry is the base fee of the upcoming block
+            base_fee = Wei.from_hex(fee_history["baseFeePerGas"][-1]).to_gwei()
+            try:
+                suggested = Wei.from_hex(response_result(suggestion)).to_gwei()
+                priority_fees = {tier: suggested * m for tier, m in self.tier_multipliers.items()}
+            except RpcError:
+                priority_fees = self._priority_from_history(fee_history)
+
+        quote = make_quote(
+            self.name,
+            base_fee + priority_fees["standard"],
+            base_fee=base_fee,
+            slow=base_fee + priority_fees["slow"],
+            fast=base_fee + priority_fees["fast"],
+            block_number=self._block_from_history(history),
+            pricing_model=PricingModel.EIP1559,
+        )
+        quote["priority_fees"] = priority_fees
+        add_congestion(quote, history)
+        return quote
+
+    @staticmethod
+    def _priority_from_history(fee_history: Dict) -> Dict[str, Gwei]:
+        """Each tier's average reward percentile, as fee_history_tiers computes it."""
+        tiers = fee_history_tiers(fee_history, ALCHEMY_FALLBACK_PERCENTILES)["tiers"]
+        return {tier: fees["max_priority_fee_per_gas"].to_gwei() for tier, fees in tiers.items()}
+
+
+class EtherscanProvider(GasProvider):
+    """Etherscan gas oracle (safe/propose/fast gas prices)."""
+
+    name = "etherscan"
+
+    def __init__(
+        self,
+        api_key: Optional[str] = None,
+        api_url: str = ETHERSCAN_API,
+        timeout: float = DEFAULT_REQUEST_TIMEOUT,
+    ):
+        self.api_key = Secret(api_key) if api_key else None
+        self.api_url = api_url
+        self.timeout = timeout
+
+    async def fetch(self, session: aiohttp.ClientSession) -> Dict:
+        """Fetch gas prices from the Etherscan gas oracle."""
+        params = {"module": "gastracker", "action": "gasoracle"}
+        if self.api_key:
+            params["apikey"] = self.api_key.reveal()
+
+        async def get():
+            async with session.get(self.api_url, params=params, timeout=self.timeout) as r:
+                r.raise_for_status()
+                return await read_json(r)
+
+        with translate_errors():
+            data = await asyncio.wait_for(get(), self.timeout)
+
+        if data.get("status") != "1":
+            raise DeserializeError(f"Etherscan error: {data.get('result') or data.get('message')}")
+
+        with translate_errors():
+            result = data["result"]
+            base_fee = result.get("suggestBaseFee")
+            return make_quote(
+                self.name,
+                float(result["ProposeGasPrice"]),
+                base_fee=float(base_fee) if base_fee else None,
+                slow=float(result["SafeGasPrice"]),
+                fast=float(result["FastGasPrice"]),
+                block_number=int(result["LastBlock"]) if result.get("LastBlock") else None,
+            )
+
+
+class BlocknativeProvider(GasProvider):
+    """
+    Blocknative gas platform estimates for the next block.
+
+    Tiers are the maxFeePerGas at 70/90/95/99% confidence (slow, standard,
+    fast, urgent); the matching maxPriorityFeePerGas values are under
+    "priority_fees". Without an API key, or when Blocknative rejects the key
+    or the quota is used up, quotes come from `fallback` if one is given.
+    """
+
+    name = "blocknative"
+
+    def __init__(
+        self,
+        api_key: Optional[str] = None,
+        chain_id: int = 1,
+        fallback: Optional[GasProvider] = None,
+        api_url: str = BLOCKNATIVE_API,
+        timeout: float = DEFAULT_REQUEST_TIMEOUT,
+    ):
+        self.api_key = Secret(api_key) if api_key else None
+        self.chain_id = chain_id
+        self.fallback = fallback
+        self.api_url = api_url
+        self.timeout = timeout
+
+    async def fetch(self, session: aiohttp.ClientSession) -> Dict:
+        """Fetch Blocknative's estimates, or the fallback's quote when Blocknative is unavailable."""
+        if self.api_key is None:
+            if self.fallback is None:
+                raise ConfigError("Blocknative needs an API key")
+            return await self.fallback.fetch(session)
+
+        try:
+            block = await fetch_blocknative(
+                session, self.api_key.reveal(), self.chain_id, self.api_url, self.timeout
+            )
+        except HttpError as e:
+            if self.fallback is None or e.status not in BLOCKNATIVE_UNAVAILABLE:
+                raise
+            return await self.fallback.fetch(session)
+
+        with translate_errors():
+            buckets = {}
+            for tier, confidence in BLOCKNATIVE_TIERS.items():
+                bucket = blocknative_bucket(block["estimatedPrices"], confidence)
+                if bucket is None:
+                    raise DeserializeError(f"Blocknative has no estimate at {confidence}% confidence")
+                buckets[tier] = bucket
+
+            base_fee = block.get("baseFeePerGas")
+            quote = make_quote(
+                self.name,
+                buckets["standard"]["maxFeePerGas"],
+                base_fee=base_fee,
+                slow=buckets["slow"]["maxFeePerGas"],
+                fast=buckets["fast"]["maxFeePerGas"],
+                block_number=block.get("blockNumber"),
+            )
+            quote["tiers"]["urgent"] = Gwei(buckets["urgent"]["maxFeePerGas"])
+            quote["priority_fees"] = {
+                tier: Gwei(bucket["maxPriorityFeePerGas"]) for tier, bucket in buckets.items()
+            }
+            quote["estimated_transaction_count"] = block.get("estimatedTransactionCount")
+            return quote
+
+
+class RaceProvider(GasProvider):
+    """Query several providers concurrently and return the first success."""
+
+    name = "race"
+
+    def __init__(self, providers: List[GasProvider]):
+        if not providers:
+            raise ValueError("RaceProvider needs at least one provider")
+        self.providers = providers
+
+    def replace_members(self, providers: List[GasProvider]) -> "RaceProvider":
+        """Get a copy racing `providers` instead, leaving this one as it is."""
+        race = copy.copy(self)
+        race.providers = list(providers)
+        return race
+
+    async def fetch(self, session: aiohttp.ClientSession) -> Dict:
+        """Return the first quote that arrives, cancelling the slower requests."""
+        pending = {spawn(traced_fetch(p, session), f"race {p.name}") for p in self.providers}
+        errors = []
+
+        try:
+            while pending:
+                done, pending = await asyncio.wait(
+                    pending, return_when=asyncio.FIRST_COMPLETED
+                )
+                successes = []
+                for task in done:
+                    if task.exception() is None:
+                        successes.append(task.result())
+                    else:
+                        errors.append(task.exception())
+                if successes:
+                    return successes[0]
+        finally:
+            for task in pending:
+                task.cancel()
+            if pending:
+                # Join the slower requests, so none outlives the race
+                await asyncio.wait(pending)
+
+        logger.error("All %d providers of %s failed: %s", len(errors), self.name, errors)
+        raise AllProvidersFailedError(errors)
+
+
+class AggregateProvider(GasProvider):
+    """
+    Query several providers and return the median of their quotes.
+
+    Providers that error or exceed the per-provider timeout are discarded,
+    which filters out stale or garbage numbers from a single bad endpoint.
+    Under a tracker deadline (GasTracker.with_deadline) providers still
+    running just before it are dropped too, so the quotes that did arrive
+    are combined instead of the whole call timing out. With
+    `max_lag_blocks`, quotes from more than that many blocks behind the
+    newest quote are left out of the median as well. With `freeze_after`,
+    members repeating the same quote that many times while the others move
+    on are reported in the quote's "frozen" list (see FreezeDetector), and
+    with `exclude_frozen` left out too, unless every member is frozen.
+
+    Each provider has a weight (1.0 unless given in `weights` or added with
+    with_provider_weighted), so a trusted paid endpoint can outvote public
+    ones; the weights of the providers that answered are what count. With
+    `aggregation=Aggregation.MEAN` the weighted mean is used instead of the
+    weighted median.
+    """
+
+    name = "aggregate"
+
+    def __init__(
+        self,
+        providers: List[GasProvider],
+        timeout: float = 10.0,
+        max_lag_blocks: Optional[int] = None,
+        weights: Optional[List[float]] = None,
+        aggregation: Aggregation = Aggregation.MEDIAN,
+        freeze_after: Optional[int] = None,
+        exclude_frozen: bool = False,
+    ):
+        if not providers:
+            raise ValueError("AggregateProvider needs at least one provider")
+        if weights is None:
+            weights = [1.0] * len(providers)
+        if len(weights) != len(providers):
+            raise ConfigError("AggregateProvider needs one weight per provider")
+        for weight in weights:
+            _check_weight(weight)
+        self.providers = list(providers)
+        self.weights = [float(w) for w in weights]
+        self.timeout = timeout
+        self.max_lag_blocks = max_lag_blocks
+        self.aggregation = Aggregation(aggregation)
+        # Keyed by member index, since members may share a name
+        self.freeze_detector = FreezeDetector(freeze_after) if freeze_after is not None else None
+        self.exclude_frozen = exclude_frozen
+
+    def frozen_members(self) -> List[GasProvider]:
+        """Get the members currently flagged frozen, empty without freeze detection."""
+        if self.freeze_detector is None:
+            return []
+        return [self.providers[i] for i in sorted(self.freeze_detector.frozen)]
+
+    def replace_members(self, providers: List[GasProvider]) -> "AggregateProvider":
+        """
+        Get a copy aggregating `providers` in place of the members, leaving this one as it is.
+
+        `providers[i]` takes the weight of member i, typically being it
+        wrapped; freeze detection starts over on the copy.
+        """
+        if len(providers) != len(self.providers):
+            raise ConfigError("replace_members needs one provider per member")
+        aggregate = copy.copy(self)
+        aggregate.providers = list(providers)
+        aggregate.weights = list(self.weights)
+        if self.freeze_detector is not None:
+            aggregate.freeze_detector = FreezeDetector(self.freeze_detector.after)
+        return aggregate
+
+    def with_provider_weighted(self, provider: GasProvider, weight: float) -> "AggregateProvider":
+        """
+        Get a copy with `provider` added, its quotes counting `weight` times as much as a weight of 1.0.
+
+        This aggregate is left as it is, so it can be shared while variants
+        are built from it.
+        """
+        _check_weight(weight)
+        aggregate = self.replace_members(self.providers)
+        aggregate.providers.append(provider)
+        aggregate.weights.append(float(weight))
+        return aggregate
+
+    def _combine(self, values: List[float], weights: List[float]) -> float:
+        if self.aggregation is Aggregation.MEAN:
+            return weighted_mean(values, weights)
+        return weighted_median(values, weights)
+
+    async def fetch(self, session: aiohttp.ClientSession) -> Dict:
+        """Fetch from every provider and combine the successful quotes."""
+        timeout = self.timeout
+        left = time_left()
+        if left is not None:
+            timeout = min(timeout, max(0.0, left - DEADLINE_SLACK))
+
+        results = await asyncio.gather(
+            *(asyncio.wait_for(traced_fetch(p, session), timeout) for p in self.providers),
+            return_exceptions=True,
+        )
+        indexed = [
+            (i, r, w) for i, (r, w) in enumerate(zip(results, self.weights)) if not isinstance(r, BaseException)
+        ]
+
+        if not indexed:
+            logger.error("All %d providers of %s failed: %s", len(results), self.name, results)
+            raise AllProvidersFailedError(list(results))
+
+        frozen: Set[int] = set()
+        if self.freeze_detector is not None:
+            before = set(self.freeze_detector.frozen)
+            frozen = self.freeze_detector.observe({i: q for i, q, _ in indexed})
+            for i in sorted(frozen - before):
+                logger.warning("%s: %s keeps returning the same quote, looks frozen", self.name, self.providers[i].name)
+            if self.exclude_frozen and len(frozen) < len(indexed):
+                indexed = [(i, q, w) for i, q, w in indexed if i not in frozen]
+        answered = [(q, w) for _, q, w in indexed]
+
+        if self.max_lag_blocks is not None:
+            newest = max((q["block_number"] for q, _ in answered if q.get("block_number") is not None), default=None)
+            if newest is not None:
+                answered = [
+                    (q, w) for q, w in answered
+                    if q.get("block_number") is None or newest - q["block_number"] <= self.max_lag_blocks
+                ]
+
+        quotes = [q for q, _ in answered]
+        weights = [w for _, w in answered]
+
+        def combine(field: Callable[[Dict], Optional[float]]) -> Optional[float]:
+            """Combine one field over the quotes that report it."""
+            pairs = [(field(q), w) for q, w in answered if field(q) is not None]
+            if not pairs:
+                return None
+            return self._combine([v for v, _ in pairs], [w for _, w in pairs])
+
+        blocks = [q["block_number"] for q in quotes if q.get("block_number") is not None]
+        quote = make_quote(
+            self.name,
+            self._combine([q["gas_price"] for q in quotes], weights),
+            base_fee=combine(lambda q: q.get("base_fee")),
+            slow=combine(lambda q: q["tiers"].get("slow")),
+            fast=combine(lambda q: q["tiers"].get("fast")),
+            block_number=max(blocks) if blocks else None,
+        )
+        priority_tiers = dict.fromkeys(tier for q in quotes for tier in q.get("priority_fees") or {})
+        if priority_tiers:
+            quote["priority_fees"] = {
+                tier: Gwei(combine(lambda q: (q.get("priority_fees") or {}).get(tier))) for tier in priority_tiers
+            }
+        quote["sources"] = [q["provider"] for q in quotes]
+        if frozen:
+            quote["frozen"] = [self.providers[i].name for i in sorted(frozen)]
+        return quote
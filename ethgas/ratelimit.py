@@ -0,0 +1,73 @@
+"""Token-bucket rate limiting for gas providers."""
+
+import asyncio
+from typing import Dict, Optional
+
+import aiohttp
+
+from .clock import SYSTEM_CLOCK, Clock
+from .providers import GasProvider
+
+
+class RateLimiter:
+    """
+    Token bucket refilled continuously from elapsed time.
+
+    Share one instance between providers or tasks that must respect a single
+    request budget (e.g. Etherscan's free tier of 5 req/s).
+    """
+
+    def __init__(self, requests_per_second: float, burst: int = 1, clock: Optional[Clock] = None):
+        """
+        Initialize rate limiter.
+
+        Args:
+            requests_per_second: Sustained request rate
+            burst: Maximum number of requests allowed back-to-back
+            clock: Time source for refills and waits (default: the system clock)
+        """
+        if requests_per_second <= 0:
+            raise ValueError("requests_per_second must be positive")
+        if burst < 1:
+            raise ValueError("burst must be at least 1")
+
+        self.rate = requests_per_second
+        self.burst = burst
+        self.tokens = float(burst)
+        self.clock = clock or SYSTEM_CLOCK
+        self.updated = self.clock.monotonic()
+        self._lock: Optional[asyncio.Lock] = None
+
+    def _refill(self) -> None:
+        """Add the tokens earned since the last refill."""
+        now = self.clock.monotonic()
+        self.tokens = min(self.burst, self.tokens + (now - self.updated) * self.rate)
+        self.updated = now
+
+    async def acquire(self) -> None:
+        """Wait until a request token is available and take it."""
+        if self._lock is None:
+            self._lock = asyncio.Lock()
+
+        # Waiters queue on the lock, so tokens are handed out in arrival order
+        async with self._lock:
+            while True:
+                self._refill()
+                if self.tokens >= 1:
+                    self.tokens -= 1
+                    return
+                await self.clock.sleep((1 - self.tokens) / self.rate)
+
+
+class RateLimitedProvider(GasProvider):
+    """Wrap a provider so its fetches wait for a rate limiter token."""
+
+    def __init__(self, provider: GasProvider, limiter: RateLimiter):
+        self.provider = provider
+        self.limiter = limiter
+        self.name = provider.name
+
+    async def fetch(self, session: aiohttp.ClientSession) -> Dict:
+        """Fetch from the wrapped provider once a token is available."""
+        await self.limiter.acquire()
+        return await self.provider.fetch(session)
@@ -0,0 +1,43 @@
+"""Comparing the fees a transaction authorized with what it actually paid."""
+
+from .models import Overpayment
+from .units import Wei
+
+
+def overpayment(max_fee_per_gas: int, effective_gas_price: int, gas_used: int) -> Overpayment:
+    """
+    Work out how much fee headroom a confirmed transaction left unused.
+
+    Args:
+        max_fee_per_gas: maxFeePerGas the transaction was signed with (gasPrice
+            for legacy transactions), in wei
+        effective_gas_price: effectiveGasPrice from the receipt, in wei
+        gas_used: gasUsed from the receipt
+
+    Returns:
+        Dictionary with the authorized, paid and unused amounts in wei, the
+        unused amount in ETH and the headroom as a percentage of the price
+        actually paid
+
+    Raises:
+        ValueError: If an amount is negative or the price paid exceeds the maximum
+    """
+    if min(max_fee_per_gas, effective_gas_price, gas_used) < 0:
+        raise ValueError("Fees and gas used must not be negative")
+    if effective_gas_price > max_fee_per_gas:
+        raise ValueError("effective_gas_price cannot exceed max_fee_per_gas")
+
+    authorized = Wei(max_fee_per_gas * gas_used)
+    paid = Wei(effective_gas_price * gas_used)
+    unused = authorized - paid
+    headroom = (max_fee_per_gas - effective_gas_price) / effective_gas_price * 100 if effective_gas_price else 0.0
+    return {
+        "gas_used": gas_used,
+        "max_fee_per_gas": Wei(max_fee_per_gas),
+        "effective_gas_price": Wei(effective_gas_price),
+        "authorized_wei": authorized,
+        "paid_wei": paid,
+        "overpaid_wei": unused,
+        "overpaid_eth": unused.to_ether(),
+        "overpaid_pct": round(headroom, 2),
+    }
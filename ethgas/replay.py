@@ -0,0 +1,214 @@
+"""
+Record JSON-RPC traffic to a file and replay it later without a network.
+
+A recording is a JSON Lines file with one object per answered call:
+
+    {"timestamp": "2024-05-01T12:00:00.123456+00:00", "method": "eth_gasPrice",
+     "params": [], "response": {"result": "0x4a817c800"}}
+
+Capture a production moment by wrapping the provider (or the session the
+tracker's own RPC calls use), then feed the file back in to debug the
+estimation math deterministically:
+
+    provider = RecordingProvider(JsonRpcProvider(url), "incident.jsonl")
+    ...
+    tracker = GasTracker("replay://", "ethereum", provider=ReplayProvider("incident.jsonl"))
+    await tracker.current(None)
+    await tracker.estimate_eip1559(ReplaySession("incident.jsonl"))
+
+Calls are matched by method and params. By default each call takes the
+next unused response recorded for it, in file order; with a `clock` (such
+as testing.MockClock) it gets the newest response recorded at or before the
+clock's time instead, so a replay can be stepped through by timestamp.
+"""
+
+import json
+from collections import defaultdict
+from datetime import datetime
+from pathlib import Path
+from typing import Dict, List, Optional, Union
+
+from .clock import Clock
+from .errors import DeserializeError, GasError
+from .providers import GasProvider, JsonRpcProvider
+
+PathLike = Union[str, Path]
+
+
+def _call_key(method: str, params) -> str:
+    return json.dumps([method, params or []], sort_keys=True)
+
+
+def _record(path: Path, payload, data) -> None:
+    """Append the calls of one request (single or batch) and their responses to `path`."""
+    requests = payload if isinstance(payload, list) else [payload]
+    responses = data if isinstance(data, list) else [data]
+    by_id = {r.get("id"): r for r in responses if isinstance(r, dict)}
+    timestamp = datetime.now().astimezone().isoformat()
+    with open(path, "a", encoding="utf-8") as f:
+        for request in requests:
+            response = by_id.get(request.get("id"))
+            if response is None and len(requests) == 1 and isinstance(data, dict):
+                response = data  # e.g. an error with a null id
+            if response is None:
+                continue
+            entry = {
+                "timestamp": timestamp,
+                "method": request["method"],
+                "params": request.get("params", []),
+                "response": {k: v for k, v in response.items() if k not in ("id", "jsonrpc")},
+            }
+            f.write(json.dumps(entry) + "\n")
+
+
+class _RecordingResponse:
+    """Response whose decoded JSON body is recorded on the way through."""
+
+    def __init__(self, response, payload, path: Path):
+        self._response = response
+        self._payload = payload
+        self._path = path
+
+    async def json(self):
+        data = await self._response.json()
+        _record(self._path, self._payload, data)
+        return data
+
+    def __getattr__(self, name):
+        return getattr(self._response, name)
+
+
+class _RecordingPost:
+    def __init__(self, request, payload, path: Path):
+        self._request = request
+        self._payload = payload
+        self._path = path
+
+    async def __aenter__(self):
+        return _RecordingResponse(await self._request.__aenter__(), self._payload, self._path)
+
+    async def __aexit__(self, *exc_info):
+        return await self._request.__aexit__(*exc_info)
+
+
+class RecordingSession:
+    """Wrap an aiohttp session so every JSON-RPC call posted through it is appended to `path`."""
+
+    def __init__(self, session, path: PathLike):
+        self.session = session
+        self.path = Path(path)
+
+    def post(self, url, json=None, **kwargs):
+        return _RecordingPost(self.session.post(url, json=json, **kwargs), json, self.path)
+
+    def __getattr__(self, name):
+        return getattr(self.session, name)
+
+
+def load_recording(path: PathLike) -> List[Dict]:
+    """Read the entries of a recording, checking each has the expected fields."""
+    entries = []
+    for number, line in enumerate(Path(path).read_text(encoding="utf-8").splitlines(), 1):
+        if not line.strip():
+            continue
+        try:
+            entry = json.loads(line)
+            entry["at"] = datetime.fromisoformat(entry["timestamp"])
+            if not isinstance(entry["method"], str) or not isinstance(entry["response"], dict):
+                raise TypeError("method must be a string and response an object")
+        except (ValueError, KeyError, TypeError) as e:
+            raise DeserializeError(f"{path}:{number}: invalid recording entry: {e!r}") from e
+        entries.append(entry)
+    return entries
+
+
+class ReplaySession:
+    """
+    Stand-in for an aiohttp session that answers JSON-RPC posts from a recording.
+
+    Usable as the session of GasTracker's own RPC calls as well as of
+    JSON-RPC providers. A call with no recorded response left raises GasError.
+    """
+
+    def __init__(self, path: PathLike, clock: Optional[Clock] = None):
+        self.clock = clock
+        self._entries = defaultdict(list)
+        for entry in load_recording(path):
+            self._entries[_call_key(entry["method"], entry.get("params"))].append(entry)
+        self._next = defaultdict(int)
+
+    def answer(self, request: Dict) -> Dict:
+        """Get the recorded response to one JSON-RPC request, with its id."""
+        key = _call_key(request["method"], request.get("params"))
+        entries = self._entries.get(key, [])
+        if self.clock is not None:
+            now = self.clock.now()
+            recorded = [e for e in entries if e["at"] <= now]
+            if not recorded:
+                raise GasError(f"No {request['method']} response recorded by {now.isoformat()}")
+            entry = recorded[-1]
+        else:
+            index = self._next[key]
+            if index >= len(entries):
+                raise GasError(f"Recording has no more {request['method']} responses for {request.get('params')}")
+            self._next[key] += 1
+            entry = entries[index]
+        return dict(entry["response"], jsonrpc="2.0", id=request["id"])
+
+    def respond(self, payload):
+        """Answer a single or batch JSON-RPC payload."""
+        if isinstance(payload, list):
+            return [self.answer(request) for request in payload]
+        return self.answer(payload)
+
+    def post(self, url, json=None, **kwargs):
+        return _ReplayResponse(self.respond(json))
+
+
+class _ReplayResponse:
+    def __init__(self, data):
+        self._data = data
+
+    async def __aenter__(self):
+        return self
+
+    async def __aexit__(self, *exc_info):
+        return False
+
+    def raise_for_status(self) -> None:
+        pass
+
+    async def json(self):
+        return self._data
+
+
+class RecordingProvider(GasProvider):
+    """Wrap a provider so every JSON-RPC response it receives is appended to a recording."""
+
+    def __init__(self, provider: GasProvider, path: PathLike):
+        self.provider = provider
+        self.path = Path(path)
+        self.name = provider.name
+
+    async def fetch(self, session) -> Dict:
+        """Fetch from the wrapped provider, recording its RPC traffic."""
+        return await self.provider.fetch(RecordingSession(session, self.path))
+
+
+class ReplayProvider(JsonRpcProvider):
+    """
+    JSON-RPC provider answered from a recording instead of a node.
+
+    Quotes go through the same code as JsonRpcProvider, so the estimation
+    math sees exactly what was recorded. The session passed to fetch() is
+    ignored; None is fine.
+    """
+
+    name = "replay"
+
+    def __init__(self, path: PathLike, clock: Optional[Clock] = None, name: Optional[str] = None):
+        super().__init__("replay://", name=name)
+        self.replay = ReplaySession(path, clock)
+
+    async def _post(self, session, payload):
+        return self.replay.respond(payload)
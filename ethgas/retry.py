@@ -0,0 +1,116 @@
+"""Retry with exponential backoff for flaky gas providers."""
+
+import asyncio
+import logging
+import random
+from email.utils import parsedate_to_datetime
+from typing import Awaitable, Callable, Dict, Optional
+
+import aiohttp
+
+from .clock import SYSTEM_CLOCK, Clock
+from .errors import GasTimeoutError, HttpError
+from .providers import GasProvider
+from .tracing import span
+
+logger = logging.getLogger(__name__)
+
+
+class RetryPolicy:
+    """
+    Exponential backoff settings for provider calls.
+
+    Timeouts, connection errors, 5xx responses and 429s are retried. Other 4xx
+    responses fail immediately since repeating them cannot succeed.
+    """
+
+    def __init__(
+        self,
+        max_retries: int = 3,
+        base_delay: float = 0.5,
+        max_delay: float = 10.0,
+        jitter: float = 0.1,
+        clock: Optional[Clock] = None,
+    ):
+        """
+        Initialize retry policy.
+
+        Args:
+            max_retries: Retries after the first attempt
+            base_delay: Delay before the first retry in seconds (doubles each retry)
+            max_delay: Upper bound on any single delay in seconds
+            jitter: Random extra delay as a fraction of the computed delay (0-1)
+            clock: Time source for Retry-After dates and the delays (default: the system clock)
+        """
+        self.max_retries = max_retries
+        self.base_delay = base_delay
+        self.max_delay = max_delay
+        self.jitter = jitter
+        self.clock = clock or SYSTEM_CLOCK
+
+    @staticmethod
+    def is_retryable(error: BaseException) -> bool:
+        """Check whether an error is transient and worth retrying."""
+        if isinstance(error, (aiohttp.ClientResponseError, HttpError)):
+            # Connection-level HttpErrors carry no status and are transient
+            return error.status is None or error.status == 429 or error.status >= 500
+        return isinstance(error, (aiohttp.ClientError, asyncio.TimeoutError, GasTimeoutError))
+
+    @staticmethod
+    def retry_after(error: BaseException, clock: Optional[Clock] = None) -> Optional[float]:
+        """Get the delay requested by a Retry-After header, if any, with dates measured from `clock`'s now."""
+        headers = getattr(error, "headers", None)
+        value = headers.get("Retry-After") if headers else None
+        if not value:
+            return None
+
+        try:
+            return max(0.0, float(value))
+        except ValueError:
+            pass
+
+        try:
+            retry_at = parsedate_to_datetime(value)
+        except (TypeError, ValueError):
+            return None
+        return max(0.0, (retry_at - (clock or SYSTEM_CLOCK).now()).total_seconds())
+
+    def delay_for(self, attempt: int, error: Optional[BaseException] = None) -> float:
+        """Compute the delay before retry number `attempt` (0-based)."""
+        requested = self.retry_after(error, self.clock) if error is not None else None
+        if requested is not None:
+            return requested
+
+        delay = min(self.max_delay, self.base_delay * (2 ** attempt))
+        return delay + random.uniform(0, delay * self.jitter)
+
+    async def run(self, func: Callable[[], Awaitable], description: str = "request"):
+        """Call `func` until it succeeds, a non-retryable error occurs or retries run out."""
+        attempt = 0
+        while True:
+            try:
+                return await func()
+            except Exception as e:
+                if attempt >= self.max_retries or not self.is_retryable(e):
+                    raise
+                delay = self.delay_for(attempt, e)
+                logger.warning(
+                    "Retrying %s in %.2fs after %r (retry %d of %d)",
+                    description, delay, e, attempt + 1, self.max_retries,
+                )
+                await self.clock.sleep(delay)
+                attempt += 1
+
+
+class RetryingProvider(GasProvider):
+    """Wrap a provider so its fetches follow a retry policy."""
+
+    def __init__(self, provider: GasProvider, policy: RetryPolicy):
+        self.provider = provider
+        self.policy = policy
+        self.name = provider.name
+
+    async def fetch(self, session: aiohttp.ClientSession) -> Dict:
+        """Fetch from the wrapped provider, retrying transient failures."""
+        with span("retry", provider=self.name):
+            return await self.policy.run(lambda: self.provider.fetch(session), description=self.name)
@@ -0,0 +1,94 @@
+"""Check that a provider's base fees move the way EIP-1559 allows between blocks."""
+
+import logging
+from typing import Dict, Optional, Tuple
+
+import aiohttp
+
+from .core import MAX_BASE_FEE_STEP
+from .errors import ConfigError, SuspectQuoteError
+from .providers import GasProvider
+
+logger = logging.getLogger(__name__)
+
+# Most the base fee may change per block, as a fraction (EIP-1559: 1/8)
+MAX_BASE_FEE_CHANGE = MAX_BASE_FEE_STEP - 1
+
+# Relative slack on the bounds, for quotes rounded to gwei floats
+BASE_FEE_TOLERANCE = 1e-6
+
+
+def base_fee_bounds(base_fee: float, blocks: int, max_change: float = MAX_BASE_FEE_CHANGE) -> Tuple[float, float]:
+    """Lowest and highest base fee `blocks` blocks after one of `base_fee`."""
+    return base_fee * (1 - max_change) ** blocks, base_fee * (1 + max_change) ** blocks
+
+
+class BaseFeeCheckProvider(GasProvider):
+    """
+    Wrap a provider so base fee jumps EIP-1559 can't produce are caught.
+
+    Each quote's base fee is compared with the last trusted quote's: across
+    a gap of n blocks it can have moved by at most (1 + max_change)^n up or
+    (1 - max_change)^n down, so polls that skipped blocks are allowed the
+    larger cumulative change. The same block reported with a different
+    base fee, or an earlier block than already seen, fails the check too.
+    A failing quote is marked "suspect" (with a "suspect_reason") or, with
+    `strict`, raises SuspectQuoteError, and isn't used as the reference
+    for the next one. Quotes without a base fee or block number pass as is.
+
+    Chains with other base fee rules (e.g. Arbitrum, or OP Stack's smaller
+    steps) need their own `max_change`.
+    """
+
+    def __init__(self, provider: GasProvider, strict: bool = False, max_change: float = MAX_BASE_FEE_CHANGE):
+        """
+        Initialize base fee check.
+
+        Args:
+            provider: Provider whose quotes are checked
+            strict: Raise SuspectQuoteError instead of marking the quote
+            max_change: Most the base fee may change per block, as a fraction
+
+        Raises:
+            ConfigError: If max_change isn't between 0 and 1
+        """
+        if not 0 < max_change < 1:
+            raise ConfigError(f"max_change must be between 0 and 1, got {max_change!r}")
+        self.provider = provider
+        self.strict = strict
+        self.max_change = max_change
+        self.name = provider.name
+        # Block number and base fee of the last quote that passed
+        self.reference: Optional[Tuple[int, float]] = None
+
+    def check(self, quote: Dict) -> Optional[str]:
+        """Get why `quote` breaks the base fee rules against the reference, or None if it doesn't."""
+        block, base_fee = quote.get("block_number"), quote.get("base_fee")
+        if block is None or base_fee is None or self.reference is None:
+            return None
+        last_block, last_fee = self.reference
+        if block < last_block:
+            return f"block {block} is older than block {last_block} already seen"
+        low, high = base_fee_bounds(last_fee, block - last_block, self.max_change)
+        if low * (1 - BASE_FEE_TOLERANCE) <= base_fee <= high * (1 + BASE_FEE_TOLERANCE):
+            return None
+        gap = f"{block - last_block} blocks" if block != last_block else "the same block"
+        return (
+            f"base fee {float(base_fee):g} gwei is outside {low:g}-{high:g} gwei, "
+            f"the range EIP-1559 allows {gap} after {float(last_fee):g} gwei at block {last_block}"
+        )
+
+    async def fetch(self, session: aiohttp.ClientSession) -> Dict:
+        """Fetch from the wrapped provider and check its base fee."""
+        quote = await self.provider.fetch(session)
+        reason = self.check(quote)
+        if reason is None:
+            if quote.get("block_number") is not None and quote.get("base_fee") is not None:
+                self.reference = (quote["block_number"], quote["base_fee"])
+            return quote
+        if self.strict:
+            raise SuspectQuoteError(self.name, reason)
+        logger.warning("%s: suspect quote, %s", self.name, reason)
+        quote["suspect"] = True
+        quote["suspect_reason"] = reason
+        return quote
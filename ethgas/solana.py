@@ -0,0 +1,190 @@
+"""
+Solana priority fees, quoted in the same tier shape as EVM gas prices.
+
+Solana transactions pay a fixed base fee per signature plus an optional
+prioritization fee: a price in micro-lamports (10**-6 lamport) per compute
+unit. SolanaProvider reads the prices recent slots landed at from
+getRecentPrioritizationFees and turns them into slow/standard/fast tiers:
+
+    provider = SolanaProvider.for_network("solana")
+    quote = await provider.fetch(session)
+    quote["tiers"]["fast"]  # micro-lamports per compute unit
+
+estimate_solana_tx_fee() prices a whole transaction in lamports for the
+compute units it uses and the number of slots it may wait.
+"""
+
+import math
+from datetime import datetime, timezone
+from typing import Dict, List, Optional, Union
+
+import aiohttp
+
+from .core import FEE_TIERS, PercentileMethod, percentile, percentile_for_target_blocks, percentile_method_param
+from .errors import ConfigError, translate_errors
+from .models import GasQuote, SolanaFee
+from .networks import SOLANA_NETWORKS
+from .providers import DEFAULT_REQUEST_TIMEOUT, JsonRpcProvider, PricingModel
+
+# Unit of SolanaProvider's tier prices
+MICRO_LAMPORTS_PER_CU = "micro-lamports/CU"
+
+# Price recommended when no recent slot paid a prioritization fee: the
+# smallest one that still ranks a transaction above unprioritized ones
+DEFAULT_MIN_PRIORITY_FEE = 1
+
+# Base fee charged per transaction signature, in lamports
+LAMPORTS_PER_SIGNATURE = 5000
+
+# Most compute units a single transaction may request
+MAX_COMPUTE_UNITS = 1_400_000
+
+MICRO_LAMPORTS_PER_LAMPORT = 10 ** 6
+
+
+def fee_percentile(fees: List[int], pct: float, method: Union[PercentileMethod, str] = PercentileMethod.LINEAR) -> int:
+    """Percentile of per-slot prioritization fees, rounded up to a whole micro-lamport."""
+    return math.ceil(percentile(fees, pct, method))
+
+
+class SolanaProvider(JsonRpcProvider):
+    """
+    Solana JSON-RPC endpoint, priced from getRecentPrioritizationFees.
+
+    The node reports the lowest fee that landed in each of its recent slots
+    (up to 150). Each tier is a percentile of those, by default those of
+    core.FEE_TIERS, picked by `percentile_method` (linear interpolation
+    by default, "nearest-rank" for a fee that actually landed), and never
+    less than `min_fee`; if no slot is reported, every tier is `min_fee`.
+    Passing the writable `accounts` a transaction locks narrows the fees
+    to slots that touched them, which is what a transaction contending
+    for those accounts competes against.
+
+    Quotes have the usual shape, but prices are plain numbers in
+    micro-lamports per compute unit (quote["unit"]), not Gwei, and
+    block_number is the latest slot.
+    """
+
+    name = "solana"
+
+    def __init__(
+        self,
+        rpc_url: str = SOLANA_NETWORKS["solana"]["rpc"],
+        name: Optional[str] = None,
+        headers: Optional[Dict[str, str]] = None,
+        api_key: Optional[str] = None,
+        timeout: float = DEFAULT_REQUEST_TIMEOUT,
+        accounts: Optional[List[str]] = None,
+        percentiles: Optional[Dict[str, float]] = None,
+        min_fee: int = DEFAULT_MIN_PRIORITY_FEE,
+        percentile_method: Union[PercentileMethod, str] = PercentileMethod.LINEAR,
+    ):
+        """
+        Initialize Solana provider.
+
+        Args:
+            rpc_url: Endpoint URL or template (see JsonRpcProvider)
+            name: Provider name used in quotes
+            headers: Static headers sent with every request
+            api_key: Value substituted for {api_key} in the URL and headers
+            timeout: Seconds allowed for connecting and reading each response
+            accounts: Base-58 account addresses to restrict the fees to
+            percentiles: Slow/standard/fast percentiles (default: FEE_TIERS)
+            min_fee: Lowest price recommended, in micro-lamports per CU
+            percentile_method: How percentiles between slot fees are picked
+                (see core.PercentileMethod)
+        """
+        super().__init__(rpc_url, name=name, headers=headers, api_key=api_key, timeout=timeout)
+        if min_fee < 0:
+            raise ConfigError("min_fee must not be negative")
+        self.accounts = list(accounts or [])
+        self.percentiles = dict(FEE_TIERS, **(percentiles or {}))
+        self.min_fee = min_fee
+        self.percentile_method = percentile_method_param(percentile_method)
+        self.pricing_model = PricingModel.SOLANA
+
+    @classmethod
+    def for_network(cls, network: str = "solana", rpc_url: Optional[str] = None, **kwargs) -> "SolanaProvider":
+        """Create a provider for a SOLANA_NETWORKS cluster."""
+        if network not in SOLANA_NETWORKS:
+            raise ConfigError(f"Unknown Solana network '{network}'; choose from {', '.join(SOLANA_NETWORKS)}")
+        return cls(rpc_url or SOLANA_NETWORKS[network]["rpc"], **kwargs)
+
+    async def recent_fees(self, session: aiohttp.ClientSession) -> List[Dict]:
+        """Get the raw getRecentPrioritizationFees entries ({"slot", "prioritizationFee"})."""
+        params = [self.accounts] if self.accounts else []
+        result = await self.rpc_call(session, "getRecentPrioritizationFees", params)
+        with translate_errors():
+            return [{"slot": int(e["slot"]), "prioritizationFee": int(e["prioritizationFee"])} for e in result]
+
+    async def fetch(self, session: aiohttp.ClientSession) -> GasQuote:
+        """Fetch recent prioritization fees and price the tiers from them."""
+        entries = await self.recent_fees(session)
+        fees = [entry["prioritizationFee"] for entry in entries]
+        tiers = {
+            tier: float(max(fee_percentile(fees, pct, self.percentile_method) if fees else 0, self.min_fee))
+            for tier, pct in self.percentiles.items()
+        }
+        return {
+            "provider": self.name,
+            "block_number": max((entry["slot"] for entry in entries), default=None),
+            "fetched_at": datetime.now(timezone.utc),
+            "base_fee": None,
+            "gas_price": tiers["standard"],
+            "pricing_model": PricingModel.SOLANA,
+            "tiers": tiers,
+            "unit": MICRO_LAMPORTS_PER_CU,
+        }
+
+    async def estimate_solana_tx_fee(
+        self, session: aiohttp.ClientSession, compute_units: int, target_slots: int, signatures: int = 1
+    ) -> SolanaFee:
+        """
+        Estimate the fee to land a transaction within `target_slots` slots.
+
+        The compute-unit price is the percentile of recent slot fees that
+        core.percentile_for_target_blocks gives for the window (1 slot pays
+        p90, 20 slots p10), never less than `min_fee`. Set it with
+        ComputeBudgetProgram.setComputeUnitPrice and the limit with
+        setComputeUnitLimit(compute_units); the prioritization fee is
+        charged on the requested limit, not the units actually used.
+
+        Args:
+            session: aiohttp session
+            compute_units: Compute unit limit of the transaction
+            target_slots: Slots the transaction may wait, 1 to 20
+            signatures: Signatures on the transaction
+
+        Returns:
+            Dictionary with the compute-unit price and the base, priority
+            and total fees in lamports
+
+        Raises:
+            ConfigError: If compute_units or target_slots is out of range
+        """
+        if isinstance(compute_units, bool) or not isinstance(compute_units, int) or compute_units <= 0:
+            raise ConfigError("compute_units must be a positive integer")
+        if compute_units > MAX_COMPUTE_UNITS:
+            raise ConfigError(f"compute_units must not exceed the {MAX_COMPUTE_UNITS:,} per-transaction limit")
+        if signatures < 1:
+            raise ConfigError("signatures must be at least 1")
+        target = percentile_for_target_blocks(target_slots)
+
+        fees = [entry["prioritizationFee"] for entry in await self.recent_fees(session)]
+        price = max(fee_percentile(fees, target, self.percentile_method) if fees else 0, self.min_fee)
+        base_fee = LAMPORTS_PER_SIGNATURE * signatures
+        # The runtime rounds the prioritization fee up to a whole lamport
+        priority_fee = -(-compute_units * price // MICRO_LAMPORTS_PER_LAMPORT)
+        return {
+            "compute_units": compute_units,
+            "target_slots": target_slots,
+            "micro_lamports_per_cu": price,
+            "base_fee": base_fee,
+            "priority_fee": priority_fee,
+            "total": base_fee + priority_fee,
+        }
+
+    async def block_height(self, session: aiohttp.ClientSession) -> Optional[int]:
+        """Get the node's latest slot from getSlot."""
+        with translate_errors():
+            return int(await self.rpc_call(session, "getSlot"))
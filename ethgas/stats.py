@@ -1,8 +1,50 @@
-"""Statistical analysis of gas prices."""
-
+"""
+Statistical analysis of gas prices.
+
+Percentiles and standard deviations of a handful of samples are noise, so
+calculate_advanced_stats, calculate_price_ranges and window_stats raise
+InsufficientHistoryError (with the `have` and `need` counts) when given
+fewer than `min_samples` base fees, MIN_STATS_SAMPLES (10) by default;
+classify_regime reports GasRegime.UNKNOWN instead. That makes them safe to
+call before history has warmed up: catch the error, or pass a lower
+`min_samples` to accept rougher numbers. cheapest_hours marks the hours of
+day with too few samples instead of leaving them out.
+
+Percentiles take a `method` (see core.PercentileMethod) for picking between
+samples, linear interpolation by default.
+"""
+
+import enum
 import statistics
-from typing import List, Dict, Optional
-from datetime import datetime, timedelta
+from typing import List, Dict, Optional, Union
+from datetime import datetime, timedelta, timezone
+
+from .core import PercentileMethod, percentile
+from .errors import InsufficientHistoryError
+from .models import HourStat
+from .units import Gwei
+
+# Fewest base fee samples the percentile statistics are computed from
+MIN_STATS_SAMPLES = 10
+
+# Fewest samples an hour of day needs in cheapest_hours
+MIN_HOUR_SAMPLES = 3
+
+
+def require_samples(have: int, need: int) -> None:
+    """Raise InsufficientHistoryError if `have` samples are fewer than `need`."""
+    if have < need:
+        raise InsufficientHistoryError(have, need)
+
+
+class GasRegime(str, enum.Enum):
+    """Where a base fee sits in the recent base fee distribution."""
+
+    CHEAP = "cheap"          # below the 25th percentile
+    NORMAL = "normal"        # 25th-75th percentile
+    EXPENSIVE = "expensive"  # above the 75th percentile
+    EXTREME = "extreme"      # above the 95th percentile
+    UNKNOWN = "unknown"      # no history to compare against
 
 
 class GasStats:
@@ -69,25 +111,30 @@ class GasStats:
             return "🔴 HIGH - Above average, consider waiting"
 
     @staticmethod
-    def calculate_advanced_stats(records: List[Dict]) -> Optional[Dict]:
+    def calculate_advanced_stats(
+        records: List[Dict],
+        min_samples: int = MIN_STATS_SAMPLES,
+        method: Union[PercentileMethod, str] = PercentileMethod.LINEAR,
+    ) -> Dict:
         """
         Calculate advanced statistics including percentiles, volatility, and standard deviation.
 
         Args:
             records: List of gas price records
+            min_samples: Fewest records with a base fee to compute from
+            method: How percentiles between records are picked
 
         Returns:
             Dictionary with advanced statistics
-        """
-        if not records:
-            return None
 
+        Raises:
+            InsufficientHistoryError: If fewer than `min_samples` records have a base fee
+        """
         base_fees = [r["base_fee"] for r in records if "base_fee" in r]
         max_fees = [r["max_fee"] for r in records if "max_fee" in r]
         priority_tips = [r.get("priority_tip", 0) for r in records]
 
-        if not base_fees:
-            return None
+        require_samples(len(base_fees), max(min_samples, 1))
 
         # Basic stats
         basic_stats = GasStats.calculate_stats(records)
@@ -100,11 +147,11 @@ class GasStats:
                 "median": statistics.median(base_fees),
                 "stdev": statistics.stdev(base_fees) if len(base_fees) > 1 else 0,
                 "variance": statistics.variance(base_fees) if len(base_fees) > 1 else 0,
-                "percentile_25": statistics.quantiles(base_fees, n=4)[0] if len(base_fees) >= 4 else min(base_fees),
-                "percentile_50": statistics.median(base_fees),
-                "percentile_75": statistics.quantiles(base_fees, n=4)[2] if len(base_fees) >= 4 else max(base_fees),
-                "percentile_90": statistics.quantiles(base_fees, n=10)[8] if len(base_fees) >= 10 else max(base_fees),
-                "percentile_95": statistics.quantiles(base_fees, n=20)[18] if len(base_fees) >= 20 else max(base_fees),
+                "percentile_25": percentile(base_fees, 25, method),
+                "percentile_50": percentile(base_fees, 50, method),
+                "percentile_75": percentile(base_fees, 75, method),
+                "percentile_90": percentile(base_fees, 90, method),
+                "percentile_95": percentile(base_fees, 95, method),
             }
         }
 
@@ -132,8 +179,8 @@ class GasStats:
                 **basic_stats["max_fee"],
                 "median": statistics.median(max_fees),
                 "stdev": statistics.stdev(max_fees) if len(max_fees) > 1 else 0,
-                "percentile_75": statistics.quantiles(max_fees, n=4)[2] if len(max_fees) >= 4 else max(max_fees),
-                "percentile_95": statistics.quantiles(max_fees, n=20)[18] if len(max_fees) >= 20 else max(max_fees),
+                "percentile_75": percentile(max_fees, 75, method),
+                "percentile_95": percentile(max_fees, 95, method),
             }
 
         # Priority tip stats
@@ -148,6 +195,139 @@ class GasStats:
 
         return advanced
 
+    @staticmethod
+    def window_stats(
+        samples: List[Dict],
+        sma_window: int = 10,
+        ema_alpha: float = 0.3,
+        min_samples: int = MIN_STATS_SAMPLES,
+        method: Union[PercentileMethod, str] = PercentileMethod.LINEAR,
+    ) -> Dict:
+        """
+        Summarize base fees over a window of history samples.
+
+        Args:
+            samples: Gas samples ordered oldest first (e.g. from HistoryStore.query)
+            sma_window: Number of most recent samples in the simple moving average
+            ema_alpha: Smoothing factor for the exponential moving average (0-1)
+            min_samples: Fewest samples with a base fee to compute from
+            method: How percentiles between samples are picked
+
+        Returns:
+            Dictionary with min/max/mean/median/percentiles and moving averages
+
+        Raises:
+            InsufficientHistoryError: If fewer than `min_samples` samples carry a base fee
+        """
+        base_fees = [s["base_fee"] for s in samples if s.get("base_fee") is not None]
+        require_samples(len(base_fees), max(min_samples, 1))
+
+        p25, p75, p95 = (percentile(base_fees, p, method) for p in (25, 75, 95))
+
+        ema = base_fees[0]
+        for fee in base_fees[1:]:
+            ema = ema_alpha * fee + (1 - ema_alpha) * ema
+
+        recent = base_fees[-sma_window:]
+
+        return {
+            "count": len(base_fees),
+            "min": min(base_fees),
+            "max": max(base_fees),
+            "mean": statistics.mean(base_fees),
+            "median": statistics.median(base_fees),
+            "p25": p25,
+            "p75": p75,
+            "p95": p95,
+            "sma": sum(recent) / len(recent),
+            "sma_window": len(recent),
+            "ema": ema,
+            "ema_alpha": ema_alpha,
+        }
+
+    @staticmethod
+    def classify_regime(
+        base_fee: float,
+        samples: List[Dict],
+        min_samples: int = MIN_STATS_SAMPLES,
+        method: Union[PercentileMethod, str] = PercentileMethod.LINEAR,
+    ) -> GasRegime:
+        """
+        Label a base fee relative to the base fees of history samples.
+
+        Args:
+            base_fee: Base fee to classify in gwei
+            samples: Gas samples to compare against (e.g. the last 24h from HistoryStore.query)
+            min_samples: Fewest samples with a base fee to compare against
+            method: How the regime boundaries between samples are picked
+
+        Returns:
+            GasRegime, UNKNOWN when fewer than `min_samples` samples have a base fee
+        """
+        try:
+            window = GasStats.window_stats(samples, min_samples=min_samples, method=method)
+        except InsufficientHistoryError:
+            return GasRegime.UNKNOWN
+        if base_fee > window["p95"]:
+            return GasRegime.EXTREME
+        if base_fee > window["p75"]:
+            return GasRegime.EXPENSIVE
+        if base_fee < window["p25"]:
+            return GasRegime.CHEAP
+        return GasRegime.NORMAL
+
+    @staticmethod
+    def savings_report(
+        samples: List[Dict], gas_limit: int, price_key: str = "base_fee"
+    ) -> Optional[Dict]:
+        """
+        Compare transacting at a window's cheapest, median and most expensive price.
+
+        Args:
+            samples: Gas samples ordered oldest first (history records or HistoryStore rows)
+            gas_limit: Gas units the transaction consumes
+            price_key: Sample field in gwei to price at ('base_fee', 'gas_price', 'max_fee')
+
+        Returns:
+            Dictionary with the trough/median/peak cost in ETH and the savings of
+            the trough against the peak and the median (ETH and percent), or None
+            when no sample has the price field. A single sample reports zero savings.
+        """
+        priced = [s for s in samples if s.get(price_key) is not None]
+        if not priced:
+            return None
+
+        def cost_eth(price: float) -> float:
+            return (Gwei(price).to_wei() * gas_limit).to_ether()
+
+        def saving(against: float, trough: float) -> Dict:
+            saved = cost_eth(against) - cost_eth(trough)
+            pct = (against - trough) / against * 100 if against > 0 else 0.0
+            return {"eth": saved, "pct": round(pct, 1)}
+
+        trough = min(priced, key=lambda s: s[price_key])
+        peak = max(priced, key=lambda s: s[price_key])
+        median = statistics.median(s[price_key] for s in priced)
+
+        return {
+            "count": len(priced),
+            "gas_limit": gas_limit,
+            "price_key": price_key,
+            "trough": {
+                "price_gwei": trough[price_key],
+                "cost_eth": cost_eth(trough[price_key]),
+                "timestamp": trough.get("timestamp"),
+            },
+            "median": {"price_gwei": median, "cost_eth": cost_eth(median)},
+            "peak": {
+                "price_gwei": peak[price_key],
+                "cost_eth": cost_eth(peak[price_key]),
+                "timestamp": peak.get("timestamp"),
+            },
+            "savings_vs_peak": saving(peak[price_key], trough[price_key]),
+            "savings_vs_median": saving(median, trough[price_key]),
+        }
+
     @staticmethod
     def calculate_volatility(records: List[Dict], window: int = 10) -> Optional[float]:
         """
@@ -175,22 +355,22 @@ class GasStats:
         return (stdev / avg * 100) if avg > 0 else 0
 
     @staticmethod
-    def calculate_price_ranges(records: List[Dict]) -> Optional[Dict]:
+    def calculate_price_ranges(records: List[Dict], min_samples: int = MIN_STATS_SAMPLES) -> Dict:
         """
         Calculate price ranges and quartiles for visualization.
 
         Args:
             records: List of gas price records
+            min_samples: Fewest records with a base fee to compute from
 
         Returns:
             Dictionary with price range information
-        """
-        if not records:
-            return None
 
+        Raises:
+            InsufficientHistoryError: If fewer than `min_samples` records have a base fee
+        """
         base_fees = [r["base_fee"] for r in records if "base_fee" in r]
-        if not base_fees:
-            return None
+        require_samples(len(base_fees), max(min_samples, 1))
 
         sorted_fees = sorted(base_fees)
         n = len(sorted_fees)
@@ -263,6 +443,48 @@ class GasStats:
 
         return hourly_stats
 
+    @staticmethod
+    def cheapest_hours(samples: List[Dict], min_samples: int = MIN_HOUR_SAMPLES) -> List[HourStat]:
+        """
+        Rank the UTC hours of day by their typical base fee, cheapest first.
+
+        Samples are bucketed by the UTC hour of their timestamp (naive
+        timestamps, as HistoryStore.query returns, are local time). All 24
+        hours are returned: those with at least `min_samples` base fees
+        sorted by median base fee, then the rest in hour order with
+        "sufficient" False and no statistics.
+
+        Args:
+            samples: Gas samples with "timestamp" and "base_fee" (e.g. from HistoryStore.query)
+            min_samples: Fewest base fees an hour needs to be ranked
+        """
+        fees: Dict[int, List[float]] = {hour: [] for hour in range(24)}
+        for sample in samples:
+            if sample.get("base_fee") is None or not sample.get("timestamp"):
+                continue
+            try:
+                hour = datetime.fromisoformat(sample["timestamp"]).astimezone(timezone.utc).hour
+            except (TypeError, ValueError):
+                continue
+            fees[hour].append(sample["base_fee"])
+
+        ranked, sparse = [], []
+        for hour, hour_fees in fees.items():
+            if len(hour_fees) >= max(min_samples, 1):
+                ranked.append(HourStat(
+                    hour=hour,
+                    samples=len(hour_fees),
+                    mean_base_fee=statistics.mean(hour_fees),
+                    median_base_fee=statistics.median(hour_fees),
+                    sufficient=True,
+                ))
+            else:
+                sparse.append(HourStat(
+                    hour=hour, samples=len(hour_fees), mean_base_fee=None, median_base_fee=None, sufficient=False
+                ))
+        ranked.sort(key=lambda h: (h["median_base_fee"], h["mean_base_fee"], h["hour"]))
+        return ranked + sparse
+
     @staticmethod
     def format_advanced_stats(stats: Dict) -> str:
         """
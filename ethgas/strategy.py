@@ -0,0 +1,71 @@
+"""
+Named gas strategies bundling the fee knobs into one choice.
+
+    tracker = GasTracker.for_network("ethereum").with_strategy(Strategy.FAST)
+
+A strategy sets the tier percentiles, the base fee headroom of max fees,
+the priority fee floor and the fee rounding together. Any with_*() call
+made after with_strategy() still overrides its part, e.g.
+`.with_strategy("economy").with_fee_rounding(FeeRounding(0.5))`.
+"""
+
+import enum
+from dataclasses import dataclass
+from typing import Dict, Tuple, Union
+
+from .core import BASE_FEE_MULTIPLIER, FeeRounding
+from .errors import ConfigError
+
+
+class Strategy(str, enum.Enum):
+    """Preset gas strategy (see STRATEGY_PROFILES)."""
+
+    # Cheapest fees that still land, for transactions that can wait
+    ECONOMY = "economy"
+    # The tracker's defaults
+    BALANCED = "balanced"
+    # Next-block inclusion under normal load, with room for a rising base fee
+    FAST = "fast"
+    # Outbidding nearly everyone, for time-critical arbitrage and liquidations
+    MEV = "mev"
+
+
+@dataclass(frozen=True)
+class StrategyProfile:
+    """
+    The settings a Strategy applies.
+
+    `percentiles` are the slow/standard/fast reward percentiles; recommend()
+    uses the standard one. max_fee_per_gas is `base_fee_multiplier` times
+    the base fee plus the priority fee. The priority fee floor is the
+    chain's (for_network's "min_priority_fee_gwei") times
+    `priority_floor_scale`, so floors stay in proportion on chains with
+    very different fee levels; chains without a floor get none.
+    """
+
+    percentiles: Tuple[float, float, float]
+    base_fee_multiplier: float
+    priority_floor_scale: float
+    rounding: FeeRounding
+
+    def tiers(self) -> Dict[str, float]:
+        """Map the slow/standard/fast tiers to their reward percentile."""
+        return dict(zip(("slow", "standard", "fast"), self.percentiles))
+
+
+STRATEGY_PROFILES: Dict[Strategy, StrategyProfile] = {
+    # About two full blocks of base fee rise, priced exactly
+    Strategy.ECONOMY: StrategyProfile((5.0, 25.0, 50.0), 1.25, 1.0, FeeRounding()),
+    Strategy.BALANCED: StrategyProfile((10.0, 50.0, 90.0), float(BASE_FEE_MULTIPLIER), 1.0, FeeRounding()),
+    Strategy.FAST: StrategyProfile((50.0, 90.0, 95.0), 3.0, 1.5, FeeRounding(0.01)),
+    Strategy.MEV: StrategyProfile((90.0, 95.0, 99.0), 4.0, 3.0, FeeRounding(0.1)),
+}
+
+
+def strategy_param(strategy: Union[Strategy, str]) -> Strategy:
+    """Validate a Strategy or its value, raising ConfigError listing the choices."""
+    try:
+        return Strategy(strategy)
+    except ValueError:
+        choices = ", ".join(s.value for s in Strategy)
+        raise ConfigError(f"strategy must be one of {choices}, got {strategy!r}") from None
@@ -0,0 +1,237 @@
+"""
+Scripted providers for testing code built on the tracker.
+
+MockProvider plays back canned quotes, errors and delays so alert or
+threshold logic can be tested without a network:
+
+    provider = MockProvider([30.0, 25.0, Delay(0.5, 18.0), HttpError("HTTP 503", status=503)])
+    tracker = GasTracker("http://localhost", "ethereum", provider=provider)
+
+    async for quote in tracker.watch(session=None, interval=0):
+        ...
+
+The session argument is never used by MockProvider, so None is fine. Note
+that GasTracker's own RPC helpers (estimate_eip1559 etc.) still go to the
+RPC URL; only current(), watch() and anything built on them use the mock.
+
+MockClock stands in for the system clock of QuoteCache, RateLimiter,
+CircuitBreakerProvider, RetryPolicy, is_stale, make_quote and
+GasTracker.with_clock, so time-based behavior can be tested without
+sleeping:
+
+    clock = MockClock()
+    cache = QuoteCache(ttl=12, clock=clock)
+    clock.advance(12)  # entries stored before this are now expired
+
+MockRpcSession stands in for the aiohttp session of the RPC providers,
+answering JSON-RPC calls (single or batched) from canned results, and
+StaticPriceFeed answers a fixed token price:
+
+    session = MockRpcSession({"eth_gasPrice": hex(25 * 10 ** 9)})
+    quote = await JsonRpcProvider("http://localhost").fetch(session)
+"""
+
+import asyncio
+import json
+from datetime import datetime, timedelta, timezone
+from typing import Any, Callable, Dict, List, Optional, Union
+
+import aiohttp
+
+from .clock import Clock
+from .errors import METHOD_NOT_FOUND
+from .prices import PriceFeed
+from .providers import GasProvider, make_quote
+
+
+class MockClock(Clock):
+    """
+    Clock that only moves when told to.
+
+    sleep() advances the clock by the requested time instead of waiting,
+    so a rate limiter waiting for a token returns immediately.
+    """
+
+    def __init__(self, start: Optional[datetime] = None):
+        self._start = start or datetime(2024, 1, 1, tzinfo=timezone.utc)
+        self._elapsed = 0.0
+
+    def monotonic(self) -> float:
+        return self._elapsed
+
+    def now(self) -> datetime:
+        return self._start + timedelta(seconds=self._elapsed)
+
+    def advance(self, seconds: float) -> None:
+        """Move the clock forward by `seconds`."""
+        if seconds < 0:
+            raise ValueError("MockClock cannot go backwards")
+        self._elapsed += seconds
+
+    async def sleep(self, seconds: float) -> None:
+        self.advance(max(0.0, seconds))
+        await asyncio.sleep(0)
+
+
+class Delay:
+    """Script step that answers with `response` after `seconds`."""
+
+    def __init__(self, seconds: float, response):
+        self.seconds = seconds
+        self.response = response
+
+
+Step = Union[Dict, float, int, BaseException, Delay]
+
+
+class MockProvider(GasProvider):
+    """
+    Provider that replays a script of responses.
+
+    Each fetch takes the next step:
+
+    - a quote dict is returned as-is (a copy),
+    - a number is returned as make_quote(name, number),
+    - an exception instance is raised,
+    - Delay(seconds, step) sleeps first, then handles `step`.
+
+    With `cycle` the script repeats; otherwise fetching past its end raises
+    IndexError. Pass `responder` instead of a script to compute each step from
+    the 0-based call number.
+    """
+
+    name = "mock"
+
+    def __init__(
+        self,
+        script: Optional[List[Step]] = None,
+        responder: Optional[Callable[[int], Step]] = None,
+        cycle: bool = True,
+        delay: float = 0.0,
+        name: Optional[str] = None,
+        clock: Optional[Clock] = None,
+    ):
+        """
+        Initialize mock provider.
+
+        Args:
+            script: Steps played back in order
+            responder: Function from call number to step (instead of a script)
+            cycle: Restart the script after its last step
+            delay: Extra delay in seconds before every response
+            name: Provider name used in the quotes it builds
+            clock: Time source for the fetched_at of the quotes it builds
+        """
+        if (script is None) == (responder is None):
+            raise ValueError("MockProvider needs exactly one of script or responder")
+        self.script = list(script) if script is not None else None
+        self.responder = responder
+        self.cycle = cycle
+        self.delay = delay
+        self.clock = clock
+        self.calls = 0
+        if name:
+            self.name = name
+
+    def _next_step(self) -> Step:
+        index = self.calls
+        self.calls += 1
+        if self.responder is not None:
+            return self.responder(index)
+        if index >= len(self.script) and not self.cycle:
+            raise IndexError(f"MockProvider script exhausted after {len(self.script)} responses")
+        return self.script[index % len(self.script)]
+
+    async def fetch(self, session) -> Dict:
+        """Play the next scripted step."""
+        step = self._next_step()
+        if self.delay:
+            await asyncio.sleep(self.delay)
+        while isinstance(step, Delay):
+            await asyncio.sleep(step.seconds)
+            step = step.response
+
+        if isinstance(step, BaseException):
+            raise step
+        if isinstance(step, dict):
+            return dict(step, tiers=dict(step.get("tiers", {})))
+        return make_quote(self.name, float(step), clock=self.clock)
+
+
+class MockResponse:
+    """
+    aiohttp response carrying a JSON body.
+
+    raise_for_status() raises ClientResponseError for a `status` of 400 or
+    more. read() returns the body as compact JSON bytes; each read() or
+    json() call is appended to `reads` ("read" or "json").
+    """
+
+    def __init__(self, data: Any, status: int = 200, reads: Optional[List[str]] = None):
+        self.data = data
+        self.status = status
+        self.reads = reads if reads is not None else []
+
+    async def __aenter__(self):
+        return self
+
+    async def __aexit__(self, *exc_info):
+        return False
+
+    def raise_for_status(self) -> None:
+        if self.status >= 400:
+            raise aiohttp.ClientResponseError(None, (), status=self.status, message="error")
+
+    async def read(self) -> bytes:
+        self.reads.append("read")
+        return json.dumps(self.data, separators=(",", ":")).encode()
+
+    async def json(self) -> Any:
+        self.reads.append("json")
+        return self.data
+
+
+class MockRpcSession:
+    """
+    aiohttp session answering JSON-RPC posts with no I/O.
+
+    Each call is answered from `results` (method to result); other methods
+    get a METHOD_NOT_FOUND error. Subclasses override answer() for results
+    that depend on the call, or respond() for how a whole post is answered.
+    Every posted payload is kept in `posts`, and the reads of all
+    responses in `reads`.
+    """
+
+    def __init__(self, results: Optional[Dict[str, Any]] = None):
+        self.results = dict(results or {})
+        self.posts: List[Any] = []
+        self.reads: List[str] = []
+
+    def answer(self, call: Dict) -> Dict:
+        """Response to a single call."""
+        if call["method"] in self.results:
+            return {"jsonrpc": "2.0", "id": call["id"], "result": self.results[call["method"]]}
+        return {"jsonrpc": "2.0", "id": call["id"], "error": {"code": METHOD_NOT_FOUND, "message": "no such method"}}
+
+    def respond(self, url: str, payload: Any) -> MockResponse:
+        """Response to a posted call or batch."""
+        if isinstance(payload, list):
+            return MockResponse([self.answer(call) for call in payload], reads=self.reads)
+        return MockResponse(self.answer(payload), reads=self.reads)
+
+    def post(self, url: str, json: Any = None, **kwargs) -> MockResponse:
+        self.posts.append(json)
+        return self.respond(url, json)
+
+
+class StaticPriceFeed(PriceFeed):
+    """Price feed answering `price`, or raising it when it's an exception."""
+
+    def __init__(self, price: Union[float, BaseException], name: str = "static"):
+        self.price = price
+        self.name = name
+
+    async def get_price_usd(self, session) -> float:
+        if isinstance(self.price, BaseException):
+            raise self.price
+        return self.price
@@ -0,0 +1,90 @@
+"""Tests for observing raw provider responses."""
+
+import asyncio
+
+import pytest
+
+from ethgas.audit import ObservedProvider, ResponseObserver
+from ethgas.errors import HttpError
+from ethgas.providers import AggregateProvider, JsonRpcProvider
+from ethgas.testing import MockRpcSession
+from ethgas.tracker import GasTracker
+
+
+class GasPriceNode(MockRpcSession):
+    """Pre-EIP-1559 node session answering eth_gasPrice with `gwei` per URL and "no such method" to most else."""
+
+    def __init__(self, gwei):
+        super().__init__({"eth_getBlockByNumber": {"number": hex(100)}})
+        self.gwei = gwei
+
+    def respond(self, url, payload):
+        self.results["eth_gasPrice"] = hex(self.gwei[url] * 10 ** 9)
+        return super().respond(url, payload)
+
+
+def test_observer_gets_the_raw_bodies_and_the_quote():
+    node = GasPriceNode({"http://a": 20})
+    observer = ResponseObserver()
+    tracker = GasTracker("http://a", "ethereum").with_response_observer(observer)
+
+    async def run():
+        quote = await tracker.current(node)
+        return quote, observer.drain()
+
+    quote, records = asyncio.run(run())
+
+    assert len(records) == 1
+    record = records[0]
+    assert record["provider"] == quote["provider"]
+    assert record["error"] is None
+    assert record["quote"]["gas_price"] == quote["gas_price"] == 20.0
+    assert "network" not in record["quote"]  # a copy, taken before current() added it
+    assert record["bodies"] and all(isinstance(body, bytes) for body in record["bodies"])
+    assert any(b'"result":"0x4a817c800"' in body for body in record["bodies"])
+    assert set(node.reads) == {"read"}
+
+
+def test_without_an_observer_responses_are_decoded_as_before():
+    node = GasPriceNode({"http://a": 20})
+    asyncio.run(GasTracker("http://a", "ethereum").current(node))
+    assert set(node.reads) == {"json"}
+
+
+def test_aggregate_members_are_observed_separately():
+    node = GasPriceNode({"http://a": 10, "http://b": 30})
+    providers = [JsonRpcProvider("http://a", name="a"), JsonRpcProvider("http://b", name="b")]
+    observer = ResponseObserver()
+    aggregate = AggregateProvider(providers)
+    tracker = GasTracker("http://a", "ethereum", provider=aggregate).clone().with_response_observer(observer)
+
+    async def run():
+        await tracker.current(node)
+        return observer.drain()
+
+    records = {record["provider"]: record for record in asyncio.run(run())}
+
+    assert records["a"]["quote"]["gas_price"] == 10.0
+    assert records["b"]["quote"]["gas_price"] == 30.0
+    assert not any(b"0x6fc23ac00" in body for body in records["a"]["bodies"])
+    assert aggregate.providers == providers
+
+
+def test_failures_are_reported_and_overflow_is_counted():
+    class Down(JsonRpcProvider):
+        async def fetch(self, session):
+            raise HttpError("HTTP 503", status=503)
+
+    observer = ResponseObserver(capacity=1)
+    provider = ObservedProvider(Down("http://down", name="down"), observer)
+
+    async def run():
+        for _ in range(3):
+            with pytest.raises(HttpError):
+                await provider.fetch(None)
+        return observer.drain()
+
+    records = asyncio.run(run())
+
+    assert [(r["provider"], r["quote"], r["error"]) for r in records] == [("down", None, "HTTP 503")]
+    assert observer.dropped == 2
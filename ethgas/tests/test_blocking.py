@@ -0,0 +1,44 @@
+"""Tests for the synchronous tracker wrapper."""
+
+import asyncio
+
+import pytest
+
+from ethgas.blocking import BlockingGasTracker
+from ethgas.providers import GasProvider, make_quote
+from ethgas.tracker import GasTracker
+
+
+class CountingProvider(GasProvider):
+    name = "counting"
+
+    def __init__(self):
+        self.calls = 0
+
+    async def fetch(self, session):
+        self.calls += 1
+        return make_quote(self.name, 10.0 + self.calls)
+
+
+def test_blocking_calls_share_tracker_state():
+    provider = CountingProvider()
+    tracker = GasTracker("http://localhost", "ethereum", provider=provider).with_cache(ttl=60)
+
+    with BlockingGasTracker(tracker=tracker) as blocking:
+        first = blocking.current()
+        cached = blocking.current()
+        forced = blocking.force_refresh()
+
+    assert first["network"] == "Ethereum"
+    assert cached["gas_price"] == first["gas_price"] == 11.0
+    assert forced["gas_price"] == 12.0
+
+
+def test_refuses_to_run_inside_event_loop():
+    blocking = BlockingGasTracker("http://localhost", "ethereum", provider=CountingProvider())
+
+    async def inside_loop():
+        blocking.current()
+
+    with pytest.raises(RuntimeError):
+        asyncio.run(inside_loop())
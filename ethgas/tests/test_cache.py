@@ -0,0 +1,209 @@
+"""Tests for the quote cache."""
+
+import asyncio
+import json
+from datetime import datetime, timedelta
+
+import pytest
+
+from ethgas.cache import CACHE_FILE_VERSION, PersistentQuoteCache, QuoteCache
+from ethgas.errors import SchemaMismatchError
+from ethgas.providers import GasProvider, PricingModel, make_quote
+from ethgas.retry import RetryPolicy
+from ethgas.testing import MockClock
+from ethgas.tracker import GasTracker
+from ethgas.units import Gwei
+
+
+class CountingProvider(GasProvider):
+    """Provider that counts fetches and answers after a short delay."""
+
+    name = "counting"
+
+    def __init__(self):
+        self.calls = 0
+
+    async def fetch(self, session):
+        self.calls += 1
+        await asyncio.sleep(0.01)
+        return make_quote(self.name, float(self.calls))
+
+
+def test_concurrent_callers_share_one_fetch():
+    provider = CountingProvider()
+    tracker = GasTracker("http://localhost", "ethereum", provider=provider).with_cache(ttl=60)
+
+    async def burst():
+        return await asyncio.gather(*(tracker.current(None) for _ in range(10)))
+
+    quotes = asyncio.run(burst())
+
+    assert provider.calls == 1
+    assert {q["gas_price"] for q in quotes} == {1.0}
+
+
+def test_cached_until_expiry_or_forced():
+    provider = CountingProvider()
+    tracker = GasTracker("http://localhost", "ethereum", provider=provider).with_cache(ttl=0.2)
+
+    async def run():
+        first = await tracker.current(None)
+        cached = await tracker.current(None)
+        forced = await tracker.force_refresh(None)
+        await asyncio.sleep(0.25)
+        expired = await tracker.current(None)
+        return first, cached, forced, expired
+
+    first, cached, forced, expired = asyncio.run(run())
+
+    assert cached["gas_price"] == first["gas_price"] == 1.0
+    assert forced["gas_price"] == 2.0
+    assert expired["gas_price"] == 3.0
+
+
+def test_expires_exactly_at_the_ttl():
+    clock = MockClock()
+    provider = CountingProvider()
+    tracker = GasTracker("http://localhost", "ethereum", provider=provider).with_cache(
+        cache=QuoteCache(ttl=12, clock=clock)
+    )
+
+    assert asyncio.run(tracker.current(None))["gas_price"] == 1.0
+    clock.advance(11.999)
+    assert asyncio.run(tracker.current(None))["gas_price"] == 1.0
+    clock.advance(0.001)
+    assert asyncio.run(tracker.current(None))["gas_price"] == 2.0
+
+
+def test_failed_fetch_is_not_cached():
+    cache = QuoteCache(ttl=60)
+
+    async def failing():
+        raise ValueError("boom")
+
+    async def run():
+        try:
+            await cache.get_or_fetch("key", failing)
+        except ValueError:
+            pass
+        return cache.get("key")
+
+    assert asyncio.run(run()) is None
+
+
+def test_clones_share_the_cache_and_provider():
+    provider = CountingProvider()
+    tracker = GasTracker("http://localhost", "ethereum", provider=provider).with_cache(ttl=60)
+    handles = [tracker.clone() for _ in range(100)]
+
+    async def burst():
+        return await asyncio.gather(*(handle.current(None) for handle in handles))
+
+    quotes = asyncio.run(burst())
+
+    assert provider.calls == 1
+    assert len(quotes) == 100 and {q["gas_price"] for q in quotes} == {1.0}
+    assert all(handle.cache is tracker.cache for handle in handles)
+    assert handles[0].with_retry(RetryPolicy()).provider is not tracker.provider
+
+
+def test_persistent_cache_survives_a_restart_until_the_ttl(tmp_path):
+    path = tmp_path / "quotes.json"
+    clock = MockClock()
+    provider = CountingProvider()
+    cache = PersistentQuoteCache(path, ttl=12, clock=clock)
+    tracker = GasTracker("http://localhost", "ethereum", provider=provider).with_cache(cache=cache)
+    first = asyncio.run(tracker.current(None))
+
+    # A restarted process reading the same file 5 seconds later
+    clock.advance(5)
+    restarted = GasTracker("http://localhost", "ethereum", provider=provider).with_cache(
+        cache=PersistentQuoteCache(path, ttl=12, clock=clock)
+    )
+    reloaded = asyncio.run(restarted.current(None))
+
+    assert provider.calls == 1
+    assert reloaded["gas_price"] == 1.0
+    assert reloaded["fetched_at"] == first["fetched_at"]
+    assert reloaded["pricing_model"] is PricingModel.LEGACY
+
+    clock.advance(7)
+    assert PersistentQuoteCache(path, ttl=12, clock=clock).get(("Ethereum", "counting")) is None
+    assert asyncio.run(restarted.current(None))["gas_price"] == 2.0
+
+
+def test_persistent_cache_keys_keep_their_type(tmp_path):
+    path = tmp_path / "quotes.json"
+    cache = PersistentQuoteCache(path, ttl=12)
+    for key in ("ethereum", ("ethereum", "counting"), ("ethereum", ("nested", 1))):
+        asyncio.run(cache.get_or_fetch(key, lambda: CountingProvider().fetch(None)))
+    # A key JSON can't hold is logged instead of failing the fetch
+    asyncio.run(cache.get_or_fetch(("ethereum", object()), lambda: CountingProvider().fetch(None)))
+
+    reloaded = PersistentQuoteCache(path, ttl=12)
+
+    assert reloaded.get("ethereum")["gas_price"] == 1.0
+    assert reloaded.get(("ethereum", "counting"))["gas_price"] == 1.0
+    assert reloaded.get(("ethereum", ("nested", 1)))["gas_price"] == 1.0
+    assert reloaded.get(tuple("ethereum")) is None
+
+
+def test_corrupt_cache_file_is_discarded(tmp_path):
+    path = tmp_path / "quotes.json"
+    for content in ("{not json", "[]", '{"entries": []}', '{"version": 1, "entries": [{"key": 1}]}'):
+        path.write_text(content)
+        cache = PersistentQuoteCache(path, ttl=12)
+        assert cache.get(("Ethereum", "counting")) is None
+
+    tracker = GasTracker("http://localhost", "ethereum", provider=CountingProvider()).with_cache(ttl=12, path=str(path))
+    asyncio.run(tracker.current(None))
+    assert PersistentQuoteCache(path, ttl=12).get(("Ethereum", "counting"))["gas_price"] == 1.0
+
+
+def test_version_1_cache_files_are_migrated(tmp_path):
+    path = tmp_path / "quotes.json"
+    clock = MockClock()
+    fetched_at = datetime(2024, 5, 1, 12, 0)
+    quote = {
+        "provider": "counting", "block_number": 7, "fetched_at": fetched_at.isoformat(), "base_fee": 9.5,
+        "gas_price": 10.0, "pricing_model": "eip1559", "tiers": {"slow": 9.75, "standard": 10.0, "fast": 11.0},
+    }
+    expires_at = clock.now() + timedelta(seconds=5)
+    path.write_text(json.dumps({
+        "version": 1,
+        "entries": [{"key": ["Ethereum", "counting"], "expires_at": expires_at.isoformat(), "quote": quote}],
+    }))
+
+    loaded = PersistentQuoteCache(path, ttl=12, clock=clock).get(("Ethereum", "counting"))
+
+    assert loaded["fetched_at"] == fetched_at
+    assert loaded["pricing_model"] is PricingModel.EIP1559
+    assert loaded["tiers"] == {"slow": 9.75, "standard": 10.0, "fast": 11.0}
+    assert isinstance(loaded["tiers"]["fast"], Gwei)
+
+
+def test_cache_files_from_newer_releases_are_rejected(tmp_path):
+    path = tmp_path / "quotes.json"
+    path.write_text('{"schema_version": 99, "entries": []}')
+
+    with pytest.raises(SchemaMismatchError) as excinfo:
+        PersistentQuoteCache(path, ttl=12)
+    assert (excinfo.value.found, excinfo.value.expected) == (99, CACHE_FILE_VERSION)
+    assert path.read_text() == '{"schema_version": 99, "entries": []}'
+
+
+def test_cancel_fetches_aborts_the_fetch_in_flight():
+    cache = QuoteCache()
+
+    async def run():
+        caller = asyncio.ensure_future(cache.get_or_fetch("key", lambda: asyncio.sleep(10)))
+        await asyncio.sleep(0)
+        names = [t.get_name() for t in asyncio.all_tasks()]
+        await cache.cancel_fetches()
+        await asyncio.wait({caller})
+        return names, caller
+
+    names, caller = asyncio.run(run())
+    assert "ethgas fetch 'key'" in names
+    assert caller.cancelled()
+    assert cache.get("key") is None
@@ -0,0 +1,133 @@
+"""Tests for the provider circuit breaker."""
+
+import asyncio
+import time
+
+import pytest
+
+from ethgas.circuit import CircuitBreakerProvider, CircuitState
+from ethgas.errors import AllProvidersFailedError, CircuitOpenError, HttpError
+from ethgas.providers import AggregateProvider, RaceProvider
+from ethgas.testing import MockClock, MockProvider
+from ethgas.tracker import GasTracker
+
+
+def down():
+    return HttpError("HTTP 503", status=503)
+
+
+def test_opens_after_consecutive_failures():
+    mock = MockProvider([down(), down(), 20.0])
+    breaker = CircuitBreakerProvider(mock, failure_threshold=2, cooldown=60)
+
+    for _ in range(2):
+        with pytest.raises(HttpError):
+            asyncio.run(breaker.fetch(None))
+    assert breaker.state is CircuitState.OPEN
+
+    with pytest.raises(CircuitOpenError) as excinfo:
+        asyncio.run(breaker.fetch(None))
+    assert excinfo.value.provider == "mock"
+    assert mock.calls == 2
+
+
+def test_success_resets_failure_count():
+    mock = MockProvider([down(), 20.0, down(), 20.0])
+    breaker = CircuitBreakerProvider(mock, failure_threshold=2, cooldown=60)
+
+    for _ in range(2):
+        with pytest.raises(HttpError):
+            asyncio.run(breaker.fetch(None))
+        assert asyncio.run(breaker.fetch(None))["gas_price"] == 20.0
+    assert breaker.state is CircuitState.CLOSED
+
+
+def test_half_open_probe_closes_or_reopens():
+    mock = MockProvider([down(), down(), 20.0])
+    breaker = CircuitBreakerProvider(mock, failure_threshold=1, cooldown=0.05)
+
+    with pytest.raises(HttpError):
+        asyncio.run(breaker.fetch(None))
+    time.sleep(0.06)
+    assert breaker.state is CircuitState.HALF_OPEN
+
+    # A failed probe opens the circuit again for a full cooldown
+    with pytest.raises(HttpError):
+        asyncio.run(breaker.fetch(None))
+    assert breaker.state is CircuitState.OPEN
+
+    time.sleep(0.06)
+    assert asyncio.run(breaker.fetch(None))["gas_price"] == 20.0
+    assert breaker.state is CircuitState.CLOSED
+
+
+def test_cooldown_follows_the_clock():
+    clock = MockClock()
+    breaker = CircuitBreakerProvider(MockProvider([down(), 20.0]), failure_threshold=1, cooldown=30, clock=clock)
+
+    with pytest.raises(HttpError):
+        asyncio.run(breaker.fetch(None))
+    clock.advance(29.9)
+    with pytest.raises(CircuitOpenError) as excinfo:
+        asyncio.run(breaker.fetch(None))
+    assert excinfo.value.retry_in == pytest.approx(0.1)
+
+    clock.advance(0.1)
+    assert breaker.state is CircuitState.HALF_OPEN
+    assert asyncio.run(breaker.fetch(None))["gas_price"] == 20.0
+
+
+def test_half_open_allows_a_single_probe():
+    mock = MockProvider([down(), 20.0], delay=0.05)
+    breaker = CircuitBreakerProvider(mock, failure_threshold=1, cooldown=0)
+
+    with pytest.raises(HttpError):
+        asyncio.run(breaker.fetch(None))
+
+    async def probe_twice():
+        return await asyncio.gather(breaker.fetch(None), breaker.fetch(None), return_exceptions=True)
+
+    probe, concurrent = asyncio.run(probe_twice())
+    assert probe["gas_price"] == 20.0
+    assert isinstance(concurrent, CircuitOpenError)
+    assert mock.calls == 2
+
+
+def test_tracker_wraps_each_raced_provider():
+    dead = MockProvider([down()], name="dead")
+    alive = MockProvider([25.0], name="alive", delay=0.01)
+    tracker = GasTracker(
+        "http://localhost", "ethereum", provider=RaceProvider([dead, alive])
+    ).with_circuit_breaker(failure_threshold=1, cooldown=60)
+
+    for _ in range(3):
+        assert asyncio.run(tracker.current(None))["gas_price"] == 25.0
+    assert dead.calls == 1
+
+    single = GasTracker("http://localhost", "ethereum", provider=MockProvider([down()]))
+    single.with_circuit_breaker(failure_threshold=1)
+    with pytest.raises(HttpError):
+        asyncio.run(single.current(None))
+    with pytest.raises(CircuitOpenError):
+        asyncio.run(single.current(None))
+
+
+def test_breakers_on_a_clone_leave_the_original_untouched():
+    for composite in (RaceProvider, AggregateProvider):
+        members = [MockProvider([25.0], name="a"), MockProvider([26.0], name="b")]
+        tracker = GasTracker("http://localhost", "ethereum", provider=composite(members))
+        clone = tracker.clone().with_circuit_breaker(failure_threshold=1)
+
+        assert tracker.provider.providers == members
+        assert clone.provider is not tracker.provider
+        assert [type(p) for p in clone.provider.providers] == [CircuitBreakerProvider] * 2
+        assert [p.provider for p in clone.provider.providers] == members
+
+
+def test_all_open_members_fail_the_race():
+    race = RaceProvider([CircuitBreakerProvider(MockProvider([down()]), failure_threshold=1)])
+    with pytest.raises(AllProvidersFailedError):
+        asyncio.run(race.fetch(None))
+    with pytest.raises(AllProvidersFailedError) as excinfo:
+        asyncio.run(race.fetch(None))
+    assert isinstance(excinfo.value.errors[0], CircuitOpenError)
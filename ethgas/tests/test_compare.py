@@ -0,0 +1,64 @@
+"""Tests for multi-chain cost comparison."""
+
+import asyncio
+
+import pytest
+
+from ethgas.compare import CompareOptions, CompareSortKey, compare_chains
+from ethgas.errors import ConfigError
+from ethgas.networks import NETWORKS
+from ethgas.testing import MockResponse, MockRpcSession
+
+GAS_PRICES = {
+    NETWORKS["ethereum"]["rpc"]: 20 * 10 ** 9,
+    NETWORKS["arbitrum"]["rpc"]: 10 ** 7,
+}
+
+
+class ChainSession(MockRpcSession):
+    """Answers eth_gasPrice (single or batched) for known pre-EIP-1559 RPC URLs and a flat $2000 token price."""
+
+    def __init__(self):
+        super().__init__({"eth_getBlockByNumber": {"number": hex(100)}})
+
+    def respond(self, url, payload):
+        if url not in GAS_PRICES:
+            return MockResponse({}, status=503)
+        self.results["eth_gasPrice"] = hex(GAS_PRICES[url])
+        return super().respond(url, payload)
+
+    def get(self, url, **kwargs):
+        coin_id = url.split("ids=")[1].split("&")[0]
+        return MockResponse({coin_id: {"usd": 2000.0}})
+
+
+def test_sorted_cheapest_first_with_partial_failures():
+    results = asyncio.run(
+        compare_chains(["ethereum", "polygon", "arbitrum", "nowhere"], session=ChainSession())
+    )
+
+    assert [r["network_id"] for r in results] == ["arbitrum", "ethereum", "polygon", "nowhere"]
+    assert results[0]["cost_usd"] < results[1]["cost_usd"]
+    assert results[1]["cost_usd"] == 0.84  # 21000 gas at 20 gwei, $2000/ETH
+    assert "error" in results[2] and "error" in results[3]
+    assert [r["rank"] for r in results] == [1, 2, None, None]
+
+
+def test_options_sort_by_name_and_filter_by_budget():
+    chains = ["ethereum", "polygon", "arbitrum"]
+    by_name = asyncio.run(
+        compare_chains(chains, session=ChainSession(), options=CompareOptions(sort_by="name", ascending=False))
+    )
+    assert [(r["network_id"], r["rank"]) for r in by_name] == [("ethereum", 1), ("arbitrum", 2), ("polygon", None)]
+
+    under = asyncio.run(
+        compare_chains(chains, session=ChainSession(), options=CompareOptions(tier="fast", only_chains_under_usd=0.5))
+    )
+    assert [r["network_id"] for r in under] == ["arbitrum", "polygon"]
+    assert under[0]["tier"] == "fast"
+
+    with pytest.raises(ConfigError):
+        CompareOptions(sort_by="gas")
+    with pytest.raises(ConfigError):
+        asyncio.run(compare_chains(chains, tier="fast", session=ChainSession(), options=CompareOptions()))
+    assert CompareOptions(sort_by="base_fee").sort_by is CompareSortKey.BASE_FEE
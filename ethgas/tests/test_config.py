@@ -0,0 +1,63 @@
+"""Tests for TOML configuration."""
+
+import pytest
+
+from ethgas.config import Config
+from ethgas.errors import ConfigError
+from ethgas.providers import RaceProvider
+from ethgas.retry import RetryingProvider
+from ethgas.tracker import GasTracker
+
+CONFIG = """
+[chain]
+network = "arbitrum"
+
+[providers]
+strategy = "race"
+order = ["infura", "etherscan"]
+
+[providers.infura]
+project_id = "from-file"
+network = "arbitrum-mainnet"
+
+[retry]
+max_retries = 5
+base_delay = 1
+
+[cache]
+ttl = 6
+"""
+
+
+def write(tmp_path, text):
+    path = tmp_path / "config.toml"
+    path.write_text(text)
+    return path
+
+
+def test_tracker_from_config_with_env_override(tmp_path):
+    config = Config.load(write(tmp_path, CONFIG), env={"ETH_GAS_INFURA_KEY": "from-env"})
+
+    tracker = GasTracker.from_config(config)
+
+    assert tracker.network_name == "Arbitrum One"
+    assert tracker.l1_fee_model == "arbitrum"
+    assert isinstance(tracker.provider, RetryingProvider)
+    assert isinstance(tracker.provider.provider, RaceProvider)
+    assert tracker.provider.provider.providers[0].rpc_url.endswith("/from-env")
+    assert tracker.retry_policy.max_retries == 5
+    assert tracker.cache.ttl == 6.0
+
+
+def test_errors_name_the_offending_field(tmp_path):
+    cases = {
+        '[retry]\nmax_retries = "three"': "retry.max_retries",
+        "[cache]\nttl_seconds = 5": "cache.ttl_seconds",
+        '[chain]\nnetwork = "moon"': "chain.network",
+        '[providers]\norder = ["alchemy"]': "providers.alchemy.api_key",
+        "[chain\n": "Invalid TOML",
+    }
+    for text, field in cases.items():
+        with pytest.raises(ConfigError, match=field.replace(".", r"\.")):
+            config = Config.load(write(tmp_path, text), env={})
+            config.build_provider()
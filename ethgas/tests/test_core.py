@@ -0,0 +1,419 @@
+"""Tests for the networking-free estimation core."""
+
+import ast
+import copy
+import json
+import random
+import subprocess
+import sys
+from pathlib import Path
+
+import pytest
+
+from ethgas.core import (
+    Congestion,
+    FeeRounding,
+    PercentileMethod,
+    RecencyWeight,
+    adaptive_headroom,
+    apply_base_fee_multiplier,
+    base_fee_rising,
+    blend_priority_fees,
+    blob_fee_from_history,
+    bump_fee,
+    check_fee_history,
+    calldata_cost,
+    congestion_from_history,
+    decayed_mean,
+    deployment_gas,
+    fee_history_from_block,
+    fee_history_recommendation,
+    fee_history_tiers,
+    fees_at_base_fee,
+    percentile,
+    priority_fee_histogram,
+    recency_weights,
+    tx_fee_fields,
+)
+from ethgas.errors import ConfigError, DeserializeError, EmptyFeeHistoryError, FeeCeilingExceededError, GasError
+from ethgas.units import Gwei, Wei
+
+PACKAGE = Path(__file__).resolve().parents[1]
+
+
+def imported_modules(module):
+    """Top-level names of everything `module` imports, following ethgas-relative imports."""
+    seen, pending, external = set(), [module], set()
+    while pending:
+        name = pending.pop()
+        if name in seen:
+            continue
+        seen.add(name)
+        for node in ast.walk(ast.parse((PACKAGE / f"{name}.py").read_text())):
+            if isinstance(node, ast.ImportFrom) and node.level == 1:
+                pending.append(node.module)
+            elif isinstance(node, ast.ImportFrom):
+                external.add(node.module.split(".")[0])
+            elif isinstance(node, ast.Import):
+                external.update(alias.name.split(".")[0] for alias in node.names)
+    return external
+
+
+def test_core_only_needs_the_standard_library():
+    stdlib = getattr(sys, "stdlib_module_names", None)
+    external = imported_modules("core")
+
+    assert "aiohttp" not in external
+    if stdlib is not None:  # Python 3.10+
+        assert external <= set(stdlib)
+
+
+def test_core_imports_with_aiohttp_unavailable():
+    # A None entry makes `import aiohttp` raise ImportError, as if it weren't installed
+    code = (
+        "import sys; sys.modules['aiohttp'] = None; import ethgas.core, ethgas; "
+        "assert 'ethgas.tracker' not in sys.modules; assert issubclass(ethgas.GasError, Exception)"
+    )
+    subprocess.run([sys.executable, "-c", code], cwd=PACKAGE.parent, check=True)
+
+
+def test_fee_history_math():
+    fee_history = {
+        "oldestBlock": hex(100),
+        "baseFeePerGas": [hex(10), hex(12)],
+        "reward": [[hex(1), hex(2), hex(3)]],
+    }
+
+    estimate = fee_history_tiers(fee_history)
+    assert estimate["base_fee_per_gas"] == 12
+    assert estimate["tiers"]["fast"] == {"max_priority_fee_per_gas": 3, "max_fee_per_gas": 27}
+
+    single = dict(fee_history, reward=[[hex(4)]])
+    assert fee_history_recommendation(single) == {"max_priority_fee_per_gas": 4, "max_fee_per_gas": 28, "block_number": 100}
+
+
+def test_malformed_fee_history_names_the_bad_field():
+    valid = {"oldestBlock": hex(100), "baseFeePerGas": [hex(10), hex(12)], "reward": [[hex(1), hex(2), hex(3)]]}
+    cases = {
+        "baseFeePerGas[1]": dict(valid, baseFeePerGas=[hex(10), "0x1g"]),
+        "no baseFeePerGas": {"reward": []},
+        "reward[0] has 1": dict(valid, reward=[[hex(1)]]),
+        "reward must be a list": dict(valid, reward="0x1"),
+        "gasUsedRatio[0]": dict(valid, gasUsedRatio=[None]),
+        "oldestBlock": dict(valid, oldestBlock=100),
+        "must be an object": [valid],
+    }
+    for message, fee_history in cases.items():
+        with pytest.raises(DeserializeError) as excinfo:
+            fee_history_tiers(fee_history)
+        assert message in str(excinfo.value)
+    assert check_fee_history(dict(valid, reward=None, gasUsedRatio=None), 3) is not None
+
+
+# Values a broken or hostile endpoint might put anywhere in a response
+FUZZ_VALUES = [
+    None, True, 0, -1, 1.5, float("nan"), float("inf"), "", "0x", "0x1", "0xzz", "1a", "0x" + "f" * 65,
+    [], {}, [None], ["0x1", 7], [[]], [["0x1"], []], {"0x1": "0x2"},
+]
+FEE_HISTORY_FIELDS = ["oldestBlock", "baseFeePerGas", "reward", "gasUsedRatio", "baseFeePerBlobGas", "blobGasUsedRatio"]
+
+
+def fuzz_value(rng):
+    return copy.deepcopy(rng.choice(FUZZ_VALUES))
+
+
+def fuzzed_fee_history(rng):
+    """A random, usually malformed, eth_feeHistory result."""
+    kind = rng.random()
+    if kind < 0.2:
+        try:
+            return json.loads(bytes(rng.randrange(256) for _ in range(rng.randrange(24))))
+        except ValueError:
+            return fuzz_value(rng)
+    fee_history = {
+        "oldestBlock": hex(100),
+        "baseFeePerGas": [hex(10), hex(12)],
+        "reward": [[hex(1), hex(2), hex(3)]],
+        "gasUsedRatio": [0.5],
+        "baseFeePerBlobGas": [hex(1), hex(1)],
+        "blobGasUsedRatio": [0.5],
+    }
+    for _ in range(rng.randrange(1, 4)):
+        field = rng.choice(FEE_HISTORY_FIELDS)
+        action = rng.random()
+        if action < 0.2:
+            fee_history.pop(field, None)
+        elif action < 0.5 or not isinstance(fee_history.get(field), list) or not fee_history[field]:
+            fee_history[field] = fuzz_value(rng)
+        else:
+            values = fee_history[field]
+            idx = rng.randrange(len(values))
+            if isinstance(values[idx], str) and values[idx]:  # truncated hex
+                values[idx] = values[idx][:rng.randrange(len(values[idx]))]
+            else:
+                values[idx] = fuzz_value(rng)
+    return fee_history
+
+
+def test_fee_history_parsers_only_raise_gas_errors_on_arbitrary_input():
+    rng = random.Random(1559)
+    parsers = [
+        fee_history_tiers,
+        fee_history_recommendation,
+        congestion_from_history,
+        blob_fee_from_history,
+        lambda fee_history: priority_fee_histogram(fee_history, 4),
+    ]
+    for _ in range(3000):
+        fee_history = fuzzed_fee_history(rng)
+        for parse in parsers:
+            try:
+                parse(fee_history)
+            except GasError:
+                pass
+            except Exception as e:  # pragma: no cover - the failure report
+                pytest.fail(f"{parse.__name__} raised {e!r} for {fee_history!r}")
+
+
+def test_bump_fee_raises_both_fees_and_respects_current_fees():
+    original = {"max_priority_fee_per_gas": 2_000_000_001, "max_fee_per_gas": 40_000_000_000, "block_number": 100}
+
+    bumped = bump_fee(original)
+    assert bumped["max_priority_fee_per_gas"] == 2_250_000_002  # rounded up, never under the bump
+    assert bumped["max_fee_per_gas"] == 45_000_000_000
+    assert bumped["block_number"] == 100
+
+    # Gas rose past the bump: the current fees are the floor
+    current = {"max_priority_fee_per_gas": 3_000_000_000, "max_fee_per_gas": 41_000_000_000, "block_number": 120}
+    bumped = bump_fee(original, 10, current=current)
+    assert bumped == {"max_priority_fee_per_gas": 3_000_000_000, "max_fee_per_gas": 44_000_000_000, "block_number": 120}
+
+
+def test_bump_fee_checks_bump_and_ceiling():
+    original = {"max_priority_fee_per_gas": 1, "max_fee_per_gas": 100, "block_number": 1}
+    with pytest.raises(ConfigError):
+        bump_fee(original, 5)
+    with pytest.raises(FeeCeilingExceededError) as error:
+        bump_fee(original, max_fee_ceiling=110)
+    assert error.value.required == 113
+    assert bump_fee(original, max_fee_ceiling=113)["max_fee_per_gas"] == 113
+
+
+def test_tx_fee_fields_are_minimal_hex_quantities():
+    fees = {"max_priority_fee_per_gas": Wei(1_500_000_000), "max_fee_per_gas": Wei(40_000_000_000), "block_number": 5}
+    assert tx_fee_fields(fees) == {"maxFeePerGas": "0x9502f9000", "maxPriorityFeePerGas": "0x59682f00"}
+    assert tx_fee_fields({"gas_price": 0}, gas_limit=21000) == {"gasPrice": "0x0", "gas": "0x5208"}
+
+    for bad, kwargs in (
+        ({"gas_price": -1}, {}),
+        ({"max_priority_fee_per_gas": 2, "max_fee_per_gas": 1}, {}),
+        ({"block_number": 5}, {}),
+        ({"gas_price": 1}, {"gas_limit": 20999}),
+    ):
+        with pytest.raises(ConfigError):
+            tx_fee_fields(bad, **kwargs)
+    with pytest.raises(TypeError):
+        tx_fee_fields({"gas_price": Gwei(30)})
+
+
+def test_priority_fee_histogram_bins_rewards_and_skips_empty_blocks():
+    history = {"reward": [[hex(1), hex(2), hex(3)], [], [hex(9), hex(10), hex(10)]]}
+
+    histogram = priority_fee_histogram(history, 3)
+
+    assert histogram == {"edges": [1, 4, 7, 10], "counts": [3, 0, 3], "samples": 6, "blocks": 2}
+    # Equal fees still give increasing edges, with everything in the first bucket
+    assert priority_fee_histogram({"reward": [[hex(5), hex(5)]]}, 2) == {
+        "edges": [5, 6, 7], "counts": [2, 0], "samples": 2, "blocks": 1
+    }
+    with pytest.raises(EmptyFeeHistoryError):
+        priority_fee_histogram({"reward": [[], []]}, 3)
+
+
+def test_fee_rounding_rounds_up_by_default():
+    fees = {"max_priority_fee_per_gas": 1_234_567_890, "max_fee_per_gas": 30_050_000_000, "block_number": 7}
+
+    assert FeeRounding(0.1).apply_fees(fees) == {
+        "max_priority_fee_per_gas": 1_300_000_000, "max_fee_per_gas": 30_100_000_000, "block_number": 7
+    }
+    nearest = FeeRounding(1, round_up=False).apply_fees(fees)
+    assert (nearest["max_priority_fee_per_gas"], nearest["max_fee_per_gas"]) == (1_000_000_000, 30_000_000_000)
+    assert FeeRounding(0.1).apply(2_000_000_000) == 2_000_000_000
+    assert FeeRounding().apply_fees(fees) == fees
+    with pytest.raises(ConfigError):
+        FeeRounding(0)
+
+
+def test_base_fee_multiplier_reprices_the_max_fee():
+    fees = {"max_priority_fee_per_gas": Wei(2), "max_fee_per_gas": Wei(22)}
+    assert apply_base_fee_multiplier(fees, 10, 1.25)["max_fee_per_gas"] == 15  # 12.5 rounded up
+    assert apply_base_fee_multiplier(fees, 10, 1)["max_fee_per_gas"] == 12
+    with pytest.raises(ConfigError):
+        apply_base_fee_multiplier(fees, 10, 0.9)
+
+
+def test_congestion_from_mean_gas_used_ratio():
+    levels = [congestion_from_history({"gasUsedRatio": r})["congestion"] for r in ([0.9, 0.95], [0.2, 0.9], [0.1, 0.3])]
+
+    assert levels == [Congestion.CONGESTED, Congestion.NORMAL, Congestion.UNDERUTILIZED]
+    assert congestion_from_history({"gasUsedRatio": []}) is None
+
+
+def test_decayed_mean_halves_weight_every_half_life():
+    assert decayed_mean([(0, 10.0), (60, 40.0)], half_life=60) == pytest.approx(20.0)
+    assert decayed_mean([(0, 10.0), (6000, 40.0)], half_life=60) == pytest.approx(10.0)
+    assert decayed_mean([(-5, 10.0)], half_life=60) == 10.0
+    with pytest.raises(ConfigError):
+        decayed_mean([(0, 10.0)], half_life=0)
+
+
+def test_adaptive_headroom_grows_with_volatility_within_base_fee_bounds():
+    calm = adaptive_headroom([100, 101, 100, 101, 100, 101], 0.9)
+    moderate = adaptive_headroom([100, 106, 100, 106, 100, 106], 0.9)
+    swinging = adaptive_headroom([100, 112, 100, 112, 100, 112], 0.9)
+
+    assert adaptive_headroom([100] * 10, 0.99) == calm == 1.125
+    assert 1.125 < moderate < swinging < 2
+    assert adaptive_headroom([100, 112, 100, 112, 100, 112], 0.99) > swinging
+    assert adaptive_headroom([10, 20, 5, 40, 1, 80], 0.99) == 1.125 ** 6
+    for confidence in (0.4, 1.0):
+        with pytest.raises(ConfigError):
+            adaptive_headroom([100, 101], confidence)
+
+
+def test_blob_fee_doubles_the_upcoming_blob_base_fee():
+    history = {
+        "oldestBlock": hex(100),
+        "baseFeePerGas": [hex(10 ** 10)] * 3,
+        "baseFeePerBlobGas": [hex(1), hex(2), hex(3)],
+        "blobGasUsedRatio": [0.5, 1.0],
+    }
+
+    fee = blob_fee_from_history(history, blobs=2)
+
+    assert fee["base_fee_per_blob_gas"] == 3
+    assert fee["max_fee_per_blob_gas"] == 6
+    assert fee["blob_gas_used_ratio"] == 0.75
+    assert fee["blob_gas"] == 2 * 131072
+    assert fee["max_cost_wei"] == 6 * 2 * 131072
+    assert fee["block_number"] == 101
+    # Nodes from before Cancun have no blob fields
+    assert blob_fee_from_history({"baseFeePerGas": [hex(10 ** 10)]}) is None
+    with pytest.raises(ConfigError):
+        blob_fee_from_history(history, blobs=7)
+
+
+def test_calldata_cost_prices_zero_bytes_cheaper():
+    assert calldata_cost(bytes([0, 0, 1]), fee_per_gas=10) == (4 + 4 + 16) * 10
+
+
+def test_fee_history_from_block_scales_the_priority_fee_per_percentile():
+    block = {"number": "0x64", "baseFeePerGas": "0x3e8", "gasUsed": "0x0", "gasLimit": "0x0"}
+    fee_history = fee_history_from_block(block, 1000, [5, 30, 50, 70, 99])
+
+    assert fee_history["degraded"] is True
+    assert fee_history["reward"] == [[hex(800), hex(900), hex(1000), hex(1250), hex(1500)]]
+    assert fee_history["gasUsedRatio"] == [0.0]
+    assert fee_history_tiers(fee_history, {"p50": 50})["block_number"] == 100
+    with pytest.raises(DeserializeError):
+        fee_history_from_block({"number": "0x64"}, 1000, [50])
+
+    lower = fee_history_from_block(block, 1000, [30, 70], PercentileMethod.LOWER)
+    assert lower["reward"] == [[hex(800), hex(1000)]]
+    assert fee_history_from_block(block, 1000, [30, 70], "higher")["reward"] == [[hex(1000), hex(1500)]]
+
+
+def test_percentile_methods_differ_between_ranks():
+    values = [10, 1, 3, 2]
+    assert [percentile(values, 50, method) for method in PercentileMethod] == [2.5, 2, 2, 3]
+    assert percentile(values, 90) == pytest.approx(7.9)
+    assert percentile(values, 90, "nearest-rank") == 10
+    # Every method agrees at the ends of the sample
+    for method in PercentileMethod:
+        assert (percentile(values, 0, method), percentile(values, 100, method)) == (1, 10)
+    assert percentile([4], 37, "linear") == 4
+
+    with pytest.raises(ConfigError, match="empty"):
+        percentile([], 50)
+    with pytest.raises(ConfigError, match="0-100"):
+        percentile(values, 101)
+    with pytest.raises(ConfigError, match="nearest-rank"):
+        percentile(values, 50, "median")
+
+
+def test_fees_at_base_fee_reprices_a_tier_without_fetching():
+    estimate = fee_history_tiers({
+        "oldestBlock": hex(100),
+        "baseFeePerGas": [hex(10), hex(12)],
+        "reward": [[hex(1), hex(2), hex(3)]],
+    })
+    standard = estimate["tiers"]["standard"]["max_priority_fee_per_gas"]
+
+    for base_fee in (0, 10 ** 9, 10 ** 11):
+        fees = fees_at_base_fee(estimate, base_fee)
+        assert fees["max_priority_fee_per_gas"] == standard
+        assert fees["max_fee_per_gas"] == base_fee * 2 + standard
+        assert fees["block_number"] == estimate["block_number"]
+    for args in ((-1,), (10 ** 9, "urgent")):
+        with pytest.raises(ConfigError):
+            fees_at_base_fee(estimate, *args)
+
+
+def test_recency_weighting_follows_a_fee_jump():
+    # Priority fees jumped from 1 to 10 gwei over the last three of ten blocks
+    tips = [10 ** 9] * 7 + [10 ** 10] * 3
+    fee_history = {"baseFeePerGas": [hex(10 ** 10)] * 11, "reward": [[hex(t)] for t in tips]}
+
+    flat = fee_history_recommendation(fee_history)["max_priority_fee_per_gas"]
+    linear = fee_history_recommendation(fee_history, RecencyWeight.LINEAR)["max_priority_fee_per_gas"]
+    exponential = fee_history_recommendation(fee_history, "exponential")["max_priority_fee_per_gas"]
+
+    assert flat == 37 * 10 ** 8
+    assert exponential > flat and linear > flat
+    assert fee_history_tiers(fee_history, {"p50": 50}, "linear")["tiers"]["p50"]["max_priority_fee_per_gas"] == linear
+    # A steady market comes out the same either way
+    steady = {"baseFeePerGas": [hex(10)] * 4, "reward": [[hex(7)]] * 3}
+    assert fee_history_recommendation(steady, "exponential")["max_priority_fee_per_gas"] == 7
+
+
+def test_recency_weights_put_the_newest_block_at_one():
+    assert recency_weights(4, "linear") == [0.25, 0.5, 0.75, 1.0]
+    assert recency_weights(6, "exponential")[0] == 0.5
+    assert recency_weights(3) == [1.0, 1.0, 1.0]
+    with pytest.raises(ConfigError):
+        recency_weights(3, "quadratic")
+
+
+def test_blend_priority_fees_and_rising_base_fee():
+    assert blend_priority_fees(1_000, 3_000, 0.25) == 1_500
+    assert blend_priority_fees(1_000, 3_000, 0.0) == 1_000
+    assert blend_priority_fees(1, 2, 0.5) == 2  # rounded to a whole wei
+    with pytest.raises(ConfigError):
+        blend_priority_fees(1, 2, -0.1)
+
+    def history(*base_fees):
+        return {"baseFeePerGas": [hex(fee) for fee in base_fees], "gasUsedRatio": [0.5] * (len(base_fees) - 1)}
+
+    assert base_fee_rising(history(100, 103, 105))
+    assert not base_fee_rising(history(100, 103, 104))
+    assert not base_fee_rising(history(100, 90))
+    assert not base_fee_rising(history(0, 0))
+
+
+def test_deployment_gas_counts_initcode_words_and_code_deposit():
+    initcode = b"\x60\x80\x60\x40" + bytes(28)  # one word: 4 non-zero and 28 zero bytes
+    gas = deployment_gas(initcode)
+
+    assert gas["intrinsic_gas"] == 21000 + 32000 + (4 * 16 + 28 * 4) + 2
+    assert gas["code_deposit_gas"] == 200 * 32
+    assert gas["gas"] == 53178 + 6400
+    assert deployment_gas(initcode + b"\x01", runtime_size=10) == {
+        "intrinsic_gas": 53178 + 16 + 2, "code_deposit_gas": 2000, "gas": 55196,
+    }
+
+    with pytest.raises(ConfigError, match="EIP-3860"):
+        deployment_gas(bytes(49153))
+    with pytest.raises(ConfigError):
+        deployment_gas(b"\x01", runtime_size=24577)
+    with pytest.raises(ConfigError):
+        deployment_gas(b"")
@@ -0,0 +1,34 @@
+"""Tests for snapshot change detection."""
+
+from ethgas.diff import diff_snapshots, fee_change
+
+GWEI = 10 ** 9
+
+
+def snapshot(block, base_gwei, **priority_gwei):
+    tiers = {tier: {"max_priority_fee_per_gas": p * GWEI, "max_fee_per_gas": 0} for tier, p in priority_gwei.items()}
+    return {"network": "Ethereum", "block_number": block, "base_fee_per_gas": base_gwei * GWEI, "tiers": tiers}
+
+
+def test_diff_reports_changes_and_threshold_crossings():
+    old = snapshot(100, 25, slow=1, fast=3)
+    new = snapshot(103, 18, slow=2, fast=3, urgent=5)
+
+    change = diff_snapshots(old, new, threshold_gwei=20)
+
+    assert change["block_delta"] == 3
+    assert change["base_fee"] == {"old": 25 * GWEI, "new": 18 * GWEI, "change": -7 * GWEI, "change_pct": -28.0}
+    assert change["tiers"]["slow"]["change"] == -6 * GWEI
+    assert change["tiers"]["fast"]["change_pct"] == -25.0
+    assert "urgent" not in change["tiers"]
+    assert (change["crossed_up"], change["crossed_down"]) == (False, True)
+
+    back = diff_snapshots(new, old, threshold_gwei=20)
+    assert (back["crossed_up"], back["crossed_down"]) == (True, False)
+    assert not diff_snapshots(old, old, threshold_gwei=20)["crossed_up"]
+
+
+def test_percent_change_from_zero_is_safe():
+    assert fee_change(0, 0)["change_pct"] == 0.0
+    assert fee_change(0, 5)["change_pct"] is None
+    assert fee_change(0, 5)["change"] == 5
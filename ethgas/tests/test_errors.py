@@ -0,0 +1,60 @@
+"""Tests for typed gas errors."""
+
+import asyncio
+
+import aiohttp
+import pytest
+
+from ethgas.errors import (
+    DeserializeError,
+    HttpError,
+    RateLimitedError,
+    RpcError,
+    check_rpc_response,
+    history_unavailable,
+    translate_errors,
+)
+from ethgas.retry import RetryPolicy
+
+
+def test_http_status_is_translated():
+    with pytest.raises(RateLimitedError) as info:
+        with translate_errors():
+            raise aiohttp.ClientResponseError(
+                None, (), status=429, message="slow down", headers={"Retry-After": "3"}
+            )
+
+    assert info.value.status == 429
+    assert RetryPolicy.retry_after(info.value) == 3.0
+
+
+def test_malformed_response_is_deserialize_error():
+    with pytest.raises(DeserializeError):
+        with translate_errors():
+            {"result": None}["result"]["baseFeePerGas"]
+
+
+def test_rpc_error_object():
+    with pytest.raises(RpcError) as info:
+        check_rpc_response({"error": {"code": -32601, "message": "method not found"}})
+
+    assert info.value.code == -32601
+
+
+def test_history_unavailable_ignores_missing_methods():
+    assert history_unavailable(RpcError(-32000, "missing trie node 1a2b"))
+    assert history_unavailable(RpcError(4444, "pruned history unavailable"))
+    assert not history_unavailable(RpcError(-32601, "the method eth_feeHistory does not exist/is not available"))
+    assert not history_unavailable(RpcError(-32000, "execution reverted"))
+
+
+def test_translated_errors_keep_retry_classification():
+    assert RetryPolicy.is_retryable(HttpError("HTTP 503", status=503))
+    assert RetryPolicy.is_retryable(HttpError("connection reset"))
+    assert not RetryPolicy.is_retryable(HttpError("HTTP 404", status=404))
+    assert not RetryPolicy.is_retryable(RpcError(-32000, "execution reverted"))
+
+    with pytest.raises(Exception) as info:
+        with translate_errors():
+            raise asyncio.TimeoutError()
+    assert RetryPolicy.is_retryable(info.value)
@@ -0,0 +1,49 @@
+"""Tests for history exports."""
+
+from datetime import datetime, timezone
+
+import pytest
+
+from ethgas.export import DataExporter
+
+
+def test_parquet_export_has_typed_utc_columns(tmp_path):
+    pa = pytest.importorskip("pyarrow")
+    pq = pytest.importorskip("pyarrow.parquet")
+    samples = [
+        {
+            "timestamp": "2024-01-01T12:00:00+00:00",
+            "network": "Ethereum",
+            "provider": "node",
+            "base_fee": 20.5,
+            "gas_price": 21.5,
+            "tiers": {"slow": 21.0, "standard": 21.5, "fast": 23.0},
+            "block_number": 19000000,
+        },
+        {
+            "timestamp": "2024-01-01T12:00:12+00:00",
+            "network": "Ethereum",
+            "provider": "node",
+            "base_fee": None,
+            "gas_price": 30.0,
+            "tiers": {"slow": None, "standard": 30.0, "fast": None},
+            "block_number": None,
+        },
+    ]
+    path = tmp_path / "history.parquet"
+
+    DataExporter.export_to_parquet(samples, path)
+    table = pq.read_table(str(path))
+
+    assert table.schema.field("timestamp").type == pa.timestamp("ns", tz="UTC")
+    assert table.schema.field("base_fee_wei").type == pa.uint64()
+    assert table.column("timestamp").to_pylist()[0] == datetime(2024, 1, 1, 12, tzinfo=timezone.utc)
+    assert table.column("base_fee_wei").to_pylist() == [20_500_000_000, None]
+    assert table.column("fast_wei").to_pylist() == [23_000_000_000, None]
+    assert table.column("block_number").to_pylist() == [19000000, None]
+
+
+def test_parquet_export_rejects_empty_history(tmp_path):
+    pytest.importorskip("pyarrow")
+    with pytest.raises(ValueError):
+        DataExporter.export_to_parquet([], tmp_path / "empty.parquet")
@@ -0,0 +1,173 @@
+"""Tests for the SQLite and in-memory history stores."""
+
+import asyncio
+import sqlite3
+from datetime import datetime, timedelta
+
+import pytest
+
+from ethgas.errors import SchemaMismatchError
+from ethgas.history import HistoryStore, InMemoryHistory
+from ethgas.providers import make_quote
+from ethgas.stats import GasRegime, GasStats
+from ethgas.testing import MockProvider
+from ethgas.tracker import GasTracker
+
+
+def quote(fee, network="Ethereum"):
+    return dict(make_quote("node", fee, base_fee=fee), network=network)
+
+
+def test_ring_buffer_evicts_oldest_samples():
+    history = InMemoryHistory(capacity=3)
+    for fee in (10.0, 20.0, 30.0, 40.0):
+        history.record(quote(fee))
+    history.record({"network": "Ethereum", "error": "boom"})
+
+    assert len(history) == 3
+    assert [s["base_fee"] for s in history.samples()] == [20.0, 30.0, 40.0]
+    assert GasStats.window_stats(history.samples(), min_samples=3)["min"] == 20.0
+
+    # Readers get copies and cannot corrupt the buffer
+    history.samples()[0]["tiers"]["fast"] = 0
+    assert history.samples()[0]["tiers"]["fast"] == 20.0
+
+    with pytest.raises(ValueError):
+        InMemoryHistory(capacity=0)
+
+
+def test_query_filters_by_time_and_network():
+    history = InMemoryHistory()
+    now = datetime.now()
+    history.record(quote(10.0), timestamp=now - timedelta(hours=30))
+    history.record(quote(20.0), timestamp=now - timedelta(hours=1))
+    history.record(quote(99.0, network="Polygon"), timestamp=now - timedelta(hours=1))
+
+    recent = history.query(now - timedelta(hours=24), now, network="Ethereum")
+
+    assert [s["base_fee"] for s in recent] == [20.0]
+
+
+class FixedBaseFeeTracker(GasTracker):
+    async def get_base_fee_gwei(self, session):
+        return 5.0
+
+
+def test_current_regime_reads_in_memory_history():
+    history = InMemoryHistory()
+    for fee in range(10, 30):
+        history.record(quote(float(fee)))
+
+    tracker = FixedBaseFeeTracker("http://localhost", "ethereum")
+
+    assert asyncio.run(tracker.current_regime(None, history)) is GasRegime.CHEAP
+
+
+def test_store_keeps_block_numbers_and_upgrades_old_databases(tmp_path):
+    db_path = str(tmp_path / "history.db")
+    old = sqlite3.connect(db_path)
+    old.execute(
+        "CREATE TABLE samples (id INTEGER PRIMARY KEY AUTOINCREMENT, timestamp REAL NOT NULL, network TEXT, "
+        "provider TEXT, base_fee REAL, gas_price REAL, slow REAL, standard REAL, fast REAL)"
+    )
+    now = datetime.now().timestamp()
+    old.execute("INSERT INTO samples (timestamp, network, gas_price) VALUES (?, 'Ethereum', 20.0)", (now,))
+    old.commit()
+    old.close()
+
+    store = HistoryStore(db_path)
+    store.record(dict(make_quote("node", 25.0, block_number=123), network="Ethereum"))
+    samples = store.query(datetime.now() - timedelta(minutes=1), datetime.now())
+
+    assert [s["block_number"] for s in samples] == [None, 123]
+    assert store.conn.execute("PRAGMA user_version").fetchone()[0] == HistoryStore.SCHEMA_VERSION
+
+
+def test_store_rejects_databases_from_newer_releases(tmp_path):
+    db_path = str(tmp_path / "history.db")
+    newer = sqlite3.connect(db_path)
+    newer.execute("PRAGMA user_version = 99")
+    newer.close()
+
+    with pytest.raises(SchemaMismatchError, match="history database schema version 99") as excinfo:
+        HistoryStore(db_path)
+    assert (excinfo.value.found, excinfo.value.expected) == (99, HistoryStore.SCHEMA_VERSION)
+
+
+def committed_rows(db_path):
+    conn = sqlite3.connect(db_path)
+    try:
+        return conn.execute("SELECT COUNT(*) FROM samples").fetchone()[0]
+    finally:
+        conn.close()
+
+
+def test_buffered_store_commits_in_batches_and_on_flush(tmp_path):
+    db_path = str(tmp_path / "history.db")
+    store = HistoryStore(db_path, buffer_size=3)
+
+    for fee in (10.0, 20.0):
+        store.record(quote(fee))
+    assert committed_rows(db_path) == 0
+    store.record(quote(30.0))
+    assert committed_rows(db_path) == 3
+
+    store.record(quote(40.0))
+    store.flush()
+    store.flush()  # nothing left to commit
+    assert committed_rows(db_path) == 4
+
+
+def test_tracker_records_fetched_quotes_and_flushes_them(tmp_path):
+    db_path = str(tmp_path / "history.db")
+    provider = MockProvider([20.0, 25.0])
+    tracker = GasTracker("http://localhost", "ethereum", provider=provider)
+    tracker.with_history(HistoryStore(db_path, buffer_size=100))
+
+    async def collect_then_flush():
+        for _ in range(2):
+            await tracker.current(None)
+        await asyncio.gather(tracker.flush(), tracker.flush())
+
+    asyncio.run(collect_then_flush())
+
+    assert committed_rows(db_path) == 2
+    samples = tracker.history.query(datetime.now() - timedelta(minutes=1), datetime.now())
+    assert [(s["network"], s["gas_price"]) for s in samples] == [("Ethereum", 20.0), ("Ethereum", 25.0)]
+
+
+def block_quote(number, block_hash, base_fee):
+    return dict(make_quote("ws", base_fee, base_fee=base_fee, block_number=number), network="Ethereum",
+                block_hash=block_hash)
+
+
+def test_two_block_reorg_keeps_history_canonical():
+    for store in (HistoryStore(":memory:"), InMemoryHistory()):
+        check_two_block_reorg(store)
+
+
+def check_two_block_reorg(store):
+    for number, block_hash, fee in ((100, "0xa0", 10.0), (101, "0xa1", 11.0), (102, "0xa2", 12.0)):
+        store.record(block_quote(number, block_hash, fee))
+
+    # Blocks 101 and 102 are replaced by a competing branch
+    assert store.handle_reorg({"number": hex(101), "hash": "0xb1", "baseFeePerGas": hex(15 * 10 ** 9)}) == 2
+    store.record(block_quote(102, "0xb2", 16.0))
+
+    window = (datetime.now() - timedelta(minutes=1), datetime.now() + timedelta(minutes=1))
+    canonical = store.query(*window)
+    assert [(s["block_number"], s["block_hash"], s["base_fee"]) for s in canonical] == [
+        (100, "0xa0", 10.0), (101, "0xb1", 15.0), (102, "0xb2", 16.0)
+    ]
+    orphaned = [s for s in store.query(*window, include_orphaned=True) if s["orphaned"]]
+    assert [(s["block_number"], s["block_hash"]) for s in orphaned] == [(102, "0xa2")]
+
+
+def test_recording_a_conflicting_hash_flags_the_old_sample():
+    store = HistoryStore(":memory:")
+    store.record(block_quote(100, "0xa0", 10.0))
+    store.record(block_quote(100, "0xb0", 11.0))
+
+    window = (datetime.now() - timedelta(minutes=1), datetime.now() + timedelta(minutes=1))
+    assert [s["block_hash"] for s in store.query(*window)] == ["0xb0"]
+    assert store.handle_reorg({"number": hex(100), "hash": "0xb0"}) == 0
@@ -0,0 +1,129 @@
+"""Tests for the IPC socket provider."""
+
+import asyncio
+import json
+import os
+
+import pytest
+
+from ethgas.errors import HttpError
+from ethgas.ipc import IPC_SUPPORTED, IpcProvider
+
+if not IPC_SUPPORTED:
+    pytest.skip("IPC needs Unix domain sockets", allow_module_level=True)
+
+GWEI = 10 ** 9
+
+RESULTS = {
+    "eth_getBlockByNumber": {"number": "0x10", "baseFeePerGas": hex(10 * GWEI)},
+    "eth_gasPrice": hex(12 * GWEI),
+    "eth_feeHistory": {"oldestBlock": "0x10", "baseFeePerGas": [hex(10 * GWEI), hex(11 * GWEI)], "gasUsedRatio": [0.5]},
+    "eth_blockNumber": "0x10",
+}
+
+
+def answer(request):
+    if isinstance(request, list):
+        return [answer(r) for r in request]
+    return {"jsonrpc": "2.0", "id": request["id"], "result": RESULTS[request["method"]]}
+
+
+class Node:
+    """Unix socket JSON-RPC server in the style of a local node."""
+
+    def __init__(self, path, newline=True, hang_up_after=None):
+        self.path = path
+        self.newline = newline
+        self.hang_up_after = hang_up_after
+        self.connections = 0
+
+    async def __aenter__(self):
+        self.server = await asyncio.start_unix_server(self.handle, path=self.path)
+        return self
+
+    async def __aexit__(self, *exc_info):
+        self.server.close()
+        await self.server.wait_closed()
+
+    async def handle(self, reader, writer):
+        self.connections += 1
+        served = 0
+        while True:
+            line = await reader.readline()
+            if not line:
+                break
+            body = json.dumps(answer(json.loads(line))).encode()
+            if self.newline:
+                writer.write(body + b"\n")
+            else:
+                # Values back to back, split across writes
+                half = len(body) // 2
+                writer.write(body[:half])
+                await writer.drain()
+                await asyncio.sleep(0.01)
+                writer.write(body[half:])
+            await writer.drain()
+            served += 1
+            if served == self.hang_up_after:
+                break
+        writer.close()
+
+
+def test_fetch_quotes_over_the_socket(tmp_path):
+    socket_path = str(tmp_path / "node.ipc")
+
+    async def run():
+        async with Node(socket_path) as node:
+            provider = IpcProvider(socket_path)
+            quote = await provider.fetch(None)
+            height = await provider.block_height(None)
+            await provider.close()
+            return quote, height, node.connections
+
+    quote, height, connections = asyncio.run(run())
+    assert quote["provider"] == "ipc"
+    assert quote["gas_price"] == 12.0
+    assert quote["base_fee"] == 11.0
+    assert quote["block_number"] == 16
+    assert height == 16
+    assert connections == 1  # one connection reused for every request
+
+
+def test_unframed_values_split_across_reads(tmp_path):
+    socket_path = str(tmp_path / "node.ipc")
+
+    async def run():
+        async with Node(socket_path, newline=False):
+            provider = IpcProvider(socket_path)
+            heights = [await provider.block_height(None) for _ in range(2)]
+            await provider.close()
+            return heights
+
+    assert asyncio.run(run()) == [16, 16]
+
+
+def test_reconnects_when_the_node_hangs_up(tmp_path):
+    socket_path = str(tmp_path / "node.ipc")
+
+    async def run():
+        async with Node(socket_path, hang_up_after=1) as node:
+            provider = IpcProvider(socket_path)
+            heights = [await provider.block_height(None) for _ in range(3)]
+            await provider.close()
+            return heights, node.connections
+
+    heights, connections = asyncio.run(run())
+    assert heights == [16, 16, 16]
+    assert connections == 3
+
+
+def test_missing_socket_is_an_http_error(tmp_path):
+    socket_path = str(tmp_path / "node.ipc")
+    with pytest.raises(HttpError, match="Cannot connect to IPC socket"):
+        asyncio.run(IpcProvider(socket_path).block_height(None))
+
+
+def test_path_is_expanded_and_shown_in_repr():
+    provider = IpcProvider("~/node.ipc", name="reth")
+    assert provider.ipc_path == os.path.expanduser("~/node.ipc")
+    assert repr(provider) == f"IpcProvider(name='reth', path={provider.ipc_path!r})"
@@ -0,0 +1,53 @@
+"""Tests for the L2 fee models."""
+
+import pytest
+
+from ethgas.l2 import OP_GET_L1_FEE_SELECTOR, calldata_gas, encode_op_l1_fee_call, op_l1_fee
+from ethgas.units import MAX_UINT256
+
+
+def test_calldata_gas_counts_zero_and_non_zero_bytes():
+    assert calldata_gas(b"") == 0
+    assert calldata_gas(b"\x00\x00\x01\xff") == 2 * 4 + 2 * 16
+
+
+def test_op_l1_fee_matches_oracle_formula():
+    # Bedrock mainnet oracle parameters: overhead 188, scalar 0.684 (684000 at 6 decimals)
+    calldata = b"\x00" * 100 + b"\x01" * 50  # 100 * 4 + 50 * 16 = 1200 gas
+    fee = op_l1_fee(calldata, l1_base_fee=30 * 10 ** 9, overhead=188, scalar=684000, decimals=6)
+
+    assert fee == (1200 + 188) * 30 * 10 ** 9 * 684000 // 10 ** 6 == 28_481_760_000_000
+
+
+def test_op_l1_fee_rounds_down():
+    assert op_l1_fee(b"", l1_base_fee=1, overhead=1, scalar=1_999_999, decimals=6) == 1
+
+
+def test_op_l1_fee_handles_large_calldata_without_overflow():
+    calldata = b"\xff" * 10 ** 6
+    fee = op_l1_fee(calldata, l1_base_fee=2 ** 64, overhead=2 ** 64, scalar=10 ** 12, decimals=6)
+
+    assert fee == (16 * 10 ** 6 + 2 ** 64) * 2 ** 64 * 10 ** 6
+
+
+def test_op_l1_fee_rejects_values_outside_uint256():
+    for kwargs in ({"l1_base_fee": -1}, {"overhead": -1}, {"scalar": MAX_UINT256 + 1}):
+        args = dict({"l1_base_fee": 1, "overhead": 0, "scalar": 1}, **kwargs)
+        with pytest.raises(ValueError):
+            op_l1_fee(b"\x01", **args)
+
+    with pytest.raises(ValueError):
+        op_l1_fee(b"\x01", l1_base_fee=MAX_UINT256, overhead=MAX_UINT256, scalar=MAX_UINT256, decimals=0)
+    with pytest.raises(ValueError, match="decimals"):
+        op_l1_fee(b"\x01", l1_base_fee=1, overhead=0, scalar=1, decimals=2 ** 64)
+
+
+def test_get_l1_fee_call_abi_encodes_the_transaction():
+    tx = bytes(range(1, 40))
+    data = encode_op_l1_fee_call(tx)
+
+    words = bytes.fromhex(data[len(OP_GET_L1_FEE_SELECTOR):])
+    assert data.startswith(OP_GET_L1_FEE_SELECTOR)
+    assert int.from_bytes(words[0:32], "big") == 32
+    assert int.from_bytes(words[32:64], "big") == len(tx)
+    assert words[64:] == tx + b"\x00" * 25
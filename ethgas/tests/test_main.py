@@ -0,0 +1,101 @@
+"""Tests for the command line: `ethgas history` and option parsing."""
+
+import argparse
+import contextlib
+import csv
+import io
+from datetime import datetime, timedelta
+
+import pytest
+
+from ethgas.history import HistoryStore
+from ethgas.main import history_command, parse_fee_gwei, parse_time_spec
+from ethgas.providers import make_quote
+
+
+def run(argv):
+    out, err = io.StringIO(), io.StringIO()
+    with contextlib.redirect_stdout(out), contextlib.redirect_stderr(err):
+        status = history_command(argv)
+    return status, out.getvalue(), err.getvalue()
+
+
+def make_store(path, fees, network="Ethereum", hours_ago=1):
+    store = HistoryStore(str(path))
+    start = datetime.now() - timedelta(hours=hours_ago)
+    for i, fee in enumerate(fees):
+        quote = dict(make_quote("node", fee, base_fee=fee, block_number=100 + i), network=network)
+        store.record(quote, timestamp=start + timedelta(minutes=i))
+    store.close()
+    return str(path)
+
+
+def test_parse_time_spec_accepts_spans_iso_and_now():
+    now = datetime(2024, 5, 2, 12, 0)
+    assert parse_time_spec("24h", now) == datetime(2024, 5, 1, 12, 0)
+    assert parse_time_spec("90m", now) == datetime(2024, 5, 2, 10, 30)
+    assert parse_time_spec("1w", now) == datetime(2024, 4, 25, 12, 0)
+    assert parse_time_spec("now", now) == now
+    assert parse_time_spec("2024-05-01T08:00:00", now) == datetime(2024, 5, 1, 8, 0)
+    with pytest.raises(argparse.ArgumentTypeError):
+        parse_time_spec("yesterday", now)
+
+
+def test_stats_summarize_the_range(tmp_path):
+    db = make_store(tmp_path / "history.db", [10.0, 20.0, 30.0, 40.0])
+    status, out, _ = run(["--db", db, "--from", "2h", "--stats", "--min-samples", "4"])
+
+    assert status == 0
+    assert "over 4 samples" in out
+    assert "min          10.00" in out
+    assert "max          40.00" in out
+    assert "mean         25.00" in out
+
+
+def test_stats_need_min_stats_samples_by_default(tmp_path):
+    db = make_store(tmp_path / "history.db", [10.0, 20.0, 30.0, 40.0])
+    status, out, err = run(["--db", db, "--from", "2h", "--stats"])
+
+    assert status == 1
+    assert "Not enough data" in err and out == ""
+
+
+def test_empty_range_is_an_error(tmp_path):
+    db = make_store(tmp_path / "history.db", [10.0], hours_ago=48)
+    status, out, err = run(["--db", db, "--from", "24h", "--stats"])
+    assert status == 1
+    assert "No history samples" in err
+    assert out == ""
+
+    status, _, err = run(["--db", db, "--network", "polygon", "--from", "3d"])
+    assert status == 1
+    assert "for Polygon" in err
+
+
+def test_missing_database_is_not_created(tmp_path):
+    status, _, err = run(["--db", str(tmp_path / "missing.db")])
+    assert status == 1
+    assert "No history database" in err
+    assert not (tmp_path / "missing.db").exists()
+
+
+def test_export_csv(tmp_path):
+    db = make_store(tmp_path / "history.db", [10.0, 20.0])
+    out_path = tmp_path / "samples.csv"
+    status, out, _ = run(["export", "--db", db, "--format", "csv", "--out", str(out_path)])
+
+    assert status == 0
+    assert "Exported 2 samples" in out
+    with open(out_path, newline="") as f:
+        rows = list(csv.DictReader(f))
+    assert [row["base_fee"] for row in rows] == ["10.0", "20.0"]
+    assert rows[1]["block_number"] == "101"
+    assert rows[0]["network"] == "Ethereum"
+
+
+def test_fee_options_accept_units():
+    assert parse_fee_gwei("2") == 2.0
+    assert parse_fee_gwei("2000000000wei") == 2.0
+    assert parse_fee_gwei("0.5gwei") == 0.5
+    with pytest.raises(argparse.ArgumentTypeError, match="negative"):
+        parse_fee_gwei("-1")
@@ -0,0 +1,135 @@
+"""Tests for mempool-based inclusion estimates."""
+
+import asyncio
+
+import pytest
+
+from ethgas.errors import ConfigError, RpcError
+from ethgas.mempool import inclusion_priority_fee, pending_priority_fees
+from ethgas.tracker import GasTracker
+from ethgas.units import Wei
+
+GWEI = 10 ** 9
+FEE_HISTORY = {
+    "baseFeePerGas": [hex(10 * GWEI), hex(10 * GWEI)],
+    "gasUsedRatio": [0.5],
+    "reward": [[hex(1 * GWEI), hex(2 * GWEI), hex(3 * GWEI)]],
+}
+
+
+class CannedTracker(GasTracker):
+    """Tracker answering RPC calls from a dict, raising for missing methods."""
+
+    def __init__(self, responses):
+        super().__init__("http://localhost", "ethereum")
+        self.responses = responses
+
+    async def rpc_result(self, session, method, params):
+        if method not in self.responses:
+            raise RpcError(-32601, f"the method {method} does not exist")
+        return self.responses[method]
+
+
+class DecimalGweiTracker(GasTracker):
+    """Tracker whose node answers every fee as a decimal gwei string."""
+
+    ANSWERS = {
+        "eth_feeHistory": {"baseFeePerGas": ["10", "10", "10"], "gasUsedRatio": [0.5, 0.5], "reward": [["2"], ["2"]]},
+        "txpool_content": {"pending": {"0xa": {"0": {"gas": hex(21000), "maxFeePerGas": "50", "maxPriorityFeePerGas": "6"}}}},
+        "eth_getBlockByNumber": {"gasLimit": hex(30_000_000), "baseFeePerGas": "10"},
+        "eth_getTransactionByHash": {"maxFeePerGas": "40", "maxPriorityFeePerGas": "2"},
+        "eth_getTransactionReceipt": {"effectiveGasPrice": "30", "gasUsed": hex(50000)},
+    }
+
+    async def eth_call(self, session, payload):
+        if isinstance(payload, list):
+            return [await self.eth_call(session, call) for call in payload]
+        return {"jsonrpc": "2.0", "id": payload["id"], "result": self.ANSWERS[payload["method"]]}
+
+
+def test_pending_fees_skip_underpriced_and_cap_tips():
+    txpool = {
+        "pending": {
+            "0xa": {"0": {"gas": hex(21000), "maxFeePerGas": hex(12 * GWEI), "maxPriorityFeePerGas": hex(5 * GWEI)}},
+            "0xb": {"0": {"gas": hex(21000), "gasPrice": hex(9 * GWEI)}},
+        }
+    }
+
+    assert pending_priority_fees(txpool, Wei(10 * GWEI)) == [(Wei(2 * GWEI), 21000)]
+
+
+def test_inclusion_fee_outbids_marginal_transaction():
+    fees = [(Wei(5), 400), (Wei(3), 400), (Wei(1), 400)]
+
+    # Room for 900 gas in the next block: the 3-wei tx is the marginal one
+    assert inclusion_priority_fee(fees, 1000, fill_ratio=1.0, blocks=1) == Wei(1 + 1)
+    assert inclusion_priority_fee(fees, 500, fill_ratio=1.0, blocks=1) == Wei(3 + 1)
+    # Everything fits once later blocks free up space
+    assert inclusion_priority_fee(fees, 500, fill_ratio=0.0, blocks=3) == Wei(1)
+
+
+def test_falls_back_to_fee_history_without_txpool():
+    tracker = CannedTracker({"eth_feeHistory": FEE_HISTORY})
+
+    estimate = asyncio.run(tracker.estimate_inclusion(None))
+
+    assert estimate["source"] == "fee_history"
+    assert estimate["priority_fee_for_next_block"] == Wei(3 * GWEI)
+    assert estimate["for_next_3_blocks"] == Wei(2 * GWEI)
+    assert estimate["fill_ratio"] == 0.5
+
+
+def hybrid_tracker(base_fees, txpool=True):
+    responses = {
+        "eth_feeHistory": {
+            "baseFeePerGas": [hex(fee * GWEI) for fee in base_fees],
+            "gasUsedRatio": [0.5] * (len(base_fees) - 1),
+            "reward": [[hex(2 * GWEI)]] * (len(base_fees) - 1),
+        },
+    }
+    if txpool:
+        tx = {"gas": hex(21000), "maxFeePerGas": hex(50 * GWEI), "maxPriorityFeePerGas": hex(6 * GWEI)}
+        responses["txpool_content"] = {"pending": {"0xa": {"0": tx}}}
+        responses["eth_getBlockByNumber"] = {"gasLimit": hex(30_000_000)}
+    return CannedTracker(responses)
+
+
+def test_hybrid_estimate_blends_history_and_mempool():
+    # Stable base fee: history-leaning, 0.7 * 2 gwei + 0.3 * 6 gwei
+    stable = asyncio.run(hybrid_tracker([10, 10, 10]).hybrid_estimate(None))
+    assert stable["source"] == "hybrid"
+    assert stable["mempool_weight"] == 0.3
+    assert stable["max_priority_fee_per_gas"] == Wei(3_200_000_000)
+    assert stable["max_fee_per_gas"] == Wei(23_200_000_000)
+
+    # Rising base fee: mempool-leaning, 0.3 * 2 gwei + 0.7 * 6 gwei
+    rising = asyncio.run(hybrid_tracker([10, 11, 12]).hybrid_estimate(None))
+    assert rising["mempool_weight"] == 0.7
+    assert rising["max_priority_fee_per_gas"] == Wei(4_800_000_000)
+
+    explicit = asyncio.run(hybrid_tracker([10, 10, 10]).hybrid_estimate(None, mempool_weight=1.0))
+    assert explicit["max_priority_fee_per_gas"] == Wei(6 * GWEI)
+
+
+def test_hybrid_estimate_without_mempool_is_fee_history():
+    fees = asyncio.run(hybrid_tracker([10, 11, 12], txpool=False).hybrid_estimate(None))
+    assert fees["source"] == "fee_history"
+    assert fees["mempool_weight"] == 0.0
+    assert fees["max_priority_fee_per_gas"] == Wei(2 * GWEI)
+    assert fees["max_fee_per_gas"] == Wei(26 * GWEI)
+
+    with pytest.raises(ConfigError):
+        asyncio.run(hybrid_tracker([10, 10]).hybrid_estimate(None, mempool_weight=1.5))
+
+
+def test_decimal_fees_are_normalized_in_transactions_and_receipts():
+    base = DecimalGweiTracker("http://localhost", "ethereum")
+    tracker = base.clone().with_fee_encoding("decimal-gwei")
+
+    fees = asyncio.run(tracker.hybrid_estimate(None, mempool_weight=1.0))
+    report = asyncio.run(tracker.overpayment_for_tx(None, "0xabc"))
+
+    assert fees["source"] == "hybrid"
+    assert fees["max_priority_fee_per_gas"] == Wei(6 * GWEI)
+    assert report["overpaid_wei"] == 10 * GWEI * 50000
+    assert base.provider.fee_encoding is not tracker.provider.fee_encoding
@@ -0,0 +1,57 @@
+"""Tests for the StatsD sink."""
+
+import asyncio
+import socket
+
+from ethgas.metrics import StatsdSink
+from ethgas.providers import make_quote
+
+
+def quote(gas_price=30.0, base_fee=28.5):
+    return dict(make_quote("node", gas_price, base_fee=base_fee), network="Ethereum")
+
+
+def receiver():
+    sock = socket.socket(socket.AF_INET, socket.SOCK_DGRAM)
+    sock.bind(("127.0.0.1", 0))
+    sock.settimeout(1.0)
+    return sock
+
+
+def test_gauge_lines_for_a_quote():
+    lines = StatsdSink(prefix="eth.gas.").gauges(quote())
+
+    assert lines[:2] == ["eth.gas.base_fee:28.5|g", "eth.gas.gas_price:30|g"]
+    assert "eth.gas.tier.fast:30|g" in lines
+    assert "eth.gas.priority_fee.standard:1.5|g" in lines
+    assert StatsdSink(prefix="").gauges(quote(base_fee=None))[0] == "gas_price:30|g"
+
+
+def test_gauges_are_batched_into_packets():
+    sink = StatsdSink(max_packet_size=50)
+    lines = [f"eth.gas.metric_{i}:1|g" for i in range(6)]  # 20 bytes each
+
+    packets = sink.packets(lines)
+
+    assert [len(p.split(b"\n")) for p in packets] == [2, 2, 2]
+    assert all(len(p) <= 50 for p in packets)
+    assert b"\n".join(packets).decode().split("\n") == lines
+    assert sink.packets(["x" * 80]) == [b"x" * 80]
+
+
+def test_forward_sends_each_watched_quote_over_udp():
+    server = receiver()
+    sink = StatsdSink(*server.getsockname())
+
+    async def stream():
+        yield quote(gas_price=30.0)
+        yield {"network": "Ethereum", "error": "HTTP 503"}
+        yield quote(gas_price=40.0)
+
+    asyncio.run(sink.forward(stream()))
+    sink.close()
+    first, second = server.recv(4096).decode(), server.recv(4096).decode()
+    server.close()
+
+    assert first.split("\n") == StatsdSink().gauges(quote(gas_price=30.0))
+    assert "eth.gas.gas_price:40|g" in second.split("\n")
@@ -0,0 +1,50 @@
+"""Tests for trend-based gas price prediction."""
+
+from datetime import datetime, timedelta
+
+import pytest
+
+from ethgas.errors import InsufficientHistoryError
+from ethgas.prediction import MIN_PREDICTION_SAMPLES, GasPredictor
+
+
+def series(base_fees):
+    start = datetime(2024, 1, 1, 12, 0)
+    return [
+        {"timestamp": start + timedelta(minutes=5 * i), "base_fee": fee}
+        for i, fee in enumerate(base_fees)
+    ]
+
+
+def test_monotonic_series_direction():
+    rising = GasPredictor(series([10 + i for i in range(12)]), min_samples=12).predict(timedelta(minutes=30))
+    falling = GasPredictor(series([30 - i for i in range(12)]), min_samples=12).predict(timedelta(minutes=30))
+    flat = GasPredictor(series([20.0] * 12), min_samples=12).predict(timedelta(minutes=30))
+
+    assert rising["direction"] == "rising"
+    assert rising["predicted_base_fee"] == 27.0  # 6 more 5-minute steps after 21
+    assert falling["direction"] == "falling"
+    assert flat["direction"] == "stable"
+    assert flat["lower_bound"] == flat["upper_bound"] == 20.0
+
+
+def test_interval_contains_prediction():
+    noisy = [10 + i + (1 if i % 2 else -1) for i in range(12)]
+    prediction = GasPredictor(series(noisy), min_samples=12).predict(timedelta(minutes=30))
+
+    assert prediction["lower_bound"] < prediction["predicted_base_fee"] < prediction["upper_bound"]
+
+
+def test_predictions_need_min_samples():
+    short = GasPredictor(series([10, 11]))
+
+    for predict in (short.predict, short.predict_next_hour, short.suggest_fee_bands):
+        with pytest.raises(InsufficientHistoryError) as excinfo:
+            predict()
+        assert (excinfo.value.have, excinfo.value.need) == (2, MIN_PREDICTION_SAMPLES)
+
+    # predict() counts only the samples inside its window
+    with pytest.raises(InsufficientHistoryError) as excinfo:
+        GasPredictor(series([20.0] * 12), min_samples=10).predict(window=timedelta(minutes=30))
+    assert excinfo.value.have == 7
+    assert "confidence" in GasPredictor(series([20.0] * 30)).predict_next_hour()
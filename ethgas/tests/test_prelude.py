@@ -0,0 +1,23 @@
+"""Tests for the ethgas.prelude star import and the top-level names."""
+
+import pytest
+
+import ethgas
+from ethgas import prelude
+from ethgas.providers import JsonRpcProvider
+
+
+def test_prelude_reexports_public_names_unchanged():
+    namespace = {}
+    exec("from ethgas.prelude import *", namespace)
+    names = set(namespace) - {"__builtins__"}
+
+    assert names == set(prelude.__all__) == set(ethgas.__all__)
+    assert all(namespace[name] is getattr(ethgas, name) for name in names)
+
+
+def test_former_top_level_names_warn_and_still_resolve():
+    with pytest.warns(DeprecationWarning, match="import it from ethgas.providers"):
+        assert ethgas.JsonRpcProvider is JsonRpcProvider
+    with pytest.raises(AttributeError):
+        ethgas.NoSuchName
@@ -0,0 +1,105 @@
+"""Tests for the price feeds: on-chain Chainlink and the median of several."""
+
+import asyncio
+from datetime import datetime, timezone
+
+import pytest
+
+from ethgas.errors import ConfigError, DeserializeError, GasError, NoPriceConsensusError, StalePriceError
+from ethgas.prices import DECIMALS_SELECTOR, LATEST_ROUND_DATA_SELECTOR, ChainlinkPriceFeed, MedianPriceFeed
+from ethgas.testing import MockClock, MockRpcSession, StaticPriceFeed
+from ethgas.tracker import GasTracker
+
+UPDATED_AT = datetime(2024, 1, 1, tzinfo=timezone.utc)
+
+
+def word(value):
+    return (value % 2 ** 256).to_bytes(32, "big").hex()
+
+
+class Aggregator(MockRpcSession):
+    """Session answering latestRoundData() and decimals() eth_calls of a feed (8 decimals by default)."""
+
+    def __init__(self, price, decimals=8):
+        super().__init__()
+        self.price = price
+        self.decimals = decimals
+
+    def answer(self, call):
+        data = call["params"][0]["data"]
+        if data == LATEST_ROUND_DATA_SELECTOR:
+            updated_at = int(UPDATED_AT.timestamp())
+            result = "0x" + word(7) + word(self.price) + word(updated_at) + word(updated_at) + word(7)
+        else:
+            assert data == DECIMALS_SELECTOR
+            result = "0x" + word(self.decimals)
+        return {"jsonrpc": "2.0", "id": call["id"], "result": result}
+
+
+def test_price_is_the_answer_scaled_by_decimals():
+    feed = ChainlinkPriceFeed("http://node", clock=MockClock(start=UPDATED_AT))
+    assert asyncio.run(feed.get_price_usd(Aggregator(2_345_67000000))) == pytest.approx(2345.67)
+
+
+def test_stale_rounds_are_rejected():
+    clock = MockClock(start=UPDATED_AT)
+    feed = ChainlinkPriceFeed("http://node", max_staleness=3600, clock=clock)
+    clock.advance(3601)
+    with pytest.raises(StalePriceError) as excinfo:
+        asyncio.run(feed.get_price_usd(Aggregator(2_345_67000000)))
+    assert excinfo.value.age == pytest.approx(3601)
+
+
+def test_non_positive_answers_are_rejected():
+    feed = ChainlinkPriceFeed("http://node", clock=MockClock(start=UPDATED_AT))
+    with pytest.raises(GasError):
+        asyncio.run(feed.get_price_usd(Aggregator(-1)))
+
+
+def test_implausible_decimals_are_rejected():
+    feed = ChainlinkPriceFeed("http://node", clock=MockClock(start=UPDATED_AT))
+    assert asyncio.run(feed.get_price_usd(Aggregator(2345 * 10 ** 18, decimals=18))) == pytest.approx(2345.0)
+    with pytest.raises(DeserializeError):
+        asyncio.run(feed.get_price_usd(Aggregator(2345, decimals=2 ** 200)))
+
+
+def test_median_ignores_failing_and_outlying_feeds():
+    feeds = [
+        StaticPriceFeed(2000.0, "chainlink"),
+        StaticPriceFeed(2010.0, "coingecko"),
+        StaticPriceFeed(2600.0, "exchange"),
+        StaticPriceFeed(GasError("HTTP 503"), "down"),
+    ]
+    quote = asyncio.run(MedianPriceFeed(feeds, max_deviation_pct=2.0).quote(None))
+
+    assert quote["price_usd"] == 2005.0
+    assert quote["sources"] == ["chainlink", "coingecko"]
+    assert set(quote["rejected"]) == {"exchange", "down"}
+    assert "from the median" in quote["rejected"]["exchange"]
+    assert quote["rejected"]["down"] == "HTTP 503"
+
+    # A heavier feed moves the consensus towards its own price
+    weighted = MedianPriceFeed(feeds[:2], weights=[3.0, 1.0])
+    assert asyncio.run(weighted.get_price_usd(None)) == 2000.0
+
+
+def test_median_without_consensus_raises():
+    apart = MedianPriceFeed([StaticPriceFeed(1000.0, "a"), StaticPriceFeed(2000.0, "b")], max_deviation_pct=5.0)
+    with pytest.raises(NoPriceConsensusError) as excinfo:
+        asyncio.run(apart.quote(None))
+    assert set(excinfo.value.rejected) == {"a", "b"}
+
+    with pytest.raises(ConfigError):
+        MedianPriceFeed([StaticPriceFeed(1.0, "a"), StaticPriceFeed(1.0, "a")])
+    with pytest.raises(ConfigError):
+        MedianPriceFeed([StaticPriceFeed(1.0, "a")], weights=[0.0])
+
+
+def test_tracker_eth_price_reports_its_sources():
+    tracker = GasTracker("http://localhost", "ethereum", price_feed=StaticPriceFeed(1990.0, "single"))
+    assert asyncio.run(tracker.eth_price(None)) == {"price_usd": 1990.0, "sources": ["single"], "rejected": {}}
+
+    tracker.with_price_feeds([StaticPriceFeed(2000.0, "a"), StaticPriceFeed(2002.0, "b"), StaticPriceFeed(1.0, "c")])
+    quote = asyncio.run(tracker.eth_price(None))
+    assert (quote["price_usd"], quote["sources"]) == (2001.0, ["a", "b"])
+    assert asyncio.run(tracker.get_token_price_usd(None)) == 2001.0
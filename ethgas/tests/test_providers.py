@@ -0,0 +1,682 @@
+"""Tests for composite gas providers."""
+
+import asyncio
+from datetime import timedelta
+
+import pytest
+
+from ethgas.core import Congestion
+from ethgas.errors import ConfigError, DeserializeError, GasTimeoutError, RateLimitedError, RpcError, RpcIdMismatchError
+from ethgas.providers import (
+    AggregateProvider,
+    Aggregation,
+    AlchemyProvider,
+    AppSession,
+    BlocknativeProvider,
+    FeeEncoding,
+    FreezeDetector,
+    GasProvider,
+    JsonRpcProvider,
+    PricingModel,
+    ProviderStatus,
+    RaceProvider,
+    check_health,
+    fee_to_hex_wei,
+    is_stale,
+    make_quote,
+    normalize_fees,
+)
+from ethgas.testing import MockResponse, MockRpcSession
+
+
+class StaticProvider(GasProvider):
+    """Provider returning a fixed quote after an optional delay."""
+
+    def __init__(self, name, gas_price, base_fee=None, delay=0.0):
+        self.name = name
+        self.gas_price = gas_price
+        self.base_fee = base_fee
+        self.delay = delay
+
+    async def fetch(self, session):
+        await asyncio.sleep(self.delay)
+        return make_quote(self.name, self.gas_price, base_fee=self.base_fee)
+
+
+class HangingProvider(GasProvider):
+    """Provider that never returns."""
+
+    name = "hanging"
+
+    async def fetch(self, session):
+        await asyncio.Event().wait()
+
+
+class FailingProvider(GasProvider):
+    """Provider that always errors."""
+
+    name = "failing"
+
+    async def fetch(self, session):
+        raise ValueError("boom")
+
+
+def test_race_completes_despite_hanging_provider():
+    race = RaceProvider([HangingProvider(), FailingProvider(), StaticProvider("fast", 20.0)])
+
+    quote = asyncio.run(asyncio.wait_for(race.fetch(None), timeout=1.0))
+
+    assert quote["provider"] == "fast"
+    assert quote["gas_price"] == 20.0
+
+
+def test_race_returns_first_success():
+    race = RaceProvider([StaticProvider("slow", 30.0, delay=0.2), StaticProvider("quick", 25.0)])
+
+    quote = asyncio.run(race.fetch(None))
+
+    assert quote["provider"] == "quick"
+
+
+def test_aggregate_takes_median_and_drops_failures():
+    aggregate = AggregateProvider(
+        [
+            StaticProvider("a", 10.0, base_fee=9.0),
+            StaticProvider("b", 12.0, base_fee=11.0),
+            StaticProvider("c", 500.0, base_fee=400.0),
+            HangingProvider(),
+            FailingProvider(),
+        ],
+        timeout=0.2,
+    )
+
+    quote = asyncio.run(asyncio.wait_for(aggregate.fetch(None), timeout=1.0))
+
+    assert quote["base_fee"] == 11.0
+    assert quote["gas_price"] == 12.0
+    assert quote["sources"] == ["a", "b", "c"]
+
+
+def test_quotes_are_stale_after_max_age():
+    quote = make_quote("static", 10.0, block_number=100)
+
+    assert not is_stale(quote, timedelta(seconds=30))
+    quote["fetched_at"] -= timedelta(minutes=1)
+    assert is_stale(quote, timedelta(seconds=30))
+
+
+class RecordingSession:
+    """Session that records POSTed requests and answers every call with 0x1."""
+
+    def __init__(self):
+        self.requests = []
+
+    def post(self, url, json=None, headers=None, **kwargs):
+        self.requests.append({"url": url, "json": json, "headers": headers})
+        return MockResponse({"jsonrpc": "2.0", "id": json["id"], "result": "0x1"})
+
+
+class App:
+    def __init__(self):
+        self.on_cleanup = []
+
+
+def test_app_session_is_shared_until_the_app_cleans_up():
+    app = App()
+    shared = AppSession(app)
+
+    async def run():
+        session = shared.get()
+        assert shared.get() is session
+        for cleanup in app.on_cleanup:
+            await cleanup(app)
+        return session
+
+    session = asyncio.run(run())
+    assert session.closed
+    assert shared.session is None
+
+
+def test_request_carries_auth_header_and_key_stays_out_of_repr():
+    provider = JsonRpcProvider(
+        "https://rpc.example/{api_key}",
+        headers={"Authorization": "Bearer {api_key}"},
+        api_key="s3cret",
+    )
+    session = RecordingSession()
+
+    asyncio.run(provider.rpc_call(session, "eth_gasPrice"))
+
+    assert session.requests[0]["url"] == "https://rpc.example/s3cret"
+    assert session.requests[0]["headers"] == {"Authorization": "Bearer s3cret"}
+    assert "s3cret" not in repr(provider)
+    assert "s3cret" not in repr(AlchemyProvider("s3cret"))
+    assert AlchemyProvider("s3cret").rpc_url == "https://eth-mainnet.g.alchemy.com/v2/s3cret"
+
+
+BLOCK_PRICES = {
+    "blockPrices": [
+        {
+            "blockNumber": 19000001,
+            "estimatedTransactionCount": 142,
+            "baseFeePerGas": 20.5,
+            "estimatedPrices": [
+                {"confidence": 99, "maxPriorityFeePerGas": 3.0, "maxFeePerGas": 44.0},
+                {"confidence": 95, "maxPriorityFeePerGas": 2.0, "maxFeePerGas": 43.0},
+                {"confidence": 90, "maxPriorityFeePerGas": 1.5, "maxFeePerGas": 42.5},
+                {"confidence": 80, "maxPriorityFeePerGas": 1.2, "maxFeePerGas": 42.2},
+                {"confidence": 70, "maxPriorityFeePerGas": 1.0, "maxFeePerGas": 42.0},
+            ],
+        },
+        {"blockNumber": 19000002, "estimatedPrices": []},
+    ]
+}
+
+
+class BlocknativeSession:
+    """Session answering GET requests with a fixed body or HTTP status."""
+
+    def __init__(self, data=None, status=200):
+        self.data = data
+        self.status = status
+        self.requests = []
+
+    def get(self, url, params=None, headers=None, **kwargs):
+        self.requests.append({"url": url, "params": params, "headers": headers})
+        return MockResponse(self.data, self.status)
+
+
+def test_blocknative_maps_confidence_to_tiers():
+    session = BlocknativeSession(BLOCK_PRICES)
+
+    quote = asyncio.run(BlocknativeProvider("bn-key", chain_id=137).fetch(session))
+
+    assert session.requests[0]["headers"] == {"Authorization": "bn-key"}
+    assert session.requests[0]["params"] == {"chainid": 137}
+    assert quote["block_number"] == 19000001
+    assert quote["base_fee"] == 20.5
+    assert quote["gas_price"] == 42.5
+    assert quote["tiers"] == {"slow": 42.0, "standard": 42.5, "fast": 43.0, "urgent": 44.0}
+    assert quote["priority_fees"] == {"slow": 1.0, "standard": 1.5, "fast": 2.0, "urgent": 3.0}
+    assert quote["estimated_transaction_count"] == 142
+    assert "bn-key" not in repr(vars(BlocknativeProvider("bn-key")))
+
+
+def test_blocknative_falls_back_without_key_or_quota():
+    fallback = StaticProvider("node", 30.0)
+
+    quote = asyncio.run(BlocknativeProvider(fallback=fallback).fetch(None))
+    assert quote["provider"] == "node"
+
+    limited = BlocknativeProvider("bn-key", fallback=fallback)
+    assert asyncio.run(limited.fetch(BlocknativeSession(status=429)))["provider"] == "node"
+
+    with pytest.raises(ConfigError):
+        asyncio.run(BlocknativeProvider().fetch(None))
+    with pytest.raises(RateLimitedError):
+        asyncio.run(BlocknativeProvider("bn-key").fetch(BlocknativeSession(status=429)))
+
+
+class RpcServer(MockRpcSession):
+    """Session answering JSON-RPC by method, optionally rejecting batches."""
+
+    def __init__(self, batching=True, eip1559=True, fee_history=True):
+        super().__init__()
+        self.batching = batching
+        self.eip1559 = eip1559
+        self.fee_history = fee_history
+
+    def answer(self, call):
+        if call["method"] == "eth_gasPrice":
+            return {"jsonrpc": "2.0", "id": call["id"], "result": hex(25 * 10 ** 9)}
+        if call["method"] == "eth_feeHistory" and self.fee_history:
+            result = {"oldestBlock": hex(500), "gasUsedRatio": [0.5], "baseFeePerGas": [hex(10 ** 10), hex(2 * 10 ** 10)]}
+            return {"jsonrpc": "2.0", "id": call["id"], "result": result}
+        if call["method"] == "eth_getBlockByNumber":
+            block = {"number": hex(500), "gasUsed": hex(27), "gasLimit": hex(30)}
+            if self.eip1559:
+                block["baseFeePerGas"] = hex(10 ** 10)
+            return {"jsonrpc": "2.0", "id": call["id"], "result": block}
+        if call["method"] == "eth_blockNumber":
+            return {"jsonrpc": "2.0", "id": call["id"], "result": hex(500)}
+        return super().answer(call)
+
+    def respond(self, url, payload):
+        if not isinstance(payload, list):
+            return super().respond(url, payload)
+        if self.batching:
+            return MockResponse([self.answer(call) for call in reversed(payload)])
+        return MockResponse({"jsonrpc": "2.0", "id": None, "error": {"code": -32600, "message": "batch not supported"}})
+
+
+def test_batch_matches_responses_by_id():
+    server = RpcServer()
+    provider = JsonRpcProvider("https://rpc.example")
+
+    responses = asyncio.run(provider.batch(server, [("eth_gasPrice", []), ("eth_chainId", [])]))
+    quote = asyncio.run(provider.fetch(server))
+
+    assert responses[0]["result"] == hex(25 * 10 ** 9)
+    assert responses[1]["error"]["code"] == -32601
+    assert quote["gas_price"] == 25.0
+    assert quote["base_fee"] == 20.0
+    assert quote["block_number"] == 500
+    # The batch, the one-off EIP-1559 probe and the quote's own batch
+    assert len(server.posts) == 3
+
+
+class CongestedServer(RpcServer):
+    """RpcServer whose fee history shows nearly full blocks."""
+
+    def answer(self, call):
+        response = super().answer(call)
+        if call["method"] == "eth_feeHistory":
+            response["result"] = dict(response["result"], gasUsedRatio=[0.95, 0.99, 0.92], oldestBlock=hex(498))
+        return response
+
+
+def test_quotes_report_the_mean_gas_used_ratio_and_congestion():
+    quote = asyncio.run(JsonRpcProvider("https://rpc.example").fetch(RpcServer()))
+    assert (quote["gas_used_ratio"], quote["congestion"]) == (0.5, Congestion.NORMAL)
+
+    server = CongestedServer()
+    quote = asyncio.run(JsonRpcProvider("https://rpc.example").fetch(server))
+    assert quote["gas_used_ratio"] == pytest.approx(0.9533, abs=1e-4)
+    assert quote["congestion"] == "congested"
+    assert quote["block_number"] == 500
+    assert server.posts[-1][1]["params"] == [10, "latest", []]
+
+
+class DecimalGweiServer(RpcServer):
+    """RpcServer answering fees as decimal gwei strings, like some L2 endpoints."""
+
+    def answer(self, call):
+        response = super().answer(call)
+        result = response.get("result")
+        if call["method"] == "eth_gasPrice":
+            response["result"] = "25"
+        elif call["method"] == "eth_feeHistory":
+            response["result"] = dict(result, baseFeePerGas=["10", "20.5"])
+        elif call["method"] == "eth_getBlockByNumber":
+            response["result"] = dict(result, baseFeePerGas="10")
+        return response
+
+
+def test_fee_values_convert_from_the_declared_encoding():
+    assert fee_to_hex_wei("1500000000", FeeEncoding.DECIMAL_WEI) == hex(15 * 10 ** 8)
+    assert fee_to_hex_wei("1.5", FeeEncoding.DECIMAL_GWEI) == hex(15 * 10 ** 8)
+    assert fee_to_hex_wei(2, FeeEncoding.DECIMAL_GWEI) == hex(2 * 10 ** 9)
+    # Hex values are still read as hex, in the declared unit
+    assert fee_to_hex_wei("0x10", FeeEncoding.DECIMAL_WEI) == "0x10"
+    for value, encoding in (("25", FeeEncoding.HEX_WEI), ("1.5", FeeEncoding.DECIMAL_WEI), ("-1", FeeEncoding.DECIMAL_GWEI)):
+        with pytest.raises(DeserializeError):
+            fee_to_hex_wei(value, encoding)
+
+    history = {"oldestBlock": "0x1f4", "baseFeePerGas": ["1", "2"], "gasUsedRatio": [0.5], "reward": [["0.1", "0.2"]]}
+    normalized = normalize_fees("eth_feeHistory", history, FeeEncoding.DECIMAL_GWEI)
+    assert normalized["baseFeePerGas"] == [hex(10 ** 9), hex(2 * 10 ** 9)]
+    assert normalized["reward"] == [[hex(10 ** 8), hex(2 * 10 ** 8)]]
+    assert normalized["oldestBlock"] == "0x1f4"
+    assert history["baseFeePerGas"] == ["1", "2"]  # the input is left alone
+    assert normalize_fees("eth_blockNumber", "500", FeeEncoding.DECIMAL_GWEI) == "500"
+
+    block = {"baseFeePerGas": "10", "transactions": [{"gasPrice": "12"}, {"maxFeePerGas": "30", "maxPriorityFeePerGas": "1"}]}
+    transactions = normalize_fees("eth_getBlockByNumber", block, FeeEncoding.DECIMAL_GWEI)["transactions"]
+    assert transactions[0] == {"gasPrice": hex(12 * 10 ** 9)}
+    assert transactions[1] == {"maxFeePerGas": hex(30 * 10 ** 9), "maxPriorityFeePerGas": hex(10 ** 9)}
+    hashes = normalize_fees("eth_getBlockByNumber", dict(block, transactions=["0xab"]), FeeEncoding.DECIMAL_GWEI)
+    assert hashes["transactions"] == ["0xab"]
+
+
+def test_provider_reads_decimal_gwei_fees():
+    server = DecimalGweiServer()
+    quote = asyncio.run(JsonRpcProvider("https://rpc.example", fee_encoding="decimal-gwei").fetch(server))
+    assert quote["gas_price"] == 25.0
+    assert quote["base_fee"] == 20.5
+    assert quote["block_number"] == 500
+
+    with pytest.raises(DeserializeError):
+        asyncio.run(JsonRpcProvider("https://rpc.example").fetch(DecimalGweiServer()))
+    with pytest.raises(ConfigError, match="fee_encoding must be one of"):
+        JsonRpcProvider("https://rpc.example", fee_encoding="octal")
+
+
+class NoPendingServer(RpcServer):
+    """RpcServer that fails eth_feeHistory for the pending block tag with `error`."""
+
+    error = {"code": -32602, "message": "pending not supported"}
+
+    def answer(self, call):
+        if call["method"] == "eth_feeHistory" and call["params"][1] == "pending":
+            return {"jsonrpc": "2.0", "id": call["id"], "error": self.error}
+        return super().answer(call)
+
+
+def test_pending_block_tag_falls_back_to_latest():
+    provider = JsonRpcProvider("https://rpc.example", block_tag="pending")
+    assert asyncio.run(provider.fetch(RpcServer()))["base_fee"] == 20.0
+
+    server = NoPendingServer()
+    assert asyncio.run(provider.fetch(server))["base_fee"] == 20.0
+    assert asyncio.run(provider.fetch(server))["base_fee"] == 20.0
+    tags = [c["params"][1] for post in server.posts for c in (post if isinstance(post, list) else [post])
+            if c["method"] == "eth_feeHistory"]
+    assert tags == ["pending", "latest", "latest"]
+
+    busy = NoPendingServer()
+    busy.error = {"code": -32005, "message": "request limit exceeded"}
+    provider = JsonRpcProvider("https://rpc.example", block_tag="pending")
+    asyncio.run(provider.fetch(busy))
+    assert provider.supports_pending
+
+    assert JsonRpcProvider("https://rpc.example", block_tag=500).history_tag() == "0x1f4"
+    with pytest.raises(ConfigError):
+        JsonRpcProvider("https://rpc.example", block_tag="safe")
+
+
+def test_missing_fee_history_quotes_the_latest_block_base_fee():
+    server = RpcServer(fee_history=False)
+    provider = JsonRpcProvider("https://rpc.example")
+
+    first = asyncio.run(provider.fetch(server))
+    second = asyncio.run(provider.fetch(server))
+
+    assert first == dict(second, fetched_at=first["fetched_at"])
+    assert first["degraded"] is True
+    assert first["base_fee"] == 10.0
+    assert first["gas_price"] == 25.0
+    assert first["block_number"] == 500
+    assert first["congestion"] == Congestion.CONGESTED
+    assert provider.supports_fee_history is False
+    # The probe, the rejected batch and its fallback, then only the fallback
+    methods = [[c["method"] for c in post] if isinstance(post, list) else post["method"] for post in server.posts]
+    assert methods[1:] == [
+        ["eth_gasPrice", "eth_feeHistory"],
+        ["eth_gasPrice", "eth_getBlockByNumber"],
+        ["eth_gasPrice", "eth_getBlockByNumber"],
+    ]
+    assert "degraded" not in asyncio.run(JsonRpcProvider("https://rpc.example").fetch(RpcServer()))
+
+
+def test_batch_falls_back_to_sequential_calls():
+    server = RpcServer(batching=False)
+    provider = JsonRpcProvider("https://rpc.example")
+
+    first = asyncio.run(provider.fetch(server))
+    second = asyncio.run(provider.fetch(server))
+
+    assert first["block_number"] == second["block_number"] == 500
+    assert provider.supports_batch is False
+    # The EIP-1559 probe, one rejected batch, then single calls only
+    assert [isinstance(p, list) for p in server.posts] == [False, True, False, False, False, False]
+
+
+class SlowResponse(MockResponse):
+    """Response whose body arrives after `delay` seconds."""
+
+    def __init__(self, data, delay):
+        super().__init__(data)
+        self.delay = delay
+
+    async def json(self):
+        await asyncio.sleep(self.delay)
+        return self.data
+
+
+class SlowServer(RpcServer):
+    def __init__(self, delay):
+        super().__init__()
+        self.delay = delay
+
+    def respond(self, url, payload):
+        return SlowResponse(super().respond(url, payload).data, self.delay)
+
+
+def test_request_timeout_bounds_a_slow_server():
+    provider = JsonRpcProvider("https://rpc.example", timeout=0.05)
+
+    with pytest.raises(GasTimeoutError):
+        asyncio.run(provider.fetch(SlowServer(delay=1.0)))
+    assert asyncio.run(provider.fetch(SlowServer(delay=0.0)))["gas_price"] == 25.0
+
+
+def test_legacy_chain_is_detected_once_and_priced_by_multiplier():
+    server = RpcServer(eip1559=False)
+    provider = JsonRpcProvider("https://bsc.example", legacy_multipliers={"fast": 1.5})
+
+    first = asyncio.run(provider.fetch(server))
+    second = asyncio.run(provider.fetch(server))
+
+    assert first["pricing_model"] == PricingModel.LEGACY
+    assert first["base_fee"] is None
+    assert first["tiers"] == {"slow": 22.5, "standard": 25.0, "fast": 37.5}
+    assert second["block_number"] == 500
+    probes = [p for p in server.posts if isinstance(p, dict) and p["method"] == "eth_getBlockByNumber"]
+    assert len(probes) == 1
+
+    eip1559 = asyncio.run(JsonRpcProvider("https://eth.example").fetch(RpcServer()))
+    assert eip1559["pricing_model"] == PricingModel.EIP1559
+
+
+class BlocklessServer(RpcServer):
+    """RpcServer failing eth_getBlockByNumber until `healthy` is set."""
+
+    healthy = False
+
+    def answer(self, call):
+        if call["method"] == "eth_getBlockByNumber" and not self.healthy:
+            return {"jsonrpc": "2.0", "id": call["id"], "error": {"code": -32000, "message": "header not found"}}
+        return super().answer(call)
+
+
+def test_failed_pricing_model_probe_is_not_cached():
+    server = BlocklessServer()
+    provider = JsonRpcProvider("https://eth.example")
+
+    with pytest.raises(RpcError):
+        asyncio.run(provider.fetch(server))
+    assert provider.pricing_model is None
+
+    server.healthy = True
+    assert asyncio.run(provider.fetch(server))["pricing_model"] == PricingModel.EIP1559
+
+
+class AlchemyServer(RpcServer):
+    """RpcServer with Alchemy's priority fee suggestion (2 gwei) unless `suggests` is False."""
+
+    def __init__(self, suggests=True):
+        super().__init__()
+        self.suggests = suggests
+
+    def answer(self, call):
+        gwei = 10 ** 9
+        if call["method"] == "eth_maxPriorityFeePerGas" and self.suggests:
+            return {"jsonrpc": "2.0", "id": call["id"], "result": hex(2 * gwei)}
+        if call["method"] == "eth_feeHistory":
+            result = {
+                "oldestBlock": hex(500),
+                "gasUsedRatio": [0.5, 0.0],
+                "baseFeePerGas": [hex(10 * gwei), hex(10 * gwei), hex(20 * gwei)],
+                "reward": [[hex(1 * gwei), hex(3 * gwei), hex(5 * gwei)], []],
+            }
+            return {"jsonrpc": "2.0", "id": call["id"], "result": result}
+        return super().answer(call)
+
+
+def test_alchemy_prices_tiers_from_its_priority_fee_suggestion():
+    server = AlchemyServer()
+    quote = asyncio.run(AlchemyProvider("key", "arb-mainnet").fetch(server))
+
+    assert len(server.posts) == 1
+    assert server.posts[0][1]["params"] == ["0x14", "latest", [10, 50, 90]]
+    assert quote["base_fee"] == 20.0
+    assert quote["gas_price"] == 22.0
+    assert quote["priority_fees"] == {"slow": 1.6, "standard": 2.0, "fast": 3.0}
+    assert quote["tiers"] == {"slow": 21.6, "standard": 22.0, "fast": 23.0}
+    assert quote["block_number"] == 501
+
+
+def test_alchemy_falls_back_to_fee_history_percentiles():
+    quote = asyncio.run(AlchemyProvider("key").fetch(AlchemyServer(suggests=False)))
+
+    assert quote["priority_fees"] == {"slow": 1.0, "standard": 3.0, "fast": 5.0}
+    assert quote["tiers"]["fast"] == 25.0
+
+
+class HeightProvider(GasProvider):
+    """Provider quoting `gas_price` at a fixed block height."""
+
+    def __init__(self, name, block_number, gas_price=10.0):
+        self.name = name
+        self.block_number = block_number
+        self.gas_price = gas_price
+
+    async def fetch(self, session):
+        return make_quote(self.name, self.gas_price, block_number=self.block_number)
+
+
+def test_check_health_flags_lagging_and_failed_providers():
+    providers = [HeightProvider("a", 1000), HeightProvider("b", 999), HeightProvider("c", 990), FailingProvider()]
+
+    health = asyncio.run(check_health(providers, None, max_lag_blocks=3))
+
+    assert [h["status"] for h in health] == [
+        ProviderStatus.HEALTHY, ProviderStatus.HEALTHY, ProviderStatus.LAGGING, ProviderStatus.FAILED
+    ]
+    assert [h["blocks_behind"] for h in health] == [0, 1, 10, None]
+    assert health[3]["error"] == "boom"
+    assert all(h["latency_ms"] >= 0 for h in health)
+
+
+def test_aggregate_can_drop_lagging_quotes():
+    providers = [HeightProvider("a", 1000, 10.0), HeightProvider("b", 1000, 12.0), HeightProvider("c", 900, 50.0)]
+
+    assert asyncio.run(AggregateProvider(providers).fetch(None))["sources"] == ["a", "b", "c"]
+    quote = asyncio.run(AggregateProvider(providers, max_lag_blocks=3).fetch(None))
+    assert quote["sources"] == ["a", "b"]
+    assert quote["gas_price"] == 11.0
+
+
+class ChainProvider(GasProvider):
+    """Provider whose block and gas price move on with every fetch."""
+
+    def __init__(self, name, block_number=1000, gas_price=10.0):
+        self.name = name
+        self.block_number = block_number
+        self.gas_price = gas_price
+
+    async def fetch(self, session):
+        self.block_number += 1
+        self.gas_price += 1.0
+        return make_quote(self.name, self.gas_price, block_number=self.block_number)
+
+
+def test_aggregate_flags_a_frozen_member_and_can_exclude_it():
+    stuck = HeightProvider("stuck", 1000, 50.0)
+    providers = [ChainProvider("a"), ChainProvider("b"), stuck]
+    aggregate = AggregateProvider(providers, freeze_after=3, exclude_frozen=True)
+
+    quotes = [asyncio.run(aggregate.fetch(None)) for _ in range(3)]
+    assert [q.get("frozen") for q in quotes] == [None, None, ["stuck"]]
+    assert quotes[1]["sources"] == ["a", "b", "stuck"]
+    assert quotes[2]["sources"] == ["a", "b"]
+    assert quotes[2]["gas_price"] == 13.0
+    assert aggregate.frozen_members() == [stuck]
+
+    health = asyncio.run(check_health(providers, None, max_lag_blocks=10, frozen=aggregate.frozen_members()))
+    assert [h["status"] for h in health] == [ProviderStatus.HEALTHY, ProviderStatus.HEALTHY, ProviderStatus.FROZEN]
+
+    # A changed quote resets the count
+    stuck.gas_price = 51.0
+    assert "frozen" not in asyncio.run(aggregate.fetch(None))
+    assert aggregate.frozen_members() == []
+
+
+def test_freeze_detector_needs_other_sources_to_move_on():
+    detector = FreezeDetector(after=2)
+    quote = make_quote("lone", 10.0, block_number=5)
+    assert [detector.observe({"lone": quote}) for _ in range(5)] == [set()] * 5
+
+    # Sources agreeing without anyone advancing don't freeze either
+    same = {"a": quote, "b": dict(quote, provider="b")}
+    assert [detector.observe(same) for _ in range(3)] == [set()] * 3
+    with pytest.raises(ConfigError):
+        FreezeDetector(after=1)
+
+
+def test_weighted_aggregate_follows_the_heavy_provider():
+    trusted = StaticProvider("alchemy", 20.0, base_fee=18.0)
+    public = [StaticProvider("a", 10.0, base_fee=9.0), StaticProvider("b", 12.0, base_fee=11.0)]
+
+    unweighted = AggregateProvider(public)
+    aggregate = unweighted.with_provider_weighted(trusted, 5.0)
+    quote = asyncio.run(aggregate.fetch(None))
+    assert quote["gas_price"] == 20.0
+    assert quote["base_fee"] == 18.0
+    assert unweighted.providers == public and unweighted.weights == [1.0, 1.0]
+
+    mean = AggregateProvider(public, aggregation=Aggregation.MEAN).with_provider_weighted(trusted, 2.0)
+    assert asyncio.run(mean.fetch(None))["gas_price"] == pytest.approx((10.0 + 12.0 + 2 * 20.0) / 4)
+
+    # Without it (or when it fails) the weights of the others renormalize
+    failing = AggregateProvider(public).with_provider_weighted(FailingProvider(), 5.0)
+    quote = asyncio.run(failing.fetch(None))
+    assert quote["gas_price"] == 11.0
+    assert quote["sources"] == ["a", "b"]
+
+
+def test_aggregate_rejects_bad_weights():
+    with pytest.raises(ConfigError):
+        AggregateProvider([StaticProvider("a", 10.0)], weights=[1.0, 2.0])
+    with pytest.raises(ConfigError):
+        AggregateProvider([StaticProvider("a", 10.0)]).with_provider_weighted(StaticProvider("b", 12.0), 0)
+
+
+class FixedIdServer(RpcServer):
+    """RpcServer whose single-call responses always carry `response_id`."""
+
+    def __init__(self, response_id):
+        super().__init__()
+        self.response_id = response_id
+
+    def answer(self, call):
+        return dict(super().answer(call), id=self.response_id)
+
+
+def test_request_ids_are_unique_and_checked():
+    server = RpcServer()
+    provider = JsonRpcProvider("https://rpc.example")
+
+    async def burst():
+        await asyncio.gather(*(provider.rpc_call(server, "eth_gasPrice") for _ in range(20)))
+        await provider.batch(server, [("eth_gasPrice", []), ("eth_blockNumber", [])])
+
+    asyncio.run(burst())
+    ids = [p["id"] for p in server.posts[:20]] + [call["id"] for call in server.posts[20]]
+    assert len(set(ids)) == len(ids) == 22
+
+    with pytest.raises(RpcIdMismatchError):
+        asyncio.run(provider.rpc_call(FixedIdServer(response_id=0), "eth_gasPrice"))
+    # Errors the server couldn't tie to a request come with a null id
+    with pytest.raises(RpcError) as excinfo:
+        asyncio.run(provider.rpc_call(FixedIdServer(response_id=None), "eth_chainId"))
+    assert excinfo.value.code == -32601
+
+
+def ethgas_tasks():
+    return sorted(t.get_name() for t in asyncio.all_tasks() if t.get_name().startswith("ethgas "))
+
+
+def test_race_tasks_are_named_and_joined():
+    race = RaceProvider([HangingProvider(), StaticProvider("quick", 25.0, delay=0.01)])
+
+    async def run():
+        fetch = asyncio.ensure_future(race.fetch(None))
+        await asyncio.sleep(0)
+        running = ethgas_tasks()
+        await fetch
+        return running, ethgas_tasks()
+
+    running, left = asyncio.run(run())
+    assert running == ["ethgas race hanging", "ethgas race quick"]
+    assert left == []
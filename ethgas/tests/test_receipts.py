@@ -0,0 +1,52 @@
+"""Tests for the overpayment report."""
+
+import asyncio
+
+import pytest
+
+from ethgas.errors import GasError
+from ethgas.receipts import overpayment
+from ethgas.tracker import GasTracker
+
+GWEI = 10 ** 9
+
+
+def test_overpayment_in_wei_eth_and_percent():
+    report = overpayment(max_fee_per_gas=50 * GWEI, effective_gas_price=20 * GWEI, gas_used=21000)
+
+    assert report["authorized_wei"] == 50 * GWEI * 21000
+    assert report["paid_wei"] == 20 * GWEI * 21000
+    assert report["overpaid_wei"] == 30 * GWEI * 21000
+    assert report["overpaid_eth"] == 0.00063
+    assert report["overpaid_pct"] == 150.0
+
+
+def test_overpayment_rejects_impossible_receipts():
+    with pytest.raises(ValueError):
+        overpayment(max_fee_per_gas=10 * GWEI, effective_gas_price=11 * GWEI, gas_used=21000)
+    with pytest.raises(ValueError):
+        overpayment(max_fee_per_gas=10 * GWEI, effective_gas_price=5 * GWEI, gas_used=-1)
+
+
+class ReceiptTracker(GasTracker):
+    """Tracker answering transaction and receipt lookups from fixed dicts."""
+
+    def __init__(self, tx, receipt):
+        super().__init__("http://localhost", "ethereum")
+        self.answers = {"eth_getTransactionByHash": tx, "eth_getTransactionReceipt": receipt}
+
+    async def rpc_batch(self, session, calls):
+        return [self.answers[method] for method, _ in calls]
+
+
+def test_overpayment_for_tx_reads_transaction_and_receipt():
+    tx = {"maxFeePerGas": hex(40 * GWEI)}
+    receipt = {"effectiveGasPrice": hex(30 * GWEI), "gasUsed": hex(50000)}
+
+    report = asyncio.run(ReceiptTracker(tx, receipt).overpayment_for_tx(None, "0xabc"))
+    legacy = asyncio.run(ReceiptTracker({"gasPrice": hex(30 * GWEI)}, receipt).overpayment_for_tx(None, "0xabc"))
+
+    assert report["overpaid_wei"] == 10 * GWEI * 50000
+    assert legacy["overpaid_wei"] == 0
+    with pytest.raises(GasError):
+        asyncio.run(ReceiptTracker(tx, None).overpayment_for_tx(None, "0xabc"))
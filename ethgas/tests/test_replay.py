@@ -0,0 +1,81 @@
+"""Tests for recording and replaying JSON-RPC traffic."""
+
+import asyncio
+import json
+from datetime import datetime, timezone
+
+import pytest
+
+from ethgas.errors import GasError
+from ethgas.providers import JsonRpcProvider
+from ethgas.replay import RecordingProvider, RecordingSession, ReplayProvider, ReplaySession
+from ethgas.testing import MockClock, MockRpcSession
+from ethgas.tracker import GasTracker
+
+
+class Node(MockRpcSession):
+    """Session for an EIP-1559 node whose gas price rises by 1 gwei per eth_gasPrice call."""
+
+    def __init__(self):
+        super().__init__()
+        self.gas_price_calls = 0
+
+    def answer(self, call):
+        if call["method"] == "eth_gasPrice":
+            self.gas_price_calls += 1
+            result = hex((20 + self.gas_price_calls) * 10 ** 9)
+        elif call["method"] == "eth_getBlockByNumber":
+            result = {"number": hex(500), "baseFeePerGas": hex(10 ** 10)}
+        else:
+            count = call["params"][0]
+            count = int(count, 16) if isinstance(count, str) else count
+            result = {
+                "oldestBlock": hex(501 - count),
+                "gasUsedRatio": [0.5] * count,
+                "baseFeePerGas": [hex(10 ** 10)] * (count + 1),
+                "reward": [[hex(10 ** 9)] * len(call["params"][2])] * count,
+            }
+        return {"jsonrpc": "2.0", "id": call["id"], "result": result}
+
+
+def test_replay_reproduces_recorded_quotes_and_estimates(tmp_path):
+    path = tmp_path / "incident.jsonl"
+    node = Node()
+    recorded = GasTracker("http://node", "ethereum", provider=RecordingProvider(JsonRpcProvider("http://node"), path))
+    live = [asyncio.run(recorded.current(node))["gas_price"] for _ in range(2)]
+    live_estimate = asyncio.run(recorded.estimate_eip1559(RecordingSession(node, path)))
+
+    replayed = GasTracker("replay://", "ethereum", provider=ReplayProvider(path))
+    assert [asyncio.run(replayed.current(None))["gas_price"] for _ in range(2)] == live == [21.0, 22.0]
+    assert asyncio.run(replayed.estimate_eip1559(ReplaySession(path))) == live_estimate
+
+    # Every recorded response has been used up
+    with pytest.raises(GasError):
+        asyncio.run(replayed.current(None))
+    assert [json.loads(line)["method"] for line in path.read_text().splitlines()].count("eth_gasPrice") == 2
+
+
+def test_replay_by_timestamp_follows_the_clock(tmp_path):
+    path = tmp_path / "timed.jsonl"
+    with open(path, "w") as f:
+        for second, price in ((0, 20), (12, 30)):
+            entry = {
+                "timestamp": datetime(2024, 1, 1, 0, 0, second, tzinfo=timezone.utc).isoformat(),
+                "method": "eth_gasPrice",
+                "params": [],
+                "response": {"result": hex(price * 10 ** 9)},
+            }
+            f.write(json.dumps(entry) + "\n")
+
+    clock = MockClock(start=datetime(2024, 1, 1, tzinfo=timezone.utc))
+    provider = ReplayProvider(path, clock=clock)
+
+    async def gas_price():
+        return int(await provider.rpc_call(None, "eth_gasPrice"), 16) // 10 ** 9
+
+    assert asyncio.run(gas_price()) == 20
+    clock.advance(11)
+    assert asyncio.run(gas_price()) == 20
+    clock.advance(1)
+    assert asyncio.run(gas_price()) == 30
+    assert asyncio.run(gas_price()) == 30
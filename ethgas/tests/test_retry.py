@@ -0,0 +1,102 @@
+"""Tests for retry with exponential backoff."""
+
+import asyncio
+import logging
+from datetime import datetime, timezone
+
+import aiohttp
+import pytest
+
+from ethgas.providers import GasProvider, make_quote
+from ethgas.retry import RetryingProvider, RetryPolicy
+from ethgas.testing import MockClock
+
+
+class FlakyProvider(GasProvider):
+    """Provider that fails with a given HTTP status before succeeding."""
+
+    name = "flaky"
+
+    def __init__(self, failures, status=503, headers=None):
+        self.failures = failures
+        self.status = status
+        self.headers = headers
+        self.calls = 0
+
+    async def fetch(self, session):
+        self.calls += 1
+        if self.calls <= self.failures:
+            raise aiohttp.ClientResponseError(
+                None, (), status=self.status, message="error", headers=self.headers
+            )
+        return make_quote(self.name, 15.0)
+
+
+def test_retries_until_success():
+    flaky = FlakyProvider(failures=2)
+    provider = RetryingProvider(flaky, RetryPolicy(max_retries=3, base_delay=0))
+
+    quote = asyncio.run(provider.fetch(None))
+
+    assert quote["gas_price"] == 15.0
+    assert flaky.calls == 3
+
+
+def test_client_errors_fail_fast():
+    flaky = FlakyProvider(failures=2, status=404)
+    provider = RetryingProvider(flaky, RetryPolicy(max_retries=3, base_delay=0))
+
+    with pytest.raises(aiohttp.ClientResponseError):
+        asyncio.run(provider.fetch(None))
+    assert flaky.calls == 1
+
+
+def test_gives_up_after_max_retries():
+    flaky = FlakyProvider(failures=5, status=429)
+    provider = RetryingProvider(flaky, RetryPolicy(max_retries=2, base_delay=0))
+
+    with pytest.raises(aiohttp.ClientResponseError):
+        asyncio.run(provider.fetch(None))
+    assert flaky.calls == 3
+
+
+def test_retry_after_header_overrides_backoff():
+    policy = RetryPolicy(base_delay=5.0)
+    error = aiohttp.ClientResponseError(
+        None, (), status=429, message="slow down", headers={"Retry-After": "2"}
+    )
+
+    assert policy.delay_for(0, error) == 2.0
+
+    clock = MockClock(start=datetime(2024, 1, 1, tzinfo=timezone.utc))
+    dated = aiohttp.ClientResponseError(
+        None, (), status=429, message="slow down", headers={"Retry-After": "Mon, 01 Jan 2024 00:00:30 GMT"}
+    )
+    assert RetryPolicy(clock=clock).delay_for(0, dated) == 30.0
+
+
+def test_retry_waits_on_the_policys_clock():
+    clock = MockClock()
+    flaky = FlakyProvider(failures=2)
+    provider = RetryingProvider(flaky, RetryPolicy(base_delay=5.0, jitter=0, clock=clock))
+
+    asyncio.run(asyncio.wait_for(provider.fetch(None), timeout=1.0))
+    assert clock.monotonic() == 5.0 + 10.0
+
+
+def test_backoff_is_capped():
+    policy = RetryPolicy(base_delay=1.0, max_delay=4.0, jitter=0)
+
+    assert [policy.delay_for(n) for n in range(4)] == [1.0, 2.0, 4.0, 4.0]
+
+
+def test_retries_are_logged_as_warnings(caplog):
+    caplog.set_level(logging.DEBUG, logger="ethgas")
+    provider = RetryingProvider(FlakyProvider(failures=1), RetryPolicy(max_retries=3, base_delay=0))
+    asyncio.run(provider.fetch(None))
+
+    warnings = [r for r in caplog.records if r.levelno == logging.WARNING]
+    assert len(warnings) == 1 and "Retrying flaky" in warnings[0].getMessage()
+    spans = [r.ethgas for r in caplog.records if hasattr(r, "ethgas")]
+    assert spans[0]["span"] == "retry" and spans[0]["provider"] == "flaky"
+    assert spans[0]["latency_ms"] >= 0
@@ -0,0 +1,60 @@
+"""Tests for the base fee jump check."""
+
+import asyncio
+
+import pytest
+
+from ethgas.errors import SuspectQuoteError
+from ethgas.providers import RaceProvider, make_quote
+from ethgas.sanity import BaseFeeCheckProvider
+from ethgas.testing import MockProvider
+from ethgas.tracker import GasTracker
+
+
+def quote(block, base_fee):
+    return make_quote("node", base_fee + 1, base_fee=base_fee, block_number=block)
+
+
+def fetch_all(provider, count):
+    return [asyncio.run(provider.fetch(None)) for _ in range(count)]
+
+
+def test_jumps_are_allowed_per_block_elapsed():
+    script = [
+        quote(100, 20.0),
+        quote(101, 22.5),  # +12.5%, the most one block allows
+        quote(102, 30.0),  # +33% in one block
+        quote(104, 28.4),  # within 1.125^3 of block 101, the last trusted quote
+        quote(104, 28.4),
+        quote(103, 28.0),  # older than block 104
+    ]
+    checked = BaseFeeCheckProvider(MockProvider(script, cycle=False))
+
+    quotes = fetch_all(checked, len(script))
+
+    assert [q.get("suspect", False) for q in quotes] == [False, False, True, False, False, True]
+    assert "outside 19.6875-25.3125 gwei" in quotes[2]["suspect_reason"]
+    assert "older than block 104" in quotes[5]["suspect_reason"]
+    assert checked.reference == (104, 28.4)
+
+
+def test_strict_mode_rejects_the_quote():
+    checked = BaseFeeCheckProvider(MockProvider([quote(100, 20.0), quote(100, 25.0)]), strict=True)
+    asyncio.run(checked.fetch(None))
+    with pytest.raises(SuspectQuoteError, match="the same block") as info:
+        asyncio.run(checked.fetch(None))
+    assert info.value.provider == "mock"
+
+
+def test_tracker_wraps_its_provider_or_each_race_member():
+    mock = MockProvider([quote(100, 20.0), quote(101, 90.0)])
+    tracker = GasTracker("http://localhost", "ethereum", provider=mock).with_base_fee_check()
+    asyncio.run(tracker.current(None))
+    assert asyncio.run(tracker.current(None))["suspect"] is True
+
+    race = RaceProvider([MockProvider([20.0], name="a"), MockProvider([20.0], name="b")])
+    tracker = GasTracker("http://localhost", "ethereum", provider=race)
+    checked = tracker.clone().with_base_fee_check(strict=True)
+    assert [type(p) for p in checked.provider.providers] == [BaseFeeCheckProvider] * 2
+    assert all(p.strict for p in checked.provider.providers)
+    assert [type(p) for p in tracker.provider.providers] == [MockProvider] * 2
@@ -0,0 +1,105 @@
+"""Tests for Solana prioritization fee quotes."""
+
+import asyncio
+
+import pytest
+
+from ethgas.errors import ConfigError
+from ethgas.providers import PricingModel
+from ethgas.solana import MICRO_LAMPORTS_PER_CU, SolanaProvider, fee_percentile
+from ethgas.testing import MockRpcSession
+
+
+class Cluster(MockRpcSession):
+    """Session for a Solana node reporting `fees` for consecutive slots from 1000."""
+
+    def __init__(self, fees):
+        super().__init__()
+        self.fees = fees
+
+    def answer(self, call):
+        if call["method"] == "getSlot":
+            result = 1000 + len(self.fees)
+        else:
+            result = [{"slot": 1000 + i, "prioritizationFee": fee} for i, fee in enumerate(self.fees)]
+        return {"jsonrpc": "2.0", "id": call["id"], "result": result}
+
+
+def test_tiers_are_percentiles_of_recent_slot_fees():
+    cluster = Cluster([0, 0, 500, 1000, 1200, 1500, 2000, 5000, 8000, 100000])
+    quote = asyncio.run(SolanaProvider("http://solana").fetch(cluster))
+
+    # Interpolated: p50 between 1200 and 1500, p90 a tenth of the way to 100000
+    assert quote["tiers"] == {"slow": 1.0, "standard": 1350.0, "fast": 17200.0}
+    assert quote["gas_price"] == 1350.0
+    assert quote["unit"] == MICRO_LAMPORTS_PER_CU
+    assert quote["pricing_model"] is PricingModel.SOLANA
+    assert quote["base_fee"] is None
+    assert quote["block_number"] == 1009
+    assert cluster.posts[0]["method"] == "getRecentPrioritizationFees"
+    assert cluster.posts[0]["params"] == []
+
+
+def test_no_recent_fees_recommends_the_minimum():
+    quote = asyncio.run(SolanaProvider("http://solana", min_fee=50).fetch(Cluster([])))
+    assert quote["tiers"] == {"slow": 50.0, "standard": 50.0, "fast": 50.0}
+    assert quote["block_number"] is None
+
+
+def test_accounts_narrow_the_query():
+    cluster = Cluster([10])
+    account = "CounterProgram1111111111111111111111111111"
+    asyncio.run(SolanaProvider("http://solana", accounts=[account]).fetch(cluster))
+    assert cluster.posts[0]["params"] == [[account]]
+
+
+def test_block_height_is_the_latest_slot():
+    assert asyncio.run(SolanaProvider("http://solana").block_height(Cluster([1, 2, 3]))) == 1003
+
+
+def test_for_network_uses_the_cluster_rpc():
+    assert SolanaProvider.for_network("solana-devnet").rpc_url == "https://api.devnet.solana.com"
+    with pytest.raises(ConfigError):
+        SolanaProvider.for_network("ethereum")
+
+
+def test_fee_percentile_nearest_rank():
+    assert fee_percentile([5, 1, 3], 0, "nearest-rank") == 1
+    assert fee_percentile([5, 1, 3], 50, "nearest-rank") == 3
+    assert fee_percentile([5, 1, 3], 100, "nearest-rank") == 5
+
+
+def test_fee_percentile_method_picks_between_slot_fees():
+    fees = [1, 2, 3, 10]
+    assert fee_percentile(fees, 50) == 3  # 2.5, rounded up
+    assert fee_percentile(fees, 50, "lower") == 2
+    assert fee_percentile(fees, 90, "higher") == 10
+
+    cluster = Cluster([0, 0, 500, 1000, 1200, 1500, 2000, 5000, 8000, 100000])
+    quote = asyncio.run(SolanaProvider("http://solana", percentile_method="nearest-rank").fetch(cluster))
+    assert quote["tiers"] == {"slow": 1.0, "standard": 1200.0, "fast": 8000.0}
+    with pytest.raises(ConfigError, match="percentile method"):
+        SolanaProvider("http://solana", percentile_method="median")
+
+
+def test_estimate_tx_fee_adds_priority_to_the_signature_fee():
+    cluster = Cluster([0, 0, 500, 1000, 1200, 1500, 2000, 5000, 8000, 100000])
+    fee = asyncio.run(SolanaProvider("http://solana").estimate_solana_tx_fee(cluster, 6000, target_slots=1))
+
+    assert fee["micro_lamports_per_cu"] == 17200
+    assert fee["base_fee"] == 5000
+    assert fee["priority_fee"] == 104  # 6000 CU * 17200 micro-lamports, rounded up
+    assert fee["total"] == 5104
+
+    slow = asyncio.run(SolanaProvider("http://solana").estimate_solana_tx_fee(cluster, 6000, target_slots=20))
+    assert slow["micro_lamports_per_cu"] == 1
+    assert slow["priority_fee"] == 1  # rounded up to a whole lamport
+
+
+def test_estimate_tx_fee_validates_compute_units_and_window():
+    provider = SolanaProvider("http://solana")
+    for compute_units in (0, 1_400_001):
+        with pytest.raises(ConfigError):
+            asyncio.run(provider.estimate_solana_tx_fee(Cluster([]), compute_units, 1))
+    with pytest.raises(ConfigError):
+        asyncio.run(provider.estimate_solana_tx_fee(Cluster([]), 1_400_000, 0))
@@ -0,0 +1,89 @@
+"""Tests for gas statistics."""
+
+import pytest
+
+from ethgas.errors import InsufficientHistoryError
+from ethgas.stats import MIN_STATS_SAMPLES, GasRegime, GasStats
+
+
+def test_savings_report_compares_trough_to_peak_and_median():
+    samples = [
+        {"timestamp": "2024-01-01T03:00:00", "base_fee": 10.0},
+        {"timestamp": "2024-01-01T12:00:00", "base_fee": 50.0},
+        {"timestamp": "2024-01-01T18:00:00", "base_fee": 20.0},
+    ]
+
+    report = GasStats.savings_report(samples, gas_limit=21000)
+
+    assert report["trough"]["timestamp"] == "2024-01-01T03:00:00"
+    assert report["trough"]["cost_eth"] == pytest.approx(0.00021)
+    assert report["peak"]["cost_eth"] == pytest.approx(0.00105)
+    assert report["savings_vs_peak"]["pct"] == 80.0
+    assert report["savings_vs_median"]["pct"] == 50.0
+
+
+def test_single_sample_reports_zero_savings():
+    report = GasStats.savings_report([{"base_fee": 15.0}], gas_limit=21000)
+
+    assert report["savings_vs_peak"] == {"eth": 0.0, "pct": 0.0}
+    assert report["savings_vs_median"] == {"eth": 0.0, "pct": 0.0}
+    assert GasStats.savings_report([], gas_limit=21000) is None
+
+
+def test_classify_regime_against_history_percentiles():
+    samples = [{"base_fee": float(fee)} for fee in range(1, 101)]
+
+    assert GasStats.classify_regime(10.0, samples) is GasRegime.CHEAP
+    assert GasStats.classify_regime(50.0, samples) is GasRegime.NORMAL
+    assert GasStats.classify_regime(80.0, samples) is GasRegime.EXPENSIVE
+    assert GasStats.classify_regime(99.0, samples) is GasRegime.EXTREME
+    assert GasStats.classify_regime(50.0, [{"base_fee": None}]) is GasRegime.UNKNOWN
+    assert GasStats.classify_regime(50.0, samples[:MIN_STATS_SAMPLES - 1]) is GasRegime.UNKNOWN
+
+
+def test_percentile_method_sets_where_stats_fall_between_samples():
+    samples = [{"base_fee": float(fee)} for fee in (1, 2, 3, 4, 5, 6, 7, 8, 9, 100)]
+
+    linear = GasStats.window_stats(samples)
+    assert linear["p25"] == pytest.approx(3.25)
+    assert linear["p95"] == pytest.approx(59.05)
+    higher = GasStats.window_stats(samples, method="higher")
+    assert (higher["p25"], higher["p95"]) == (4.0, 100.0)
+    assert GasStats.calculate_advanced_stats(samples, method="lower")["base_fee"]["percentile_90"] == 9.0
+
+    # 50 gwei is below the interpolated p95 (59.05) but above the one LOWER picks (9)
+    assert GasStats.classify_regime(50.0, samples) is GasRegime.EXPENSIVE
+    assert GasStats.classify_regime(50.0, samples, method="lower") is GasRegime.EXTREME
+
+
+def test_percentile_stats_need_min_samples():
+    few = [{"base_fee": float(fee)} for fee in range(1, 4)]
+
+    for stats in (GasStats.calculate_advanced_stats, GasStats.calculate_price_ranges, GasStats.window_stats):
+        with pytest.raises(InsufficientHistoryError) as excinfo:
+            stats(few)
+        assert (excinfo.value.have, excinfo.value.need) == (3, MIN_STATS_SAMPLES)
+        assert stats(few, min_samples=3)
+    with pytest.raises(InsufficientHistoryError):
+        GasStats.window_stats([], min_samples=0)
+
+
+def test_cheapest_hours_ranks_utc_hours_and_marks_sparse_ones():
+    samples = [
+        {"timestamp": f"2024-01-0{day}T{hour:02d}:30:00+00:00", "base_fee": fee}
+        for day in (1, 2, 3)
+        for hour, fee in ((6, 10.0 + day), (14, 40.0 + day), (20, 25.0))
+    ]
+    samples.append({"timestamp": "2024-01-01T09:00:00+02:00", "base_fee": 12.0})  # 07:00 UTC
+    samples.append({"timestamp": "2024-01-01T08:00:00+00:00", "base_fee": None})
+
+    hours = GasStats.cheapest_hours(samples)
+
+    assert len(hours) == 24
+    assert [h["hour"] for h in hours[:3]] == [6, 20, 14]
+    assert hours[0] == {"hour": 6, "samples": 3, "mean_base_fee": 12.0, "median_base_fee": 12.0, "sufficient": True}
+    sparse = {h["hour"]: h for h in hours[3:]}
+    assert sparse[7] == {"hour": 7, "samples": 1, "mean_base_fee": None, "median_base_fee": None, "sufficient": False}
+    assert sparse[8]["samples"] == 0
+    assert [h["hour"] for h in hours[3:]] == sorted(sparse)
+    assert [h["hour"] for h in GasStats.cheapest_hours(samples, min_samples=1)[:2]] == [6, 7]
@@ -0,0 +1,66 @@
+"""Tests for the named gas strategies."""
+
+import asyncio
+
+import pytest
+
+from ethgas.errors import ConfigError
+from ethgas.strategy import STRATEGY_PROFILES, Strategy
+from ethgas.tracker import GasTracker
+
+GWEI = 10 ** 9
+
+
+class PercentileTracker(GasTracker):
+    """Tracker whose blocks all have a 10 gwei base fee and reward p at p/100 gwei."""
+
+    calls = None
+
+    async def rpc_result(self, session, method, params):
+        self.calls = (self.calls or []) + [params[2]]
+        return {
+            "oldestBlock": hex(100),
+            "baseFeePerGas": [hex(10 * GWEI)] * 3,
+            "reward": [[hex(int(p * 10 ** 7)) for p in params[2]]] * 2,
+        }
+
+
+def test_strategy_sets_percentile_headroom_floor_and_rounding():
+    tracker = PercentileTracker.for_network("ethereum").with_strategy(Strategy.FAST)
+
+    fees = asyncio.run(tracker.recommend(None))
+    assert tracker.calls == [[90.0]]
+    # p90 is 0.9 gwei, raised to 1.5x Ethereum's 1 gwei floor; max fee 3x the base fee
+    assert fees["max_priority_fee_per_gas"] == 15 * 10 ** 8
+    assert fees["max_fee_per_gas"] == 315 * 10 ** 8
+    assert fees["floor_applied"] is True
+
+    estimate = asyncio.run(tracker.estimate_eip1559(None))
+    assert tracker.calls[-1] == [50.0, 90.0, 95.0]
+    assert [t["percentile"] for t in estimate["tiers"].values()] == [50.0, 90.0, 95.0]
+
+    economy = asyncio.run(PercentileTracker.for_network("ethereum").with_strategy("economy").recommend(None))
+    assert economy["max_fee_per_gas"] == 125 * 10 ** 8 + GWEI  # 1.25x the base fee, at the 1 gwei floor
+
+    # MEV rounds to 0.1 gwei: 0.95 gwei becomes 1 gwei, then the 3 gwei floor lifts both fees by 2
+    mev = asyncio.run(PercentileTracker.for_network("ethereum").with_strategy("mev").recommend(None))
+    assert (mev["max_priority_fee_per_gas"], mev["max_fee_per_gas"]) == (3 * GWEI, 43 * GWEI)
+
+
+def test_later_calls_override_the_strategy():
+    tracker = (
+        PercentileTracker.for_network("ethereum")
+        .with_strategy("fast")
+        .with_base_fee_multiplier(None)
+        .with_min_priority_fee(0)
+    )
+    fees = asyncio.run(tracker.recommend(None))
+    assert (fees["max_priority_fee_per_gas"], fees["max_fee_per_gas"]) == (9 * 10 ** 8, 209 * 10 ** 8)
+
+    # Chains without a floor don't get one from the strategy
+    assert PercentileTracker("http://localhost", "ethereum").with_strategy("mev").min_priority_fee is None
+    assert set(STRATEGY_PROFILES) == set(Strategy)
+    with pytest.raises(ConfigError, match="economy, balanced, fast, mev"):
+        tracker.with_strategy("turbo")
+    with pytest.raises(ConfigError):
+        tracker.with_base_fee_multiplier(0.5)
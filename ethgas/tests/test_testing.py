@@ -0,0 +1,118 @@
+"""Tests for the scripted mock provider and the other test doubles."""
+
+import asyncio
+from datetime import timedelta
+
+import pytest
+
+from ethgas.alerts import filter_below, significant_changes
+from ethgas.errors import HttpError
+from ethgas.providers import JsonRpcProvider, is_stale, make_quote
+from ethgas.ratelimit import RateLimiter
+from ethgas.testing import Delay, MockClock, MockProvider, MockRpcSession, StaticPriceFeed
+from ethgas.tracker import GasTracker
+
+
+def test_script_drives_threshold_logic():
+    provider = MockProvider(
+        [30.0, 18.0, HttpError("HTTP 503", status=503), Delay(0.01, 17.0), 25.0, 15.0],
+        cycle=False,
+    )
+    tracker = GasTracker("http://localhost", "ethereum", provider=provider)
+
+    async def crossings():
+        found = []
+        stream = filter_below(tracker.watch(None, interval=0), "standard", below_gwei=20)
+        while len(found) < 2:
+            found.append((await stream.__anext__())["gas_price"])
+        return found
+
+    assert asyncio.run(crossings()) == [18.0, 15.0]
+    assert provider.calls == 6
+
+
+def test_significant_changes_drops_moves_within_the_band():
+    async def quotes():
+        for fee in (20.0, 20.5, 19.6, 20.9, 21.5, 21.0, 19.9, 25.0):
+            yield make_quote("mock", fee, base_fee=fee)
+
+    async def collect(**kwargs):
+        return [quote["base_fee"] async for quote in significant_changes(quotes(), **kwargs)]
+
+    # 20.5, 19.6 and 20.9 stay within 5% of 20, and 21.0 within 5% of 21.5
+    assert asyncio.run(collect(pct=5)) == [20.0, 21.5, 19.9, 25.0]
+    # Moving into another whole gwei counts too
+    assert asyncio.run(collect(pct=5, step_gwei=1)) == [20.0, 19.6, 20.9, 21.5, 19.9, 25.0]
+
+
+def test_significant_changes_on_a_watch_handle_stops_the_stream():
+    provider = MockProvider([20.0, 20.1, 19.9, 30.0, 30.2])
+    tracker = GasTracker("http://localhost", "ethereum", provider=provider)
+
+    async def run():
+        changes = tracker.watch(None, interval=0).significant_changes(pct=10)
+        seen = [(await changes.__anext__())["gas_price"] for _ in range(2)]
+        await changes.stop()
+        calls = provider.calls
+        await asyncio.sleep(0.01)
+        return seen, calls
+
+    seen, calls = asyncio.run(run())
+    assert seen == [20.0, 30.0]
+    assert provider.calls == calls
+
+
+def test_responder_and_exhausted_script():
+    provider = MockProvider(responder=lambda n: 10.0 * (n + 1))
+    once = MockProvider([1.0], cycle=False)
+
+    async def run():
+        quotes = [await provider.fetch(None) for _ in range(3)]
+        await once.fetch(None)
+        with pytest.raises(IndexError):
+            await once.fetch(None)
+        return [q["gas_price"] for q in quotes]
+
+    assert asyncio.run(run()) == [10.0, 20.0, 30.0]
+
+
+def test_mock_clock_drives_rate_limits_and_staleness():
+    clock = MockClock()
+    limiter = RateLimiter(requests_per_second=2, burst=2, clock=clock)
+
+    async def take(n):
+        for _ in range(n):
+            await limiter.acquire()
+
+    asyncio.run(asyncio.wait_for(take(4), timeout=1.0))
+    assert clock.monotonic() == pytest.approx(1.0)  # two tokens, then two waits of 0.5s
+
+    quote = make_quote("static", 10.0, clock=clock)
+    assert quote["fetched_at"] == clock.now()
+    clock.advance(30)
+    assert not is_stale(quote, timedelta(seconds=30), clock)
+    clock.advance(0.5)
+    assert is_stale(quote, timedelta(seconds=30), clock)
+
+    tracker = GasTracker("http://localhost", "ethereum", provider=MockProvider([10.0], clock=clock)).with_clock(clock)
+    assert asyncio.run(tracker.current(None))["fetched_at"] == clock.now() == tracker.status()["last_success_at"]
+
+
+def test_mock_rpc_session_answers_single_and_batched_calls():
+    session = MockRpcSession({"eth_gasPrice": hex(25 * 10 ** 9), "eth_blockNumber": hex(7)})
+    provider = JsonRpcProvider("http://localhost")
+
+    price, block, missing = asyncio.run(
+        provider.batch(session, [("eth_gasPrice", []), ("eth_blockNumber", []), ("eth_chainId", [])])
+    )
+
+    assert (price["result"], block["result"]) == (hex(25 * 10 ** 9), hex(7))
+    assert missing["error"]["code"] == -32601
+    assert asyncio.run(provider.rpc_call(session, "eth_blockNumber")) == hex(7)
+    assert len(session.posts) == 2 and isinstance(session.posts[0], list)
+
+
+def test_static_price_feed_answers_or_raises():
+    assert asyncio.run(StaticPriceFeed(2000.0).get_price_usd(None)) == 2000.0
+    with pytest.raises(HttpError):
+        asyncio.run(StaticPriceFeed(HttpError("HTTP 503", status=503), "down").get_price_usd(None))
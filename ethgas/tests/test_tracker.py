@@ -0,0 +1,1242 @@
+"""Tests for the gas tracker's quote stream."""
+
+import asyncio
+import logging
+from datetime import datetime, timedelta, timezone
+
+import pytest
+
+from ethgas.core import FeeRounding, PercentileMethod, RecencyWeight
+from ethgas.errors import (
+    ConfigError,
+    DeserializeError,
+    EstimationRevertedError,
+    GasTimeoutError,
+    HistoricalDataUnavailableError,
+    HttpError,
+    RpcError,
+    UnknownProviderError,
+)
+from ethgas.history import HistoryStore, InMemoryHistory
+from ethgas.l2 import OP_GET_L1_FEE_SELECTOR, OP_L1_BASE_FEE_SELECTOR, encode_op_l1_fee_call
+from ethgas.networks import NETWORKS, register_network
+from ethgas.providers import AggregateProvider, GasProvider, JsonRpcProvider, PricingModel, make_quote
+from ethgas.retry import RetryingProvider, RetryPolicy
+from ethgas.stats import GasRegime
+from ethgas.testing import Delay, MockProvider, StaticPriceFeed
+from ethgas.tracker import FeeHistoryConfig, GasTracker, compute_tiers, percentile_for_target_blocks
+
+
+class StuckProvider(GasProvider):
+    """Provider that keeps reporting the same block."""
+
+    name = "stuck"
+
+    async def fetch(self, session):
+        return make_quote(self.name, 10.0, block_number=100)
+
+
+def test_watch_warns_once_when_block_stops_advancing():
+    tracker = GasTracker("http://localhost", "ethereum", provider=StuckProvider())
+
+    async def collect():
+        items = []
+        async for item in tracker.watch(None, interval=0.01, stuck_after=0.02):
+            items.append(item)
+            if len(items) == 8:
+                return items
+
+    items = asyncio.run(collect())
+    warnings = [item for item in items if "warning" in item]
+
+    assert len(warnings) == 1
+    assert warnings[0]["block_number"] == 100
+    assert items.index(warnings[0]) > 0
+
+
+def test_fee_history_config_validation():
+    for kwargs in (
+        {"block_count": 0},
+        {"reward_percentiles": [50, 10]},
+        {"reward_percentiles": [10, 101]},
+        {"reward_percentiles": []},
+    ):
+        with pytest.raises(ConfigError):
+            FeeHistoryConfig(**kwargs)
+
+
+def test_recency_weight_is_validated_and_kept_across_block_count_overrides():
+    config = FeeHistoryConfig(recency_weight="exponential")
+    assert config.recency_weight is RecencyWeight.EXPONENTIAL
+    assert FeeHistoryConfig().recency_weight is RecencyWeight.NONE
+    with pytest.raises(ConfigError):
+        FeeHistoryConfig(recency_weight="newest")
+
+    class JumpTracker(RecordingFeeHistoryTracker):
+        async def rpc_result(self, session, method, params):
+            return {"baseFeePerGas": [hex(10)] * 5, "reward": [[hex(1)], [hex(1)], [hex(1)], [hex(9)]]}
+
+    tracker = JumpTracker().with_fee_history(FeeHistoryConfig(reward_percentiles=[50], recency_weight="linear"))
+    # Weighted 1:2:3:4 the tips average 4.2 wei instead of 3
+    assert asyncio.run(tracker.recommend(None))["max_priority_fee_per_gas"] == 4
+    assert asyncio.run(tracker.estimate_eip1559(None, block_count=4))["tiers"]["p50"]["max_priority_fee_per_gas"] == 4
+    tracker.with_fee_history(FeeHistoryConfig(reward_percentiles=[50]))
+    assert asyncio.run(tracker.recommend(None))["max_priority_fee_per_gas"] == 3
+
+
+def test_tiers_follow_configured_percentiles():
+    tracker = GasTracker("http://localhost", "ethereum").with_fee_history(
+        FeeHistoryConfig(block_count=4, reward_percentiles=[25, 75])
+    )
+    fee_history = {
+        "baseFeePerGas": [hex(10), hex(10)],
+        "reward": [[hex(1), hex(3)]],
+    }
+
+    estimate = tracker._estimate_from_fee_history(fee_history, tracker.fee_history.tiers())
+
+    assert tracker.fee_history.params() == ["0x4", "latest", [25, 75]]
+    assert list(estimate["tiers"]) == ["p25", "p75"]
+    assert estimate["tiers"]["p75"]["max_priority_fee_per_gas"] == 3
+
+
+class FeeHistoryTracker(GasTracker):
+    """Tracker answering eth_feeHistory with a fixed 10 gwei base fee and 1/2/3 gwei tips."""
+
+    async def rpc_result(self, session, method, params):
+        gwei = 10 ** 9
+        return {
+            "baseFeePerGas": [hex(10 * gwei), hex(10 * gwei)],
+            "reward": [[hex(1 * gwei), hex(2 * gwei), hex(3 * gwei)]],
+        }
+
+
+def test_to_tx_fields_follows_the_pricing_model():
+    tracker = FeeHistoryTracker("http://localhost", "ethereum", provider=MockProvider([make_quote("m", 30.0, base_fee=10.0)]))
+    fields = asyncio.run(tracker.to_tx_fields(None, "fast", gas_limit=50_000))
+    assert fields == {"maxFeePerGas": hex(23 * 10 ** 9), "maxPriorityFeePerGas": hex(3 * 10 ** 9), "gas": hex(50_000)}
+
+    legacy = GasTracker("http://localhost", "ethereum", provider=MockProvider([make_quote("m", 30.0, slow=25.5)]))
+    assert asyncio.run(legacy.to_tx_fields(None, "slow")) == {"gasPrice": hex(25_500_000_000)}
+    with pytest.raises(ConfigError, match="Unknown tier 'urgent'"):
+        asyncio.run(legacy.to_tx_fields(None, "urgent"))
+
+
+def test_snapshot_prices_every_tx_type_and_tier():
+    tracker = FeeHistoryTracker("http://localhost", "ethereum", price_feed=StaticPriceFeed(2000.0))
+
+    snapshot = asyncio.run(tracker.snapshot(None))
+
+    assert snapshot["token_price_usd"] == 2000.0
+    assert snapshot["base_fee_per_gas"] == 10 * 10 ** 9
+    simple = snapshot["costs"]["simple"]["standard"]
+    assert simple["cost_wei"] == 12 * 10 ** 9 * 21000
+    assert simple["cost_usd"] == round(12e-9 * 21000 * 2000, 2)
+    assert set(snapshot["costs"]["swap"]) == {"slow", "standard", "fast"}
+
+
+def test_snapshot_survives_price_feed_failure():
+    tracker = FeeHistoryTracker(
+        "http://localhost", "ethereum", price_feed=StaticPriceFeed(HttpError("HTTP 503", status=503))
+    )
+
+    snapshot = asyncio.run(tracker.snapshot(None))
+
+    assert snapshot["token_price_usd"] is None
+    assert snapshot["tiers"]["fast"]["max_priority_fee_per_gas"] == 3 * 10 ** 9
+    assert snapshot["costs"]["erc20"]["fast"]["cost_usd"] is None
+    assert snapshot["costs"]["erc20"]["fast"]["cost_eth"] > 0
+
+
+class ChainTracker(GasTracker):
+    """Tracker serving eth_getBlockByNumber from a fake chain, optionally failing one batch."""
+
+    def __init__(self, fail_at=None, batching=True):
+        super().__init__("http://localhost", "ethereum")
+        self.fail_at = fail_at
+        self.batching = batching
+        self.requests = []
+
+    def block(self, call):
+        number = int(call["params"][0], 16)
+        header = {"number": hex(number), "timestamp": hex(1700000000 + 12 * number)}
+        if number >= 10:
+            header["baseFeePerGas"] = hex(number * 10 ** 9)
+        return {"jsonrpc": "2.0", "id": call["id"], "result": header}
+
+    async def eth_call(self, session, payload):
+        self.requests.append(payload)
+        calls = payload if isinstance(payload, list) else [payload]
+        if self.fail_at is not None and any(int(c["params"][0], 16) == self.fail_at for c in calls):
+            raise HttpError("HTTP 503", status=503)
+        if isinstance(payload, list) and not self.batching:
+            return {"jsonrpc": "2.0", "id": None, "error": {"code": -32600, "message": "batch not supported"}}
+        responses = [self.block(c) for c in calls]
+        return list(reversed(responses)) if isinstance(payload, list) else responses[0]
+
+
+def test_backfill_stores_base_fees_in_batches():
+    tracker = ChainTracker()
+    store = HistoryStore(":memory:")
+
+    samples = asyncio.run(tracker.backfill(None, store, 8, 14, batch_size=3))
+
+    assert [s["block_number"] for s in samples] == [10, 11, 12, 13, 14]
+    assert samples[0]["base_fee"] == 10.0
+    assert samples[0]["fetched_at"].timestamp() == 1700000000 + 120
+    assert len(tracker.requests) == 3
+    assert len(store.conn.execute("SELECT * FROM samples").fetchall()) == 5
+
+
+def test_backfill_resumes_from_checkpoint():
+    store = HistoryStore(":memory:")
+    with pytest.raises(HttpError):
+        asyncio.run(ChainTracker(fail_at=15).backfill(None, store, 10, 17, batch_size=2))
+    assert store.checkpoint("backfill:Ethereum:10-17") == 13
+
+    resumed = ChainTracker(batching=False)
+    samples = asyncio.run(resumed.backfill(None, store, 10, 17, batch_size=2))
+
+    assert [s["block_number"] for s in samples] == [14, 15, 16, 17]
+    assert len(store.conn.execute("SELECT * FROM samples").fetchall()) == 8
+
+
+class ArchiveTracker(GasTracker):
+    """Tracker on a node at block 100 that keeps `retained` blocks of history."""
+
+    def __init__(self, retained=None):
+        super().__init__("http://localhost", "ethereum")
+        self.retained = retained
+        self.calls = []
+
+    def answer(self, call):
+        self.calls.append((call["method"], call["params"]))
+        result = None
+        if call["method"] == "eth_blockNumber":
+            result = hex(100)
+        elif call["method"] == "eth_getBlockByNumber":
+            number = int(call["params"][0], 16)
+            result = {"number": hex(number), "timestamp": hex(1700000000 + 12 * number), "baseFeePerGas": hex(20 * 10 ** 9)}
+        elif call["method"] == "eth_feeHistory":
+            newest = int(call["params"][1], 16)
+            if self.retained is not None and newest < 100 - self.retained:
+                return {"jsonrpc": "2.0", "id": call["id"], "error": {"code": -32000, "message": "missing trie node abc"}}
+            count = int(call["params"][0], 16)
+            result = {
+                "oldestBlock": hex(newest - count + 1),
+                "baseFeePerGas": [hex(20 * 10 ** 9)] * (count + 1),
+                "gasUsedRatio": [0.5] * count,
+                "reward": [[hex(10 ** 9), hex(2 * 10 ** 9), hex(3 * 10 ** 9)]] * count,
+            }
+        return {"jsonrpc": "2.0", "id": call["id"], "result": result}
+
+    async def eth_call(self, session, payload):
+        if isinstance(payload, list):
+            return [self.answer(call) for call in payload]
+        return self.answer(payload)
+
+
+def test_at_block_prices_tiers_on_the_blocks_base_fee():
+    tracker = ArchiveTracker()
+    quote = asyncio.run(tracker.at_block(None, 90))
+
+    assert quote["block_number"] == 90
+    assert quote["base_fee"] == 20.0
+    assert quote["tiers"] == {"slow": 21.0, "standard": 22.0, "fast": 23.0}
+    assert quote["priority_fees"]["fast"] == 3.0
+    assert quote["gas_price"] == 22.0
+    assert quote["fetched_at"] == datetime.fromtimestamp(1700000000 + 12 * 90, timezone.utc)
+    assert ("eth_feeHistory", [hex(20), hex(90), [10, 50, 90]]) in tracker.calls
+
+    # The window is cut off at genesis
+    asyncio.run(tracker.at_block(None, 4))
+    assert tracker.calls[-1] == ("eth_feeHistory", [hex(5), hex(4), [10, 50, 90]])
+
+
+def test_at_block_reports_pruned_history_and_future_blocks():
+    tracker = ArchiveTracker(retained=64)
+    assert asyncio.run(tracker.at_block(None, 90))["block_number"] == 90
+    with pytest.raises(HistoricalDataUnavailableError, match="archive node") as info:
+        asyncio.run(tracker.at_block(None, 10))
+    assert info.value.block_number == 10
+    with pytest.raises(ConfigError, match="after the latest block"):
+        asyncio.run(tracker.at_block(None, 101))
+    with pytest.raises(ConfigError):
+        asyncio.run(tracker.at_block(None, -1))
+
+
+def test_deadline_bounds_current_across_retries():
+    failing = MockProvider([Delay(0.03, HttpError("HTTP 503", status=503))])
+    tracker = (
+        GasTracker("http://localhost", "ethereum", provider=failing)
+        .with_retry(RetryPolicy(max_retries=10, base_delay=0.05))
+        .with_deadline(0.2)
+    )
+
+    async def timed():
+        loop = asyncio.get_running_loop()
+        started = loop.time()
+        with pytest.raises(GasTimeoutError):
+            await tracker.current(None)
+        return loop.time() - started
+
+    assert asyncio.run(timed()) < 0.3
+    assert 1 < failing.calls < 11
+
+
+def test_aggregate_returns_quotes_that_beat_the_deadline():
+    providers = [
+        MockProvider([20.0], name="a"),
+        MockProvider([30.0], name="b", delay=0.02),
+        MockProvider([Delay(5.0, 99.0)], name="slow"),
+    ]
+    tracker = GasTracker(
+        "http://localhost", "ethereum", provider=AggregateProvider(providers, timeout=10.0)
+    ).with_deadline(0.2)
+
+    quote = asyncio.run(tracker.current(None))
+
+    assert quote["sources"] == ["a", "b"]
+    assert quote["gas_price"] == 25.0
+
+
+def test_current_from_queries_one_member_with_the_retry_policy():
+    flaky = MockProvider([HttpError("HTTP 503", status=503), 30.0], name="b", cycle=False)
+    other = MockProvider([20.0], name="a")
+    tracker = GasTracker(
+        "http://localhost", "ethereum", provider=AggregateProvider([other, flaky])
+    ).with_retry(RetryPolicy(max_retries=2, base_delay=0))
+
+    quote = asyncio.run(tracker.current_from(None, "b"))
+
+    assert quote["provider"] == "b"
+    assert quote["gas_price"] == 30.0
+    assert quote["network"] == "Ethereum"
+    assert flaky.calls == 2
+    assert other.calls == 0
+
+
+def test_current_from_rejects_unknown_names():
+    tracker = GasTracker("http://localhost", "ethereum", provider=AggregateProvider([MockProvider([1.0], name="a")]))
+    with pytest.raises(UnknownProviderError) as excinfo:
+        asyncio.run(tracker.current_from(None, "missing"))
+    assert excinfo.value.available == ["a"]
+    assert str(excinfo.value) == "Unknown provider 'missing'; configured: a"
+
+
+def test_estimate_batch_prices_every_transaction_with_one_quote():
+    provider = MockProvider([make_quote("node", 20.0, block_number=7), make_quote("node", 99.0)], cycle=False)
+    tracker = GasTracker("http://localhost", "ethereum", provider=provider, price_feed=StaticPriceFeed(2000.0))
+
+    batch = asyncio.run(tracker.estimate_batch(None, ["simple", "erc20", "simple"]))
+
+    assert provider.calls == 1
+    assert [tx["tx_type"] for tx in batch["transactions"]] == ["simple", "erc20", "simple"]
+    assert batch["total_gas"] == 21000 + 65000 + 21000
+    assert batch["total_cost_wei"] == 20 * 10 ** 9 * 107000
+    assert batch["total_cost_gwei"] == 20.0 * 107000
+    assert batch["total_cost_usd"] == round(20e-9 * 107000 * 2000, 2)
+    assert batch["block_number"] == 7
+    with pytest.raises(ValueError):
+        asyncio.run(tracker.estimate_batch(None, ["simple", "teleport"]))
+
+
+def test_empty_batch_is_zero_without_requests():
+    provider = MockProvider([20.0])
+    batch = asyncio.run(GasTracker("http://localhost", "ethereum", provider=provider).estimate_batch(None, []))
+
+    assert provider.calls == 0
+    assert batch["transactions"] == [] and batch["total_gas"] == 0
+    assert batch["total_cost_wei"] == 0 and batch["total_cost_usd"] == 0.0
+
+
+def test_current_regime_uses_this_networks_recent_history():
+    store = HistoryStore(":memory:")
+    for fee in range(1, 21):
+        store.record(dict(make_quote("node", fee, base_fee=float(fee)), network="Ethereum"))
+        store.record(dict(make_quote("node", 100.0, base_fee=100.0), network="Polygon"))
+    tracker = FeeHistoryTracker("http://localhost", "ethereum")
+
+    # The fake node reports a 10 gwei base fee
+    assert asyncio.run(tracker.current_regime(None, store)) is GasRegime.NORMAL
+    assert asyncio.run(tracker.current_regime(None, HistoryStore(":memory:"))) is GasRegime.UNKNOWN
+
+
+def test_smoothed_current_blends_recent_history_by_half_life():
+    history = InMemoryHistory()
+    now = datetime.now()
+    history.record(dict(make_quote("node", 40.0, base_fee=40.0), network="Ethereum"), now - timedelta(seconds=60))
+    history.record(dict(make_quote("node", 90.0, base_fee=90.0), network="Polygon"), now - timedelta(seconds=60))
+    history.record(dict(make_quote("node", 99.0, base_fee=99.0), network="Ethereum"), now - timedelta(hours=1))
+    tracker = GasTracker("http://localhost", "ethereum", provider=MockProvider([make_quote("node", 10.0, base_fee=10.0)]))
+
+    quote = asyncio.run(tracker.smoothed_current(None, history, half_life=timedelta(seconds=60)))
+
+    # The 60 s old sample weighs half as much as the live quote; the others are ignored
+    assert quote["gas_price"] == pytest.approx(20.0, abs=0.01)
+    assert quote["base_fee"] == pytest.approx(20.0, abs=0.01)
+    assert quote["tiers"]["fast"] == pytest.approx(20.0, abs=0.01)
+    assert quote["smoothed_samples"] == 1
+
+    with pytest.raises(ConfigError):
+        asyncio.run(tracker.smoothed_current(None, history, half_life=timedelta(0)))
+
+
+class EstimateGasTracker(GasTracker):
+    """Tracker whose eth_estimateGas returns 50000 gas or fails with `error`."""
+
+    def __init__(self, error=None):
+        super().__init__("http://localhost", "ethereum", provider=MockProvider([20.0]))
+        self.error = error
+        self.calls = []
+
+    async def rpc_result(self, session, method, params):
+        self.calls.append((method, params))
+        if self.error:
+            raise self.error
+        return hex(50000)
+
+
+def test_estimate_for_tx_prices_estimated_gas_at_each_tier():
+    tracker = EstimateGasTracker()
+    tx = {"from": "0xabc", "to": "0xdef", "data": "0x1234", "value": "0x10"}
+
+    cost = asyncio.run(tracker.estimate_for_tx(None, tx, eth_price_usd=2000.0))
+
+    assert tracker.calls == [("eth_estimateGas", [{"from": "0xabc", "to": "0xdef", "data": "0x1234", "value": "0x10"}])]
+    assert cost["gas_limit"] == 50000
+    assert cost["tiers"]["standard"]["cost_wei"] == 20 * 10 ** 9 * 50000
+    assert cost["tiers"]["standard"]["cost_usd"] == 2.0
+
+
+def test_estimate_for_tx_takes_a_full_transaction_object():
+    tracker = EstimateGasTracker()
+    # As eth_getTransactionByHash returns it
+    tx = {
+        "type": "0x2", "chainId": "0x1", "nonce": "0x2a", "gas": "0x5208", "gasPrice": "0x4a817c800",
+        "maxFeePerGas": "0x6fc23ac00", "maxPriorityFeePerGas": "0x3b9aca00", "accessList": [],
+        "from": "0xabc", "to": "0xdef", "input": "0x1234", "value": "0x10", "hash": "0x" + "11" * 32,
+        "blockHash": "0x" + "22" * 32, "blockNumber": "0x10", "transactionIndex": "0x0",
+        "v": "0x1", "r": "0x" + "33" * 32, "s": "0x" + "44" * 32, "yParity": "0x1",
+    }
+
+    asyncio.run(tracker.estimate_for_tx(None, tx))
+
+    assert tracker.calls == [("eth_estimateGas", [{"from": "0xabc", "to": "0xdef", "data": "0x1234", "value": "0x10"}])]
+    with pytest.raises(ValueError, match="Unknown transaction fields: colour"):
+        asyncio.run(tracker.estimate_for_tx(None, dict(tx, colour="red")))
+
+
+def test_reverted_estimate_surfaces_reason():
+    # Error(string) with message "Insufficient balance"
+    reason = "Insufficient balance".encode().hex()
+    data = "0x08c379a0" + f"{32:064x}" + f"{20:064x}" + reason.ljust(64, "0")
+    for error, expected in (
+        (RpcError(3, "execution reverted", data), "Insufficient balance"),
+        (RpcError(-32000, "execution reverted: Ownable: caller is not the owner"), "Ownable: caller is not the owner"),
+        (RpcError(-32000, "execution reverted"), None),
+    ):
+        with pytest.raises(EstimationRevertedError) as excinfo:
+            asyncio.run(EstimateGasTracker(error).estimate_for_tx(None, {"to": "0xdef"}))
+        assert excinfo.value.reason == expected
+
+    with pytest.raises(RpcError) as excinfo:
+        asyncio.run(EstimateGasTracker(RpcError(-32000, "insufficient funds for gas")).estimate_for_tx(None, {}))
+    assert not isinstance(excinfo.value, EstimationRevertedError)
+
+
+class OpStackTracker(GasTracker):
+    """Tracker whose post-Ecotone GasPriceOracle answers l1BaseFee() and getL1Fee() but reverts on overhead()."""
+
+    def __init__(self, l1_fee="0x" + f"{28_481_760_000_000:064x}"):
+        super().__init__("http://localhost", "optimism")
+        self.l1_fee_model = "op-stack"
+        self.l1_fee = l1_fee
+        self.oracle_calls = []
+
+    async def rpc_result(self, session, method, params):
+        if method == "eth_gasPrice":
+            return hex(10 ** 6)
+        data = params[0]["data"]
+        self.oracle_calls.append(data)
+        if data == OP_L1_BASE_FEE_SELECTOR:
+            return "0x" + f"{30 * 10 ** 9:064x}"
+        if data.startswith(OP_GET_L1_FEE_SELECTOR):
+            return self.l1_fee
+        raise RpcError(-32000, "execution reverted")
+
+    async def eth_call(self, session, payload):
+        return {}  # No batch support: rpc_batch falls back to rpc_result
+
+
+def test_op_stack_l2_cost_asks_the_oracle_for_the_l1_fee():
+    tx = b"\x00" * 100 + b"\x01" * 50
+    tracker = OpStackTracker()
+    cost = asyncio.run(tracker.estimate_l2_cost(None, tx))
+
+    assert tracker.oracle_calls == [OP_L1_BASE_FEE_SELECTOR, encode_op_l1_fee_call(tx)]
+    assert cost["l1_base_fee_wei"] == 30 * 10 ** 9
+    assert cost["l1_data_fee_wei"] == 28_481_760_000_000
+    assert cost["total_fee_wei"] == 21000 * 10 ** 6 + 28_481_760_000_000
+
+    with pytest.raises(DeserializeError):
+        asyncio.run(OpStackTracker(l1_fee="0x1" + "0" * 64).estimate_l2_cost(None, b"\x01"))
+
+
+class RecordingFeeHistoryTracker(GasTracker):
+    """Tracker recording its RPC calls and answering eth_feeHistory for blocks 0x64-0x66."""
+
+    def __init__(self):
+        super().__init__("http://localhost", "ethereum")
+        self.calls = []
+
+    async def rpc_result(self, session, method, params):
+        self.calls.append((method, params))
+        return {
+            "oldestBlock": hex(100),
+            "baseFeePerGas": [hex(8), hex(9), hex(10), hex(11)],
+            "reward": [[hex(1)], [], [hex(4)]],
+        }
+
+
+class PercentileRewardTracker(RecordingFeeHistoryTracker):
+    """Tracker whose eth_feeHistory reward at percentile p is p gwei."""
+
+    async def rpc_result(self, session, method, params):
+        self.calls.append((method, params))
+        return {"baseFeePerGas": [hex(10 ** 10)] * 2, "reward": [[hex(int(p) * 10 ** 9) for p in params[2]]]}
+
+
+def test_custom_tiers_join_the_defaults_in_estimate_eip1559():
+    tracker = PercentileRewardTracker().with_tier("aggressive", 80.0)
+
+    estimate = asyncio.run(tracker.estimate_eip1559(None))
+
+    assert tracker.calls[0][1][2] == [10, 50, 80, 90]
+    priority = {tier: fees["max_priority_fee_per_gas"] for tier, fees in estimate["tiers"].items()}
+    assert priority == {"slow": 10 * 10 ** 9, "standard": 50 * 10 ** 9, "fast": 90 * 10 ** 9, "aggressive": 80 * 10 ** 9}
+
+
+def test_cleared_tiers_are_replaced_and_validated():
+    tracker = PercentileRewardTracker().clear_tiers().with_tier("p99", 99).with_tier("also-p99", 99)
+
+    estimate = asyncio.run(tracker.estimate_eip1559(None))
+
+    assert tracker.calls[0][1][2] == [99]
+    assert set(estimate["tiers"]) == {"p99", "also-p99"}
+    with pytest.raises(ConfigError):
+        tracker.with_tier("p99", 95)
+    with pytest.raises(ConfigError):
+        tracker.with_tier("too-high", 101)
+    with pytest.raises(ConfigError):
+        asyncio.run(PercentileRewardTracker().clear_tiers().estimate_eip1559(None))
+
+
+def test_estimate_tiers_carry_their_percentile_and_label():
+    tracker = PercentileRewardTracker().with_tier("aggressive", 80.0)
+
+    tiers = asyncio.run(tracker.estimate_eip1559(None))["tiers"]
+
+    assert [(name, tier["percentile"], tier["label"]) for name, tier in tiers.items()] == [
+        ("slow", 10, "Slow"), ("standard", 50, "Standard"), ("fast", 90, "Fast"), ("aggressive", 80.0, "aggressive")
+    ]
+    assert tiers.tier("aggressive") is tiers["aggressive"]
+    assert tiers.tier("aggressive")["max_priority_fee_per_gas"] == 80 * 10 ** 9
+    with pytest.raises(ConfigError, match="available: slow, standard, fast, aggressive"):
+        tiers.tier("urgent")
+    default = asyncio.run(PercentileRewardTracker().estimate_eip1559(None))["tiers"]
+    assert default.tier("fast")["label"] == "Fast"
+
+
+def test_estimate_at_base_fee_keeps_the_tiers_priority_fee():
+    tracker = PercentileRewardTracker()
+
+    fees = asyncio.run(tracker.estimate_at_base_fee(None, 100))
+
+    assert fees == {"max_priority_fee_per_gas": 50 * 10 ** 9, "max_fee_per_gas": 250 * 10 ** 9, "block_number": None}
+    assert asyncio.run(tracker.estimate_at_base_fee(None, 0, "fast"))["max_fee_per_gas"] == 90 * 10 ** 9
+    assert len(tracker.calls) == 2
+    estimate = asyncio.run(tracker.estimate_eip1559(None))
+    assert asyncio.run(tracker.estimate_at_base_fee(None, 100, estimate=estimate)) == fees
+    assert asyncio.run(tracker.estimate_at_base_fee(None, 200, estimate=estimate))["max_fee_per_gas"] == 450 * 10 ** 9
+    assert len(tracker.calls) == 3
+
+    # The cap also holds against a base fee below the current 10 gwei
+    capped = asyncio.run(tracker.with_max_priority_ratio(1.0).estimate_at_base_fee(None, 5))
+    assert capped["max_priority_fee_per_gas"] == 5 * 10 ** 9 and capped["priority_capped"]
+    for base_fee in (-1, float("nan"), "100", True):
+        with pytest.raises(ConfigError):
+            asyncio.run(tracker.estimate_at_base_fee(None, base_fee))
+    with pytest.raises(ConfigError):
+        asyncio.run(tracker.estimate_at_base_fee(None, 100, "urgent"))
+
+
+def test_blob_fee_estimate_is_none_without_blob_fields():
+    tracker = RecordingFeeHistoryTracker()
+
+    assert asyncio.run(tracker.blob_fee_estimate(None)) is None
+    assert tracker.calls == [("eth_feeHistory", ["0x14", "latest", []])]
+
+
+def test_recommend_makes_one_fee_history_call_for_the_standard_tier():
+    tracker = RecordingFeeHistoryTracker()
+
+    recommendation = asyncio.run(tracker.recommend(None))
+
+    assert tracker.calls == [("eth_feeHistory", ["0x14", "latest", [50]])]
+    assert recommendation == {"max_priority_fee_per_gas": 2, "max_fee_per_gas": 24, "block_number": 102}
+
+
+class NoFeeHistoryTracker(RecordingFeeHistoryTracker):
+    """Tracker whose node lacks eth_feeHistory, optionally eth_maxPriorityFeePerGas too."""
+
+    def __init__(self, suggests_priority=True):
+        super().__init__()
+        self.suggests_priority = suggests_priority
+
+    async def rpc_result(self, session, method, params):
+        self.calls.append((method, params))
+        if method == "eth_getBlockByNumber":
+            return {"number": hex(200), "baseFeePerGas": hex(10 ** 10), "gasUsed": hex(15), "gasLimit": hex(30)}
+        if method == "eth_maxPriorityFeePerGas" and self.suggests_priority:
+            return hex(2 * 10 ** 9)
+        raise RpcError(-32601, f"the method {method} does not exist/is not available")
+
+
+def test_missing_fee_history_degrades_to_the_latest_block_once():
+    tracker = NoFeeHistoryTracker()
+
+    estimate = asyncio.run(tracker.estimate_eip1559(None))
+    recommendation = asyncio.run(tracker.recommend(None))
+
+    assert estimate["degraded"] is True
+    assert estimate["base_fee_per_gas"] == 10 ** 10
+    assert estimate["block_number"] == 200
+    priority = {tier: fees["max_priority_fee_per_gas"] for tier, fees in estimate["tiers"].items()}
+    # 0.8x, 1x and 1.5x the node's 2 gwei suggestion
+    assert priority == {"slow": 16 * 10 ** 8, "standard": 2 * 10 ** 9, "fast": 3 * 10 ** 9}
+    assert recommendation == {
+        "max_priority_fee_per_gas": 2 * 10 ** 9, "max_fee_per_gas": 22 * 10 ** 9, "block_number": 200, "degraded": True
+    }
+    # eth_feeHistory is probed only by the first call
+    assert [method for method, _ in tracker.calls] == [
+        "eth_feeHistory", "eth_getBlockByNumber", "eth_maxPriorityFeePerGas",
+        "eth_getBlockByNumber", "eth_maxPriorityFeePerGas",
+    ]
+
+
+def test_percentile_method_applies_to_degraded_fee_history():
+    config = FeeHistoryConfig(reward_percentiles=[30, 70], percentile_method="lower")
+    assert config.percentile_method is PercentileMethod.LOWER
+    assert FeeHistoryConfig().percentile_method is PercentileMethod.LINEAR
+    with pytest.raises(ConfigError, match="percentile method"):
+        FeeHistoryConfig(percentile_method="median")
+
+    tracker = NoFeeHistoryTracker().with_fee_history(config)
+    # Snapped down to the 0.8x and 1x anchors instead of interpolated to 0.9x and 1.25x
+    estimate = asyncio.run(tracker.estimate_eip1559(None, block_count=5))
+    assert [fees["max_priority_fee_per_gas"] for fees in estimate["tiers"].values()] == [16 * 10 ** 8, 2 * 10 ** 9]
+
+
+def test_missing_priority_suggestion_uses_the_static_estimate():
+    tracker = NoFeeHistoryTracker(suggests_priority=False)
+
+    asyncio.run(tracker.recommend(None))
+    recommendation = asyncio.run(tracker.recommend(None))
+
+    assert recommendation["max_priority_fee_per_gas"] == 15 * 10 ** 8
+    assert [method for method, _ in tracker.calls][3:] == ["eth_getBlockByNumber"]
+
+
+def test_other_fee_history_errors_still_raise():
+    class FailingTracker(RecordingFeeHistoryTracker):
+        async def rpc_result(self, session, method, params):
+            raise RpcError(-32000, "header not found")
+
+    tracker = FailingTracker()
+    with pytest.raises(RpcError):
+        asyncio.run(tracker.recommend(None))
+    assert tracker._supports_fee_history is True
+
+
+def test_replacement_fee_is_floored_at_the_current_recommendation():
+    tracker = RecordingFeeHistoryTracker()
+    original = {"max_priority_fee_per_gas": 1, "max_fee_per_gas": 30, "block_number": 90}
+
+    assert asyncio.run(tracker.replacement_fee(None, original)) == {
+        "max_priority_fee_per_gas": 2, "max_fee_per_gas": 34, "block_number": 102
+    }
+
+
+def test_fee_rounding_applies_to_recommendations_and_tiers():
+    tracker = RecordingFeeHistoryTracker().with_fee_rounding(FeeRounding(1))
+
+    assert asyncio.run(tracker.recommend(None)) == {
+        "max_priority_fee_per_gas": 10 ** 9, "max_fee_per_gas": 10 ** 9, "block_number": 102
+    }
+    tracker.with_fee_history(FeeHistoryConfig(reward_percentiles=[50]))
+    assert asyncio.run(tracker.estimate_eip1559(None))["tiers"]["p50"]["max_fee_per_gas"] == 10 ** 9
+
+
+def test_min_priority_fee_raises_near_zero_tips_and_marks_them():
+    tracker = RecordingFeeHistoryTracker().with_min_priority_fee(1)
+
+    assert asyncio.run(tracker.recommend(None)) == {
+        "max_priority_fee_per_gas": 10 ** 9, "max_fee_per_gas": 10 ** 9 + 22, "block_number": 102, "floor_applied": True
+    }
+    tracker.with_fee_history(FeeHistoryConfig(reward_percentiles=[50]))
+    tier = asyncio.run(tracker.estimate_eip1559(None))["tiers"]["p50"]
+    assert tier["floor_applied"] and tier["max_priority_fee_per_gas"] == 10 ** 9
+
+    tracker.with_min_priority_fee(0)
+    assert "floor_applied" not in asyncio.run(tracker.recommend(None))
+    for floor in (-1, float("nan"), "1", True):
+        with pytest.raises(ConfigError):
+            tracker.with_min_priority_fee(floor)
+
+
+def test_min_priority_fee_raises_current_quote_tiers():
+    quote = make_quote("mock", 20.5, base_fee=20.0, slow=20.1, fast=22.0, block_number=7)
+    quote["priority_fees"] = {"slow": 0.1, "standard": 0.5, "fast": 2.0}
+    tracker = GasTracker("http://localhost", "ethereum", provider=MockProvider([quote, quote])).with_min_priority_fee(1)
+
+    floored = asyncio.run(tracker.current(None))
+
+    assert floored["tiers"] == {"slow": 21.0, "standard": 21.0, "fast": 22.0}
+    assert floored["priority_fees"] == {"slow": 1.0, "standard": 1.0, "fast": 2.0}
+    assert floored["gas_price"] == 21.0 and floored["floor_applied"] is True
+    assert "floor_applied" not in asyncio.run(tracker.with_min_priority_fee(None).current(None))
+
+
+def test_max_priority_ratio_caps_tips_relative_to_the_base_fee_and_logs(caplog):
+    caplog.set_level(logging.WARNING, logger="ethgas")
+    tracker = RecordingFeeHistoryTracker().with_max_priority_ratio(0.1)
+    recommendation = asyncio.run(tracker.recommend(None))
+
+    # 0.1x the upcoming 11 wei base fee
+    assert recommendation == {
+        "max_priority_fee_per_gas": 1, "max_fee_per_gas": 23, "block_number": 102, "priority_capped": True
+    }
+    assert [r.levelno for r in caplog.records] == [logging.WARNING]
+    assert "capped to 1 wei" in caplog.records[0].getMessage()
+
+    # The floor wins over the cap
+    assert asyncio.run(tracker.with_min_priority_fee(0.000000002).recommend(None))["max_priority_fee_per_gas"] == 2
+    assert "priority_capped" not in asyncio.run(tracker.with_max_priority_ratio(None).recommend(None))
+    for ratio in (0, -1, float("inf"), "3"):
+        with pytest.raises(ConfigError):
+            tracker.with_max_priority_ratio(ratio)
+
+
+def test_for_network_uses_the_chains_priority_fee_floor():
+    assert GasTracker.for_network("ethereum").min_priority_fee == 10 ** 9
+    assert GasTracker.for_network("base").min_priority_fee is None
+    chain = register_network(
+        "floored", "Floored", "http://floored", 991, min_priority_fee_gwei=0.5, max_priority_ratio=3, replace=True
+    )
+    assert GasTracker.for_network(chain).min_priority_fee == 5 * 10 ** 8
+    assert GasTracker.for_network(chain).max_priority_ratio == 3.0
+    assert GasTracker.for_network("ethereum").max_priority_ratio is None
+
+
+def test_adaptive_max_fee_uses_lower_headroom_when_the_base_fee_is_steady():
+    tracker = PercentileRewardTracker()
+
+    max_fee = asyncio.run(tracker.adaptive_max_fee(None, "fast", confidence=0.95))
+
+    # Flat 10 gwei base fee: one block's rise of headroom plus the p90 tip
+    assert max_fee == 11_250_000_000 + 90 * 10 ** 9
+    with pytest.raises(ConfigError):
+        asyncio.run(tracker.adaptive_max_fee(None, "urgent"))
+    with pytest.raises(ConfigError):
+        asyncio.run(tracker.adaptive_max_fee(None, confidence=0.999))
+
+
+class NoPendingTracker(RecordingFeeHistoryTracker):
+    """RecordingFeeHistoryTracker whose endpoint fails pending block tag requests with `error`."""
+
+    error = (-32602, "pending not supported")
+
+    async def rpc_result(self, session, method, params):
+        if params[1] == "pending":
+            self.calls.append((method, params))
+            raise RpcError(*self.error)
+        return await super().rpc_result(session, method, params)
+
+
+def test_pending_block_tag_is_used_and_falls_back_to_latest():
+    tracker = RecordingFeeHistoryTracker().with_block_tag("pending")
+    asyncio.run(tracker.recommend(None))
+    assert tracker.calls == [("eth_feeHistory", ["0x14", "pending", [50]])]
+    assert tracker.provider.block_tag == "pending"
+
+    tracker = NoPendingTracker().with_block_tag("pending")
+    assert asyncio.run(tracker.recommend(None))["max_fee_per_gas"] == 24
+    asyncio.run(tracker.get_base_fee_gwei(None))
+    assert [params[1] for _, params in tracker.calls] == ["pending", "latest", "latest"]
+
+
+def test_block_tag_on_a_clone_leaves_the_original_providers_alone():
+    members = [JsonRpcProvider("http://a", name="a"), RetryingProvider(JsonRpcProvider("http://b", name="b"), RetryPolicy())]
+    tracker = GasTracker("http://localhost", "ethereum", provider=AggregateProvider(members)).with_retry(RetryPolicy())
+
+    tagged = tracker.clone().with_block_tag("pending")
+
+    member_a, member_b = tagged.provider.provider.providers
+    assert (member_a.block_tag, member_b.provider.block_tag) == ("pending", "pending")
+    assert tracker.provider.provider.providers == members
+    assert [members[0].block_tag, members[1].provider.block_tag] == ["latest", "latest"]
+    assert tracker.block_tag == "latest"
+
+    single = GasTracker("http://localhost", "ethereum", provider=members[0])
+    assert single.clone().with_block_tag(500).provider.block_tag == 500
+    assert single.provider.block_tag == "latest"
+
+
+def test_transient_pending_failure_keeps_the_pending_tag():
+    tracker = NoPendingTracker().with_block_tag("pending")
+    tracker.error = (-32005, "request limit exceeded")
+
+    with pytest.raises(RpcError):
+        asyncio.run(tracker.recommend(None))
+    with pytest.raises(RpcError):
+        asyncio.run(tracker.recommend(None))
+    assert [params[1] for _, params in tracker.calls] == ["pending", "pending"]
+
+
+class ChainIdTracker(GasTracker):
+    """Tracker whose endpoint reports chain ID 10 and a 1 gwei gas price."""
+
+    async def rpc_result(self, session, method, params):
+        return hex(10)
+
+
+def test_custom_chain_warns_once_on_chain_id_mismatch():
+    chain = register_network("testrollup", "Test Rollup", "http://localhost", 999999, eip1559=False, replace=True)
+    try:
+        tracker = ChainIdTracker.for_network(chain, provider=MockProvider([1.0, 1.0]))
+
+        with pytest.warns(RuntimeWarning, match="chain ID 10, expected 999999") as record:
+            asyncio.run(tracker.current(None))
+            asyncio.run(tracker.current(None))
+
+        assert len([w for w in record if issubclass(w.category, RuntimeWarning)]) == 1
+        assert GasTracker.for_network("testrollup").provider.pricing_model == PricingModel.LEGACY
+    finally:
+        del NETWORKS["testrollup"]
+
+
+class SlowChainIdTracker(GasTracker):
+    """Tracker whose endpoint takes a second to answer eth_chainId."""
+
+    async def rpc_result(self, session, method, params):
+        await asyncio.sleep(1.0)
+        return hex(1)
+
+
+def test_deadline_bounds_the_chain_id_check():
+    tracker = SlowChainIdTracker.for_network("ethereum", provider=MockProvider([1.0])).with_deadline(0.05)
+
+    with pytest.raises(GasTimeoutError):
+        asyncio.run(tracker.current(None))
+
+
+def test_register_network_validates_fields():
+    for kwargs in ({"network_id": "ethereum"}, {"chain_id": 0}, {"l1_fee_model": "zk"}):
+        args = dict({"network_id": "newchain", "name": "New", "rpc": "http://localhost", "chain_id": 5}, **kwargs)
+        with pytest.raises(ConfigError):
+            register_network(**args)
+
+
+def test_current_logs_spans_with_chain_and_block():
+    records = []
+    handler = logging.Handler()
+    handler.emit = records.append
+    logger = logging.getLogger("ethgas")
+    logger.addHandler(handler)
+    logger.setLevel(logging.DEBUG)
+    try:
+        tracker = GasTracker("http://localhost", "ethereum", provider=StuckProvider())
+        asyncio.run(tracker.current(None))
+    finally:
+        logger.removeHandler(handler)
+        logger.setLevel(logging.NOTSET)
+
+    spans = {r.ethgas["span"]: r.ethgas for r in records}
+    assert spans["fetch"]["provider"] == "stuck" and spans["fetch"]["block_number"] == 100
+    assert spans["current"]["chain"] == "Ethereum" and spans["current"]["block_number"] == 100
+
+
+def test_check_budget_caps_max_fee_at_the_ceiling():
+    quote = make_quote("mock", 30.0, base_fee=20.0, fast=40.0, block_number=7)
+    tracker = GasTracker("http://localhost", "ethereum", provider=MockProvider([quote]))
+
+    allowed = asyncio.run(tracker.check_budget(None, "standard", 35.0))
+    rejected = asyncio.run(tracker.check_budget(None, "fast", 35.0))
+
+    assert allowed and allowed.current == 30.0
+    assert allowed.recommendation == {
+        "max_priority_fee_per_gas": 10 * 10 ** 9,
+        "max_fee_per_gas": 35 * 10 ** 9,
+        "block_number": 7,
+    }
+    assert not rejected and rejected.recommendation is None
+    assert rejected.current == 40.0 and rejected.ceiling == 35.0
+
+
+def test_wait_until_below_returns_first_quote_within_budget():
+    tracker = GasTracker("http://localhost", "ethereum", provider=MockProvider([50.0, HttpError("down"), 25.0], cycle=False))
+
+    quote = asyncio.run(tracker.wait_until_below(None, "standard", 30.0, poll=0, timeout=1.0))
+    assert quote["gas_price"] == 25.0
+
+    stuck = GasTracker("http://localhost", "ethereum", provider=MockProvider([50.0]))
+    with pytest.raises(GasTimeoutError):
+        asyncio.run(stuck.wait_until_below(None, "standard", 30.0, poll=0.01, timeout=0.05))
+
+
+def test_provider_health_reports_each_member_behind_wrappers():
+    aggregate = AggregateProvider([StuckProvider(), MockProvider([make_quote("fresh", 10.0, block_number=110)])])
+    tracker = GasTracker("http://localhost", "ethereum", provider=aggregate).with_retry(RetryPolicy(base_delay=0))
+
+    health = asyncio.run(tracker.check_provider_health(None))
+
+    assert [(h["provider"], h["blocks_behind"]) for h in health] == [("stuck", 10), ("mock", 0)]
+    assert health[0]["status"] == "lagging"
+
+
+def test_freeze_detection_on_a_clone_leaves_the_original_aggregate_alone():
+    aggregate = AggregateProvider([StuckProvider(), MockProvider([10.0])])
+    tracker = GasTracker("http://localhost", "ethereum", provider=aggregate).with_retry(RetryPolicy(base_delay=0))
+    clone = tracker.clone().with_freeze_detection(after=3, exclude=True)
+
+    assert aggregate.freeze_detector is None and not aggregate.exclude_frozen
+    assert tracker.provider.provider is aggregate
+    assert clone.provider.provider is not aggregate
+    assert clone.provider.provider.freeze_detector.after == 3
+    assert clone.provider.provider.providers == aggregate.providers
+
+
+def test_frozen_member_is_warned_about_and_reported():
+    live = MockProvider([make_quote("live", 10.0 + i, block_number=101 + i) for i in range(10)], name="live")
+    tracker = GasTracker(
+        "http://localhost", "ethereum", provider=AggregateProvider([live, StuckProvider()])
+    ).with_freeze_detection(after=3)
+
+    async def collect():
+        items = []
+        async for item in tracker.watch(None, interval=0, stuck_after=60):
+            items.append(item)
+            if len(items) == 6:
+                return items
+
+    items = asyncio.run(collect())
+    warnings = [item for item in items if "warning" in item]
+    assert [w["provider"] for w in warnings] == ["stuck"]
+    assert items.index(warnings[0]) == 2  # just before the third identical quote
+    assert items[-1]["frozen"] == ["stuck"]
+
+    health = asyncio.run(tracker.check_provider_health(None, max_lag_blocks=10))
+    assert [h["status"] for h in health] == ["healthy", "frozen"]
+    with pytest.raises(ConfigError, match="AggregateProvider"):
+        GasTracker("http://localhost", "ethereum", provider=live).with_freeze_detection()
+
+
+def test_status_reports_counters_without_network_calls():
+    down = HttpError("HTTP 503", status=503)
+    failing = MockProvider([down], name="down")
+    working = MockProvider([make_quote("up", 10.0, block_number=120)], name="up")
+    tracker = (
+        GasTracker("http://localhost", "ethereum", provider=AggregateProvider([failing, working]))
+        .with_circuit_breaker(failure_threshold=2)
+        .with_cache(ttl=60)
+    )
+    assert tracker.status()["last_success_at"] is None
+
+    asyncio.run(tracker.current(None))
+    asyncio.run(tracker.force_refresh(None))
+    asyncio.run(tracker.current(None))
+    status = tracker.status()
+
+    assert status["block_number"] == 120
+    assert status["last_success_at"] is not None
+    assert status["cache_hit_rate"] == pytest.approx(1 / 3)
+    assert status["providers"] == [
+        {"provider": "down", "consecutive_failures": 2, "circuit_state": "open"},
+        {"provider": "up", "consecutive_failures": 0, "circuit_state": "closed"},
+    ]
+    assert failing.calls == 2 and working.calls == 2
+
+
+class FastBlocksProvider(GasProvider):
+    """Provider pushing `count` blocks `spacing` seconds apart, like a sub-second L2."""
+
+    name = "fast-blocks"
+
+    def __init__(self, count, spacing):
+        self.count = count
+        self.spacing = spacing
+
+    async def fetch(self, session):
+        raise AssertionError("fetch() should not be called")
+
+    async def watch_blocks(self, session):
+        for number in range(1, self.count + 1):
+            await asyncio.sleep(self.spacing)
+            yield make_quote(self.name, 10.0, block_number=number)
+
+
+def test_coalesced_block_stream_throttles_and_keeps_the_last_block():
+    tracker = GasTracker("http://localhost", "ethereum", provider=FastBlocksProvider(40, 0.005))
+
+    async def collect():
+        loop = asyncio.get_running_loop()
+        received = []
+        async for quote in tracker.watch_blocks_coalesced(None, min_interval=0.05):
+            received.append((loop.time(), quote["block_number"]))
+        return received
+
+    received = asyncio.run(asyncio.wait_for(collect(), timeout=5.0))
+    blocks = [block for _, block in received]
+
+    assert len(blocks) < 10
+    assert blocks == sorted(set(blocks))
+    assert blocks[-1] == 40
+    assert all(b - a >= 0.045 for (a, _), (b, _) in zip(received, received[1:]))
+
+
+def test_target_blocks_map_to_interpolated_percentiles():
+    assert [percentile_for_target_blocks(n) for n in (1, 2, 3, 4, 10, 15, 20)] == [90, 75, 60, 50, 25, 17.5, 10]
+    for blocks in (0, 21):
+        with pytest.raises(ConfigError):
+            percentile_for_target_blocks(blocks)
+
+    tracker = RecordingFeeHistoryTracker()
+    recommendation = asyncio.run(tracker.fee_for_target_blocks(None, 1))
+
+    assert tracker.calls == [("eth_feeHistory", ["0x14", "latest", [90]])]
+    assert recommendation["block_number"] == 102
+
+
+def test_estimate_deployment_prices_creation_gas_at_the_tier():
+    tracker = GasTracker("http://localhost", "ethereum")
+    quote = make_quote("node", 10.0, fast=20.0)
+    initcode = b"\x60\x80\x60\x40" + bytes(28)
+
+    cost = tracker.estimate_deployment(quote, initcode, tier="fast", eth_price_usd=2000.0)
+
+    assert cost["gas_limit"] == 59578
+    assert cost["initcode_size"] == 32
+    assert cost["cost_wei"] == 20 * 10 ** 9 * 59578
+    assert cost["cost_usd"] == round(20e-9 * 59578 * 2000, 2)
+
+    with pytest.raises(ConfigError, match="EIP-3860"):
+        tracker.estimate_deployment(quote, bytes(49153))
+    with pytest.raises(ConfigError, match="Unknown tier"):
+        tracker.estimate_deployment(quote, initcode, tier="urgent")
+
+
+def test_usd_threshold_alerts_on_downward_crossings_with_hysteresis():
+    # A 150k gas swap at $2000: $0.30 per gwei, so $3 is 10 gwei and the 10% re-arm level 11 gwei
+    provider = MockProvider([12.0, 9.0, 10.5, 9.5, 11.5, 8.0])
+    tracker = GasTracker("http://localhost", "ethereum", provider=provider, price_feed=StaticPriceFeed(2000.0))
+    alerts = []
+
+    async def run():
+        watching = asyncio.ensure_future(
+            tracker.on_usd_threshold(None, "swap", below_usd=3.0, callback=alerts.append, interval=0)
+        )
+        while len(alerts) < 2:
+            await asyncio.sleep(0)
+        watching.cancel()
+        await asyncio.wait({watching})
+
+    asyncio.run(run())
+
+    assert [alert["gas_price_gwei"] for alert in alerts] == [9.0, 8.0]
+    assert alerts[0]["cost_usd"] == 2.7
+    assert alerts[0]["below_usd"] == 3.0
+    assert alerts[0]["token_price_usd"] == 2000.0
+    assert provider.calls == 6
+
+
+def test_usd_threshold_needs_a_price_feed_at_setup():
+    no_feed = GasTracker("http://localhost", None)
+    with pytest.raises(ConfigError, match="price feed"):
+        no_feed.on_usd_threshold(None, "swap", below_usd=2.0, callback=print)
+
+    tracker = GasTracker("http://localhost", "ethereum", price_feed=StaticPriceFeed(2000.0))
+    with pytest.raises(ValueError, match="Unknown transaction type"):
+        tracker.usd_cost_alerts(None, "teleport", below_usd=2.0)
+    with pytest.raises(ConfigError):
+        tracker.usd_cost_alerts(None, "swap", below_usd=2.0, rearm_usd=1.0)
+
+
+class SessionRecordingProvider(GasProvider):
+    def __init__(self, name):
+        self.name = name
+        self.sessions = []
+
+    async def fetch(self, session):
+        self.sessions.append(session)
+        return make_quote(self.name, 10.0)
+
+
+class FakeSession:
+    def __init__(self):
+        self.closed = False
+
+    async def close(self):
+        self.closed = True
+
+
+def test_providers_and_clones_share_one_session():
+    first, second = SessionRecordingProvider("first"), SessionRecordingProvider("second")
+    tracker = GasTracker("http://localhost", "ethereum", provider=AggregateProvider([first, second]))
+    clone = tracker.clone()
+
+    async def run():
+        await tracker.current(tracker.http_session())
+        await clone.current(clone.http_session())
+        shared = tracker.http_session()
+        assert clone.http_session() is shared
+        await clone.close_session()
+        assert tracker.http_session() is not shared  # a closed session is replaced
+        await tracker.close_session()
+        return shared
+
+    shared = asyncio.run(run())
+    assert first.sessions == second.sessions == [shared, shared]
+
+
+def test_injected_session_stays_open():
+    session = FakeSession()
+    tracker = GasTracker("http://localhost", "ethereum").with_session(session)
+
+    asyncio.run(tracker.clone().close_session())
+    assert tracker.clone().http_session() is session
+    assert not session.closed
+
+
+def test_closing_the_session_cancels_cached_fetches_in_flight():
+    tracker = GasTracker("http://localhost", "ethereum", provider=MockProvider([Delay(10, 1.0)])).with_cache(ttl=12)
+
+    async def run():
+        caller = asyncio.ensure_future(tracker.current(tracker.http_session()))
+        await asyncio.sleep(0.01)
+        await tracker.close_session()
+        await asyncio.wait({caller})
+        return caller
+
+    assert asyncio.run(asyncio.wait_for(run(), timeout=1.0)).cancelled()
+
+
+def test_watch_tasks_are_named_and_end_with_the_handle():
+    tracker = GasTracker("http://localhost", "ethereum", provider=FastBlocksProvider(1000, 0.005))
+
+    def ethgas_tasks():
+        return {t.get_name() for t in asyncio.all_tasks() if t.get_name().startswith("ethgas ")}
+
+    async def run():
+        handle = tracker.watch_blocks_coalesced(None, min_interval=0.01)
+        await handle.__anext__()
+        step = asyncio.ensure_future(handle.__anext__())
+        await asyncio.sleep(0)
+        running = ethgas_tasks()
+        await handle.stop()
+        await asyncio.wait({step})
+        assert isinstance(step.exception(), StopAsyncIteration)
+        return running, ethgas_tasks()
+
+    running, left = asyncio.run(run())
+    assert "ethgas watch_blocks_coalesced Ethereum step" in running
+    assert "ethgas watch_blocks_coalesced Ethereum receiver" in running
+    assert left == set()
+
+
+def test_watch_multi_keeps_chains_independent():
+    def tracker(name, provider):
+        return GasTracker("http://localhost", "ethereum", name, provider=provider)
+
+    chains = [
+        tracker("Fast", MockProvider(responder=float)),
+        tracker("Broken", MockProvider([HttpError("HTTP 503", status=503)])),
+        tracker("Stalled", MockProvider([Delay(30, 5.0)])),
+    ]
+
+    async def run():
+        handle = GasTracker.watch_multi(None, chains, interval=0.001)
+        items = [await handle.__anext__() for _ in range(6)]
+        await asyncio.sleep(0.05)  # the consumer falls behind
+        latest = await handle.__anext__()
+        while latest[0] != "Fast":
+            latest = await handle.__anext__()
+        await handle.stop()
+        left = {t.get_name() for t in asyncio.all_tasks() if t.get_name().startswith("ethgas ")}
+        return items, latest, left
+
+    items, latest, left = asyncio.run(asyncio.wait_for(run(), 5))
+    tags = {tag for tag, _ in items}
+    assert tags == {"Fast", "Broken"}
+    assert all("HTTP 503" in item["error"] for tag, item in items if tag == "Broken")
+    # Chains take turns, and only the newest update of a busy stretch is kept
+    assert [tag for tag, _ in items[:2]] in (["Fast", "Broken"], ["Broken", "Fast"])
+    fast = [item["gas_price"] for tag, item in items if tag == "Fast"]
+    assert latest[1]["gas_price"] > fast[-1] + 1
+    assert left == set()
+
+    with pytest.raises(ConfigError, match="more than once"):
+        GasTracker.watch_multi(None, [chains[0], chains[0]])
+    with pytest.raises(ConfigError, match="at least one chain"):
+        GasTracker.watch_multi(None, [])
+
+
+def test_compute_tiers_from_a_hand_written_history():
+    gwei = 10 ** 9
+    history = {
+        "oldestBlock": hex(100),
+        "baseFeePerGas": [hex(10 * gwei), hex(11 * gwei), hex(12 * gwei)],
+        "gasUsedRatio": [0.9, 0.8],
+        "reward": [[hex(1 * gwei), hex(3 * gwei)], [hex(3 * gwei), hex(5 * gwei)]],
+    }
+
+    estimate = compute_tiers(history, FeeHistoryConfig(block_count=2, reward_percentiles=[25, 75]), network="Testnet")
+
+    assert estimate["network"] == "Testnet"
+    assert estimate["block_number"] == 101
+    assert estimate["base_fee_per_gas"] == 12 * gwei
+    assert estimate["base_fee_history"] == [10 * gwei, 11 * gwei, 12 * gwei]
+    assert list(estimate["tiers"]) == ["p25", "p75"]
+    assert estimate["tiers"]["p25"] == {
+        "max_priority_fee_per_gas": 2 * gwei,
+        "max_fee_per_gas": 26 * gwei,
+        "percentile": 25,
+        "label": "p25",
+    }
+    assert estimate["tiers"]["p75"]["max_priority_fee_per_gas"] == 4 * gwei
+
+
+def test_compute_tiers_matches_estimate_eip1559_without_adjustments():
+    tracker = FeeHistoryTracker("http://localhost", "ethereum")
+    history = asyncio.run(tracker.rpc_result(None, "eth_feeHistory", []))
+
+    assert compute_tiers(history) == asyncio.run(tracker.estimate_eip1559(None))
+    with pytest.raises(DeserializeError, match="reward"):
+        compute_tiers(dict(history, reward=[["0x1"]]))
+
+
+def test_cheapest_hours_reads_the_network_history():
+    history = InMemoryHistory()
+    yesterday = (datetime.now(timezone.utc) - timedelta(days=1)).replace(minute=0, second=0, microsecond=0)
+    for hour, fee in ((6, 10.0), (6, 12.0), (18, 30.0)):
+        history.record(dict(make_quote("node", fee, base_fee=fee), network="Ethereum"), timestamp=yesterday.replace(hour=hour))
+    history.record(dict(make_quote("node", 1.0, base_fee=1.0), network="Polygon"), timestamp=yesterday.replace(hour=3))
+    tracker = GasTracker("http://localhost", "ethereum").with_history(history)
+
+    hours = tracker.cheapest_hours(days=2, min_samples=1)
+
+    assert [h["hour"] for h in hours[:2]] == [6, 18]
+    assert hours[0]["median_base_fee"] == 11.0
+    assert not next(h for h in hours if h["hour"] == 3)["sufficient"]
+    with pytest.raises(ConfigError, match="No history store"):
+        GasTracker("http://localhost", "ethereum").cheapest_hours()
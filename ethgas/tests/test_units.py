@@ -0,0 +1,103 @@
+"""Tests for wei/gwei amounts."""
+
+import pytest
+
+from ethgas.errors import ConfigError, DeserializeError
+from ethgas.units import MAX_UINT256, Gwei, Wei, format_eth, parse_fee, parse_hex_quantity, to_jsonable
+
+
+def test_hex_parsing_and_conversion():
+    wei = Wei.from_hex("0x4a817c800")
+
+    assert wei == 20_000_000_000
+    assert wei.to_gwei() == 20.0
+    assert Gwei("0.05").to_wei() == Wei(50_000_000)
+
+
+def test_parse_hex_quantity_edge_cases():
+    assert parse_hex_quantity("0x0") == 0
+    assert parse_hex_quantity("0x") == 0
+    assert parse_hex_quantity("0x00001a") == 26
+    assert parse_hex_quantity("0xabc") == 0xABC
+    assert parse_hex_quantity("0x" + "ff" * 32) == MAX_UINT256
+    assert Wei.from_hex("0x" + "ff" * 32) == MAX_UINT256
+
+    for bad in ("1a", "0x1g", "0x-1", "0x_1", "0x1 ", "0x1" + "0" * 64, None, 26):
+        with pytest.raises(DeserializeError):
+            parse_hex_quantity(bad)
+
+
+def test_units_do_not_mix():
+    with pytest.raises(TypeError):
+        Wei(1) + Gwei(1)
+    with pytest.raises(TypeError):
+        Gwei(1) < Wei(1)
+    with pytest.raises(TypeError):
+        Wei(Gwei(20))
+    assert Wei(1) != Gwei(1)
+
+
+def test_arithmetic_keeps_unit():
+    assert isinstance(Wei(10) + Wei(5), Wei)
+    assert isinstance(Wei(10) * 3, Wei)
+    assert isinstance(sum([Gwei(1.5), Gwei(2.5)]), Gwei)
+    assert Gwei(3) / Gwei(2) == 1.5
+
+
+def test_display_and_serialization():
+    assert str(Wei(21000)) == "21000 wei"
+    assert str(Gwei(12.5)) == "12.5 gwei"
+    assert f"{Gwei(12.345):.2f}" == "12.35"
+    assert to_jsonable({"fee": Wei(10 ** 30), "tiers": [Gwei(0.00001)]}) == {
+        "fee": "1000000000000000000000000000000",
+        "tiers": ["0.00001"],
+    }
+
+
+def test_format_eth_is_exact():
+    assert format_eth(1) == "0.000000000000000001"
+    assert format_eth(10 ** 18) == "1"
+    assert Wei(1_234_500_000_000_000_000).to_ether_str() == "1.2345"
+    assert format_eth(MAX_UINT256) == "115792089237316195423570985008687907853269984665640564039457.584007913129639935"
+    assert format_eth(10 ** 18 + 1) == "1.000000000000000001"  # lost by float: 1.0
+
+    # Half up at the requested precision, trailing zeros dropped
+    assert format_eth(1_234_500_000_000_000_000, 3) == "1.235"
+    assert format_eth(1_995_000_000_000_000_000, 2) == "2"
+    assert format_eth(15 * 10 ** 17, 0) == "2"
+    assert format_eth(4 * 10 ** 11, 6) == "0"
+    assert format_eth(-5 * 10 ** 17) == "-0.5"
+    with pytest.raises(TypeError):
+        format_eth(Gwei(1))
+
+
+def test_parse_fee_formats():
+    gwei = 10 ** 9
+    assert parse_fee("30") == Wei(30 * gwei)  # bare numbers are gwei
+    assert parse_fee("1.5") == Wei(1_500_000_000)
+    assert parse_fee("30gwei") == parse_fee(" 30 GWei ") == Wei(30 * gwei)
+    assert parse_fee("0.00000003eth") == parse_fee("0.00000003 ether") == Wei(30 * gwei)
+    assert parse_fee("21000wei") == Wei(21000)
+    assert parse_fee("3e10") == Wei(30 * gwei)  # too large to mean gwei, so wei
+    assert parse_fee("30000000000") == Wei(30 * gwei)
+    assert parse_fee("2.5e-3gwei") == Wei(2_500_000)
+    assert parse_fee(f"{MAX_UINT256}wei") == MAX_UINT256
+    assert isinstance(parse_fee("0"), Wei)
+
+
+def test_parse_fee_rejects_bad_input():
+    cases = {
+        "-5": "negative",
+        "-5gwei": "negative",
+        "5000000": "Ambiguous",
+        "30 gwie": "Not a fee",
+        "0x10": "Not a fee",
+        "": "Not a fee",
+        "nan": "Not a fee",
+        "1e-10": "whole number of wei",
+        "0.5wei": "whole number of wei",
+        "1e60eth": "exceeds uint256",
+    }
+    for text, message in cases.items():
+        with pytest.raises(ConfigError, match=message):
+            parse_fee(text)
@@ -0,0 +1,73 @@
+"""Tests for lossless quote serialization."""
+
+import asyncio
+import json
+
+import pytest
+
+from ethgas.errors import DeserializeError, SchemaMismatchError
+from ethgas.providers import make_quote
+from ethgas.testing import StaticPriceFeed
+from ethgas.tracker import GasTracker
+from ethgas.units import Gwei, Wei
+from ethgas.wire import WIRE_SCHEMA_VERSION, from_cbor, from_json, to_cbor, to_json
+
+
+class FeeHistoryTracker(GasTracker):
+    async def rpc_result(self, session, method, params):
+        return {"baseFeePerGas": [hex(10 ** 10)] * 2, "reward": [[hex(10 ** 9), hex(2 * 10 ** 9), hex(3 * 10 ** 9)]]}
+
+
+def sample_quote():
+    quote = make_quote("infura", 21.5, base_fee=20.123456789, slow=20.5, fast=25.0, block_number=19000000)
+    quote["network"] = "Ethereum"
+    return quote
+
+
+def test_quote_survives_json_and_cbor_identically():
+    pytest.importorskip("cbor2")
+    quote = sample_quote()
+
+    via_json = from_json(to_json(quote))
+    via_cbor = from_cbor(to_cbor(quote))
+
+    assert via_json == via_cbor == quote
+    assert isinstance(via_cbor["gas_price"], Gwei)
+    assert via_json["fetched_at"] == quote["fetched_at"]
+
+
+def test_snapshot_keeps_wei_exact():
+    pytest.importorskip("cbor2")
+    tracker = FeeHistoryTracker("http://localhost", "ethereum", price_feed=StaticPriceFeed(2000.0))
+    snapshot = asyncio.run(tracker.snapshot(None))
+    snapshot["base_fee_per_gas"] = Wei(2 ** 256 - 1)
+
+    for decoded in (from_json(to_json(snapshot)), from_cbor(to_cbor(snapshot))):
+        assert decoded == snapshot
+        assert isinstance(decoded["costs"]["swap"]["fast"]["cost_wei"], Wei)
+        assert decoded["base_fee_per_gas"] == 2 ** 256 - 1
+
+
+def test_garbage_payload_raises_deserialize_error():
+    with pytest.raises(DeserializeError):
+        from_json("{not json")
+
+
+def test_payloads_of_another_schema_version_are_rejected():
+    payload = json.loads(to_json(sample_quote()))
+    assert payload["schema_version"] == WIRE_SCHEMA_VERSION
+
+    for envelope in (dict(payload, schema_version=WIRE_SCHEMA_VERSION + 1), payload["value"]):
+        with pytest.raises(SchemaMismatchError) as excinfo:
+            from_json(json.dumps(envelope))
+        assert excinfo.value.expected == WIRE_SCHEMA_VERSION
+
+
+def test_serialization_is_byte_stable_across_key_order():
+    pytest.importorskip("cbor2")
+    quote = sample_quote()
+    reordered = dict(reversed(list(quote.items())))
+    reordered["tiers"] = dict(reversed(list(quote["tiers"].items())))
+
+    assert to_json(quote) == to_json(quote) == to_json(reordered)
+    assert to_cbor(quote) == to_cbor(quote) == to_cbor(reordered)
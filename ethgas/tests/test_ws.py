@@ -0,0 +1,124 @@
+"""Tests for the WebSocket newHeads provider."""
+
+import asyncio
+import json
+
+import aiohttp
+
+from ethgas.retry import RetryPolicy
+from ethgas.tracker import GasTracker
+from ethgas.ws import WsProvider
+
+
+class Message:
+    def __init__(self, data=None, type=aiohttp.WSMsgType.TEXT):
+        self.type = type
+        self.data = json.dumps(data)
+
+
+class FakeWs:
+    """Socket that answers requests and pushes scripted headers, then closes."""
+
+    def __init__(self, headers):
+        self.incoming = []
+        self.headers = list(headers)
+
+    async def __aenter__(self):
+        return self
+
+    async def __aexit__(self, *exc):
+        pass
+
+    async def send_json(self, request):
+        result = {"eth_subscribe": "0xsub", "eth_gasPrice": hex(7 * 10 ** 9)}[request["method"]]
+        self.incoming.append({"jsonrpc": "2.0", "id": request["id"], "result": result})
+        if request["method"] == "eth_subscribe":
+            for header in self.headers:
+                self.incoming.append({"method": "eth_subscription", "params": {"subscription": "0xsub", "result": header}})
+
+    async def receive(self):
+        if not self.incoming:
+            return Message(type=aiohttp.WSMsgType.CLOSED)
+        return Message(self.incoming.pop(0))
+
+
+class FakeSession:
+    def __init__(self, *connections):
+        self.connections = list(connections)
+
+    def ws_connect(self, url, **kwargs):
+        return self.connections.pop(0)
+
+
+def test_quotes_follow_new_heads_across_reconnects():
+    session = FakeSession(
+        FakeWs([{"number": "0x1", "baseFeePerGas": hex(10 * 10 ** 9)}]),
+        FakeWs([{"number": "0x2"}]),
+    )
+    provider = WsProvider("ws://localhost", priority_tips={"slow": 1, "standard": 2, "fast": 3},
+                          reconnect=RetryPolicy(base_delay=0))
+
+    async def collect():
+        stream = provider.watch_blocks(session)
+        return [await stream.__anext__() for _ in range(2)]
+
+    first, second = asyncio.run(collect())
+
+    assert first["block_number"] == 1
+    assert first["base_fee"] == 10.0
+    assert first["tiers"] == {"slow": 11.0, "standard": 12.0, "fast": 13.0}
+    # No baseFeePerGas in the header: falls back to eth_gasPrice
+    assert second["block_number"] == 2
+    assert second["base_fee"] is None
+    assert second["gas_price"] == 7.0
+
+
+class OpenWs(FakeWs):
+    """Socket that stays open after its scripted headers and records when it is closed."""
+
+    def __init__(self, headers):
+        super().__init__(headers)
+        self.closed = False
+
+    async def __aexit__(self, *exc):
+        self.closed = True
+
+    async def receive(self):
+        if not self.incoming:
+            await asyncio.Event().wait()
+        return Message(self.incoming.pop(0))
+
+
+def test_dropping_the_stream_closes_the_websocket():
+    ws = OpenWs([{"number": "0x1", "baseFeePerGas": hex(10 * 10 ** 9)}])
+    tracker = GasTracker("http://localhost", "ethereum", provider=WsProvider("ws://localhost"))
+
+    async def drop():
+        stream = tracker.watch_blocks(FakeSession(ws))
+        assert (await stream.__anext__())["block_number"] == 1
+        del stream
+        for _ in range(5):
+            await asyncio.sleep(0)
+
+    asyncio.run(drop())
+    assert ws.closed
+
+
+def test_stop_cancels_a_waiting_coalesced_stream():
+    ws = OpenWs([{"number": "0x1", "baseFeePerGas": hex(10 * 10 ** 9)}])
+    tracker = GasTracker("http://localhost", "ethereum", provider=WsProvider("ws://localhost"))
+
+    async def stop_while_waiting():
+        stream = tracker.watch_blocks_coalesced(FakeSession(ws), min_interval=0)
+        assert (await stream.__anext__())["block_number"] == 1
+        waiting = asyncio.ensure_future(stream.__anext__())
+        await asyncio.sleep(0.01)
+        await stream.stop()
+        assert ws.closed
+        await stream.stop()  # idempotent
+        try:
+            await waiting
+        except StopAsyncIteration:
+            return "stopped"
+
+    assert asyncio.run(stop_while_waiting()) == "stopped"
@@ -0,0 +1,120 @@
+"""
+Timing spans for provider calls, logged through the standard logging module.
+
+Everything is logged under the "ethgas" logger, which has a NullHandler, so
+nothing is printed unless the application configures logging:
+
+    logging.basicConfig()
+    logging.getLogger("ethgas").setLevel(logging.DEBUG)
+
+Span records carry their fields as record.ethgas (a dict with "span",
+"latency_ms" and e.g. "provider", "chain", "block_number") for structured
+log handlers.
+
+traced_fetch also counts each provider's consecutive failures (see
+consecutive_failures), which GasTracker.status reports, and providers read
+responses with read_json so capture_bodies can keep their raw bytes (see
+audit.ObservedProvider).
+
+Background tasks are started with spawn, which names them ("ethgas race
+node", "ethgas watch Ethereum step", ...) so they can be told apart in
+asyncio.all_tasks() dumps and debuggers such as aiomonitor.
+"""
+
+import asyncio
+import json
+import logging
+import time
+import weakref
+from contextlib import contextmanager
+from contextvars import ContextVar
+from typing import Awaitable, Dict, Iterator, List, Optional
+
+logger = logging.getLogger(__name__)
+
+# Failures in a row per provider instance, reset by a successful fetch
+_failures: "weakref.WeakKeyDictionary" = weakref.WeakKeyDictionary()
+
+# Raw response bodies read in the current capture_bodies() block, if any
+_captured: ContextVar[Optional[List[bytes]]] = ContextVar("ethgas_captured_bodies", default=None)
+
+
+def spawn(awaitable: Awaitable, name: str) -> asyncio.Future:
+    """
+    Schedule `awaitable` as a task named "ethgas <name>".
+
+    The caller keeps the returned task and is responsible for awaiting or
+    cancelling it; nothing in the package starts a task it doesn't track.
+    """
+    task = asyncio.ensure_future(awaitable)
+    if isinstance(task, asyncio.Task):  # a Future passed in is returned as is
+        task.set_name(f"ethgas {name}")
+    return task
+
+
+@contextmanager
+def span(name: str, **fields) -> Iterator[Dict]:
+    """
+    Log how long the block took at DEBUG level, with `fields`.
+
+    Yields the fields dict so the block can add values it learns, such as
+    the block number of a fetched quote.
+    """
+    start = time.monotonic()
+    try:
+        yield fields
+    except BaseException as e:
+        fields["error"] = repr(e)
+        raise
+    finally:
+        if logger.isEnabledFor(logging.DEBUG):
+            fields["latency_ms"] = round((time.monotonic() - start) * 1000, 1)
+            details = " ".join(f"{key}={value}" for key, value in fields.items())
+            logger.debug("%s %s", name, details, extra={"ethgas": dict(fields, span=name)})
+
+
+async def traced_fetch(provider, session) -> Dict:
+    """Fetch a quote from `provider` inside a "fetch" span."""
+    with span("fetch", provider=provider.name) as fields:
+        try:
+            quote = await provider.fetch(session)
+        except Exception:
+            _failures[provider] = _failures.get(provider, 0) + 1
+            raise
+        _failures[provider] = 0
+        fields["block_number"] = quote.get("block_number")
+        return quote
+
+
+def consecutive_failures(provider) -> int:
+    """Get how many fetches in a row through traced_fetch `provider` has failed."""
+    return _failures.get(provider, 0)
+
+
+@contextmanager
+def capture_bodies() -> Iterator[List[bytes]]:
+    """
+    Collect the raw body of every response read with read_json in the block.
+
+    Tasks started inside the block (e.g. an AggregateProvider's members)
+    add to the same list. A nested block's bodies also count for the outer one.
+    """
+    outer = _captured.get()
+    bodies: List[bytes] = []
+    token = _captured.set(bodies)
+    try:
+        yield bodies
+    finally:
+        _captured.reset(token)
+        if outer is not None:
+            outer.extend(bodies)
+
+
+async def read_json(response):
+    """Decode an aiohttp response's JSON body, keeping the raw bytes inside capture_bodies()."""
+    bodies = _captured.get()
+    if bodies is None:
+        return await response.json()
+    body = await response.read()
+    bodies.append(body)
+    return json.loads(body)
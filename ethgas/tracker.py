@@ -1,47 +1,2285 @@
 """Core gas tracking functionality."""
 
+import asyncio
+import collections
+import copy
+import logging
+import math
+import warnings
 import aiohttp
-from typing import Dict, Optional
+from datetime import datetime, timedelta, timezone
+from fractions import Fraction
+from typing import AsyncIterator, Awaitable, Callable, Dict, List, Optional, Sequence, Set, Tuple, Union
 
-COINGECKO_API = "https://api.coingecko.com/api/v3/simple/price"
+from .alerts import GasAlerts
+from .audit import ObservedProvider, ResponseObserver
+from .cache import PersistentQuoteCache, QuoteCache
+from .circuit import CircuitBreakerProvider
+from .clock import SYSTEM_CLOCK, Clock
+from .config import Config
+from .core import (
+    DEFAULT_REPLACEMENT_BUMP_PCT,
+    FEE_TIERS,
+    HISTOGRAM_PERCENTILES,
+    STATIC_PRIORITY_FEE,
+    FeeRounding,
+    PercentileMethod,
+    RecencyWeight,
+    adaptive_headroom,
+    apply_base_fee_multiplier,
+    apply_priority_floor,
+    blend_priority_fees,
+    blob_fee_from_history,
+    bump_fee,
+    congestion_from_history,
+    cap_priority_fee,
+    decayed_mean,
+    deployment_gas,
+    fee_history_from_block,
+    fee_history_recommendation,
+    fee_history_tiers,
+    fees_at_base_fee,
+    hybrid_mempool_weight,
+    percentile_for_target_blocks,
+    percentile_method_param,
+    priority_fee_histogram,
+    tx_fee_fields,
+)
+from .errors import (
+    METHOD_NOT_FOUND,
+    ConfigError,
+    DeserializeError,
+    EmptyFeeHistoryError,
+    EstimationRevertedError,
+    GasError,
+    GasTimeoutError,
+    HistoricalDataUnavailableError,
+    HttpError,
+    RpcError,
+    UnknownProviderError,
+    history_unavailable,
+    rejects_block_tag,
+    revert_reason,
+    translate_errors,
+)
+from .history import HistoryStore, InMemoryHistory
+from .l2 import (
+    ARB_NODE_INTERFACE,
+    OP_GAS_PRICE_ORACLE,
+    OP_L1_BASE_FEE_SELECTOR,
+    ZERO_ADDRESS,
+    encode_l1_component_call,
+    encode_op_l1_fee_call,
+)
+from .mempool import (
+    confidence_for_blocks,
+    fetch_blocknative,
+    inclusion_priority_fee,
+    mean_fill_ratio,
+    pending_priority_fees,
+    pick_blocknative_fee,
+)
+from .models import (
+    BatchCost,
+    BlobFee,
+    BudgetDecision,
+    Chain,
+    DeploymentCost,
+    Eip1559Estimate,
+    FeeHistory,
+    FeeRecommendation,
+    Histogram,
+    HourStat,
+    GasQuote,
+    Overpayment,
+    PriceQuote,
+    ProviderCounters,
+    ProviderHealth,
+    TIER_LABELS,
+    TierEstimate,
+    TierEstimates,
+    TrackerStatus,
+    TransactionRequest,
+    TxFeeFields,
+)
+from .networks import NETWORKS, TX_TYPES
+from .prices import DEFAULT_MAX_PRICE_DEVIATION_PCT, CoinGeckoPriceFeed, MedianPriceFeed, PriceFeed
+from .providers import (
+    DEFAULT_BLOCK_TAG,
+    DEFAULT_FREEZE_AFTER,
+    DEFAULT_MAX_LAG_BLOCKS,
+    DEFAULT_REQUEST_TIMEOUT,
+    AggregateProvider,
+    BlockTag,
+    FeeEncoding,
+    FreezeDetector,
+    GasProvider,
+    JsonRpcProvider,
+    PricingModel,
+    RaceProvider,
+    RpcCall,
+    batch_payload,
+    block_tag_param,
+    check_health,
+    check_response_id,
+    fee_encoding_param,
+    make_quote,
+    make_session,
+    match_batch_responses,
+    normalize_fees,
+    operation_deadline,
+    response_result,
+    rpc_request,
+)
+from .ratelimit import RateLimitedProvider, RateLimiter
+from .retry import RetryPolicy, RetryingProvider
+from .receipts import overpayment
+from .sanity import MAX_BASE_FEE_CHANGE, BaseFeeCheckProvider
+from .strategy import STRATEGY_PROFILES, Strategy, strategy_param
+from .stats import MIN_HOUR_SAMPLES, GasRegime, GasStats
+from .tracing import consecutive_failures, span, spawn, traced_fetch
+from .units import Gwei, Wei, parse_hex_quantity
+from .watch import WatchHandle, close_stream
+
+logger = logging.getLogger(__name__)
+
+# Nodes cap eth_feeHistory at 1024 blocks per call
+MAX_FEE_HISTORY_BLOCKS = 1024
+
+# How far (percent) above its threshold a USD cost alert re-arms by default
+USD_ALERT_REARM_PCT = 10.0
+
+# Updates kept per chain while a watch_multi consumer is busy; older ones are dropped
+WATCH_MULTI_BUFFER = 1
+
+
+class FeeHistoryConfig:
+    """
+    Lookback window and reward percentiles passed to eth_feeHistory.
+
+    Three percentiles map to the slow/standard/fast tiers; any other number
+    of percentiles gives one tier per percentile, named like "p25".
+
+    Each tier's priority fee is the average of the blocks' rewards at its
+    percentile. With a `recency_weight` of "linear" or "exponential" the
+    newest blocks count more (see core.RecencyWeight), so the estimate
+    follows a fee jump within a few blocks instead of lagging by half
+    the window.
+
+    The node ranks the rewards itself, so `percentile_method` only applies
+    where the history is approximated from one block on nodes without
+    eth_feeHistory (see core.fee_history_from_block).
+    """
+
+    def __init__(
+        self,
+        block_count: int = 20,
+        reward_percentiles: Optional[List[float]] = None,
+        recency_weight: Union[RecencyWeight, str] = RecencyWeight.NONE,
+        percentile_method: Union[PercentileMethod, str] = PercentileMethod.LINEAR,
+    ):
+        """
+        Initialize fee history settings.
+
+        Args:
+            block_count: Number of recent blocks to sample (1-1024)
+            reward_percentiles: Ascending percentiles in 0-100 (default: 10, 50, 90)
+            recency_weight: How much more recent blocks count in the
+                averages: "none" (the default), "linear" or "exponential"
+            percentile_method: How percentiles between samples are picked:
+                "linear" (the default), "nearest-rank", "lower" or "higher"
+
+        Raises:
+            ConfigError: If the block count, percentiles, weighting or method are invalid
+        """
+        percentiles = list(reward_percentiles) if reward_percentiles is not None else list(FEE_TIERS.values())
+        if not 1 <= block_count <= MAX_FEE_HISTORY_BLOCKS:
+            raise ConfigError(f"block_count must be between 1 and {MAX_FEE_HISTORY_BLOCKS}")
+        if not percentiles:
+            raise ConfigError("At least one reward percentile is required")
+        if any(not 0 <= p <= 100 for p in percentiles):
+            raise ConfigError("Reward percentiles must be within 0-100")
+        if any(a >= b for a, b in zip(percentiles, percentiles[1:])):
+            raise ConfigError("Reward percentiles must be sorted ascending without duplicates")
+
+        try:
+            recency_weight = RecencyWeight(recency_weight)
+        except ValueError:
+            choices = ", ".join(w.value for w in RecencyWeight)
+            raise ConfigError(f"recency_weight must be one of {choices}, got {recency_weight!r}") from None
+
+        self.block_count = block_count
+        self.reward_percentiles = percentiles
+        self.recency_weight = recency_weight
+        self.percentile_method = percentile_method_param(percentile_method)
+
+    def tiers(self) -> Dict[str, float]:
+        """Map tier names to their reward percentile."""
+        if len(self.reward_percentiles) == len(FEE_TIERS):
+            return dict(zip(FEE_TIERS, self.reward_percentiles))
+        return {f"p{p:g}": p for p in self.reward_percentiles}
+
+    def params(self, block_tag: BlockTag = DEFAULT_BLOCK_TAG) -> list:
+        """eth_feeHistory params for the history ending at `block_tag`."""
+        return [hex(self.block_count), block_tag_param(block_tag), self.reward_percentiles]
+
+
+def _tier_estimate(fees: Dict, name: str, percentile: float) -> TierEstimate:
+    return dict(fees, percentile=percentile, label=TIER_LABELS.get(name, name))
+
+
+def compute_tiers(
+    fee_history: FeeHistory, config: Optional[FeeHistoryConfig] = None, network: str = "Ethereum"
+) -> Eip1559Estimate:
+    """
+    Build an Eip1559Estimate from an eth_feeHistory result, without network calls.
+
+    This is the tier math behind GasTracker.estimate_eip1559, before the
+    tracker's fee rounding and priority fee floor and cap, so hand-written
+    histories give exact, repeatable results:
+
+        history = {"oldestBlock": "0x10", "baseFeePerGas": [hex(10 * 10**9), hex(11 * 10**9)],
+                   "gasUsedRatio": [0.6], "reward": [[hex(10**9), hex(2 * 10**9), hex(3 * 10**9)]]}
+        compute_tiers(history)["tiers"]["fast"]["max_priority_fee_per_gas"]  # Wei(3 * 10**9)
+
+    Args:
+        fee_history: eth_feeHistory result (see FeeHistory for the array
+            shapes); each reward row needs one entry per percentile of `config`
+        config: Percentiles and recency weighting the history was requested
+            with (default: FeeHistoryConfig())
+        network: Network name put in the estimate
+
+    Raises:
+        DeserializeError: If the history is malformed or has too few rewards per block
+        EmptyFeeHistoryError: If baseFeePerGas is empty
+    """
+    config = config or FeeHistoryConfig()
+    return _fee_history_estimate(fee_history, config.tiers(), config.recency_weight, network)
+
+
+def _fee_history_estimate(
+    fee_history: Dict,
+    fee_tiers: Dict[str, float],
+    recency_weight: RecencyWeight,
+    network: str,
+    adjust: Optional[Callable[[Dict, Wei, str], Dict]] = None,
+) -> Eip1559Estimate:
+    """compute_tiers, with `adjust(fees, base_fee, tier)` applied to each tier's fees."""
+    estimate = fee_history_tiers(fee_history, fee_tiers, recency_weight)
+    base_fee = estimate["base_fee_per_gas"]
+    estimate["tiers"] = TierEstimates(
+        (tier, _tier_estimate(adjust(fees, base_fee, tier) if adjust else fees, tier, fee_tiers[tier]))
+        for tier, fees in estimate["tiers"].items()
+    )
+    if fee_history.get("degraded"):
+        estimate["degraded"] = True
+    return dict(network=network, **estimate)
+
+
+async def _deliver(alerts: WatchHandle, callback: Callable[[Dict], None]) -> None:
+    """Pass every item of `alerts` to `callback`, stopping the stream when done or cancelled."""
+    try:
+        async for alert in alerts:
+            callback(alert)
+    finally:
+        await alerts.stop()
+
+
+async def _watch_multi(
+    session: aiohttp.ClientSession, trackers: Dict[str, "GasTracker"], interval: float, buffer: int
+) -> AsyncIterator[Tuple[str, Dict]]:
+    """Merge the trackers' watch() streams into (tag, item) pairs, taking turns between chains."""
+    buffers = {tag: collections.deque(maxlen=buffer) for tag in trackers}
+    ready = asyncio.Event()
+    running = len(trackers)
+
+    async def pump(tag: str, tracker: "GasTracker") -> None:
+        nonlocal running
+        handle = tracker.watch(session, interval)
+        try:
+            async for item in handle:
+                if len(buffers[tag]) == buffer:
+                    logger.debug("%s: watch_multi consumer is behind, dropping the oldest update", tag)
+                buffers[tag].append(item)
+                ready.set()
+        except Exception as e:
+            # watch() reports failed fetches itself, so this chain's stream broke; the others carry on
+            buffers[tag].append({"network": tracker.network_name, "error": str(e)})
+        finally:
+            running -= 1
+            ready.set()
+            await handle.stop()
+
+    tags = list(trackers)
+    pumps = [spawn(pump(tag, tracker), f"watch_multi {tag}") for tag, tracker in trackers.items()]
+    turn = 0
+    try:
+        while True:
+            ready.clear()
+            for offset in range(len(tags)):
+                tag = tags[(turn + offset) % len(tags)]
+                if buffers[tag]:
+                    turn = (turn + offset + 1) % len(tags)
+                    yield tag, buffers[tag].popleft()
+                    break
+            else:
+                if not running:
+                    return
+                await ready.wait()
+    finally:
+        for task in pumps:
+            task.cancel()
+        await asyncio.wait(pumps)
 
 
 class GasTracker:
-    """Tracks gas prices for a specific network."""
+    """
+    Tracks gas prices for a specific network.
+
+    A configured tracker is safe to share between concurrent tasks, e.g. the
+    request handlers of a web server. clone() gives each component its own
+    handle cheaply: clones share the provider, cache, rate limiter and price
+    feed, so every clone's current() joins the same single-flight cache.
+    """
 
-    def __init__(self, rpc_url: str, coingecko_id: str, network_name: str = "Ethereum"):
+    def __init__(
+        self,
+        rpc_url: str,
+        coingecko_id: str,
+        network_name: str = "Ethereum",
+        provider: Optional[GasProvider] = None,
+        price_feed: Optional[PriceFeed] = None,
+        l1_fee_model: Optional[str] = None,
+        rpc_headers: Optional[Dict[str, str]] = None,
+        chain_id: Optional[int] = None,
+    ):
         self.rpc_url = rpc_url
+        # Extra headers (e.g. Authorization) sent with the tracker's own RPC calls
+        self.rpc_headers = rpc_headers
         self.coingecko_id = coingecko_id
         self.network_name = network_name
+        # "op-stack", "arbitrum" or None for chains without an L1 data fee
+        self.l1_fee_model = l1_fee_model
+        # Compared against eth_chainId on the first quote when set
+        self.chain_id = chain_id
+        self._chain_id_checked = False
+        # Quotes come from the node's eth_gasPrice unless another source is given
+        self.provider = provider or JsonRpcProvider(rpc_url, headers=rpc_headers)
+        self.price_feed = price_feed or CoinGeckoPriceFeed(coingecko_id)
+        self.retry_policy: Optional[RetryPolicy] = None
+        self.rate_limiter: Optional[RateLimiter] = None
+        # Seconds allowed for each of the tracker's own RPC requests
+        self.request_timeout = DEFAULT_REQUEST_TIMEOUT
+        self.deadline: Optional[float] = None
+        self.cache: Optional[QuoteCache] = None
+        self.fee_history = FeeHistoryConfig()
+        # Store every fetched quote is recorded into (see with_history)
+        self.history: Optional[Union[HistoryStore, InMemoryHistory]] = None
+        self.blocknative_api_key: Optional[str] = None
+        self.blocknative_chain_id = 1
+        self.block_tag: BlockTag = DEFAULT_BLOCK_TAG
+        # Encoding of fees in the tracker's own RPC results (see with_fee_encoding)
+        self.fee_encoding = FeeEncoding.HEX_WEI
+        self.fee_rounding = FeeRounding()
+        # Lowest priority fee recommended, in wei (see with_min_priority_fee)
+        self.min_priority_fee: Optional[Wei] = None
+        # Highest priority fee recommended, as a multiple of the base fee (see with_max_priority_ratio)
+        self.max_priority_ratio: Optional[float] = None
+        # The chain's own floor, which with_strategy() scales
+        self.chain_min_priority_fee: Optional[Wei] = None
+        # Multiple of the base fee in max fees; None keeps core.BASE_FEE_MULTIPLIER (see with_base_fee_multiplier)
+        self.base_fee_multiplier: Optional[float] = None
+        # Reward percentile of recommend() (see with_strategy)
+        self.recommend_percentile = FEE_TIERS["standard"]
+        self.strategy: Optional[Strategy] = None
+        # Named tier percentiles for estimate_eip1559; None uses the FeeHistoryConfig's
+        self.fee_tiers: Optional[Dict[str, float]] = None
+        # Cleared once the endpoint rejects the "pending" tag
+        self._supports_pending = True
+        # Cleared once the endpoint turns out not to implement eth_feeHistory
+        # or eth_maxPriorityFeePerGas (see _fee_history)
+        self._supports_fee_history = True
+        self._supports_priority_suggestion = True
+        # Time source of status()'s last_success_at (see with_clock)
+        self.clock: Clock = SYSTEM_CLOCK
+        # Time and block of the last successful fetch, shared with clones
+        self._last_success: Dict = {"at": None, "block_number": None}
+        # HTTP session shared with clones, and whether the tracker created it (see http_session)
+        self._session: Dict = {"session": None, "owned": False}
+
+    @classmethod
+    def for_network(cls, network: Union[str, Chain], rpc_url: Optional[str] = None, **kwargs) -> "GasTracker":
+        """
+        Create a tracker from a NETWORKS ID or a Chain dict, keeping its L2 fee model.
+
+        The first quote checks the endpoint's eth_chainId against the chain's
+        and warns on a mismatch. Chains marked "eip1559": False are priced as
+        legacy chains without probing for a base fee, and a chain's
+        "min_priority_fee_gwei" and "max_priority_ratio" become the
+        tracker's priority fee floor and cap.
+        """
+        if isinstance(network, str):
+            network = NETWORKS[network]
+        tracker = cls(
+            rpc_url or network["rpc"],
+            network["coingecko_id"],
+            network["name"],
+            l1_fee_model=network.get("l1_fee_model"),
+            chain_id=network["chain_id"],
+            **kwargs,
+        )
+        if network.get("eip1559") is not None and isinstance(tracker.provider, JsonRpcProvider):
+            tracker.provider.pricing_model = PricingModel.EIP1559 if network["eip1559"] else PricingModel.LEGACY
+        if network.get("min_priority_fee_gwei") is not None:
+            tracker.with_min_priority_fee(network["min_priority_fee_gwei"])
+            tracker.chain_min_priority_fee = tracker.min_priority_fee
+        if network.get("max_priority_ratio") is not None:
+            tracker.with_max_priority_ratio(network["max_priority_ratio"])
+        return tracker
+
+    def clone(self) -> "GasTracker":
+        """
+        Get a handle sharing this tracker's provider, cache, rate limiter and price feed.
+
+        Only references are copied, so cloning is cheap. Builder calls on a
+        clone replace its own attributes without affecting the original.
+        """
+        return copy.copy(self)
+
+    def with_session(self, session: aiohttp.ClientSession) -> "GasTracker":
+        """
+        Use `session` as this tracker's http_session(), e.g. an application-wide one.
+
+        The caller keeps ownership: close_session() leaves it open.
+        """
+        self._session = {"session": session, "owned": False}
+        return self
+
+    def http_session(self) -> aiohttp.ClientSession:
+        """
+        Get the session to pass to this tracker's calls, shared with its clones.
+
+        Without with_session() it is created by make_session() on first use,
+        inside the running event loop, so every provider and clone polled
+        through it reuses the same pooled connections.
+        """
+        if self._session["session"] is None:
+            self._session.update(session=make_session(), owned=True)
+        return self._session["session"]
+
+    async def close_session(self) -> None:
+        """
+        Close the session http_session() created, cancelling the cache's fetches in flight.
+
+        A session given to with_session() stays open, and so do its fetches.
+        """
+        session = self._session["session"]
+        if session is not None and self._session["owned"]:
+            self._session.update(session=None, owned=False)
+            if self.cache:
+                await self.cache.cancel_fetches()
+            await session.close()
+
+    def with_retry(self, policy: RetryPolicy) -> "GasTracker":
+        """Retry transient provider and RPC failures with exponential backoff."""
+        self.retry_policy = policy
+        self.provider = RetryingProvider(self.provider, policy)
+        return self
+
+    def with_clock(self, clock: Clock) -> "GasTracker":
+        """
+        Timestamp successful fetches and expire caches made by with_cache() on `clock`.
+
+        Pass testing.MockClock in tests; give the provider, retry policy and
+        other components their own clock.
+        """
+        self.clock = clock
+        return self
+
+    def with_deadline(self, seconds: float) -> "GasTracker":
+        """
+        Bound current() and force_refresh() to `seconds` in total, retries included.
+
+        Past the deadline they raise GasTimeoutError; an AggregateProvider
+        returns the quotes that arrived before it instead.
+        """
+        if seconds <= 0:
+            raise ConfigError("deadline must be positive")
+        self.deadline = seconds
+        return self
+
+    def with_rate_limit(self, limiter: RateLimiter) -> "GasTracker":
+        """Make provider fetches and the tracker's own RPC calls wait for `limiter` tokens."""
+        self.rate_limiter = limiter
+        self.provider = RateLimitedProvider(self.provider, limiter)
+        return self
+
+    def with_circuit_breaker(self, failure_threshold: int = 5, cooldown: float = 30.0) -> "GasTracker":
+        """
+        Stop querying a provider for `cooldown` seconds after `failure_threshold` failures in a row.
+
+        For a RaceProvider or AggregateProvider each member gets its own
+        breaker, so one dead source is skipped while the others keep serving.
+        """
+        if isinstance(self.provider, (RaceProvider, AggregateProvider)):
+            self.provider = self.provider.replace_members(
+                [CircuitBreakerProvider(p, failure_threshold, cooldown) for p in self.provider.providers]
+            )
+        else:
+            self.provider = CircuitBreakerProvider(self.provider, failure_threshold, cooldown)
+        return self
+
+    def with_base_fee_check(self, strict: bool = False, max_change: float = MAX_BASE_FEE_CHANGE) -> "GasTracker":
+        """
+        Flag quotes whose base fee jumped further than EIP-1559 allows for the blocks elapsed.
+
+        Such quotes get "suspect": True, or with `strict` fail with
+        SuspectQuoteError, so a retry or the other providers take over (see
+        sanity.BaseFeeCheckProvider). As with with_circuit_breaker(), each
+        member of a RaceProvider or AggregateProvider is checked on its own.
+        """
+        if isinstance(self.provider, (RaceProvider, AggregateProvider)):
+            self.provider = self.provider.replace_members(
+                [BaseFeeCheckProvider(p, strict, max_change) for p in self.provider.providers]
+            )
+        else:
+            self.provider = BaseFeeCheckProvider(self.provider, strict, max_change)
+        return self
+
+    def with_freeze_detection(self, after: int = DEFAULT_FREEZE_AFTER, exclude: bool = False) -> "GasTracker":
+        """
+        Flag AggregateProvider members that return the same quote `after` times while the others move on.
+
+        A frozen member is listed in the quote's "frozen" list, reported as
+        FROZEN by check_provider_health() and warned about once by watch();
+        with `exclude` its quotes are also left out of the aggregate. See
+        providers.FreezeDetector.
+
+        Raises:
+            ConfigError: If the provider isn't an AggregateProvider or after is below 2
+        """
+        if self._aggregate() is None:
+            raise ConfigError("Freeze detection compares the members of an AggregateProvider")
+        detector = FreezeDetector(after)
+
+        def configure(aggregate: AggregateProvider) -> AggregateProvider:
+            aggregate = aggregate.replace_members(aggregate.providers)
+            aggregate.freeze_detector = detector
+            aggregate.exclude_frozen = exclude
+            return aggregate
+
+        self.provider = self._rebuild_aggregate(self.provider, configure)
+        return self
+
+    def with_price_feeds(
+        self,
+        feeds: List[PriceFeed],
+        weights: Optional[List[float]] = None,
+        max_deviation_pct: float = DEFAULT_MAX_PRICE_DEVIATION_PCT,
+    ) -> "GasTracker":
+        """
+        Price USD costs at the median of several feeds instead of a single one.
+
+        Replaces the configured feed (include CoinGeckoPriceFeed to keep it).
+        Failing feeds and those further than `max_deviation_pct` percent from
+        the median are ignored; see prices.MedianPriceFeed and eth_price().
+
+        Raises:
+            ConfigError: If there are no feeds, a name repeats or a weight isn't positive
+        """
+        self.price_feed = MedianPriceFeed(feeds, weights, max_deviation_pct)
+        return self
+
+    def with_response_observer(self, observer: ResponseObserver) -> "GasTracker":
+        """
+        Report every provider fetch's raw response bodies and quote to `observer`.
+
+        As with with_circuit_breaker(), each member of a RaceProvider or
+        AggregateProvider is observed on its own, so every source's
+        responses are kept apart (see audit.ObservedProvider).
+        """
+        if isinstance(self.provider, (RaceProvider, AggregateProvider)):
+            self.provider = self.provider.replace_members([ObservedProvider(p, observer) for p in self.provider.providers])
+        else:
+            self.provider = ObservedProvider(self.provider, observer)
+        return self
+
+    @classmethod
+    def from_config(cls, config: Config) -> "GasTracker":
+        """Create a tracker with the chain, providers, retry policy and cache from a Config."""
+        tracker = cls.for_network(
+            config.chain.network,
+            rpc_url=config.chain.rpc_url,
+            provider=config.build_provider(),
+            rpc_headers=config.chain.rpc_headers or None,
+        )
+        policy = config.retry_policy()
+        if policy:
+            tracker.with_retry(policy)
+        if config.cache:
+            tracker.with_cache(config.cache.ttl, path=config.cache.path)
+        return tracker
+
+    def with_fee_history(self, config: FeeHistoryConfig) -> "GasTracker":
+        """Use a custom eth_feeHistory window and percentiles in estimate_eip1559."""
+        self.fee_history = config
+        return self
+
+    def with_tier(self, name: str, percentile: float) -> "GasTracker":
+        """
+        Add a named tier priced at a reward percentile, e.g. with_tier("aggressive", 80.0).
+
+        estimate_eip1559() returns every configured tier. Added tiers join
+        the FeeHistoryConfig's tiers (slow/standard/fast by default) unless
+        clear_tiers() was called first; once tiers are added they take the
+        place of the config's percentiles.
+
+        Raises:
+            ConfigError: If the name is taken or the percentile isn't within 0-100
+        """
+        if not isinstance(name, str) or not name:
+            raise ConfigError("Tier name must be a non-empty string")
+        if isinstance(percentile, bool) or not isinstance(percentile, (int, float)) or not 0 <= percentile <= 100:
+            raise ConfigError(f"Percentile of tier '{name}' must be within 0-100, got {percentile!r}")
+        tiers = self.fee_tiers if self.fee_tiers is not None else self.fee_history.tiers()
+        if name in tiers:
+            raise ConfigError(f"Tier '{name}' is already configured")
+        # A new dict, so clones keep their own tiers
+        self.fee_tiers = dict(tiers, **{name: float(percentile)})
+        return self
+
+    def clear_tiers(self) -> "GasTracker":
+        """Remove all tiers, including the defaults, before adding new ones with with_tier()."""
+        self.fee_tiers = {}
+        return self
+
+    def with_fee_rounding(self, rounding: FeeRounding) -> "GasTracker":
+        """Round the fees of recommend(), fee_for_target_blocks() and estimate_eip1559() tiers."""
+        self.fee_rounding = rounding
+        return self
+
+    def with_min_priority_fee(self, gwei: Optional[float]) -> "GasTracker":
+        """
+        Never recommend a priority fee below `gwei` (None removes the floor).
+
+        Quiet blocks can put fee history percentiles near zero, and a tip
+        that low may never be included. Tiers and recommendations from
+        estimate_eip1559(), recommend() and fee_for_target_blocks() below the
+        floor are raised to it and marked with floor_applied=True, as are
+        current() quotes whose tiers tip less than it over the base fee.
+        for_network() sets the chain's "min_priority_fee_gwei" (1 gwei on Ethereum).
+
+        Raises:
+            ConfigError: If the floor is negative or not a number
+        """
+        if gwei is None:
+            self.min_priority_fee = None
+            return self
+        if isinstance(gwei, bool) or not isinstance(gwei, (int, float)) or not 0 <= gwei < math.inf:
+            raise ConfigError(f"min_priority_fee must be a non-negative number of gwei, got {gwei!r}")
+        self.min_priority_fee = Gwei(gwei).to_wei()
+        return self
+
+    def with_base_fee_multiplier(self, multiplier: Optional[float]) -> "GasTracker":
+        """
+        Price max fees at `multiplier` times the base fee plus the priority fee (None restores 2x).
+
+        A lower multiplier caps what a transaction can pay more tightly but
+        leaves it stuck sooner if the base fee climbs; each 1.125 of it
+        covers one more full block. Applies to estimate_eip1559() tiers,
+        recommend(), estimate_at_base_fee() and hybrid_estimate().
+
+        Raises:
+            ConfigError: If the multiplier is below 1 or not a number
+        """
+        if multiplier is not None:
+            # Validated the way it is applied
+            apply_base_fee_multiplier({"max_priority_fee_per_gas": 0}, 0, multiplier)
+        self.base_fee_multiplier = multiplier
+        return self
+
+    def with_strategy(self, strategy: Union[Strategy, str]) -> "GasTracker":
+        """
+        Apply a preset Strategy: tier percentiles, base fee headroom, priority floor and rounding at once.
+
+        See strategy.STRATEGY_PROFILES. The fee history window, weighting
+        and priority cap are kept; tiers added with with_tier() before are
+        replaced. Calls made afterwards (with_tier, with_fee_rounding,
+        with_min_priority_fee, ...) override the preset's settings.
+
+        Raises:
+            ConfigError: If the strategy is unknown
+        """
+        strategy = strategy_param(strategy)
+        profile = STRATEGY_PROFILES[strategy]
+        config = self.fee_history
+        self.fee_history = FeeHistoryConfig(
+            config.block_count, list(profile.percentiles), config.recency_weight, config.percentile_method
+        )
+        self.fee_tiers = None
+        self.recommend_percentile = profile.tiers()["standard"]
+        self.base_fee_multiplier = profile.base_fee_multiplier
+        floor = self.chain_min_priority_fee
+        self.min_priority_fee = Wei(math.ceil(floor * Fraction(profile.priority_floor_scale))) if floor is not None else None
+        self.fee_rounding = profile.rounding
+        self.strategy = strategy
+        return self
+
+    def with_max_priority_ratio(self, ratio: Optional[float]) -> "GasTracker":
+        """
+        Never recommend a priority fee above `ratio` times the base fee (None, the default, removes the cap).
+
+        A block of spam or a bad percentile can make fee history tips absurdly
+        high. Tiers and recommendations from estimate_eip1559(), recommend()
+        and fee_for_target_blocks() above the cap are lowered to it, marked
+        with priority_capped=True and logged. The min_priority_fee floor
+        still applies afterwards, so a recommendation always stays landable.
+
+        Raises:
+            ConfigError: If the ratio isn't a positive number
+        """
+        if ratio is None:
+            self.max_priority_ratio = None
+            return self
+        if isinstance(ratio, bool) or not isinstance(ratio, (int, float)) or not 0 < ratio < math.inf:
+            raise ConfigError(f"max_priority_ratio must be a positive number, got {ratio!r}")
+        self.max_priority_ratio = float(ratio)
+        return self
+
+    def with_cache(
+        self, ttl: float = 12.0, cache: Optional[QuoteCache] = None, path: Optional[str] = None
+    ) -> "GasTracker":
+        """
+        Serve quotes younger than `ttl` seconds from a cache (optionally a shared one).
+
+        With `path` the quotes are also kept in that file (see
+        PersistentQuoteCache), so a restarted process reuses them until they expire.
+        """
+        if cache is None:
+            cache = PersistentQuoteCache(path, ttl, self.clock) if path else QuoteCache(ttl, self.clock)
+        self.cache = cache
+        return self
+
+    def with_history(self, store: Union[HistoryStore, InMemoryHistory]) -> "GasTracker":
+        """
+        Record every quote fetched from the provider (not cache hits) into `store`.
+
+        With a buffered HistoryStore, call flush() before the process exits
+        so the last batch isn't lost.
+        """
+        self.history = store
+        return self
+
+    async def flush(self) -> None:
+        """
+        Commit history samples still buffered by the with_history() store.
+
+        Idempotent and safe to call from several tasks at once: the store
+        serializes flushes, and a flush with nothing pending does nothing.
+        """
+        if self.history is not None:
+            self.history.flush()
+
+    def with_block_tag(self, tag: BlockTag) -> "GasTracker":
+        """
+        Read fees at "latest" (the default), "pending" or a specific block number.
+
+        "pending" gives the freshest base fee, since the fee history then
+        ends at the pending block, but some nodes don't support it; if the
+        endpoint rejects it, "latest" is used instead. The tag applies to
+        the tracker's own eth_feeHistory calls and to its JSON-RPC providers.
+        """
+        block_tag_param(tag)
+        self.block_tag = tag
+        self._supports_pending = True
+
+        def tagged(provider: GasProvider) -> GasProvider:
+            # Copies down to the JsonRpcProvider, so clones sharing it keep their tag
+            if isinstance(provider, JsonRpcProvider):
+                member = copy.copy(provider)
+                member.block_tag = tag
+                member.supports_pending = True
+                return member
+            if not hasattr(provider, "provider"):
+                return provider
+            wrapper = copy.copy(provider)
+            wrapper.provider = tagged(provider.provider)
+            return wrapper
+
+        self.provider = self._rebuild_members(self.provider, tagged)
+        return self
+
+    def with_fee_encoding(self, encoding: Union[FeeEncoding, str]) -> "GasTracker":
+        """
+        Read fees from an endpoint that doesn't send them as hex wei (see FeeEncoding).
+
+        Applies to the tracker's own RPC calls and, when the provider is a
+        JsonRpcProvider, to its quotes; give other providers their own
+        fee_encoding. Values are converted to hex wei before any estimate.
+
+        Raises:
+            ConfigError: If the encoding is unknown
+        """
+        self.fee_encoding = fee_encoding_param(encoding)
+        if isinstance(self.provider, JsonRpcProvider):
+            # A copy, so clones sharing the provider keep their encoding
+            self.provider = copy.copy(self.provider)
+            self.provider.fee_encoding = self.fee_encoding
+        return self
+
+    def with_blocknative(self, api_key: str, chain_id: int = 1) -> "GasTracker":
+        """Prefer Blocknative's mempool estimates in estimate_inclusion."""
+        self.blocknative_api_key = api_key
+        self.blocknative_chain_id = chain_id
+        return self
 
     async def eth_call(self, session: aiohttp.ClientSession, payload: dict) -> dict:
         """Make a JSON-RPC call to the network."""
-        async with session.post(self.rpc_url, json=payload, timeout=15) as r:
-            r.raise_for_status()
-            return await r.json()
+        async def call():
+            if self.rate_limiter:
+                await self.rate_limiter.acquire()
+            async def post():
+                async with session.post(
+                    self.rpc_url, json=payload, headers=self.rpc_headers, timeout=self.request_timeout
+                ) as r:
+                    r.raise_for_status()
+                    return await r.json()
+
+            with translate_errors():
+                return await asyncio.wait_for(post(), self.request_timeout)
+
+        if self.retry_policy:
+            return await self.retry_policy.run(call)
+        return await call()
+
+    async def rpc_result(self, session: aiohttp.ClientSession, method: str, params: list):
+        """Make a JSON-RPC call and return its result, raising on RPC errors."""
+        payload = rpc_request(method, params)
+        response = await self.eth_call(session, payload)
+        check_response_id(response, payload)
+        return normalize_fees(method, response_result(response), self.fee_encoding)
+
+    async def rpc_batch(self, session: aiohttp.ClientSession, calls: List[RpcCall]) -> List:
+        """
+        Make several JSON-RPC calls in one HTTP request and return their results in order.
+
+        Responses are matched by id. Nodes that answer a batch with a single
+        object (no batch support) are queried one call at a time instead.
+        """
+        payload = batch_payload(calls)
+        data = await self.eth_call(session, payload)
+        if not isinstance(data, list):
+            return [await self.rpc_result(session, method, params) for method, params in calls]
+        return [
+            normalize_fees(method, response_result(response), self.fee_encoding)
+            for (method, _), response in zip(calls, match_batch_responses(data, payload))
+        ]
+
+    async def current(self, session: aiohttp.ClientSession) -> GasQuote:
+        """Get the latest gas quote from the configured provider (or the cache)."""
+        return await self._quote(session, force=False)
+
+    async def force_refresh(self, session: aiohttp.ClientSession) -> GasQuote:
+        """Get a fresh gas quote, bypassing the cache."""
+        return await self._quote(session, force=True)
+
+    async def current_from(self, session: aiohttp.ClientSession, provider_name: str) -> GasQuote:
+        """
+        Get a fresh quote from one named provider of the configured set.
+
+        The race or aggregate is skipped, as is the cache, but the tracker's
+        retry policy and rate limiter still apply to the fetch. Useful to
+        check what a single endpoint reports.
+
+        Raises:
+            UnknownProviderError: If no configured provider has that name
+        """
+        members = self.provider_members()
+        provider = next((member for member in members if member.name == provider_name), None)
+        if provider is None:
+            raise UnknownProviderError(provider_name, [member.name for member in members])
+        if self.retry_policy:
+            provider = RetryingProvider(provider, self.retry_policy)
+        if self.rate_limiter:
+            provider = RateLimitedProvider(provider, self.rate_limiter)
+        with span("current", chain=self.network_name, provider=provider.name) as fields:
+            quote = await traced_fetch(provider, session)
+            quote["network"] = self.network_name
+            fields["block_number"] = quote.get("block_number")
+            return quote
+
+    def provider_members(self) -> List[GasProvider]:
+        """Get the members of the (possibly wrapped) Race/AggregateProvider, or the provider itself."""
+        provider = self.provider
+        while not isinstance(provider, (RaceProvider, AggregateProvider)) and hasattr(provider, "provider"):
+            provider = provider.provider
+        if isinstance(provider, (RaceProvider, AggregateProvider)):
+            return list(provider.providers)
+        return [self.provider]
+
+    def _aggregate(self) -> Optional[AggregateProvider]:
+        """Get the (possibly wrapped) AggregateProvider, if the tracker uses one."""
+        provider = self.provider
+        while not isinstance(provider, AggregateProvider) and hasattr(provider, "provider"):
+            provider = provider.provider
+        return provider if isinstance(provider, AggregateProvider) else None
+
+    def _rebuild_members(self, provider: GasProvider, rebuild: Callable[[GasProvider], GasProvider]) -> GasProvider:
+        """
+        Copy the wrappers down to the Race/AggregateProvider, with rebuild(member) for each member.
+
+        Without one, the provider itself is rebuilt.
+        """
+        if isinstance(provider, (RaceProvider, AggregateProvider)):
+            return provider.replace_members([rebuild(p) for p in provider.providers])
+        if isinstance(provider, JsonRpcProvider) or not hasattr(provider, "provider"):
+            return rebuild(provider)
+        wrapper = copy.copy(provider)
+        wrapper.provider = self._rebuild_members(provider.provider, rebuild)
+        return wrapper
+
+    def _rebuild_aggregate(
+        self, provider: GasProvider, rebuild: Callable[[AggregateProvider], AggregateProvider]
+    ) -> GasProvider:
+        """Copy the wrappers down to the AggregateProvider, with rebuild(aggregate) in its place."""
+        if isinstance(provider, AggregateProvider):
+            return rebuild(provider)
+        wrapper = copy.copy(provider)
+        wrapper.provider = self._rebuild_aggregate(provider.provider, rebuild)
+        return wrapper
+
+    async def check_provider_health(
+        self, session: aiohttp.ClientSession, max_lag_blocks: int = DEFAULT_MAX_LAG_BLOCKS
+    ) -> List[ProviderHealth]:
+        """
+        Compare the block height of every configured provider (see providers.check_health).
+
+        A lagging node gives estimates for old blocks without any error, so
+        this is worth logging or alerting on in long-running services. With
+        with_freeze_detection(), members repeating one quote are FROZEN.
+        """
+        aggregate = self._aggregate()
+        frozen = aggregate.frozen_members() if aggregate is not None else []
+        return await check_health(self.provider_members(), session, max_lag_blocks, frozen)
+
+    async def _check_chain_id(self, session: aiohttp.ClientSession) -> None:
+        """Warn once if the endpoint serves a different chain than configured."""
+        try:
+            actual = parse_hex_quantity(await self.rpc_result(session, "eth_chainId", []))
+        except GasError:
+            return  # Not conclusive; try again with the next quote
+        self._chain_id_checked = True
+        if actual != self.chain_id:
+            warnings.warn(
+                f"{self.network_name}: endpoint reports chain ID {actual}, expected {self.chain_id}",
+                RuntimeWarning,
+            )
+
+    async def _quote(self, session: aiohttp.ClientSession, force: bool) -> Dict:
+        """Fetch a quote through the cache when one is configured."""
+        with span("current", chain=self.network_name, provider=self.provider.name) as fields:
+            if self.cache:
+                key = (self.network_name, self.provider.name)
+                quote = await self.cache.get_or_fetch(key, lambda: self._fetch(session), force=force)
+            else:
+                quote = await self._fetch(session)
+            quote = self._floor_quote(quote)
+            quote["network"] = self.network_name
+            fields["block_number"] = quote.get("block_number")
+            return quote
+
+    def _floor_quote(self, quote: Dict) -> Dict:
+        """
+        Raise a quote's tiers whose tip over the base fee is below the min_priority_fee floor.
+
+        The tip is the tier's "priority_fees" entry when the provider reports
+        one, else its price minus the base fee; raised tips (and gas_price,
+        with the standard tier) move up to the floor and the quote gets
+        floor_applied=True. Quotes without a base fee are left as they are.
+        """
+        base_fee = quote.get("base_fee")
+        if self.min_priority_fee is None or base_fee is None:
+            return quote
+        floor = self.min_priority_fee.to_gwei()
+        tiers = dict(quote["tiers"])
+        priority_fees = dict(quote.get("priority_fees") or {})
+        for tier, price in quote["tiers"].items():
+            priority = priority_fees.get(tier, price - base_fee)
+            if priority < floor:
+                tiers[tier] = Gwei(price + floor - priority)
+                if tier in priority_fees:
+                    priority_fees[tier] = floor
+        if tiers == quote["tiers"]:
+            return quote
+        floored = dict(quote, tiers=tiers, floor_applied=True)
+        if "standard" in tiers:
+            floored["gas_price"] = Gwei(quote["gas_price"] + tiers["standard"] - quote["tiers"]["standard"])
+        if priority_fees:
+            floored["priority_fees"] = priority_fees
+        return floored
+
+    async def _fetch(self, session: aiohttp.ClientSession) -> Dict:
+        """Check the chain ID if still due and fetch from the provider, both within the deadline if one is set."""
+        async def fetch() -> Dict:
+            if self.chain_id is not None and not self._chain_id_checked:
+                await self._check_chain_id(session)
+            return await traced_fetch(self.provider, session)
+
+        if self.deadline is None:
+            quote = await fetch()
+        else:
+            with operation_deadline(self.deadline):
+                try:
+                    quote = await asyncio.wait_for(fetch(), self.deadline)
+                except asyncio.TimeoutError:
+                    raise GasTimeoutError(f"No quote from {self.provider.name} within {self.deadline}s") from None
+        self._last_success.update(at=self.clock.now(), block_number=quote.get("block_number"))
+        if self.history is not None:
+            self.history.record(dict(quote, network=self.network_name))
+        return quote
+
+    def status(self) -> TrackerStatus:
+        """
+        Summarize the tracker's health from internal counters, without any network calls.
+
+        Reports when the last fetch succeeded and its block number, the
+        failures in a row and circuit breaker state of every provider, and
+        the cache hit rate. Cheap enough for a readiness probe to call on
+        every request; units.to_jsonable makes it JSON-serializable apart
+        from the datetime.
+        """
+        providers: List[ProviderCounters] = []
+        for member in self.provider_members():
+            breaker = member
+            while not isinstance(breaker, CircuitBreakerProvider) and hasattr(breaker, "provider"):
+                breaker = breaker.provider
+            providers.append({
+                "provider": member.name,
+                "consecutive_failures": consecutive_failures(member),
+                "circuit_state": breaker.state.value if isinstance(breaker, CircuitBreakerProvider) else None,
+            })
+        return {
+            "network": self.network_name,
+            "last_success_at": self._last_success["at"],
+            "block_number": self._last_success["block_number"],
+            "cache_hit_rate": self.cache.hit_rate if self.cache else None,
+            "providers": providers,
+        }
+
+    def watch(
+        self, session: aiohttp.ClientSession, interval: float = 12.0, stuck_after: float = 60.0
+    ) -> WatchHandle:
+        """
+        Stream gas quotes from the provider every `interval` seconds.
+
+        A failed fetch yields {"network": ..., "error": "..."} and the stream
+        carries on. Quotes are only fetched when the consumer asks for the next
+        one, so a slow consumer never accumulates background requests.
+
+        If the block number stops advancing for `stuck_after` seconds, a
+        {"network": ..., "warning": "...", "block_number": ...} item is yielded
+        once before the quote, since the RPC endpoint is probably stuck.
+        Likewise, with with_freeze_detection(), a {"network": ..., "warning":
+        "...", "provider": ...} item is yielded when a member becomes frozen.
+
+        Args:
+            session: HTTP session used for provider calls
+            interval: Seconds between the start of consecutive fetches
+            stuck_after: Seconds on the same block before warning
+
+        Returns:
+            WatchHandle yielding gas quote dictionaries (or error and warning
+            dictionaries); stop() it, or drop it, to end the stream
+        """
+        return WatchHandle(self._watch(session, interval, stuck_after), f"watch {self.network_name}")
+
+    async def _watch(self, session: aiohttp.ClientSession, interval: float, stuck_after: float) -> AsyncIterator[Dict]:
+        loop = asyncio.get_running_loop()
+        last_block = None
+        block_seen_at = 0.0
+        warned = False
+        frozen: Set[str] = set()
+        while True:
+            started = loop.time()
+            try:
+                quote = await self.current(session)
+            except Exception as e:
+                yield {"network": self.network_name, "error": str(e)}
+            else:
+                block = quote.get("block_number")
+                if block is None or block != last_block:
+                    last_block, block_seen_at, warned = block, started, False
+                elif not warned and started - block_seen_at >= stuck_after:
+                    warned = True
+                    yield {
+                        "network": self.network_name,
+                        "warning": f"No new block for {started - block_seen_at:.0f}s, RPC may be stuck",
+                        "block_number": block,
+                    }
+                now_frozen = set(quote.get("frozen") or ())
+                for name in sorted(now_frozen - frozen):
+                    yield {
+                        "network": self.network_name,
+                        "warning": f"{name} keeps returning the same quote, looks frozen",
+                        "provider": name,
+                    }
+                frozen = now_frozen
+                yield quote
+
+            await asyncio.sleep(max(0.0, interval - (loop.time() - started)))
+
+    @classmethod
+    def watch_multi(
+        cls,
+        session: aiohttp.ClientSession,
+        chains: Sequence[Union[str, Chain, "GasTracker"]],
+        interval: float = 12.0,
+        buffer: int = WATCH_MULTI_BUFFER,
+        **kwargs,
+    ) -> WatchHandle:
+        """
+        Watch several chains at once, merged into one stream of (chain, item) pairs.
+
+        Each chain is polled with watch() in its own task, so a slow or
+        failing chain never holds up the others: its failed fetches arrive
+        as its own error items, and if its stream breaks altogether, one last
+        error item ends just that chain. While the consumer is busy, each
+        chain keeps its newest `buffer` updates and drops older ones, and
+        chains with updates waiting take turns.
+
+        Args:
+            session: HTTP session shared by every chain's calls
+            chains: NETWORKS IDs, Chain dicts (both built with for_network and
+                `kwargs`) or configured trackers
+            interval: Seconds between each chain's fetches
+            buffer: Updates kept per chain for a busy consumer
+
+        Returns:
+            WatchHandle yielding (chain, quote) tuples, with watch()'s error and
+            warning dictionaries in place of quotes; the chain is the ID as
+            given, or the network name for Chain dicts and trackers. Stopping
+            it stops every chain's stream.
+
+        Raises:
+            ConfigError: If no chains are given, one is given twice or buffer is below 1
+        """
+        if buffer < 1:
+            raise ConfigError("buffer must be at least 1")
+        trackers: Dict[str, GasTracker] = {}
+        for chain in chains:
+            if isinstance(chain, GasTracker):
+                tag, tracker = chain.network_name, chain
+            else:
+                tag = chain if isinstance(chain, str) else chain["name"]
+                tracker = cls.for_network(chain, **kwargs)
+            if tag in trackers:
+                raise ConfigError(f"Chain {tag!r} is given more than once")
+            trackers[tag] = tracker
+        if not trackers:
+            raise ConfigError("watch_multi needs at least one chain")
+        return WatchHandle(_watch_multi(session, trackers, interval, buffer), f"watch_multi {', '.join(trackers)}")
+
+    def watch_blocks(self, session: aiohttp.ClientSession, interval: float = 12.0) -> WatchHandle:
+        """
+        Stream one quote per new block if the provider pushes blocks (WsProvider).
+
+        Other providers fall back to polling with watch() every `interval`
+        seconds. Stopping the returned WatchHandle closes the WebSocket.
+        """
+        return WatchHandle(self._watch_blocks(session, interval), f"watch_blocks {self.network_name}")
+
+    async def _watch_blocks(self, session: aiohttp.ClientSession, interval: float) -> AsyncIterator[Dict]:
+        watch_blocks = getattr(self.provider, "watch_blocks", None)
+        stream = watch_blocks(session) if watch_blocks is not None else self._watch(session, interval, 60.0)
+        try:
+            async for quote in stream:
+                quote["network"] = self.network_name
+                yield quote
+        finally:
+            # Close the connection now rather than whenever the inner stream is collected
+            await close_stream(stream)
+
+    def watch_blocks_coalesced(
+        self, session: aiohttp.ClientSession, min_interval: float, interval: float = 12.0
+    ) -> WatchHandle:
+        """
+        Stream watch_blocks() quotes, at most one per `min_interval` seconds.
+
+        Every block is still received, but only the newest one is yielded
+        when the interval has passed, so chains with sub-second blocks don't
+        flood slow consumers. A block that arrives during the wait is always
+        yielded once the interval ends, even if nothing follows it. Errors
+        from the underlying stream are raised after the last quote. Stopping
+        the returned WatchHandle cancels the receiving task and waits for it.
+        """
+        return WatchHandle(
+            self._watch_blocks_coalesced(session, min_interval, interval), f"watch_blocks_coalesced {self.network_name}"
+        )
+
+    async def _watch_blocks_coalesced(
+        self, session: aiohttp.ClientSession, min_interval: float, interval: float
+    ) -> AsyncIterator[Dict]:
+        loop = asyncio.get_running_loop()
+        latest: Optional[Dict] = None
+        arrived = asyncio.Event()
+
+        async def receive():
+            nonlocal latest
+            async for quote in self._watch_blocks(session, interval):
+                latest = quote
+                arrived.set()
+
+        receiver = spawn(receive(), f"watch_blocks_coalesced {self.network_name} receiver")
+        waiter = None
+        last_yield = None
+        try:
+            while True:
+                waiter = spawn(arrived.wait(), f"watch_blocks_coalesced {self.network_name} waiter")
+                await asyncio.wait({waiter, receiver}, return_when=asyncio.FIRST_COMPLETED)
+                waiter.cancel()
+                if not arrived.is_set():
+                    receiver.result()  # the stream ended; raise its error, if any
+                    return
+                if last_yield is not None:
+                    await asyncio.sleep(max(0.0, last_yield + min_interval - loop.time()))
+                arrived.clear()
+                last_yield = loop.time()
+                yield latest
+        finally:
+            tasks = {receiver} if waiter is None else {receiver, waiter}
+            for task in tasks:
+                task.cancel()
+            await asyncio.wait(tasks)
+
+    def usd_cost_alerts(
+        self,
+        session: aiohttp.ClientSession,
+        tx_type: str,
+        below_usd: float,
+        tier: str = "standard",
+        rearm_usd: Optional[float] = None,
+        interval: float = 12.0,
+    ) -> WatchHandle:
+        """
+        Stream an alert each time a transaction type's USD cost drops below a level.
+
+        Every watch() quote is priced with the token price from the tracker's
+        price feed (see estimate_tx_cost), so the alert follows both gas and
+        the token. An alert fires on the crossing below `below_usd`, and not
+        again until the cost has climbed back above `rearm_usd`. Quotes the
+        price feed can't price are skipped, as are watch() errors.
+
+        Args:
+            session: HTTP session used for quotes and prices
+            tx_type: Key in TX_TYPES (e.g. 'simple', 'erc20', 'swap')
+            below_usd: Cost in USD to alert below
+            tier: Tier to price at ('slow', 'standard' or 'fast')
+            rearm_usd: Cost the price must exceed before the next alert
+                (default: 10% above below_usd)
+            interval: Seconds between quotes
+
+        Returns:
+            WatchHandle yielding estimate_tx_cost results with network,
+            block_number, token_price_usd and below_usd added
+
+        Raises:
+            ConfigError: If the tracker has no usable price feed or the
+                thresholds are invalid
+            ValueError: If the transaction type is unknown
+        """
+        if tx_type not in TX_TYPES:
+            raise ValueError(f"Unknown transaction type: {tx_type}")
+        feed = self.price_feed
+        if feed is None or (isinstance(feed, CoinGeckoPriceFeed) and not feed.coin_id):
+            raise ConfigError("USD alerts need a price feed: pass price_feed= or a coingecko_id")
+        if below_usd <= 0:
+            raise ConfigError("below_usd must be positive")
+        if rearm_usd is None:
+            rearm_usd = below_usd * (1 + USD_ALERT_REARM_PCT / 100)
+        elif rearm_usd < below_usd:
+            raise ConfigError("rearm_usd must not be below below_usd")
+        return WatchHandle(
+            self._usd_cost_alerts(session, tx_type, below_usd, tier, rearm_usd, interval),
+            f"usd_cost_alerts {self.network_name} {tx_type}",
+        )
+
+    async def _usd_cost_alerts(
+        self, session: aiohttp.ClientSession, tx_type: str, below_usd: float, tier: str, rearm_usd: float, interval: float
+    ) -> AsyncIterator[Dict]:
+        alerts = GasAlerts(threshold=below_usd, rearm_above=rearm_usd)
+        stream = self._watch(session, interval, 60.0)
+        try:
+            async for quote in stream:
+                if "error" in quote or "warning" in quote:
+                    continue
+                token_price = await self.get_token_price_usd(session)
+                if token_price is None:
+                    logger.warning("%s: no token price, skipping a USD alert check", self.network_name)
+                    continue
+                cost = self.estimate_tx_cost(quote, tx_type, tier, eth_price_usd=token_price)
+                # Unrounded, so a cost just under the threshold isn't rounded up to it
+                cost_usd = cost["cost_eth"] * token_price
+                if alerts.check_alert(cost_usd):
+                    yield dict(
+                        cost,
+                        network=self.network_name,
+                        block_number=quote.get("block_number"),
+                        token_price_usd=token_price,
+                        below_usd=below_usd,
+                    )
+        finally:
+            await close_stream(stream)
+
+    def on_usd_threshold(
+        self,
+        session: aiohttp.ClientSession,
+        tx_type: str,
+        below_usd: float,
+        callback: Callable[[Dict], None],
+        tier: str = "standard",
+        rearm_usd: Optional[float] = None,
+        interval: float = 12.0,
+    ) -> Awaitable[None]:
+        """
+        Call `callback` with each usd_cost_alerts() alert, e.g. "a swap costs under $2".
+
+        The arguments are checked by this call, so a missing price feed fails
+        here rather than inside a background task. Await the result, or run
+        it as a task and cancel that to stop watching.
+
+        Raises:
+            ConfigError: If the tracker has no usable price feed or the
+                thresholds are invalid
+            ValueError: If the transaction type is unknown
+        """
+        return _deliver(self.usd_cost_alerts(session, tx_type, below_usd, tier, rearm_usd, interval), callback)
+
+    async def backfill(
+        self,
+        session: aiohttp.ClientSession,
+        store: HistoryStore,
+        from_block: int,
+        to_block: int,
+        batch_size: int = 100,
+    ) -> List[Dict]:
+        """
+        Load the base fee of every block in a range into a HistoryStore.
+
+        Blocks are fetched with batched eth_getBlockByNumber calls, each batch
+        waiting for the tracker's rate limiter if one is set. Every stored batch
+        also saves a checkpoint, so calling backfill again with the same range
+        after a crash resumes after the last stored block. Blocks from before
+        EIP-1559 have no base fee and are skipped.
+
+        Args:
+            session: HTTP session used for RPC calls
+            store: History store receiving the samples
+            from_block: First block of the range (inclusive)
+            to_block: Last block of the range (inclusive)
+            batch_size: Blocks requested per HTTP call
+
+        Returns:
+            Quote-shaped samples stored by this call, oldest first (fetched_at
+            is the block timestamp; all tiers equal the base fee)
+        """
+        if from_block > to_block:
+            raise ValueError("from_block must not be after to_block")
+        if batch_size < 1:
+            raise ValueError("batch_size must be at least 1")
+
+        checkpoint = f"backfill:{self.network_name}:{from_block}-{to_block}"
+        done = store.checkpoint(checkpoint)
+        start = from_block if done is None else done + 1
+
+        samples = []
+        for first in range(start, to_block + 1, batch_size):
+            last = min(first + batch_size - 1, to_block)
+            numbers = range(first, last + 1)
+            blocks = await self.rpc_batch(
+                session, [("eth_getBlockByNumber", [hex(n), False]) for n in numbers]
+            )
 
-    async def get_base_fee_gwei(self, session: aiohttp.ClientSession) -> float:
+            batch = []
+            with translate_errors():
+                for number, block in zip(numbers, blocks):
+                    if block is None:
+                        raise DeserializeError(f"Block {number} not found")
+                    if not block.get("baseFeePerGas"):
+                        continue
+                    base_fee = Wei.from_hex(block["baseFeePerGas"]).to_gwei()
+                    sample = make_quote("backfill", base_fee, base_fee=base_fee, block_number=number)
+                    sample["fetched_at"] = datetime.fromtimestamp(
+                        parse_hex_quantity(block["timestamp"]), timezone.utc
+                    )
+                    sample["network"] = self.network_name
+                    batch.append(sample)
+
+            store.record_many(batch, checkpoint=(checkpoint, last))
+            samples.extend(batch)
+        return samples
+
+    async def current_regime(
+        self,
+        session: aiohttp.ClientSession,
+        store: HistoryStore,
+        window: timedelta = timedelta(hours=24),
+    ) -> GasRegime:
+        """
+        Classify the current base fee against this network's stored history.
+
+        Cheap is below the window's 25th percentile, normal up to the 75th,
+        expensive above it and extreme above the 95th (see GasStats.classify_regime).
+
+        Args:
+            session: HTTP session used for the RPC call
+            store: HistoryStore or InMemoryHistory with samples recorded for this network
+            window: How far back to compare
+
+        Returns:
+            GasRegime (UNKNOWN when the store has fewer than
+            stats.MIN_STATS_SAMPLES samples in the window)
+        """
+        now = datetime.now()
+        samples = store.query(now - window, now, network=self.network_name)
+        if not samples:
+            return GasRegime.UNKNOWN
+        return GasStats.classify_regime(await self.get_base_fee_gwei(session), samples)
+
+    def cheapest_hours(
+        self,
+        days: int = 7,
+        store: Optional[Union[HistoryStore, InMemoryHistory]] = None,
+        min_samples: int = MIN_HOUR_SAMPLES,
+    ) -> List[HourStat]:
+        """
+        Rank the UTC hours of day by this network's median base fee over the last `days` days.
+
+        Reads the samples recorded for this network (see GasStats.cheapest_hours);
+        hours with fewer than `min_samples` samples come last, marked as not
+        "sufficient" instead of reporting zeros.
+
+        Args:
+            days: How many days of history to bucket
+            store: History to read (default: the with_history() store)
+            min_samples: Fewest samples an hour needs to be ranked
+
+        Raises:
+            ConfigError: If days isn't positive or there is no history store
+        """
+        if days < 1:
+            raise ConfigError("days must be at least 1")
+        store = store if store is not None else self.history
+        if store is None:
+            raise ConfigError("No history store; pass one or configure it with with_history()")
+        now = datetime.now()
+        samples = store.query(now - timedelta(days=days), now, network=self.network_name)
+        return GasStats.cheapest_hours(samples, min_samples)
+
+    async def smoothed_current(
+        self,
+        session: aiohttp.ClientSession,
+        history: Union[InMemoryHistory, HistoryStore],
+        half_life: timedelta = timedelta(minutes=1),
+    ) -> GasQuote:
+        """
+        Get the current quote blended with recent history, for a value that doesn't jitter.
+
+        The latest quote and this network's samples in `history` are
+        averaged with weights halving every `half_life` of age (see
+        core.decayed_mean); samples older than ten half-lives are ignored.
+        The base fee, gas price and every tier are smoothed separately.
+
+        The result lags the true fee slightly by design: after a jump it
+        takes about one half-life to move halfway to the new level. Use it
+        for display, and current() for pricing transactions.
+
+        Args:
+            session: HTTP session used for the quote
+            history: Buffer the tracker's quotes are recorded into, usually
+                an InMemoryHistory fed from watch()
+            half_life: Age at which a sample counts half as much; shorter
+                follows trends faster, longer is smoother
+
+        Returns:
+            Copy of the current quote with smoothed prices and
+            "smoothed_samples", the number of history samples blended in
+        """
+        seconds = half_life.total_seconds()
+        if seconds <= 0:
+            raise ConfigError("half_life must be positive")
+        quote = await self.current(session)
+        now = datetime.now()
+        samples = history.query(now - half_life * 10, now, network=self.network_name)
+        ages = [(now - datetime.fromisoformat(sample["timestamp"])).total_seconds() for sample in samples]
+
+        def smooth(latest, values):
+            if latest is None:
+                return None
+            points = [(0.0, latest)] + [(age, v) for age, v in zip(ages, values) if v is not None]
+            return Gwei(decayed_mean(points, seconds))
+
+        smoothed = dict(quote)
+        smoothed["base_fee"] = smooth(quote.get("base_fee"), [s["base_fee"] for s in samples])
+        smoothed["gas_price"] = smooth(quote["gas_price"], [s["gas_price"] for s in samples])
+        smoothed["tiers"] = {
+            tier: smooth(price, [s["tiers"].get(tier) for s in samples]) for tier, price in quote["tiers"].items()
+        }
+        smoothed["smoothed_samples"] = len(samples)
+        return smoothed
+
+    async def _fee_history(self, session: aiohttp.ClientSession, block_count: int, percentiles: List[float]) -> Dict:
+        """
+        Call eth_feeHistory for the blocks up to the tracker's block tag.
+
+        Nodes that reject "pending" (invalid params or an error naming the
+        tag) are asked for "latest" instead, then and for every later call;
+        other errors are raised and "pending" is tried again next time. Nodes that don't implement eth_feeHistory at
+        all (error -32601) get a stand-in built from the latest block and
+        eth_maxPriorityFeePerGas, marked "degraded" (see
+        core.fee_history_from_block); that is remembered too, so they
+        aren't probed again.
+        """
+        tag = block_tag_param(self.block_tag)
+        if tag == "pending" and not self._supports_pending:
+            tag = "latest"
+        if not self._supports_fee_history:
+            return await self._degraded_fee_history(session, tag, percentiles)
+        params = [hex(block_count), tag, list(percentiles)]
+        try:
+            return await self.rpc_result(session, "eth_feeHistory", params)
+        except RpcError as e:
+            if e.code == METHOD_NOT_FOUND:
+                self._supports_fee_history = False
+                logger.warning(
+                    "%s does not implement eth_feeHistory, approximating fees from the latest block: %s",
+                    self.network_name, e,
+                )
+                return await self._degraded_fee_history(session, tag, percentiles)
+            if tag != "pending" or not rejects_block_tag(e):
+                raise
+            self._supports_pending = False
+            logger.warning("%s rejected the pending block tag, using latest: %s", self.network_name, e)
+            return await self.rpc_result(session, "eth_feeHistory", [params[0], "latest", params[2]])
+
+    async def _degraded_fee_history(self, session: aiohttp.ClientSession, tag: str, percentiles: List[float]) -> Dict:
+        """Stand-in eth_feeHistory result from one block and the node's priority fee suggestion."""
+        # Nodes old enough to lack eth_feeHistory often can't return the pending block either
+        block = await self.rpc_result(session, "eth_getBlockByNumber", ["latest" if tag == "pending" else tag, False])
+        priority_fee = STATIC_PRIORITY_FEE
+        if self._supports_priority_suggestion:
+            try:
+                with translate_errors():
+                    priority_fee = Wei.from_hex(await self.rpc_result(session, "eth_maxPriorityFeePerGas", []))
+            except RpcError as e:
+                if e.code != METHOD_NOT_FOUND:
+                    raise
+                self._supports_priority_suggestion = False
+        with translate_errors():
+            return fee_history_from_block(block, priority_fee, percentiles, self.fee_history.percentile_method)
+
+    async def get_base_fee_gwei(self, session: aiohttp.ClientSession) -> Gwei:
         """Get current base fee in gwei using eth_feeHistory."""
-        payload = {
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "eth_feeHistory",
-            "params": [1, "latest", []],
+        result = await self._fee_history(session, 1, [])
+        with translate_errors():
+            if not result["baseFeePerGas"]:
+                raise EmptyFeeHistoryError("eth_feeHistory returned no base fees")
+            base_fee = Wei.from_hex(result["baseFeePerGas"][-1])
+        return base_fee.to_gwei()
+
+    async def estimate_eip1559(
+        self, session: aiohttp.ClientSession, block_count: Optional[int] = None
+    ) -> Eip1559Estimate:
+        """
+        Estimate EIP-1559 fees from eth_feeHistory over the last N blocks.
+
+        Args:
+            session: HTTP session used for the RPC call
+            block_count: Number of recent blocks to sample (default: from the
+                tracker's FeeHistoryConfig)
+
+        Returns:
+            Dictionary with the next block's base fee (wei), the raw base fee
+            history and a TierEstimates with one entry per configured tier
+            (see with_tier): its priority and max fees (wei), the reward
+            percentile it was priced at and a display label
+
+        Raises:
+            ConfigError: If clear_tiers() left no tiers
+        """
+        config = self.fee_history
+        if block_count is not None:
+            config = FeeHistoryConfig(
+                block_count, config.reward_percentiles, config.recency_weight, config.percentile_method
+            )
+        if self.fee_tiers is None:
+            result = await self._fee_history(session, config.block_count, config.reward_percentiles)
+            with translate_errors():
+                return self._estimate_from_fee_history(result, config.tiers())
+
+        if not self.fee_tiers:
+            raise ConfigError("No fee tiers configured; add some with with_tier()")
+        # eth_feeHistory wants each percentile once, in ascending order
+        percentiles = sorted(set(self.fee_tiers.values()))
+        result = await self._fee_history(session, config.block_count, percentiles)
+        with translate_errors():
+            estimate = self._estimate_from_fee_history(result, {p: p for p in percentiles})
+        estimate["tiers"] = TierEstimates(
+            (name, _tier_estimate(estimate["tiers"][p], name, p)) for name, p in self.fee_tiers.items()
+        )
+        return estimate
+
+    async def at_block(self, session: aiohttp.ClientSession, block_number: int) -> GasQuote:
+        """
+        Get the gas quote as it stood at a past block, e.g. to reconcile a transaction.
+
+        Each tier is that block's own base fee plus the tier's priority fee
+        from the fee history window ending at the block, averaged and
+        weighted as in estimate_eip1559 (before fee rounding and the
+        priority floor); the rewards include what the block's transactions
+        paid. fetched_at is the block's timestamp and "priority_fees" holds
+        the per-tier priority fees. The tracker's own RPC endpoint is used,
+        not the configured provider.
+
+        Args:
+            session: HTTP session used for the RPC calls
+            block_number: Block to price (at most the node's latest)
+
+        Raises:
+            ConfigError: If block_number is negative, after the latest block,
+                or clear_tiers() left no tiers
+            HistoricalDataUnavailableError: If the node has pruned the block,
+                as nodes that aren't archive nodes do
+        """
+        if isinstance(block_number, bool) or not isinstance(block_number, int) or block_number < 0:
+            raise ConfigError(f"block_number must be a non-negative integer, got {block_number!r}")
+        if self.fee_tiers is not None and not self.fee_tiers:
+            raise ConfigError("No fee tiers configured; add some with with_tier()")
+        config = self.fee_history
+        if self.fee_tiers is None:
+            tiers = config.tiers()
+        else:
+            tiers = {p: p for p in sorted(set(self.fee_tiers.values()))}
+        # The window can't reach back past genesis
+        block_count = min(config.block_count, block_number + 1)
+
+        try:
+            head, block = await self.rpc_batch(
+                session, [("eth_blockNumber", []), ("eth_getBlockByNumber", [hex(block_number), False])]
+            )
+            with translate_errors():
+                head = parse_hex_quantity(head)
+            if block_number > head:
+                raise ConfigError(f"Block {block_number} is after the latest block {head}")
+            if block is None:
+                raise HistoricalDataUnavailableError(block_number, "no block returned")
+            history = await self.rpc_result(
+                session, "eth_feeHistory", [hex(block_count), hex(block_number), list(tiers.values())]
+            )
+        except RpcError as e:
+            if history_unavailable(e):
+                raise HistoricalDataUnavailableError(block_number, e.message) from e
+            raise
+
+        with translate_errors():
+            estimate = _fee_history_estimate(history, tiers, config.recency_weight, self.network_name)
+            if estimate["block_number"] is not None and estimate["block_number"] != block_number:
+                raise HistoricalDataUnavailableError(
+                    block_number, f"fee history ends at block {estimate['block_number']}"
+                )
+            base_fee = Wei.from_hex(block["baseFeePerGas"]) if block.get("baseFeePerGas") else None
+            fetched_at = datetime.fromtimestamp(parse_hex_quantity(block["timestamp"]), timezone.utc)
+
+        if self.fee_tiers is None:
+            priorities = {name: fees["max_priority_fee_per_gas"] for name, fees in estimate["tiers"].items()}
+        else:
+            priorities = {
+                name: estimate["tiers"][p]["max_priority_fee_per_gas"] for name, p in self.fee_tiers.items()
+            }
+        prices = {name: (priority + (base_fee or 0)).to_gwei() for name, priority in priorities.items()}
+        names = list(prices)
+        quote = make_quote(
+            "at_block",
+            prices.get("standard", prices[names[len(names) // 2]]),
+            base_fee=base_fee.to_gwei() if base_fee is not None else None,
+            block_number=block_number,
+        )
+        quote["tiers"] = prices
+        quote["priority_fees"] = {name: priority.to_gwei() for name, priority in priorities.items()}
+        quote["fetched_at"] = fetched_at
+        quote["network"] = self.network_name
+        quote.update(congestion_from_history(history) or {})
+        return quote
+
+    async def adaptive_max_fee(
+        self, session: aiohttp.ClientSession, tier: str = "standard", confidence: float = 0.9
+    ) -> Wei:
+        """
+        Get max_fee_per_gas for a tier with base fee headroom sized by recent volatility.
+
+        Instead of doubling the base fee, the headroom comes from how much
+        the base fee moved across the estimate_eip1559() window (see
+        core.adaptive_headroom): close to one block's rise when it held
+        steady, up to about 2x when it swung. Higher confidence adds more
+        standard deviations of headroom.
+
+        Args:
+            session: HTTP session used for the RPC call
+            tier: Tier whose priority fee is added (see with_tier)
+            confidence: Chance, 0.5-0.99, that the max fee still covers the
+                base fee after HEADROOM_BLOCKS blocks
+
+        Raises:
+            ConfigError: If the tier is unknown or confidence is out of range
+        """
+        if not 0.5 <= confidence <= 0.99:
+            raise ConfigError("confidence must be within 0.5-0.99")
+        estimate = await self.estimate_eip1559(session)
+        if tier not in estimate["tiers"]:
+            raise ConfigError(f"Unknown tier '{tier}' (available: {', '.join(estimate['tiers'])})")
+        factor = adaptive_headroom(estimate["base_fee_history"], confidence)
+        base_fee = Wei(math.ceil(int(estimate["base_fee_per_gas"]) * Fraction(factor)))
+        return self.fee_rounding.apply(base_fee + estimate["tiers"][tier]["max_priority_fee_per_gas"])
+
+    async def estimate_at_base_fee(
+        self,
+        session: aiohttp.ClientSession,
+        base_fee_gwei: float,
+        tier: str = "standard",
+        estimate: Optional[Eip1559Estimate] = None,
+    ) -> FeeRecommendation:
+        """
+        Get a tier's fees as they would be if the base fee were `base_fee_gwei`.
+
+        The tier's priority fee comes from `estimate`, or a fresh
+        estimate_eip1559() without one, so the tracker's priority floor
+        applies, and its cap against both the current and the hypothetical
+        base fee. For many scenarios, fetch one estimate_eip1559() and pass
+        it to each call: repricing it makes no network calls.
+
+        Args:
+            session: HTTP session used for the RPC call
+            base_fee_gwei: Hypothetical base fee in gwei
+            tier: Tier whose priority fee is used (see with_tier)
+            estimate: An earlier estimate_eip1559() result of this tracker to reprice
+
+        Returns:
+            Dictionary with max_priority_fee_per_gas and max_fee_per_gas (wei)
+            and the latest block the priority fees are based on
+
+        Raises:
+            ConfigError: If the tier is unknown or the base fee isn't a non-negative number
+        """
+        if isinstance(base_fee_gwei, bool) or not isinstance(base_fee_gwei, (int, float)) or not 0 <= base_fee_gwei < math.inf:
+            raise ConfigError(f"base_fee_gwei must be a non-negative number, got {base_fee_gwei!r}")
+        base_fee = Gwei(base_fee_gwei).to_wei()
+        if estimate is None:
+            estimate = await self.estimate_eip1559(session)
+        fees = self._adjust_fees(fees_at_base_fee(estimate, base_fee, tier), base_fee, tier)
+        if estimate.get("degraded"):
+            fees = dict(fees, degraded=True)
+        return fees
+
+    async def recommend(self, session: aiohttp.ClientSession) -> FeeRecommendation:
+        """
+        Get the standard-tier fees for signing a transaction, with a single eth_feeHistory call.
+
+        A lighter estimate_eip1559(): only the median reward is requested and
+        only the standard tier is computed, over the tracker's configured
+        lookback window.
+
+        Returns:
+            Dictionary with max_priority_fee_per_gas and max_fee_per_gas (wei)
+            and the latest block the estimate is based on
+        """
+        return await self._recommend_at(session, self.recommend_percentile)
+
+    async def to_tx_fields(
+        self, session: aiohttp.ClientSession, tier: str = "standard", gas_limit: Optional[int] = None
+    ) -> TxFeeFields:
+        """
+        Get a tier's fees as transaction fields, ready to merge into an eth_sendTransaction object.
+
+        EIP-1559 chains get maxFeePerGas and maxPriorityFeePerGas from
+        estimate_eip1559(), so the tracker's rounding and priority fee floor
+        and cap apply; chains the provider prices as legacy get the tier's
+        gasPrice from current(). See core.tx_fee_fields for the encoding.
+
+        Args:
+            session: HTTP session used for the RPC calls
+            tier: Tier to sign at (see with_tier)
+            gas_limit: Gas limit to add as "gas", e.g. from estimate_gas()
+
+        Raises:
+            ConfigError: If the tier is unknown or the gas limit is below 21000
+        """
+        quote = await self.current(session)
+        if quote.get("pricing_model") == PricingModel.LEGACY:
+            if tier not in quote["tiers"]:
+                raise ConfigError(f"Unknown tier '{tier}' (available: {', '.join(quote['tiers'])})")
+            return tx_fee_fields({"gas_price": Gwei(quote["tiers"][tier]).to_wei()}, gas_limit)
+        estimate = await self.estimate_eip1559(session)
+        if tier not in estimate["tiers"]:
+            raise ConfigError(f"Unknown tier '{tier}' (available: {', '.join(estimate['tiers'])})")
+        return tx_fee_fields(estimate["tiers"][tier], gas_limit)
+
+    async def fee_for_target_blocks(self, session: aiohttp.ClientSession, blocks: int) -> FeeRecommendation:
+        """
+        Get fees for inclusion within `blocks` blocks (1-20) instead of a named tier.
+
+        The window picks the reward percentile (see percentile_for_target_blocks),
+        averaged over the tracker's fee history lookback like recommend().
+        """
+        return await self._recommend_at(session, percentile_for_target_blocks(blocks))
+
+    async def priority_fee_histogram(
+        self, session: aiohttp.ClientSession, buckets: int = 10, block_count: Optional[int] = None
+    ) -> Histogram:
+        """
+        Get the distribution of priority fees paid in recent blocks.
+
+        Each block's rewards at every 5th percentile (HISTOGRAM_PERCENTILES)
+        are binned into `buckets` equal-width buckets, so a tightly clustered
+        market shows as one tall bucket and a spread-out one as many.
+        Blocks without transactions are skipped.
+
+        Args:
+            session: HTTP session used for the RPC call
+            buckets: Number of buckets
+            block_count: Number of recent blocks to sample (default: from the
+                tracker's FeeHistoryConfig)
+        """
+        count = block_count if block_count is not None else self.fee_history.block_count
+        result = await self._fee_history(session, count, HISTOGRAM_PERCENTILES)
+        with translate_errors():
+            return priority_fee_histogram(result, buckets)
+
+    async def blob_fee_estimate(
+        self, session: aiohttp.ClientSession, blobs: int = 1, block_count: Optional[int] = None
+    ) -> Optional[BlobFee]:
+        """
+        Recommend maxFeePerBlobGas for an EIP-4844 transaction carrying `blobs` blobs.
+
+        Reads baseFeePerBlobGas and blobGasUsedRatio from eth_feeHistory (see
+        core.blob_fee_from_history). Returns None when the node doesn't
+        report blob fields, as on chains and clients from before Cancun.
+
+        Args:
+            session: HTTP session used for the RPC call
+            blobs: Blobs the transaction carries (1-6)
+            block_count: Number of recent blocks to sample (default: from the
+                tracker's FeeHistoryConfig)
+        """
+        count = block_count if block_count is not None else self.fee_history.block_count
+        result = await self._fee_history(session, count, [])
+        with translate_errors():
+            return blob_fee_from_history(result, blobs)
+
+    async def replacement_fee(
+        self,
+        session: aiohttp.ClientSession,
+        original: FeeRecommendation,
+        min_bump_pct: float = DEFAULT_REPLACEMENT_BUMP_PCT,
+        max_fee_ceiling: Optional[int] = None,
+    ) -> FeeRecommendation:
+        """
+        Get fees for replacing a stuck transaction signed with `original` fees.
+
+        Both fees are bumped by `min_bump_pct` and floored at the current
+        recommend() fees (see core.bump_fee); FeeCeilingExceededError is
+        raised if the max fee would exceed `max_fee_ceiling` wei.
+        """
+        current = await self.recommend(session)
+        return bump_fee(original, min_bump_pct, current=current, max_fee_ceiling=max_fee_ceiling)
+
+    async def _recommend_at(self, session: aiohttp.ClientSession, percentile: float) -> FeeRecommendation:
+        """Fees at one reward percentile from a single eth_feeHistory call."""
+        result = await self._fee_history(session, self.fee_history.block_count, [percentile])
+        with translate_errors():
+            fees = fee_history_recommendation(result, self.fee_history.recency_weight)
+            base_fee = Wei.from_hex(result["baseFeePerGas"][-1])
+        fees = self._adjust_fees(fees, base_fee, f"p{percentile:g}")
+        if result.get("degraded"):
+            fees = dict(fees, degraded=True)
+        return fees
+
+    async def check_budget(self, session: aiohttp.ClientSession, tier: str, max_gwei: float) -> BudgetDecision:
+        """
+        Check whether a tier of the current quote is at or below a gas price ceiling.
+
+        Allowed decisions carry a recommendation for the tier: on EIP-1559
+        chains the priority fee is the tier price above the base fee and
+        max_fee_per_gas is capped at the ceiling, so a transaction signed
+        with it can never pay more; on legacy chains both are the tier price.
+
+        Args:
+            session: HTTP session used for the quote
+            tier: Tier to check ('slow', 'standard' or 'fast')
+            max_gwei: Highest acceptable price in gwei
+
+        Returns:
+            BudgetDecision, truthy when the tier is within budget
+        """
+        quote = await self.current(session)
+        if tier not in quote["tiers"]:
+            raise ConfigError(f"Unknown tier '{tier}' (available: {', '.join(quote['tiers'])})")
+        price = Gwei(quote["tiers"][tier])
+        decision = BudgetDecision(price <= max_gwei, tier, price, max_gwei, quote)
+        if decision.allowed:
+            base_fee = quote.get("base_fee")
+            if base_fee is None:
+                priority = max_fee = price.to_wei()
+            else:
+                priority = Gwei(max(price - base_fee, 0)).to_wei()
+                max_fee = min(Gwei(base_fee).to_wei() * 2 + priority, Gwei(max_gwei).to_wei())
+            decision.recommendation = {
+                "max_priority_fee_per_gas": priority,
+                "max_fee_per_gas": max_fee,
+                "block_number": quote.get("block_number"),
+            }
+        return decision
+
+    async def wait_until_below(
+        self,
+        session: aiohttp.ClientSession,
+        tier: str,
+        max_gwei: float,
+        poll: float = 12.0,
+        timeout: float = 600.0,
+    ) -> GasQuote:
+        """
+        Poll until a tier drops to or below a ceiling and return the qualifying quote.
+
+        Failed polls are skipped, as in watch().
+
+        Raises:
+            GasTimeoutError: If the tier stays above the ceiling for `timeout` seconds
+        """
+        async def poll_until_allowed() -> GasQuote:
+            while True:
+                try:
+                    decision = await self.check_budget(session, tier, max_gwei)
+                    if decision:
+                        return decision.quote
+                except ConfigError:
+                    raise
+                except GasError:
+                    pass
+                await asyncio.sleep(poll)
+
+        try:
+            return await asyncio.wait_for(poll_until_allowed(), timeout)
+        except asyncio.TimeoutError:
+            raise GasTimeoutError(f"{tier} gas stayed above {max_gwei} gwei for {timeout}s") from None
+
+    def _estimate_from_fee_history(self, fee_history: Dict, fee_tiers: Optional[Dict] = None) -> Dict:
+        """Build fee tiers (slow/standard/fast by default) from an eth_feeHistory result."""
+        return _fee_history_estimate(
+            fee_history,
+            fee_tiers or FEE_TIERS,
+            self.fee_history.recency_weight,
+            self.network_name,
+            self._adjust_fees,
+        )
+
+    def _adjust_fees(self, fees: Dict, base_fee: Wei, label: str) -> Dict:
+        """Apply the base fee multiplier, the rounding, then the priority cap and floor to one tier or recommendation."""
+        if self.base_fee_multiplier is not None:
+            fees = apply_base_fee_multiplier(fees, base_fee, self.base_fee_multiplier)
+        return self._limit_priority(self.fee_rounding.apply_fees(fees), base_fee, label)
+
+    def _limit_priority(self, fees: Dict, base_fee: Wei, label: str) -> Dict:
+        """Apply the max_priority_ratio cap, then the min_priority_fee floor, to one tier or recommendation."""
+        capped = cap_priority_fee(fees, base_fee, self.max_priority_ratio)
+        if capped is not fees:
+            logger.warning(
+                "%s %s priority fee of %s wei is above %gx the %s wei base fee; capped to %s wei",
+                self.network_name, label, fees["max_priority_fee_per_gas"], self.max_priority_ratio,
+                base_fee, capped["max_priority_fee_per_gas"],
+            )
+        return apply_priority_floor(capped, self.min_priority_fee)
+
+    async def estimate_inclusion(
+        self, session: aiohttp.ClientSession, target: float = 0.9, block_count: int = 20
+    ) -> Dict:
+        """
+        Estimate the priority fee needed for inclusion soon, from the pending mempool.
+
+        Uses Blocknative when configured, otherwise the node's txpool_content.
+        Nodes that don't expose txpool_content fall back to eth_feeHistory
+        percentiles. All of these are heuristics, not guarantees.
+
+        Args:
+            session: HTTP session used for RPC calls
+            target: Desired inclusion probability (0-1)
+            block_count: Number of recent blocks used for the fill ratio
+
+        Returns:
+            Dictionary with priority fees (wei) for the next block and the next
+            3 blocks, the recent fill ratio and the estimate's source
+        """
+        fee_history = await self._fee_history(session, block_count, list(FEE_TIERS.values()))
+        with translate_errors():
+            estimate = self._estimate_from_fee_history(fee_history)
+            fill_ratio = mean_fill_ratio(fee_history.get("gasUsedRatio", []))
+        base_fee = estimate["base_fee_per_gas"]
+        result = {
+            "network": self.network_name,
+            "base_fee_per_gas": base_fee,
+            "fill_ratio": fill_ratio,
         }
-        data = await self.eth_call(session, payload)
-        base_wei = int(data["result"]["baseFeePerGas"][-1], 16)
-        return base_wei / 1e9
+
+        mempool = await self._mempool_priority_fees(session, base_fee, fill_ratio, target)
+        if mempool is not None:
+            result.update(mempool)
+            return result
+
+        tiers = estimate["tiers"]
+        result.update(
+            source="fee_history",
+            priority_fee_for_next_block=tiers["fast"]["max_priority_fee_per_gas"],
+            for_next_3_blocks=tiers["standard"]["max_priority_fee_per_gas"],
+        )
+        return result
+
+    async def _mempool_priority_fees(
+        self, session: aiohttp.ClientSession, base_fee: Wei, fill_ratio: float, target: float
+    ) -> Optional[Dict]:
+        """Next-block and next-3-blocks priority fees from Blocknative or the txpool, None without either."""
+        if self.blocknative_api_key:
+            try:
+                prices = await fetch_blocknative(
+                    session, self.blocknative_api_key, self.blocknative_chain_id
+                )
+                with translate_errors():
+                    next_block = pick_blocknative_fee(prices["estimatedPrices"], target)
+                    next_3 = pick_blocknative_fee(
+                        prices["estimatedPrices"], confidence_for_blocks(target, 3)
+                    )
+                if next_block is not None and next_3 is not None:
+                    return {
+                        "source": "blocknative",
+                        "priority_fee_for_next_block": next_block,
+                        "for_next_3_blocks": next_3,
+                    }
+            except GasError:
+                pass
+
+        try:
+            txpool = await self.rpc_result(session, "txpool_content", [])
+            block = await self.rpc_result(session, "eth_getBlockByNumber", ["latest", False])
+        except (RpcError, HttpError):
+            # Most public endpoints don't expose the txpool namespace
+            return None
+
+        with translate_errors():
+            fees = pending_priority_fees(txpool, base_fee)
+            gas_limit = parse_hex_quantity(block["gasLimit"])
+        return {
+            "source": "txpool",
+            "priority_fee_for_next_block": inclusion_priority_fee(fees, gas_limit, fill_ratio, 1, target),
+            "for_next_3_blocks": inclusion_priority_fee(fees, gas_limit, fill_ratio, 3, target),
+        }
+
+    async def hybrid_estimate(
+        self, session: aiohttp.ClientSession, mempool_weight: Optional[float] = None, target: float = 0.9
+    ) -> FeeRecommendation:
+        """
+        Get standard-tier fees blending fee history with the mempool's view.
+
+        Fee history (as in recommend()) only shows what recent blocks paid;
+        the mempool (Blocknative or txpool_content, as in estimate_inclusion)
+        shows what is competing for the next ones but reacts to every burst.
+        The priority fee is their weighted mean, with the mempool's estimate
+        for inclusion within 3 blocks. Without a mempool source the result
+        is the fee history estimate alone.
+
+        Args:
+            session: HTTP session used for RPC calls
+            mempool_weight: Share of the mempool estimate (0-1); by default
+                0.7 while the base fee is rising and 0.3 otherwise
+            target: Inclusion probability for the mempool estimate (0-1)
+
+        Returns:
+            Dictionary with max_priority_fee_per_gas and max_fee_per_gas (wei),
+            the latest block, the source ("hybrid" or "fee_history") and the
+            mempool weight used
+
+        Raises:
+            ConfigError: If mempool_weight is outside 0-1
+        """
+        if mempool_weight is not None and not 0 <= mempool_weight <= 1:
+            raise ConfigError("mempool_weight must be between 0 and 1")
+        result = await self._fee_history(session, self.fee_history.block_count, [FEE_TIERS["standard"]])
+        with translate_errors():
+            fees = fee_history_recommendation(result, self.fee_history.recency_weight)
+            base_fee = Wei.from_hex(result["baseFeePerGas"][-1])
+            fill_ratio = mean_fill_ratio(result.get("gasUsedRatio", []))
+
+        mempool = await self._mempool_priority_fees(session, base_fee, fill_ratio, target)
+        if mempool is None:
+            fees = dict(fees, source="fee_history", mempool_weight=0.0)
+        else:
+            if mempool_weight is None:
+                with translate_errors():
+                    mempool_weight = hybrid_mempool_weight(result)
+            priority = blend_priority_fees(fees["max_priority_fee_per_gas"], mempool["for_next_3_blocks"], mempool_weight)
+            fees = dict(
+                fees,
+                max_priority_fee_per_gas=priority,
+                max_fee_per_gas=base_fee * 2 + priority,
+                source="hybrid",
+                mempool_weight=mempool_weight,
+            )
+        fees = self._adjust_fees(fees, base_fee, "hybrid")
+        if result.get("degraded"):
+            fees = dict(fees, degraded=True)
+        return fees
+
+    async def estimate_l2_cost(
+        self,
+        session: aiohttp.ClientSession,
+        calldata: bytes,
+        l2_gas: int = 21000,
+        to: str = ZERO_ADDRESS,
+    ) -> Dict:
+        """
+        Estimate the total cost of an L2 transaction including its L1 data fee.
+
+        OP-stack chains charge an L1 data fee that the GasPriceOracle
+        predeploy's getL1Fee() computes with the formula of the chain's
+        current upgrade. Arbitrum charges extra L2 gas units for the L1
+        component, as reported by NodeInterface. Other chains have no L1 fee.
+
+        Args:
+            session: HTTP session used for RPC calls
+            calldata: The RLP-encoded transaction on OP-stack chains, its
+                input data on Arbitrum
+            l2_gas: L2 execution gas for the transaction
+            to: Destination address (used by the Arbitrum estimate)
+
+        Returns:
+            Dictionary with execution fee, L1 data fee and total, all in wei
+        """
+        l2_gas_price = Wei.from_hex(await self.rpc_result(session, "eth_gasPrice", []))
+        l1_base_fee = None
+        l1_data_fee = Wei(0)
+
+        if self.l1_fee_model == "op-stack":
+            calls = [
+                ("eth_call", [{"to": OP_GAS_PRICE_ORACLE, "data": data}, "latest"])
+                for data in (OP_L1_BASE_FEE_SELECTOR, encode_op_l1_fee_call(calldata))
+            ]
+            base_fee, fee = await self.rpc_batch(session, calls)
+            l1_base_fee = Wei(parse_hex_quantity(base_fee))
+            l1_data_fee = Wei(parse_hex_quantity(fee))
+        elif self.l1_fee_model == "arbitrum":
+            call = {"to": ARB_NODE_INTERFACE, "data": encode_l1_component_call(to, calldata)}
+            result = await self.rpc_result(session, "eth_call", [call, "latest"])
+            words = bytes.fromhex(result[2:])
+            l1_gas_units = int.from_bytes(words[0:32], "big")
+            l1_base_fee = Wei(int.from_bytes(words[64:96], "big"))
+            l1_data_fee = l1_gas_units * l2_gas_price
+
+        execution_fee = l2_gas * l2_gas_price
+        total = execution_fee + l1_data_fee
+
+        return {
+            "network": self.network_name,
+            "l1_fee_model": self.l1_fee_model,
+            "l2_gas": l2_gas,
+            "l2_gas_price_wei": l2_gas_price,
+            "l1_base_fee_wei": l1_base_fee,
+            "execution_fee_wei": execution_fee,
+            "l1_data_fee_wei": l1_data_fee,
+            "total_fee_wei": total,
+            "total_fee_eth": total.to_ether(),
+        }
+
+    async def overpayment_for_tx(self, session: aiohttp.ClientSession, tx_hash: str) -> Overpayment:
+        """
+        Compare a mined transaction's maximum fee with the price it actually paid.
+
+        The transaction and its receipt are fetched in one batch; see
+        receipts.overpayment for the result.
+
+        Raises:
+            GasError: If the transaction is unknown or not mined yet
+        """
+        tx, receipt = await self.rpc_batch(session, [
+            ("eth_getTransactionByHash", [tx_hash]),
+            ("eth_getTransactionReceipt", [tx_hash]),
+        ])
+        if tx is None or receipt is None:
+            raise GasError(f"Transaction {tx_hash} is {'unknown' if tx is None else 'not mined yet'}")
+        with translate_errors():
+            # Legacy transactions have no maxFeePerGas and pay their gasPrice
+            max_fee = parse_hex_quantity(tx.get("maxFeePerGas") or tx["gasPrice"])
+            return overpayment(
+                max_fee,
+                parse_hex_quantity(receipt["effectiveGasPrice"]),
+                parse_hex_quantity(receipt["gasUsed"]),
+            )
+
+    async def eth_price(self, session: aiohttp.ClientSession) -> PriceQuote:
+        """
+        Get the USD price of the chain's native token with the feeds it came from.
+
+        With with_price_feeds() this is the consensus of the feeds; with a
+        single feed, that feed's price. Unlike get_token_price_usd(), failures
+        raise instead of returning None.
+
+        Raises:
+            NoPriceConsensusError: If no feed of with_price_feeds() gave a usable price
+            GasError: If a single feed failed
+        """
+        feed = self.price_feed
+        if isinstance(feed, MedianPriceFeed):
+            return await feed.quote(session)
+        with translate_errors():
+            price = await feed.get_price_usd(session)
+        return {"price_usd": price, "sources": [feed.name], "rejected": {}}
 
     async def get_token_price_usd(self, session: aiohttp.ClientSession) -> Optional[float]:
-        """Get token price in USD from CoinGecko."""
+        """Get token price in USD from the configured price feed (CoinGecko by default)."""
         try:
-            url = f"{COINGECKO_API}?ids={self.coingecko_id}&vs_currencies=usd"
-            async with session.get(url, timeout=10) as r:
-                data = await r.json()
-                return float(data[self.coingecko_id]["usd"])
+            return await self.price_feed.get_price_usd(session)
         except Exception:
             return None
 
+    async def snapshot(self, session: aiohttp.ClientSession) -> Dict:
+        """
+        Fetch the EIP-1559 estimate and the token price together, with costs pre-computed.
+
+        Both requests run concurrently. If the price feed fails the snapshot
+        still returns the gas data, with token_price_usd and every cost_usd
+        set to None.
+
+        Returns:
+            Dictionary with the estimate_eip1559 fields, token_price_usd and
+            "costs" mapping each TX_TYPES key to per-tier costs in wei, ETH
+            and USD (priced at base fee + priority fee)
+        """
+        with span("snapshot", chain=self.network_name):
+            estimate, token_price = await asyncio.gather(
+                self.estimate_eip1559(session), self.get_token_price_usd(session)
+            )
+
+        base_fee = estimate["base_fee_per_gas"]
+        costs = {}
+        for tx_type, info in TX_TYPES.items():
+            costs[tx_type] = {}
+            for tier, fees in estimate["tiers"].items():
+                cost_wei = (base_fee + fees["max_priority_fee_per_gas"]) * info["gas"]
+                cost_eth = cost_wei.to_ether()
+                costs[tx_type][tier] = {
+                    "gas_limit": info["gas"],
+                    "cost_wei": cost_wei,
+                    "cost_eth": cost_eth,
+                    "cost_usd": round(cost_eth * token_price, 2) if token_price is not None else None,
+                }
+
+        return dict(estimate, token_price_usd=token_price, costs=costs)
+
     async def get_gas_data(
         self, session: aiohttp.ClientSession, priority_tip: float = 1.5
     ) -> Dict:
@@ -66,3 +2304,256 @@ class GasTracker:
         cost_usd = cost_native * token_price_usd if token_price_usd else None
 
         return {"cost_native": cost_native, "cost_usd": cost_usd, "gas_units": gas_units}
+
+    def cost_in_usd(
+        self, gas_price_gwei: float, gas_limit: int, eth_price_usd: float
+    ) -> Dict:
+        """
+        Convert the cost of a gas limit at a given gas price to ETH and USD.
+
+        Args:
+            gas_price_gwei: Gas price in gwei
+            gas_limit: Gas units the transaction may consume
+            eth_price_usd: Price of the native token in USD
+
+        Returns:
+            Dictionary with the cost in wei, ETH (unrounded) and USD (cents)
+        """
+        cost_wei = Gwei(gas_price_gwei).to_wei() * gas_limit
+        cost_eth = cost_wei.to_ether()
+
+        return {
+            "gas_limit": gas_limit,
+            "cost_wei": cost_wei,
+            "cost_eth": cost_eth,
+            "cost_usd": round(cost_eth * eth_price_usd, 2),
+        }
+
+    def tier_costs_in_usd(self, quote: Dict, gas_limit: int, eth_price_usd: float) -> Dict:
+        """Convert the cost of a gas limit at each quoted tier to ETH and USD."""
+        return {
+            tier: self.cost_in_usd(price, gas_limit, eth_price_usd)
+            for tier, price in quote["tiers"].items()
+        }
+
+    def estimate_tx_cost(
+        self,
+        quote: Dict,
+        tx_type: str,
+        tier: str = "standard",
+        gas_limit: Optional[int] = None,
+        eth_price_usd: Optional[float] = None,
+    ) -> Dict:
+        """
+        Estimate the cost of a common transaction type at a quoted tier.
+
+        Args:
+            quote: Gas quote from current()
+            tx_type: Key in TX_TYPES (e.g. 'simple', 'erc20', 'swap')
+            tier: Tier to price at ('slow', 'standard' or 'fast')
+            gas_limit: Override the canonical gas limit for the transaction type
+            eth_price_usd: Native token price; adds a USD total when given
+
+        Returns:
+            Dictionary with gas limit, total cost in gwei/wei/ETH and optional
+            USD; cost_wei is exact (format it with units.format_eth), the ETH
+            and USD figures are approximate floats
+        """
+        if tx_type not in TX_TYPES:
+            raise ValueError(f"Unknown transaction type: {tx_type}")
+
+        gas_limit = gas_limit or TX_TYPES[tx_type]["gas"]
+        gas_price = quote["tiers"][tier]
+        cost_gwei = gas_price * gas_limit
+        cost_wei = gas_price.to_wei() * gas_limit
+        cost_eth = cost_wei.to_ether()
+
+        return {
+            "tx_type": tx_type,
+            "name": TX_TYPES[tx_type]["name"],
+            "tier": tier,
+            "gas_limit": gas_limit,
+            "gas_price_gwei": gas_price,
+            "cost_gwei": cost_gwei,
+            "cost_wei": cost_wei,
+            "cost_eth": cost_eth,
+            "cost_usd": round(cost_eth * eth_price_usd, 2) if eth_price_usd else None,
+        }
+
+    def estimate_deployment(
+        self,
+        quote: Dict,
+        bytecode: bytes,
+        tier: str = "standard",
+        runtime_size: Optional[int] = None,
+        eth_price_usd: Optional[float] = None,
+    ) -> DeploymentCost:
+        """
+        Estimate the cost of deploying a contract at a quoted tier.
+
+        The gas is that of a CREATE transaction carrying `bytecode` as init
+        code (see core.deployment_gas): intrinsic gas plus 200 per byte of
+        deployed code. The constructor's own execution isn't included, so
+        add it (or use estimate_gas) for constructors that do real work.
+
+        Args:
+            quote: Gas quote from current()
+            bytecode: Init code of the deployment transaction
+            tier: Tier to price at ('slow', 'standard' or 'fast')
+            runtime_size: Size of the deployed runtime code in bytes
+                (default: the size of `bytecode`)
+            eth_price_usd: Native token price; adds a USD total when given
+
+        Raises:
+            ConfigError: If the tier is unknown or the bytecode exceeds the
+                EIP-3860 init code limit of 49152 bytes
+        """
+        if tier not in quote["tiers"]:
+            raise ConfigError(f"Unknown tier '{tier}' (available: {', '.join(quote['tiers'])})")
+        gas = deployment_gas(bytes(bytecode), runtime_size)
+        gas_price = Gwei(quote["tiers"][tier])
+        cost_wei = gas_price.to_wei() * gas["gas"]
+        cost_eth = cost_wei.to_ether()
+        return {
+            "tier": tier,
+            "initcode_size": len(bytecode),
+            "intrinsic_gas": gas["intrinsic_gas"],
+            "code_deposit_gas": gas["code_deposit_gas"],
+            "gas_limit": gas["gas"],
+            "gas_price_gwei": gas_price,
+            "cost_gwei": gas_price * gas["gas"],
+            "cost_wei": cost_wei,
+            "cost_eth": cost_eth,
+            "cost_usd": round(cost_eth * eth_price_usd, 2) if eth_price_usd else None,
+        }
+
+    async def estimate_batch(
+        self, session: aiohttp.ClientSession, tx_types: List[str], tier: str = "standard"
+    ) -> BatchCost:
+        """
+        Price a batch of common transaction types with a single quote.
+
+        The quote and the token price are fetched once, concurrently, and
+        every transaction is priced with them (see estimate_tx_cost), so the
+        totals are consistent. total_gas can be checked against the block
+        gas limit. An empty batch costs nothing and makes no requests; if
+        the price feed fails, the USD figures are None.
+
+        Args:
+            session: HTTP session used for the quote and price
+            tx_types: TX_TYPES keys, one per transaction (repeats allowed)
+            tier: Tier to price at ('slow', 'standard' or 'fast')
+
+        Raises:
+            ValueError: If a transaction type is unknown
+        """
+        unknown = sorted(set(tx_types) - set(TX_TYPES))
+        if unknown:
+            raise ValueError(f"Unknown transaction type: {', '.join(unknown)}")
+        if not tx_types:
+            return {
+                "network": self.network_name,
+                "tier": tier,
+                "transactions": [],
+                "total_gas": 0,
+                "total_cost_gwei": Gwei(0),
+                "total_cost_wei": Wei(0),
+                "total_cost_eth": 0.0,
+                "total_cost_usd": 0.0,
+                "token_price_usd": None,
+                "block_number": None,
+            }
+
+        with span("estimate_batch", chain=self.network_name, transactions=len(tx_types)):
+            quote, token_price = await asyncio.gather(self.current(session), self.get_token_price_usd(session))
+        if tier not in quote["tiers"]:
+            raise ConfigError(f"Unknown tier '{tier}' (available: {', '.join(quote['tiers'])})")
+        transactions = [self.estimate_tx_cost(quote, tx_type, tier, eth_price_usd=token_price) for tx_type in tx_types]
+        total_wei = sum((tx["cost_wei"] for tx in transactions), Wei(0))
+        total_eth = total_wei.to_ether()
+        return {
+            "network": self.network_name,
+            "tier": tier,
+            "transactions": transactions,
+            "total_gas": sum(tx["gas_limit"] for tx in transactions),
+            "total_cost_gwei": sum((tx["cost_gwei"] for tx in transactions), Gwei(0)),
+            "total_cost_wei": total_wei,
+            "total_cost_eth": total_eth,
+            "total_cost_usd": round(total_eth * token_price, 2) if token_price else None,
+            "token_price_usd": token_price,
+            "block_number": quote.get("block_number"),
+        }
+
+    async def estimate_gas(self, session: aiohttp.ClientSession, tx: TransactionRequest) -> int:
+        """
+        Get a transaction's gas limit from eth_estimateGas.
+
+        Raises:
+            EstimationRevertedError: If the transaction would revert (with the
+                decoded revert reason when the node reports one)
+        """
+        try:
+            result = await self.rpc_result(session, "eth_estimateGas", [tx.to_rpc()])
+        except RpcError as e:
+            if "revert" not in e.message.lower():
+                raise
+            raise EstimationRevertedError(e.code, e.message, e.data, revert_reason(e)) from e
+        with translate_errors():
+            return parse_hex_quantity(result)
+
+    async def estimate_for_tx(
+        self,
+        session: aiohttp.ClientSession,
+        tx,
+        eth_price_usd: Optional[float] = None,
+    ) -> Dict:
+        """
+        Price an arbitrary transaction at every quoted tier.
+
+        The gas limit comes from eth_estimateGas, fetched concurrently with
+        the current quote.
+
+        Args:
+            session: HTTP session used for RPC and provider calls
+            tx: TransactionRequest, or a JSON-style dict with from/to/data/value
+            eth_price_usd: Native token price; adds USD totals when given
+
+        Returns:
+            Dictionary with the gas limit and, per tier, the gas price (gwei)
+            and the cost in wei, ETH and optionally USD
+
+        Raises:
+            EstimationRevertedError: If the transaction would revert
+        """
+        if not isinstance(tx, TransactionRequest):
+            tx = TransactionRequest.from_dict(tx)
+
+        gas_limit, quote = await asyncio.gather(self.estimate_gas(session, tx), self.current(session))
+
+        tiers = {}
+        for tier, price in quote["tiers"].items():
+            cost_wei = price.to_wei() * gas_limit
+            cost_eth = cost_wei.to_ether()
+            tiers[tier] = {
+                "gas_price_gwei": price,
+                "cost_wei": cost_wei,
+                "cost_eth": cost_eth,
+                "cost_usd": round(cost_eth * eth_price_usd, 2) if eth_price_usd else None,
+            }
+        return {"network": self.network_name, "gas_limit": gas_limit, "tiers": tiers}
+
+    async def get_fiat_costs(self, session: aiohttp.ClientSession, gas_limit: int) -> Dict:
+        """
+        Fetch a quote and the token price, then price a gas limit at every tier.
+
+        Args:
+            session: HTTP session used for provider and price feed calls
+            gas_limit: Gas units the transaction may consume
+
+        Returns:
+            Dictionary mapping tier name to its fiat cost
+        """
+        quote, eth_price = await asyncio.gather(
+            self.current(session), self.price_feed.get_price_usd(session)
+        )
+        return self.tier_costs_in_usd(quote, gas_limit, eth_price)
@@ -0,0 +1,315 @@
+"""Wei and gwei amounts that refuse to mix with each other."""
+
+import enum
+import re
+from decimal import Decimal, localcontext
+from typing import Any
+
+from .errors import ConfigError, DeserializeError
+
+WEI_PER_GWEI = 10 ** 9
+WEI_PER_ETHER = 10 ** 18
+MAX_UINT256 = 2 ** 256 - 1
+
+_HEX_DIGITS = re.compile(r"[0-9a-fA-F]*")
+
+# Fee units parse_fee accepts as suffixes, in wei
+FEE_UNITS = {"wei": 1, "gwei": WEI_PER_GWEI, "eth": WEI_PER_ETHER, "ether": WEI_PER_ETHER}
+
+# parse_fee reads numbers without a unit below this as gwei, and from
+# BARE_WEI_FROM up as wei; the range between is ambiguous and rejected
+BARE_GWEI_BELOW = 10 ** 6
+BARE_WEI_FROM = 10 ** 9
+
+_FEE = re.compile(r"(?P<number>[+-]?(?:\d+\.?\d*|\.\d+)(?:[eE][+-]?\d{1,4})?)\s*(?P<unit>[a-zA-Z]*)")
+
+
+def parse_hex_quantity(value: Any) -> int:
+    """
+    Parse a JSON-RPC hex quantity such as "0x1a" into an int.
+
+    "0x" alone (returned by some nodes for empty values) is read as zero, and
+    leading zeros and odd-length digit strings are accepted. Anything else
+    that isn't a "0x"-prefixed uint256 raises DeserializeError.
+    """
+    if not isinstance(value, str):
+        raise DeserializeError(f"Expected a hex quantity string, got {value!r}")
+    if value[:2] not in ("0x", "0X"):
+        raise DeserializeError(f"Hex quantity must start with 0x: {value!r}")
+
+    digits = value[2:]
+    if not _HEX_DIGITS.fullmatch(digits):
+        raise DeserializeError(f"Invalid hex quantity: {value!r}")
+    number = int(digits, 16) if digits else 0
+    if number > MAX_UINT256:
+        raise DeserializeError(f"Hex quantity exceeds uint256: {value!r}")
+    return number
+
+
+def format_eth(wei: int, decimals: int = 18) -> str:
+    """
+    Format an integer wei amount as decimal ether without going through float.
+
+    The result is rounded half up to `decimals` fractional digits and has
+    trailing zeros removed, so format_eth(1) is "0.000000000000000001",
+    format_eth(10 ** 18) is "1" and format_eth(15 * 10 ** 17, 0) is "2".
+    Exact for any uint256; use float only for approximate figures like USD.
+    """
+    if isinstance(wei, Gwei) or not isinstance(wei, int):
+        raise TypeError("format_eth takes an integer amount of wei")
+    if decimals < 0:
+        raise ValueError("decimals must not be negative")
+    sign, wei = ("-", -int(wei)) if wei < 0 else ("", int(wei))
+    if decimals < 18:
+        step = 10 ** (18 - decimals)
+        wei = (wei + step // 2) // step * step
+    whole, fraction = divmod(wei, WEI_PER_ETHER)
+    digits = f"{fraction:018d}"[:decimals].rstrip("0")
+    if not whole and not digits:
+        sign = ""
+    return f"{sign}{whole}.{digits}" if digits else f"{sign}{whole}"
+
+
+def _reject(other: Any, unit: type) -> None:
+    """Raise TypeError if `other` is an amount in a different unit."""
+    if isinstance(other, (Wei, Gwei)) and not isinstance(other, unit):
+        raise TypeError(
+            f"Cannot combine {unit.__name__} with {type(other).__name__}; convert explicitly first"
+        )
+
+
+class Wei(int):
+    """
+    Integer amount of wei.
+
+    Adding, subtracting or comparing with Gwei raises TypeError, so a gwei
+    value can never be silently treated as wei. Plain ints are accepted.
+    """
+
+    def __new__(cls, value: Any = 0):
+        if isinstance(value, Gwei):
+            raise TypeError("Use Gwei.to_wei() to convert gwei to wei")
+        if isinstance(value, float):
+            raise TypeError("Wei must be an integer; use Gwei(...).to_wei() for fractional amounts")
+        return super().__new__(cls, value)
+
+    @classmethod
+    def from_hex(cls, value: str) -> "Wei":
+        """Parse a JSON-RPC hex quantity (e.g. "0x4a817c800")."""
+        return cls(parse_hex_quantity(value))
+
+    def to_gwei(self) -> "Gwei":
+        """Convert to gwei."""
+        return Gwei(int(self) / WEI_PER_GWEI)
+
+    def to_ether(self) -> float:
+        """Convert to ether (as a float; see to_ether_str for an exact value)."""
+        return int(self) / WEI_PER_ETHER
+
+    def to_ether_str(self, decimals: int = 18) -> str:
+        """Format as a decimal ether string without precision loss (see format_eth)."""
+        return format_eth(self, decimals)
+
+    def to_json(self) -> str:
+        """Serialize as a decimal string (exact for any uint256)."""
+        return str(int(self))
+
+    def __add__(self, other):
+        _reject(other, Wei)
+        return Wei(int(self) + other) if isinstance(other, int) else NotImplemented
+
+    __radd__ = __add__
+
+    def __sub__(self, other):
+        _reject(other, Wei)
+        return Wei(int(self) - other) if isinstance(other, int) else NotImplemented
+
+    def __rsub__(self, other):
+        _reject(other, Wei)
+        return Wei(other - int(self)) if isinstance(other, int) else NotImplemented
+
+    def __mul__(self, other):
+        if isinstance(other, (Wei, Gwei)):
+            raise TypeError("Cannot multiply two amounts; multiply by a plain number")
+        return Wei(int(self) * other) if isinstance(other, int) else NotImplemented
+
+    __rmul__ = __mul__
+
+    def __floordiv__(self, other):
+        _reject(other, Wei)
+        if isinstance(other, Wei):
+            return int(self) // int(other)
+        return Wei(int(self) // other) if isinstance(other, int) else NotImplemented
+
+    def __eq__(self, other):
+        if isinstance(other, Gwei):
+            return False
+        return int.__eq__(self, other)
+
+    def __ne__(self, other):
+        return not self == other
+
+    def __lt__(self, other):
+        _reject(other, Wei)
+        return int.__lt__(self, other)
+
+    def __le__(self, other):
+        _reject(other, Wei)
+        return int.__le__(self, other)
+
+    def __gt__(self, other):
+        _reject(other, Wei)
+        return int.__gt__(self, other)
+
+    def __ge__(self, other):
+        _reject(other, Wei)
+        return int.__ge__(self, other)
+
+    __hash__ = int.__hash__
+
+    def __str__(self) -> str:
+        return f"{int(self)} wei"
+
+    def __repr__(self) -> str:
+        return f"Wei({int(self)})"
+
+    def __format__(self, spec: str) -> str:
+        return str(self) if not spec else format(int(self), spec)
+
+
+class Gwei(float):
+    """
+    Amount of gwei, the unit gas prices are quoted in.
+
+    Behaves like a float for formatting and statistics, but adding,
+    subtracting or comparing with Wei raises TypeError.
+    """
+
+    def __new__(cls, value: Any = 0.0):
+        if isinstance(value, Wei):
+            raise TypeError("Use Wei.to_gwei() to convert wei to gwei")
+        return super().__new__(cls, value)
+
+    def to_wei(self) -> Wei:
+        """Convert to wei, rounding to the nearest whole wei."""
+        return Wei(round(Decimal(repr(float(self))) * WEI_PER_GWEI))
+
+    def to_json(self) -> str:
+        """Serialize as a decimal string without exponent notation."""
+        return format(Decimal(repr(float(self))).normalize(), "f")
+
+    def __add__(self, other):
+        _reject(other, Gwei)
+        result = float.__add__(self, other)
+        return NotImplemented if result is NotImplemented else Gwei(result)
+
+    __radd__ = __add__
+
+    def __sub__(self, other):
+        _reject(other, Gwei)
+        result = float.__sub__(self, other)
+        return NotImplemented if result is NotImplemented else Gwei(result)
+
+    def __rsub__(self, other):
+        _reject(other, Gwei)
+        result = float.__rsub__(self, other)
+        return NotImplemented if result is NotImplemented else Gwei(result)
+
+    def __mul__(self, other):
+        if isinstance(other, (Wei, Gwei)):
+            raise TypeError("Cannot multiply two amounts; multiply by a plain number")
+        result = float.__mul__(self, other)
+        return NotImplemented if result is NotImplemented else Gwei(result)
+
+    __rmul__ = __mul__
+
+    def __truediv__(self, other):
+        _reject(other, Gwei)
+        result = float.__truediv__(self, other)
+        if result is NotImplemented or isinstance(other, Gwei):
+            # Gwei / Gwei is a plain ratio
+            return result
+        return Gwei(result)
+
+    def __eq__(self, other):
+        if isinstance(other, Wei):
+            return False
+        return float.__eq__(self, other)
+
+    def __ne__(self, other):
+        return not self == other
+
+    def __lt__(self, other):
+        _reject(other, Gwei)
+        return float.__lt__(self, other)
+
+    def __le__(self, other):
+        _reject(other, Gwei)
+        return float.__le__(self, other)
+
+    def __gt__(self, other):
+        _reject(other, Gwei)
+        return float.__gt__(self, other)
+
+    def __ge__(self, other):
+        _reject(other, Gwei)
+        return float.__ge__(self, other)
+
+    __hash__ = float.__hash__
+
+    def __str__(self) -> str:
+        return f"{self.to_json()} gwei"
+
+    def __repr__(self) -> str:
+        return f"Gwei({float(self)!r})"
+
+    def __format__(self, spec: str) -> str:
+        return str(self) if not spec else format(float(self), spec)
+
+
+def to_jsonable(value: Any) -> Any:
+    """Recursively replace Wei and Gwei amounts with their decimal strings (and enums with their values)."""
+    if isinstance(value, (Wei, Gwei)):
+        return value.to_json()
+    if isinstance(value, enum.Enum):
+        return value.value
+    if isinstance(value, dict):
+        return {k: to_jsonable(v) for k, v in value.items()}
+    if isinstance(value, (list, tuple)):
+        return [to_jsonable(v) for v in value]
+    return value
+
+
+def parse_fee(text: str) -> Wei:
+    """
+    Parse a fee typed by a user, e.g. "30", "30gwei", "0.00000003 eth" or "3e10".
+
+    A unit suffix (wei, gwei, eth or ether, in any case) may follow the
+    number, which may be written with a fraction or an exponent. Without a
+    unit, numbers below BARE_GWEI_BELOW are gwei (the unit fees are quoted
+    in) and numbers from BARE_WEI_FROM up are wei, since nobody means a
+    billion gwei; numbers in between could be either and need a unit.
+
+    Raises:
+        ConfigError: If the text isn't a fee, is negative or ambiguous, or
+            isn't a whole number of wei
+    """
+    match = _FEE.fullmatch(text.strip())
+    unit = match["unit"].lower() if match else ""
+    if not match or (unit and unit not in FEE_UNITS):
+        raise ConfigError(f"Not a fee: {text!r} (expected e.g. 30, 30gwei, 0.00000003eth or 30000000000wei)")
+    number = Decimal(match["number"])
+    if number < 0:
+        raise ConfigError(f"Fee must not be negative: {text!r}")
+    if not unit:
+        if BARE_GWEI_BELOW <= number < BARE_WEI_FROM:
+            raise ConfigError(f"Ambiguous fee {text!r}: add a unit, e.g. {text.strip()}gwei or {text.strip()}wei")
+        unit = "gwei" if number < BARE_GWEI_BELOW else "wei"
+    with localcontext() as context:
+        context.prec = 100  # exact for any uint256
+        wei = number * FEE_UNITS[unit]
+    if wei > MAX_UINT256:
+        raise ConfigError(f"Fee {text!r} exceeds uint256")
+    if wei != wei.to_integral_value():
+        raise ConfigError(f"Fee {text!r} is not a whole number of wei")
+    return Wei(int(wei))
@@ -0,0 +1,99 @@
+"""Handle for GasTracker's quote streams that owns and tears down their work."""
+
+import asyncio
+from typing import AsyncGenerator, AsyncIterator, Dict, Optional
+
+from .alerts import significant_changes
+from .tracing import spawn
+
+
+class WatchHandle:
+    """
+    Quote stream returned by GasTracker.watch(), watch_blocks() and watch_blocks_coalesced().
+
+    Iterate it with `async for` as before. The stream's connections and
+    background tasks shut down when it is stopped:
+
+    - explicitly with `await handle.stop()` (or leaving `async with`),
+      which also cancels a fetch that is in flight, or
+    - when the handle is dropped, through the event loop's async generator
+      finalizer, which closes the stream on its next iteration.
+
+    After stop() a pending or later __anext__ ends the iteration. Each step
+    runs as a task named after the handle, e.g. "ethgas watch Ethereum step".
+    """
+
+    def __init__(self, stream: AsyncGenerator[Dict, None], name: str = "watch"):
+        self._stream = stream
+        self.name = name
+        self._pending: Optional[asyncio.Future] = None
+        self.stopped = False
+
+    def __repr__(self) -> str:
+        return f"{type(self).__name__}(name={self.name!r}, stopped={self.stopped})"
+
+    def __aiter__(self) -> AsyncIterator[Dict]:
+        return self
+
+    async def __anext__(self) -> Dict:
+        if self.stopped:
+            raise StopAsyncIteration
+        # Each step runs as its own task so stop() can cancel it from another task
+        pending = self._pending = spawn(self._stream.__anext__(), f"{self.name} step")
+        try:
+            await asyncio.wait({pending})
+        except asyncio.CancelledError:
+            pending.cancel()
+            # Let the step unwind, so the stream isn't closed while still running
+            await asyncio.wait({pending})
+            raise
+        finally:
+            self._pending = None
+        if pending.cancelled():
+            raise StopAsyncIteration  # stop() was called during the step
+        return pending.result()
+
+    async def stop(self) -> None:
+        """Cancel any in-flight step and close the stream; safe to call more than once."""
+        if self.stopped:
+            return
+        self.stopped = True
+        pending = self._pending
+        if pending is not None:
+            pending.cancel()
+            await asyncio.wait({pending})
+        await self._stream.aclose()
+
+    aclose = stop
+
+    def significant_changes(self, pct: float, step_gwei: Optional[float] = None) -> "WatchHandle":
+        """
+        Get a handle yielding only this stream's significant base fee changes.
+
+        See alerts.significant_changes; stopping the new handle stops this one.
+        """
+        stream = _stopping(significant_changes(self, pct, step_gwei), self)
+        return WatchHandle(stream, f"{self.name} significant changes")
+
+    async def __aenter__(self) -> "WatchHandle":
+        return self
+
+    async def __aexit__(self, *exc_info) -> None:
+        await self.stop()
+
+
+async def close_stream(stream) -> None:
+    """Close an async generator or WatchHandle if it has aclose()."""
+    aclose = getattr(stream, "aclose", None)
+    if aclose is not None:
+        await aclose()
+
+
+async def _stopping(stream: AsyncIterator[Dict], handle: WatchHandle) -> AsyncIterator[Dict]:
+    """Yield from `stream`, stopping `handle` when done or closed."""
+    try:
+        async for quote in stream:
+            yield quote
+    finally:
+        await close_stream(stream)
+        await handle.stop()
@@ -2,11 +2,12 @@
 import asyncio
 import json
 from pathlib import Path
-from typing import Optional
 from datetime import datetime
 from aiohttp import web
 import aiohttp
 
+from .errors import InsufficientHistoryError
+from .providers import AppSession
 from .tracker import GasTracker
 from .networks import NETWORKS, TX_TYPES
 from .history import GasHistory
@@ -15,6 +16,11 @@ from .compare import NetworkComparator
 from .prediction import GasPredictor
 
 
+def insufficient_history_response(error: InsufficientHistoryError) -> web.Response:
+    """JSON error for stats or predictions requested before enough history exists."""
+    return web.json_response({"error": str(error), "have": error.have, "need": error.need}, status=422)
+
+
 class WebUI:
     """Web-based user interface for gas tracking."""
 
@@ -24,8 +30,14 @@ class WebUI:
         self.port = port
         self.app = web.Application()
         self.history = GasHistory()
+        # One pooled session for every request's RPC calls, closed with the app
+        self.session = AppSession(self.app)
         self._setup_routes()
 
+    def http_session(self) -> aiohttp.ClientSession:
+        """Get the server's shared HTTP session, created on first use."""
+        return self.session.get()
+
     def _setup_routes(self):
         """Setup HTTP routes."""
         self.app.router.add_get("/", self.handle_index)
@@ -73,11 +85,11 @@ class WebUI:
         tracker = GasTracker(network["rpc"], network["coingecko_id"], network["name"])
 
         try:
-            async with aiohttp.ClientSession() as session:
-                data = await tracker.get_gas_data(session)
-                data["network"] = network["name"]
-                data["network_id"] = network_id
-                return web.json_response(data)
+            session = self.http_session()
+            data = await tracker.get_gas_data(session)
+            data["network"] = network["name"]
+            data["network_id"] = network_id
+            return web.json_response(data)
         except Exception as e:
             return web.json_response(
                 {"error": str(e)},
@@ -134,7 +146,10 @@ class WebUI:
                 status=404
             )
 
-        stats = GasStats.calculate_advanced_stats(filtered)
+        try:
+            stats = GasStats.calculate_advanced_stats(filtered)
+        except InsufficientHistoryError as e:
+            return insufficient_history_response(e)
         return web.json_response(stats)
 
     async def api_predict(self, request):
@@ -158,7 +173,10 @@ class WebUI:
             )
 
         predictor = GasPredictor(records)
-        prediction = predictor.predict_next_hour(method=method)
+        try:
+            prediction = predictor.predict_next_hour(method=method)
+        except InsufficientHistoryError as e:
+            return insufficient_history_response(e)
 
         return web.json_response(prediction)
 
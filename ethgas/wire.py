@@ -0,0 +1,161 @@
+"""
+Lossless serialization of quotes, samples and snapshots for sending between services.
+
+to_json()/from_json() and to_cbor()/from_cbor() both keep Wei, Gwei and
+datetime values typed, so a decoded quote compares equal to the original:
+
+    from_cbor(to_cbor(quote)) == from_json(to_json(quote)) == quote
+
+Both encodings are deterministic: map keys are written in sorted order
+(CBOR uses canonical encoding), so equal values always serialize to the same
+bytes regardless of the order their dicts were built in. That makes the
+output usable for snapshot tests and for content-addressing quotes.
+
+Payloads are wrapped as {"schema_version": ..., "value": ...}; decoding
+one written in another schema version (or without one) raises
+SchemaMismatchError, as the persistent quote cache does, rather than
+misreading it.
+
+CBOR needs the optional cbor2 package (pip install eth-gas-tracker[cbor]).
+For human-readable output without type information use units.to_jsonable.
+"""
+
+import json
+from datetime import datetime
+from typing import Any
+
+from .errors import DeserializeError, SchemaMismatchError
+from .units import Gwei, Wei
+
+# CBOR tags for amounts (from the first-come-first-served range)
+CBOR_TAG_WEI = 61000
+CBOR_TAG_GWEI = 61001
+
+# Version of the payload envelope and value encoding; bump when either changes
+WIRE_SCHEMA_VERSION = 1
+
+
+def _require_cbor2():
+    try:
+        import cbor2
+    except ImportError:
+        raise ImportError(
+            "cbor2 is required for CBOR serialization. "
+            "Install with: pip install eth-gas-tracker[cbor]"
+        )
+    return cbor2
+
+
+def _walk(value: Any, convert) -> Any:
+    """Apply `convert` to every leaf of nested dicts and lists."""
+    if isinstance(value, dict):
+        return {k: _walk(v, convert) for k, v in value.items()}
+    if isinstance(value, (list, tuple)):
+        return [_walk(v, convert) for v in value]
+    return convert(value)
+
+
+def _to_json_leaf(value: Any) -> Any:
+    if isinstance(value, Wei):
+        return {"$wei": value.to_json()}
+    if isinstance(value, Gwei):
+        return {"$gwei": repr(float(value))}
+    if isinstance(value, datetime):
+        return {"$datetime": value.isoformat()}
+    return value
+
+
+def _from_json_object(obj: dict) -> Any:
+    if len(obj) == 1:
+        key, value = next(iter(obj.items()))
+        if key == "$wei":
+            return Wei(int(value))
+        if key == "$gwei":
+            return Gwei(float(value))
+        if key == "$datetime":
+            return datetime.fromisoformat(value)
+    return obj
+
+
+def tag_values(value: Any) -> Any:
+    """Get a JSON-compatible copy of `value` with its Wei, Gwei and datetime values tagged, as to_json() writes it."""
+    return _walk(value, _to_json_leaf)
+
+
+def untag_values(value: Any) -> Any:
+    """Restore the typed values of a tag_values() result read back with plain json.loads."""
+    if isinstance(value, dict):
+        return _from_json_object({k: untag_values(v) for k, v in value.items()})
+    if isinstance(value, list):
+        return [untag_values(v) for v in value]
+    return value
+
+
+def _envelope(value: Any) -> dict:
+    return {"schema_version": WIRE_SCHEMA_VERSION, "value": value}
+
+
+def _open_envelope(payload: Any) -> Any:
+    """Get the value of a decoded payload, raising SchemaMismatchError unless it's in this schema version."""
+    found = payload.get("schema_version") if isinstance(payload, dict) else None
+    if found != WIRE_SCHEMA_VERSION or "value" not in payload:
+        raise SchemaMismatchError(found, WIRE_SCHEMA_VERSION, "wire payload")
+    return payload["value"]
+
+
+def to_json(value: Any) -> str:
+    """Serialize a quote, sample or snapshot to JSON, tagging Wei, Gwei and datetime values."""
+    return json.dumps(_envelope(_walk(value, _to_json_leaf)), sort_keys=True)
+
+
+def from_json(data: str) -> Any:
+    """
+    Decode to_json() output back into typed values.
+
+    Raises:
+        SchemaMismatchError: If the payload was written in another schema version
+        DeserializeError: If it isn't valid JSON
+    """
+    try:
+        payload = json.loads(data, object_hook=_from_json_object)
+    except ValueError as e:
+        raise DeserializeError(f"Invalid JSON payload: {e}") from e
+    return _open_envelope(payload)
+
+
+def to_cbor(value: Any) -> bytes:
+    """Serialize a quote, sample or snapshot to CBOR (requires cbor2)."""
+    cbor2 = _require_cbor2()
+
+    def tag(leaf):
+        if isinstance(leaf, Wei):
+            return cbor2.CBORTag(CBOR_TAG_WEI, int(leaf))
+        if isinstance(leaf, Gwei):
+            return cbor2.CBORTag(CBOR_TAG_GWEI, float(leaf))
+        return leaf
+
+    return cbor2.dumps(_envelope(_walk(value, tag)), canonical=True)
+
+
+def from_cbor(data: bytes) -> Any:
+    """
+    Decode to_cbor() output back into typed values (requires cbor2).
+
+    Raises:
+        SchemaMismatchError: If the payload was written in another schema version
+        DeserializeError: If it isn't valid CBOR
+    """
+    cbor2 = _require_cbor2()
+
+    def tag_hook(decoder, tag):
+        if tag.tag == CBOR_TAG_WEI:
+            return Wei(tag.value)
+        if tag.tag == CBOR_TAG_GWEI:
+            return Gwei(tag.value)
+        return tag
+
+    try:
+        payload = cbor2.loads(data, tag_hook=tag_hook)
+    except (cbor2.CBORDecodeError, ValueError, TypeError) as e:
+        raise DeserializeError(f"Invalid CBOR payload: {e}") from e
+    return _open_envelope(payload)
@@ -0,0 +1,144 @@
+"""WebSocket JSON-RPC provider driven by eth_subscribe("newHeads")."""
+
+import asyncio
+import json
+from collections import deque
+from typing import AsyncIterator, Dict, Optional
+
+import aiohttp
+
+from .errors import GasTimeoutError, HttpError, check_rpc_response, translate_errors
+from .providers import GasProvider, make_quote
+from .retry import RetryPolicy
+from .units import Gwei, Wei, parse_hex_quantity
+
+# Priority tips (gwei) added to the header's base fee for each tier
+DEFAULT_PRIORITY_TIPS = {"slow": 1.0, "standard": 1.5, "fast": 2.5}
+
+
+class RpcSocket:
+    """JSON-RPC over one WebSocket connection, keeping subscription pushes apart from responses."""
+
+    def __init__(self, ws):
+        self.ws = ws
+        self._next_id = 0
+        self._notifications: deque = deque()
+
+    async def request(self, method: str, params: list):
+        """Send a request and wait for its response, queueing notifications that arrive first."""
+        self._next_id += 1
+        request_id = self._next_id
+        await self.ws.send_json({"jsonrpc": "2.0", "id": request_id, "method": method, "params": params})
+
+        while True:
+            message = await self._receive()
+            if message.get("id") == request_id:
+                check_rpc_response(message)
+                return message["result"]
+            self._notifications.append(message)
+
+    async def next_notification(self) -> Dict:
+        """Get the next message that wasn't a response to one of our requests."""
+        if self._notifications:
+            return self._notifications.popleft()
+        return await self._receive()
+
+    async def _receive(self) -> Dict:
+        """Read one JSON message, raising HttpError when the socket closes."""
+        msg = await self.ws.receive()
+        if msg.type != aiohttp.WSMsgType.TEXT:
+            raise HttpError(f"WebSocket closed ({msg.type})")
+        return json.loads(msg.data)
+
+
+class WsProvider(GasProvider):
+    """
+    Gas quotes pushed by a node over WebSocket as each block arrives.
+
+    Every new head's baseFeePerGas is turned into a quote straight away, so
+    watch_blocks() follows the chain without polling. Headers without a base
+    fee (pre-London or non-EIP-1559 chains) fall back to eth_gasPrice over the
+    same connection. Dropped connections are re-established with backoff.
+    """
+
+    name = "websocket"
+
+    def __init__(
+        self,
+        ws_url: str,
+        name: Optional[str] = None,
+        priority_tips: Optional[Dict[str, float]] = None,
+        reconnect: Optional[RetryPolicy] = None,
+    ):
+        """
+        Initialize WebSocket provider.
+
+        Args:
+            ws_url: WebSocket JSON-RPC endpoint (ws:// or wss://)
+            name: Provider name used in quotes
+            priority_tips: Tip in gwei per tier on top of the base fee
+            reconnect: Backoff used between reconnect attempts; its max_retries
+                bounds consecutive failed connects
+        """
+        self.ws_url = ws_url
+        if name:
+            self.name = name
+        self.priority_tips = priority_tips or DEFAULT_PRIORITY_TIPS
+        self.reconnect = reconnect or RetryPolicy(max_retries=10, base_delay=1.0, max_delay=30.0)
+
+    async def fetch(self, session: aiohttp.ClientSession) -> Dict:
+        """Fetch a quote for the latest block over a short-lived connection."""
+        with translate_errors():
+            async with session.ws_connect(self.ws_url, timeout=15) as ws:
+                rpc = RpcSocket(ws)
+                header = await rpc.request("eth_getBlockByNumber", ["latest", False])
+                return await self._quote_from_header(rpc, header)
+
+    async def watch_blocks(self, session: aiohttp.ClientSession) -> AsyncIterator[Dict]:
+        """
+        Yield a quote for every new block header, reconnecting on disconnect.
+
+        Raises the last connection error once `reconnect.max_retries`
+        consecutive attempts have failed.
+        """
+        attempt = 0
+        while True:
+            try:
+                with translate_errors():
+                    async with session.ws_connect(self.ws_url, timeout=15) as ws:
+                        rpc = RpcSocket(ws)
+                        await rpc.request("eth_subscribe", ["newHeads"])
+                        attempt = 0
+                        while True:
+                            message = await rpc.next_notification()
+                            if message.get("method") != "eth_subscription":
+                                continue
+                            header = message["params"]["result"]
+                            yield await self._quote_from_header(rpc, header)
+            except (HttpError, GasTimeoutError) as e:
+                if attempt >= self.reconnect.max_retries:
+                    raise
+                await asyncio.sleep(self.reconnect.delay_for(attempt, e))
+                attempt += 1
+
+    async def _quote_from_header(self, rpc: RpcSocket, header: Dict) -> Dict:
+        """Build a quote from a block header's base fee (or eth_gasPrice without one)."""
+        block_number = parse_hex_quantity(header["number"]) if header.get("number") else None
+        if not header.get("baseFeePerGas"):
+            gas_price = Wei.from_hex(await rpc.request("eth_gasPrice", [])).to_gwei()
+            quote = make_quote(self.name, gas_price, block_number=block_number)
+        else:
+            base_fee = Wei.from_hex(header["baseFeePerGas"]).to_gwei()
+            tiers = {tier: base_fee + Gwei(tip) for tier, tip in self.priority_tips.items()}
+            quote = make_quote(
+                self.name,
+                tiers["standard"],
+                base_fee=base_fee,
+                slow=tiers.get("slow"),
+                fast=tiers.get("fast"),
+                block_number=block_number,
+            )
+        if header.get("hash"):
+            # Lets history stores notice reorgs (see HistoryStore.handle_reorg)
+            quote["block_hash"] = header["hash"]
+        return quote
@@ -30,17 +30,21 @@ setup(
     ],
     python_requires=">=3.8",
     install_requires=[
-        "aiohttp>=3.9",
         "python-dateutil>=2.8.0",
+        'tomli>=1.1.0; python_version < "3.11"',
     ],
     extras_require={
+        # Providers, the tracker and the CLI; ethgas.core works without it
+        "http": ["aiohttp>=3.9"],
         "excel": ["openpyxl>=3.1.0"],
         "notifications": ["plyer>=2.1.0"],
-        "all": ["openpyxl>=3.1.0", "plyer>=2.1.0"],
+        "cbor": ["cbor2>=5.4"],
+        "parquet": ["pyarrow>=12.0"],
+        "all": ["aiohttp>=3.9", "openpyxl>=3.1.0", "plyer>=2.1.0", "cbor2>=5.4", "pyarrow>=12.0"],
     },
     entry_points={
         "console_scripts": [
-            "ethgas=ethgas.main:main",
+            "ethgas=ethgas.main:run",
         ],
     },
 )